@@ -2,11 +2,16 @@
 API v1 版本路由
 """
 from fastapi import APIRouter
+from app.config import settings
+from app.core.compression import supported_encodings
 from .clipboard import router as clipboard_router
 from .devices import router as devices_router
 from .websocket import router as websocket_router
 from .files import router as files_router
 from .auth import router as auth_router
+from .crash_reports import router as crash_reports_router
+from .telemetry import router as telemetry_router
+from .keys import router as keys_router
 
 api_router = APIRouter()
 api_router.include_router(auth_router, prefix="/auth", tags=["认证"])
@@ -14,3 +19,25 @@ api_router.include_router(clipboard_router, prefix="/clipboard", tags=["剪贴
 api_router.include_router(devices_router, prefix="/devices", tags=["设备管理"])
 api_router.include_router(websocket_router, tags=["WebSocket"])
 api_router.include_router(files_router, prefix="/files", tags=["文件管理"])
+api_router.include_router(crash_reports_router, prefix="/crash-reports", tags=["崩溃报告"])
+api_router.include_router(telemetry_router, prefix="/telemetry", tags=["使用统计"])
+api_router.include_router(keys_router, prefix="/keys", tags=["密钥同步"])
+
+
+def server_capabilities() -> list[str]:
+    """服务器支持的功能，客户端据此启用或关闭对应功能"""
+    capabilities = ["websocket", "signed_items", "change_feed"]
+    capabilities.extend(supported_encodings())
+    return capabilities
+
+
+@api_router.get("/version", tags=["版本"])
+async def api_version():
+    """API 版本信息和服务器能力（客户端保存服务器地址前用于验证，连接时用于能力协商）"""
+    return {
+        "service": settings.PROJECT_NAME,
+        "version": settings.VERSION,
+        "api_version": "v1",
+        "api_versions": ["v1"],
+        "capabilities": server_capabilities()
+    }
@@ -5,18 +5,19 @@ import hashlib
 from datetime import datetime, timezone
 from fastapi import APIRouter, Depends, HTTPException, Query, Body
 from typing import Optional
-from sqlalchemy import select, delete, func, or_
+from sqlalchemy import select, delete, func, or_, and_
 from sqlalchemy.ext.asyncio import AsyncSession
 from loguru import logger
 from pathlib import Path
 
 from app.api.deps import get_db
-from app.models.db_models import ClipboardHistory, User as DBUser
+from app.models.db_models import ClipboardDeletion, ClipboardHistory, User as DBUser
 from app.models.schemas import (
     ClipboardItem,
     ClipboardItemCreate,
     ClipboardItemUpdate,
     ClipboardListResponse,
+    ClipboardChangesResponse,
     ApiResponse
 )
 from app.core.security import get_current_active_user
@@ -129,6 +130,10 @@ async def create_clipboard_item(
             file_name=item.file_name,
             file_size=item.file_size,
             mime_type=item.mime_type,
+            representations=[r.model_dump() for r in item.representations] or None,
+            signature=item.signature,
+            public_key=item.public_key,
+            file_hash=item.file_hash,
             user_id=current_user.id  # 设置用户 ID
         )
 
@@ -148,6 +153,10 @@ async def create_clipboard_item(
             "file_name": db_item.file_name,
             "file_size": db_item.file_size,
             "mime_type": db_item.mime_type,
+            "representations": db_item.representations or [],
+            "signature": db_item.signature,
+            "public_key": db_item.public_key,
+            "file_hash": db_item.file_hash,
             "created_at": db_item.created_at.isoformat(),
             "is_duplicate": False  # 新内容
         }
@@ -202,6 +211,10 @@ async def create_clipboard_item(
                 await db.execute(
                     delete(ClipboardHistory).where(ClipboardHistory.id.in_(old_item_ids))
                 )
+                db.add_all([
+                    ClipboardDeletion(item_id=item_id, user_id=current_user.id)
+                    for item_id in old_item_ids
+                ])
 
                 logger.info(
                     f"自动清理历史数据: User={current_user.id}, "
@@ -243,7 +256,7 @@ async def get_clipboard_list(
 
         if search:
             query = query.where(ClipboardHistory.content.like(f"%{search}%"))
-        
+
         # 获取总数
         count_query = select(func.count()).select_from(query.subquery())
         total_result = await db.execute(count_query)
@@ -271,6 +284,92 @@ async def get_clipboard_list(
         raise HTTPException(status_code=500, detail=str(e))
 
 
+# 增量同步的排序键：更新时间统一格式化为毫秒精度的字符串
+# （SQLite 中服务器默认值不带小数秒，直接比较字符串会错位）
+SYNC_KEY_FORMAT = '%Y-%m-%d %H:%M:%f'
+
+
+def parse_changes_cursor(cursor: Optional[str]) -> tuple[Optional[str], int, int]:
+    """解析增量同步游标 `<更新时间>|<剪贴板项ID>|<删除记录ID>`，为空表示从头开始"""
+    if not cursor:
+        return None, 0, 0
+    try:
+        updated_key, item_id, deletion_id = cursor.rsplit('|', 2)
+        return updated_key or None, int(item_id), int(deletion_id)
+    except ValueError:
+        raise HTTPException(status_code=400, detail="无效的同步游标")
+
+
+@router.get("/changes", response_model=ClipboardChangesResponse, summary="获取增量变更")
+async def get_clipboard_changes(
+    cursor: Optional[str] = Query(None, description="上次返回的 next_cursor，为空时从头开始"),
+    limit: int = Query(100, ge=1, le=500, description="每次最多返回的项数和删除数"),
+    db: AsyncSession = Depends(get_db),
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """按 (更新时间, ID) 游标返回之后更新的项，以及之后删除的项 ID（需要认证）
+
+    游标稳定：翻页期间有新的更新不会导致跳过或重复。
+    更新时间只精确到秒，当前这一秒内的更新留到下次返回，之后的更新总是排在游标之后。
+    """
+    try:
+        updated_key, last_item_id, last_deletion_id = parse_changes_cursor(cursor)
+
+        sync_key = func.strftime(
+            SYNC_KEY_FORMAT,
+            func.coalesce(ClipboardHistory.updated_at, ClipboardHistory.created_at)
+        )
+        query = (
+            select(ClipboardHistory, sync_key.label("sync_key"))
+            .where(ClipboardHistory.user_id == current_user.id)
+            .where(sync_key < func.strftime(SYNC_KEY_FORMAT, func.datetime('now')))
+        )
+        if updated_key:
+            query = query.where(or_(
+                sync_key > updated_key,
+                and_(sync_key == updated_key, ClipboardHistory.id > last_item_id)
+            ))
+        query = query.order_by(sync_key.asc(), ClipboardHistory.id.asc()).limit(limit + 1)
+        rows = (await db.execute(query)).all()
+
+        deletions_result = await db.execute(
+            select(ClipboardDeletion)
+            .where(ClipboardDeletion.user_id == current_user.id)
+            .where(ClipboardDeletion.id > last_deletion_id)
+            .order_by(ClipboardDeletion.id.asc())
+            .limit(limit + 1)
+        )
+        deletions = deletions_result.scalars().all()
+
+        has_more = len(rows) > limit or len(deletions) > limit
+        rows = rows[:limit]
+        deletions = deletions[:limit]
+
+        if rows:
+            updated_key, last_item_id = rows[-1].sync_key, rows[-1].ClipboardHistory.id
+        if deletions:
+            last_deletion_id = deletions[-1].id
+        next_cursor = f"{updated_key or ''}|{last_item_id}|{last_deletion_id}"
+
+        logger.info(
+            f"获取增量变更: User={current_user.id}, 更新={len(rows)}, "
+            f"删除={len(deletions)}, 还有更多={has_more}"
+        )
+
+        return {
+            "items": [row.ClipboardHistory for row in rows],
+            "deleted_ids": [deletion.item_id for deletion in deletions],
+            "next_cursor": next_cursor,
+            "has_more": has_more
+        }
+
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"获取增量变更失败: {e}")
+        raise HTTPException(status_code=500, detail=str(e))
+
+
 @router.get("/{item_id}", response_model=ClipboardItem, summary="获取单个剪贴板项")
 async def get_clipboard_item(
     item_id: int,
@@ -367,8 +466,9 @@ async def delete_clipboard_item(
             delete_file_if_exists(file_id)
             logger.info(f"删除剪贴板项关联文件: ID={item_id}, FileID={file_id}")
 
-        # 删除项
+        # 删除项，并记录删除供其他设备同步
         await db.delete(item)
+        db.add(ClipboardDeletion(item_id=item.id, user_id=current_user.id))
         await db.flush()
 
         logger.info(f"删除剪贴板项成功: ID={item_id}, User={current_user.id}")
@@ -424,6 +524,10 @@ async def batch_delete_clipboard_items(
             .where(ClipboardHistory.user_id == current_user.id)
         )
         result = await db.execute(stmt)
+        db.add_all([
+            ClipboardDeletion(item_id=item.id, user_id=current_user.id)
+            for item in items
+        ])
         await db.flush()
 
         deleted_count = result.rowcount
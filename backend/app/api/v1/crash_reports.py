@@ -0,0 +1,48 @@
+"""
+客户端崩溃报告 API
+"""
+from fastapi import APIRouter, Depends
+from pydantic import BaseModel
+from pathlib import Path
+from typing import Optional
+from loguru import logger
+import json
+import re
+
+from app.models.db_models import User as DBUser
+from app.models.schemas import ApiResponse
+from app.core.security import get_current_active_user
+from app.config import settings
+
+router = APIRouter()
+
+# 崩溃报告存储目录（从配置读取）
+CRASH_REPORT_DIR = Path(settings.CRASH_REPORT_DIR)
+
+
+class CrashReport(BaseModel):
+    """客户端上传的崩溃报告"""
+    id: str
+    occurred_at: str
+    app_version: str
+    os: str
+    arch: str
+    thread: str
+    message: str
+    location: Optional[str] = None
+    backtrace: str
+
+
+@router.post("", response_model=ApiResponse, summary="上传崩溃报告")
+async def upload_crash_report(
+    report: CrashReport,
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """保存客户端崩溃报告（用户同意后由客户端上传）"""
+    # 报告 ID 用作文件名，只保留安全字符
+    safe_id = re.sub(r"[^A-Za-z0-9_-]", "_", report.id)[:128]
+    path = CRASH_REPORT_DIR / f"{current_user.id}-{safe_id}.json"
+    path.write_text(json.dumps(report.model_dump(), ensure_ascii=False, indent=2), encoding="utf-8")
+
+    logger.warning(f"收到崩溃报告: {report.app_version} {report.os}/{report.arch} - {report.message}")
+    return ApiResponse(success=True, message="崩溃报告已保存")
@@ -6,11 +6,13 @@ from sqlalchemy import select
 from sqlalchemy.ext.asyncio import AsyncSession
 from sqlalchemy.sql import func
 from loguru import logger
+from datetime import datetime
 
 from app.api.deps import get_db
 from app.models.db_models import Device as DeviceModel, User as DBUser
-from app.models.schemas import Device, DeviceCreate, ApiResponse
+from app.models.schemas import Device, DeviceCreate, DeviceUpdate, ApiResponse, HandoffRequest
 from app.core.security import get_current_active_user
+from app.core.websocket import manager
 
 router = APIRouter()
 
@@ -102,3 +104,90 @@ async def get_device(
         raise HTTPException(status_code=500, detail=str(e))
 
 
+@router.patch("/{device_id}", response_model=Device, summary="重命名设备")
+async def rename_device(
+    device_id: str,
+    update: DeviceUpdate,
+    db: AsyncSession = Depends(get_db),
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """修改设备名称"""
+    try:
+        result = await db.execute(
+            select(DeviceModel).where(DeviceModel.device_id == device_id)
+        )
+        device = result.scalar_one_or_none()
+
+        if not device:
+            raise HTTPException(status_code=404, detail="设备不存在")
+
+        device.device_name = update.device_name
+        await db.flush()
+        await db.refresh(device)
+        logger.info(f"重命名设备: {device_id} -> {update.device_name}")
+        return device
+
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"重命名设备失败: {e}")
+        raise HTTPException(status_code=500, detail=str(e))
+
+
+@router.delete("/{device_id}", response_model=ApiResponse, summary="撤销设备")
+async def revoke_device(
+    device_id: str,
+    db: AsyncSession = Depends(get_db),
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """删除设备记录并断开其实时连接"""
+    try:
+        result = await db.execute(
+            select(DeviceModel).where(DeviceModel.device_id == device_id)
+        )
+        device = result.scalar_one_or_none()
+
+        if not device:
+            raise HTTPException(status_code=404, detail="设备不存在")
+
+        await db.delete(device)
+        await db.flush()
+
+        websocket = manager.active_connections.get(device_id)
+        if websocket:
+            try:
+                await websocket.close(code=1008, reason="Device revoked")
+            except Exception:
+                pass
+            manager.disconnect(device_id)
+
+        logger.info(f"撤销设备: {device_id}")
+        return {"success": True, "message": "设备已撤销"}
+
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"撤销设备失败: {e}")
+        raise HTTPException(status_code=500, detail=str(e))
+
+
+@router.post("/{device_id}/handoff", response_model=ApiResponse, summary="跨设备接力")
+async def handoff_to_device(
+    device_id: str,
+    handoff: HandoffRequest,
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """通过 WebSocket 通知目标设备立即打开指定剪贴板项"""
+    device_info = manager.device_info.get(device_id)
+    if not device_info or device_info.get("user_id") != current_user.id:
+        raise HTTPException(status_code=404, detail="目标设备不在线")
+
+    await manager.send_personal_message({
+        "type": "handoff",
+        "data": handoff.model_dump(),
+        "timestamp": datetime.now().isoformat()
+    }, device_id)
+
+    logger.info(f"跨设备接力: {handoff.source_device_id} -> {device_id}, 剪贴板项={handoff.item_id}")
+
+    return {"success": True, "message": "已发送接力请求"}
@@ -2,7 +2,7 @@
 文件上传和下载 API
 """
 from fastapi import APIRouter, UploadFile, File, HTTPException, Depends, Request
-from fastapi.responses import FileResponse, StreamingResponse
+from fastapi.responses import FileResponse, Response, StreamingResponse
 from pathlib import Path
 import uuid
 import shutil
@@ -16,6 +16,7 @@ from sqlalchemy import select
 from app.models.db_models import User as DBUser, ClipboardHistory
 from app.core.security import get_current_active_user, get_current_user_flexible
 from app.core.database import get_db
+from app.core.compression import compress, decompress_stream, is_compressible
 from app.config import settings
 
 router = APIRouter()
@@ -28,6 +29,7 @@ UPLOAD_DIR = Path(settings.UPLOAD_DIR)
 async def upload_file(
     file: UploadFile = File(...),
     device_id: Optional[str] = None,
+    encoding: Optional[str] = None,
     current_user: DBUser = Depends(get_current_active_user)
 ):
     """
@@ -36,10 +38,14 @@ async def upload_file(
     Args:
         file: 上传的文件
         device_id: 设备ID
+        encoding: 文件内容的压缩格式（目前支持 zstd），服务器解压后保存
     
     Returns:
         文件信息和访问URL
     """
+    if encoding and encoding != "zstd":
+        raise HTTPException(status_code=400, detail=f"不支持的压缩格式: {encoding}")
+
     try:
         # 生成唯一文件名
         file_ext = Path(file.filename).suffix
@@ -48,7 +54,10 @@ async def upload_file(
         
         # 保存文件
         with open(file_path, "wb") as buffer:
-            shutil.copyfileobj(file.file, buffer)
+            if encoding == "zstd":
+                decompress_stream(file.file, buffer)
+            else:
+                shutil.copyfileobj(file.file, buffer)
         
         # 获取文件大小
         file_size = file_path.stat().st_size
@@ -185,6 +194,22 @@ async def download_file(
             # 内联显示模式（浏览器决定如何处理）
             content_disposition = f'inline; filename="{original_filename}"; filename*=UTF-8\'\'{encoded_filename}'
 
+        # 客户端支持时压缩文本类内容
+        accept_encoding = request.headers.get("accept-encoding", "")
+        if "zstd" in accept_encoding and is_compressible(mime_type, file_size):
+            with open(file_path, "rb") as f:
+                compressed = compress(f.read())
+            logger.info(f"zstd 压缩传输: {file_size} -> {len(compressed)} bytes")
+            return Response(
+                content=compressed,
+                media_type=mime_type,
+                headers={
+                    "Content-Disposition": content_disposition,
+                    "Content-Encoding": "zstd",
+                    "Vary": "Accept-Encoding",
+                }
+            )
+
         return FileResponse(
             path=file_path,
             media_type=mime_type,
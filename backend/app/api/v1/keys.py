@@ -0,0 +1,63 @@
+"""
+主密钥同步相关API路由
+
+服务器只保存客户端用密码包装后的主密钥，无法解开；设备据此跟随其他设备的密码修改和密钥轮换
+"""
+import json
+
+from fastapi import APIRouter, Depends, HTTPException
+from sqlalchemy.ext.asyncio import AsyncSession
+from loguru import logger
+
+from app.api.deps import get_db
+from app.models.db_models import User as DBUser
+from app.models.schemas import KeyMaterial
+from app.core.security import get_current_active_user
+
+router = APIRouter()
+
+
+@router.get("/", response_model=KeyMaterial, summary="获取包装后的主密钥")
+async def get_key_material(
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """获取当前用户保存的主密钥，尚未上传时返回 404"""
+    if not current_user.key_material:
+        raise HTTPException(status_code=404, detail="尚未上传主密钥")
+    return KeyMaterial(
+        **json.loads(current_user.key_material),
+        version=current_user.key_material_version or 0
+    )
+
+
+@router.put("/", response_model=KeyMaterial, summary="上传包装后的主密钥")
+async def put_key_material(
+    material: KeyMaterial,
+    db: AsyncSession = Depends(get_db),
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """
+    上传主密钥，`version` 须与服务器当前版本一致（首次上传为 0），
+    避免两台设备同时轮换时互相覆盖；成功后返回新版本
+    """
+    current_version = current_user.key_material_version or 0
+    if material.version != current_version:
+        raise HTTPException(
+            status_code=409,
+            detail=f"主密钥已被其他设备更新 (服务器版本 {current_version})"
+        )
+
+    current_user.key_material = json.dumps({
+        "kdf": material.kdf,
+        "wrapped_master_key": material.wrapped_master_key,
+    })
+    current_user.key_material_version = current_version + 1
+    db.add(current_user)
+    await db.flush()
+    logger.info(f"用户 {current_user.username} 更新主密钥: 版本 {current_user.key_material_version}")
+
+    return KeyMaterial(
+        kdf=material.kdf,
+        wrapped_master_key=material.wrapped_master_key,
+        version=current_user.key_material_version
+    )
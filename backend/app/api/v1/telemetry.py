@@ -0,0 +1,49 @@
+"""
+客户端匿名使用统计 API
+"""
+from fastapi import APIRouter, Depends
+from pydantic import BaseModel
+from datetime import datetime, timezone
+from pathlib import Path
+from loguru import logger
+import json
+
+from app.models.db_models import User as DBUser
+from app.models.schemas import ApiResponse
+from app.core.security import get_current_active_user
+from app.config import settings
+
+router = APIRouter()
+
+# 使用统计存储文件（从配置读取）
+TELEMETRY_PATH = Path(settings.TELEMETRY_PATH)
+
+
+class FeatureUsage(BaseModel):
+    """一项功能的使用次数和按错误码统计的失败次数"""
+    count: int
+    errors: dict[str, int] = {}
+
+
+class TelemetryBatch(BaseModel):
+    """客户端上传的一批统计数据（不含设备标识）"""
+    app_version: str
+    os: str
+    arch: str
+    since: str
+    features: dict[str, FeatureUsage]
+
+
+@router.post("", response_model=ApiResponse, summary="上传使用统计")
+async def upload_telemetry(
+    batch: TelemetryBatch,
+    current_user: DBUser = Depends(get_current_active_user)
+):
+    """追加保存客户端使用统计（用户开启后由客户端定期上传）"""
+    record = batch.model_dump()
+    record["received_at"] = datetime.now(timezone.utc).isoformat()
+    with TELEMETRY_PATH.open("a", encoding="utf-8") as f:
+        f.write(json.dumps(record, ensure_ascii=False) + "\n")
+
+    logger.info(f"收到使用统计: {batch.app_version} {batch.os}/{batch.arch}, {len(batch.features)} 项功能")
+    return ApiResponse(success=True, message="使用统计已保存")
@@ -12,7 +12,7 @@ from jose import JWTError, jwt
 
 from app.core.websocket import manager
 from app.core.database import db
-from app.models.db_models import ClipboardHistory, User as DBUser
+from app.models.db_models import ClipboardDeletion, ClipboardHistory, User as DBUser
 from app.core.security import SECRET_KEY, ALGORITHM, get_user_by_username
 from app.config import settings
 
@@ -244,6 +244,10 @@ async def websocket_endpoint(
                                 file_name=clipboard_data.get("file_name"),
                                 file_size=clipboard_data.get("file_size"),
                                 mime_type=clipboard_data.get("mime_type"),
+                                representations=clipboard_data.get("representations") or None,
+                                signature=clipboard_data.get("signature"),
+                                public_key=clipboard_data.get("public_key"),
+                                file_hash=clipboard_data.get("file_hash"),
                                 synced=True  # WebSocket 同步的标记为已同步
                             )
 
@@ -294,6 +298,10 @@ async def websocket_endpoint(
                                     await session.execute(
                                         delete(ClipboardHistory).where(ClipboardHistory.id.in_(old_item_ids))
                                     )
+                                    session.add_all([
+                                        ClipboardDeletion(item_id=item_id, user_id=user.id)
+                                        for item_id in old_item_ids
+                                    ])
                                     await session.commit()
 
                                     logger.info(
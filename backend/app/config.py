@@ -26,6 +26,12 @@ class Settings(BaseSettings):
     # 文件上传配置
     UPLOAD_DIR: str = "./uploads"
 
+    # 客户端崩溃报告保存目录
+    CRASH_REPORT_DIR: str = "./crash_reports"
+
+    # 客户端使用统计保存路径（每批一行 JSON）
+    TELEMETRY_PATH: str = "./data/telemetry.jsonl"
+
     # API 配置
     API_PREFIX: str = "/api/v1"
     PROJECT_NAME: str = "CloudPaste History API"
@@ -43,3 +49,4 @@ settings = Settings()
 Path(settings.DATABASE_PATH).parent.mkdir(parents=True, exist_ok=True)
 Path(settings.LOG_PATH).mkdir(parents=True, exist_ok=True)
 Path(settings.UPLOAD_DIR).mkdir(parents=True, exist_ok=True)
+Path(settings.CRASH_REPORT_DIR).mkdir(parents=True, exist_ok=True)
@@ -0,0 +1,41 @@
+"""
+传输压缩（zstd）
+
+未安装 zstandard 时不启用，健康检查中不会声明支持
+"""
+from typing import BinaryIO
+
+try:
+    import zstandard
+except ImportError:  # pragma: no cover - 可选依赖
+    zstandard = None
+
+# 小于该字节数的内容不压缩
+MIN_COMPRESS_BYTES = 16 * 1024
+
+# 可压缩的 MIME 类型（文本类）
+COMPRESSIBLE_MIME_TYPES = ("application/json", "application/xml", "application/javascript")
+
+
+def supported_encodings() -> list[str]:
+    """服务器支持的传输压缩格式"""
+    return ["zstd"] if zstandard else []
+
+
+def is_compressible(mime_type: str, size: int) -> bool:
+    """判断下载内容是否值得压缩"""
+    if not zstandard or size < MIN_COMPRESS_BYTES:
+        return False
+    return mime_type.startswith("text/") or mime_type in COMPRESSIBLE_MIME_TYPES
+
+
+def decompress_stream(source: BinaryIO, target: BinaryIO) -> None:
+    """将 zstd 压缩的数据流解压写入目标文件"""
+    if not zstandard:
+        raise ValueError("服务器未启用 zstd 压缩")
+    zstandard.ZstdDecompressor().copy_stream(source, target)
+
+
+def compress(data: bytes) -> bytes:
+    """使用 zstd 压缩数据"""
+    return zstandard.ZstdCompressor(level=3).compress(data)
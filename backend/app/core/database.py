@@ -8,6 +8,7 @@ from sqlalchemy.ext.asyncio import (
     AsyncSession,
     AsyncEngine
 )
+from sqlalchemy import inspect, text
 from loguru import logger
 from app.config import settings
 from app.models.db_models import Base
@@ -50,12 +51,28 @@ class Database:
             
             async with self.engine.begin() as conn:
                 await conn.run_sync(Base.metadata.create_all)
+                await conn.run_sync(self._add_missing_columns)
             
             logger.info("数据库表创建成功")
         except Exception as e:
             logger.error(f"数据库表创建失败: {e}")
             raise
     
+    @staticmethod
+    def _add_missing_columns(conn):
+        """为已存在的表补充模型中新增的可空列 (create_all 不会修改已有表)"""
+        inspector = inspect(conn)
+        for table in Base.metadata.sorted_tables:
+            if not inspector.has_table(table.name):
+                continue
+            existing = {column["name"] for column in inspector.get_columns(table.name)}
+            for column in table.columns:
+                if column.name in existing or not column.nullable:
+                    continue
+                column_type = column.type.compile(dialect=conn.dialect)
+                conn.execute(text(f'ALTER TABLE {table.name} ADD COLUMN "{column.name}" {column_type}'))
+                logger.info(f"数据库表 {table.name} 新增列: {column.name}")
+    
     async def drop_tables(self):
         """删除所有数据库表 (谨慎使用)"""
         try:
@@ -8,6 +8,7 @@ from loguru import logger
 
 from app.config import settings
 from app.core.database import db
+from app.core.compression import supported_encodings
 from app.core.logger import setup_logger
 from app.api.v1 import api_router
 
@@ -72,5 +73,6 @@ async def health_check():
     return {
         "status": "healthy",
         "service": settings.PROJECT_NAME,
-        "version": settings.VERSION
+        "version": settings.VERSION,
+        "compression": supported_encodings()
     }
@@ -3,7 +3,7 @@ SQLAlchemy 数据库模型定义
 """
 from datetime import datetime
 from typing import Optional
-from sqlalchemy import String, Text, Boolean, Integer, DateTime, Index
+from sqlalchemy import String, Text, Boolean, Integer, DateTime, Index, JSON
 from sqlalchemy.orm import DeclarativeBase, Mapped, mapped_column
 from sqlalchemy.sql import func
 
@@ -33,6 +33,10 @@ class User(Base):
     # 用户设置
     max_history_items: Mapped[int] = mapped_column(Integer, default=1000, nullable=False, comment="历史数据最大保留条数")
 
+    # 客户端用密码包装后的主密钥（JSON），服务器无法解开，只用于在设备间同步
+    key_material: Mapped[Optional[str]] = mapped_column(Text, comment="包装后的主密钥")
+    key_material_version: Mapped[Optional[int]] = mapped_column(Integer, comment="主密钥版本，每次更新加一")
+
     # 时间戳
     created_at: Mapped[datetime] = mapped_column(
         DateTime,
@@ -76,6 +80,14 @@ class ClipboardHistory(Base):
     file_name: Mapped[Optional[str]] = mapped_column(String(255), comment="原始文件名")
     file_size: Mapped[Optional[int]] = mapped_column(Integer, comment="文件大小(字节)")
     mime_type: Mapped[Optional[str]] = mapped_column(String(100), comment="MIME类型")
+
+    # 同一内容的多种表示格式（纯文本 / HTML / RTF / 图片），粘贴时由目标应用选择
+    representations: Mapped[Optional[list]] = mapped_column(JSON, comment="表示格式列表")
+
+    # 发送设备的 ed25519 签名，接收端据此校验内容未被服务器篡改
+    signature: Mapped[Optional[str]] = mapped_column(String(128), comment="内容签名(Base64)")
+    public_key: Mapped[Optional[str]] = mapped_column(String(64), comment="签名公钥(Base64)")
+    file_hash: Mapped[Optional[str]] = mapped_column(String(64), comment="文件内容哈希(SHA256)，包含在签名中")
     
     # 设备信息
     device_id: Mapped[Optional[str]] = mapped_column(String(100), comment="设备ID")
@@ -119,6 +131,25 @@ class ClipboardHistory(Base):
         return f"<ClipboardHistory(id={self.id}, content='{self.content[:20]}...', created_at={self.created_at})>"
 
 
+class ClipboardDeletion(Base):
+    """剪贴板项删除记录（墓碑），客户端增量同步时据此删除本地副本"""
+    __tablename__ = "clipboard_deletions"
+
+    id: Mapped[int] = mapped_column(Integer, primary_key=True, autoincrement=True)
+    item_id: Mapped[int] = mapped_column(Integer, nullable=False, comment="被删除的剪贴板项ID")
+    user_id: Mapped[Optional[int]] = mapped_column(Integer, comment="用户ID")
+    deleted_at: Mapped[datetime] = mapped_column(
+        DateTime,
+        server_default=func.now(),
+        nullable=False,
+        comment="删除时间"
+    )
+
+    __table_args__ = (
+        Index('idx_deletion_user', 'user_id', 'id'),
+    )
+
+
 class Device(Base):
     """设备信息模型"""
     __tablename__ = "devices"
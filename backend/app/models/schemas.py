@@ -1,8 +1,8 @@
 """
 数据模型定义
 """
-from pydantic import BaseModel, Field, EmailStr, ConfigDict
-from typing import Optional
+from pydantic import BaseModel, Field, EmailStr, ConfigDict, field_validator
+from typing import List, Optional
 from datetime import datetime
 
 
@@ -75,6 +75,13 @@ class LoginRequest(BaseModel):
 # ==================== 剪贴板相关模型 ====================
 
 
+class ClipboardRepresentation(BaseModel):
+    """剪贴板项的一种表示格式"""
+    format: str = Field(..., description="格式: text/html/rtf/image")
+    content: str = Field(..., description="该格式的内容，图片为文件ID")
+    mime_type: Optional[str] = Field(None, description="MIME类型")
+
+
 class ClipboardItemBase(BaseModel):
     """剪贴板项基础模型"""
     content: str = Field(..., description="剪贴板内容或文件路径")
@@ -86,6 +93,18 @@ class ClipboardItemBase(BaseModel):
     file_name: Optional[str] = Field(None, description="原始文件名")
     file_size: Optional[int] = Field(None, description="文件大小(字节)")
     mime_type: Optional[str] = Field(None, description="MIME类型")
+    representations: List[ClipboardRepresentation] = Field(
+        default_factory=list, description="同一内容的多种表示格式"
+    )
+    signature: Optional[str] = Field(None, description="发送设备对内容的签名(Base64)")
+    public_key: Optional[str] = Field(None, description="发送设备的签名公钥(Base64)")
+    file_hash: Optional[str] = Field(None, description="文件内容的 SHA256(十六进制)，包含在签名中")
+
+    @field_validator("representations", mode="before")
+    @classmethod
+    def _none_as_empty(cls, value):
+        # 旧记录没有表示格式
+        return value or []
 
 
 class ClipboardItemCreate(ClipboardItemBase):
@@ -121,6 +140,11 @@ class DeviceCreate(DeviceBase):
     pass
 
 
+class DeviceUpdate(BaseModel):
+    """更新设备的模型"""
+    device_name: str = Field(..., min_length=1, max_length=100, description="设备名称")
+
+
 class Device(DeviceBase):
     """设备完整模型"""
     id: int
@@ -130,6 +154,20 @@ class Device(DeviceBase):
     model_config = ConfigDict(from_attributes=True)
 
 
+class HandoffRequest(BaseModel):
+    """跨设备接力请求"""
+    item_id: int = Field(..., description="剪贴板项ID")
+    source_device_id: Optional[str] = Field(None, description="发起设备ID")
+    source_device_name: Optional[str] = Field(None, description="发起设备名称")
+
+
+class KeyMaterial(BaseModel):
+    """客户端用密码包装后的主密钥"""
+    kdf: dict = Field(..., description="密钥派生参数")
+    wrapped_master_key: str = Field(..., description="加密后的主密钥(Base64)")
+    version: int = Field(0, description="主密钥版本；上传时为客户端所基于的版本")
+
+
 class PaginationParams(BaseModel):
     """分页参数"""
     page: int = Field(default=1, ge=1, description="页码")
@@ -144,6 +182,14 @@ class ClipboardListResponse(BaseModel):
     items: list[ClipboardItem]
 
 
+class ClipboardChangesResponse(BaseModel):
+    """增量同步响应：游标之后更新的项和删除的项"""
+    items: list[ClipboardItem]
+    deleted_ids: list[int]
+    next_cursor: str = Field(..., description="下次请求使用的游标")
+    has_more: bool = Field(..., description="是否还有更多变更")
+
+
 class ApiResponse(BaseModel):
     """通用API响应"""
     success: bool
@@ -13,8 +13,10 @@ import { useSettingsStore } from '@cloudpaste/shared/store/useSettingsStore';
 import { useWebSocket } from '@cloudpaste/shared/hooks/useWebSocket';
 import { WebSocketProvider } from '@cloudpaste/shared/contexts/WebSocketContext';
 import { toast } from 'sonner';
+import { invoke } from '@tauri-apps/api/core';
 import type { ClipboardItem } from '@cloudpaste/shared/types';
 import { useClipboard } from '../../lib/hooks/useClipboard';
+import { listenBackendEvent } from '../../lib/events';
 
 export default function DashboardLayout({
   children,
@@ -89,13 +91,28 @@ export default function DashboardLayout({
           mime_type: data.mime_type,
           updated_at: new Date().toISOString(),
           synced: true,
+          representations: data.representations,
+          signature: data.signature,
+          public_key: data.public_key,
+          file_hash: data.file_hash,
         };
 
         addItem(newItem);
 
+        // 校验签名：只有签名有效且来自已信任设备的内容才自动写入剪贴板（未签名的内容同样视为未验证）
+        const trust = await invoke<string>('verify_clipboard_item', { item: newItem }).catch(
+          () => 'unsigned'
+        );
+        const trusted = trust === 'verified';
+        if (trust === 'invalid') {
+          toast.error('收到的剪贴板内容签名无效，可能已被篡改');
+        } else if (!trusted) {
+          toast.warning(`来自未验证设备的剪贴板内容: ${data.device_name || data.device_id}`);
+        }
+
         // 根据设置决定是否将同步的内容写入本地剪贴板
         console.log('自动复制设置:', uploadSettings.autoCopyToClipboard);
-        if (uploadSettings.autoCopyToClipboard) {
+        if (uploadSettings.autoCopyToClipboard && trusted) {
           try {
             await writeToClipboard(
               data.content,
@@ -157,6 +174,44 @@ export default function DashboardLayout({
   
   const { writeToClipboard } = useClipboard(clipboardOptions);
 
+  // WebDAV 后端：收到其他设备写入的内容（WebDAV 上的内容没有服务器 ID，不加入列表）
+  useEffect(() => {
+    const unlisten = listenBackendEvent('webdav-item-received', async ({ item }) => {
+      const trust = await invoke<string>('verify_clipboard_item', {
+        item: { ...item, id: 0, favorite: false, updated_at: new Date().toISOString(), synced: true },
+      }).catch(() => 'unsigned');
+      if (trust !== 'verified') {
+        toast.warning(`来自未验证设备的剪贴板内容: ${item.device_name || item.device_id}`);
+        return;
+      }
+      if (!uploadSettings.autoCopyToClipboard) {
+        toast.success('收到新的剪贴板内容');
+        return;
+      }
+      try {
+        // 图片和文件在 WebDAV 上，由 Rust 下载到缓存后写入剪贴板
+        const localPath =
+          item.content_type === 'text'
+            ? undefined
+            : await invoke<string>('cache_item_file', {
+                location: item.content,
+                fileName: item.content_type === 'file' ? item.file_name ?? null : null,
+              });
+        await writeToClipboard(item.content, item.content_type, {
+          fileName: item.file_name ?? undefined,
+          mimeType: item.mime_type ?? undefined,
+          localPath,
+        });
+        toast.success('收到新的剪贴板内容，已自动复制');
+      } catch (error) {
+        console.error('写入剪贴板失败:', error);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [uploadSettings.autoCopyToClipboard, writeToClipboard]);
+
   // 检查认证状态（等待 hydration 完成）
   useEffect(() => {
     if (hasHydrated && !isAuthenticated) {
@@ -15,7 +15,8 @@ import {
   Image as ImageIcon,
   File as FileIcon,
   Check,
-  Plus
+  Plus,
+  Upload
 } from 'lucide-react';
 import { toast } from 'sonner';
 import { invoke } from '@tauri-apps/api/core';
@@ -202,6 +203,8 @@ export default function DashboardPage() {
 
     try {
       await clipboardApi.delete(id);
+      // 同时从本地历史和缓存中彻底删除
+      await invoke('delete_history_items', { ids: [id] });
       removeItem(id);
       toast.success('删除成功');
     } catch (error) {
@@ -227,6 +230,7 @@ export default function DashboardPage() {
 
     try {
       await clipboardApi.batchDelete(selectedIds);
+      await invoke('delete_history_items', { ids: selectedIds });
       removeItems(selectedIds);
       clearSelection();
       toast.success('批量删除成功');
@@ -235,6 +239,17 @@ export default function DashboardPage() {
     }
   };
 
+  // 导出选中的记录到 SFTP / FTP 服务器
+  const handleBatchExport = async () => {
+    try {
+      const paths = await invoke<string[]>('export_to_remote', { itemIds: selectedIds });
+      toast.success(`已导出 ${paths.length} 个文件到服务器`);
+    } catch (error) {
+      console.error('导出失败:', error);
+      toast.error('导出失败，请检查导出服务器设置');
+    }
+  };
+
   // 下载文件
   const handleDownload = async (url: string, fileName?: string) => {
     try {
@@ -329,6 +344,13 @@ export default function DashboardPage() {
             <span className="hidden sm:inline">刷新</span>
           </Button>
 
+          {selectedIds.length > 0 && (
+            <Button size="sm" variant="secondary" onClick={handleBatchExport} className="whitespace-nowrap">
+              <Upload className="w-4 h-4 sm:mr-1.5" />
+              <span className="hidden sm:inline">导出到服务器</span>
+            </Button>
+          )}
+
           {selectedIds.length > 0 && (
             <Button size="sm" variant="danger" onClick={handleBatchDelete} className="whitespace-nowrap">
               <Trash2 className="w-4 h-4 sm:mr-1.5" />
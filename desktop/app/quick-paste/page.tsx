@@ -0,0 +1,145 @@
+'use client';
+
+/**
+ * 快速粘贴面板 - 由全局快捷键打开，选择记录后写入剪贴板并可自动粘贴到之前的窗口
+ */
+import { useEffect, useRef, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { FileText, Image as ImageIcon, Paperclip } from 'lucide-react';
+
+interface FuzzyHit {
+  id: number;
+  title: string;
+  content_type: string;
+  score: number;
+  indices: number[];
+}
+
+interface QuickPasteSettings {
+  auto_paste: boolean;
+  paste_delay_ms: number;
+}
+
+export default function QuickPastePage() {
+  const [query, setQuery] = useState('');
+  const [hits, setHits] = useState<FuzzyHit[]>([]);
+  const [selected, setSelected] = useState(0);
+  const [settings, setSettings] = useState<QuickPasteSettings | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    invoke<QuickPasteSettings>('get_quick_paste_settings').then(setSettings);
+
+    // 每次打开面板时清空搜索并聚焦输入框
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) {
+        setQuery('');
+        inputRef.current?.focus();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  useEffect(() => {
+    invoke<FuzzyHit[]>('fuzzy_find', { query, limit: 50 })
+      .then((results) => {
+        setHits(results);
+        setSelected(0);
+      })
+      .catch((error) => console.error('搜索失败:', error));
+  }, [query]);
+
+  const pick = async (hit: FuzzyHit, paste?: boolean) => {
+    try {
+      await invoke('quick_paste_item', { itemId: hit.id, format: null, paste: paste ?? null });
+    } catch (error) {
+      console.error('快速粘贴失败:', error);
+    }
+  };
+
+  // 只写入纯文本，粘贴到编辑器时不带格式
+  const pickPlainText = async (hit: FuzzyHit) => {
+    try {
+      await invoke('paste_plain_text', { itemId: hit.id, paste: settings?.auto_paste ?? false });
+    } catch (error) {
+      console.error('纯文本粘贴失败:', error);
+    }
+  };
+
+  const toggleAutoPaste = async () => {
+    if (!settings) return;
+    const next = { ...settings, auto_paste: !settings.auto_paste };
+    await invoke('set_quick_paste_settings', { settings: next });
+    setSettings(next);
+  };
+
+  const handleKeyDown = (event: React.KeyboardEvent) => {
+    if (event.key === 'ArrowDown') {
+      event.preventDefault();
+      setSelected((index) => Math.min(index + 1, hits.length - 1));
+    } else if (event.key === 'ArrowUp') {
+      event.preventDefault();
+      setSelected((index) => Math.max(index - 1, 0));
+    } else if (event.key === 'Enter' && event.altKey && hits[selected]) {
+      event.preventDefault();
+      pickPlainText(hits[selected]);
+    } else if (event.key === 'Enter' && hits[selected]) {
+      event.preventDefault();
+      // Shift+Enter 只复制不粘贴
+      pick(hits[selected], event.shiftKey ? false : undefined);
+    } else if (event.key === 'Escape') {
+      invoke('hide_quick_paste');
+    }
+  };
+
+  const iconFor = (contentType: string) => {
+    if (contentType === 'image') return <ImageIcon className="w-4 h-4 shrink-0" />;
+    if (contentType === 'file') return <Paperclip className="w-4 h-4 shrink-0" />;
+    return <FileText className="w-4 h-4 shrink-0" />;
+  };
+
+  return (
+    <div
+      className="h-screen flex flex-col bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 select-none"
+      onKeyDown={handleKeyDown}
+    >
+      <input
+        ref={inputRef}
+        autoFocus
+        value={query}
+        onChange={(event) => setQuery(event.target.value)}
+        placeholder="搜索剪贴板历史..."
+        className="m-2 px-3 py-2 text-sm rounded border border-gray-200 dark:border-gray-600 bg-gray-50 dark:bg-gray-700 text-gray-900 dark:text-white outline-none"
+      />
+      <ul className="flex-1 overflow-y-auto px-2">
+        {hits.map((hit, index) => (
+          <li
+            key={hit.id}
+            onMouseEnter={() => setSelected(index)}
+            onClick={() => pick(hit)}
+            className={`flex items-center gap-2 px-2 py-1.5 rounded text-sm cursor-pointer ${
+              index === selected
+                ? 'bg-blue-600 text-white'
+                : 'text-gray-700 dark:text-gray-200'
+            }`}
+          >
+            {iconFor(hit.content_type)}
+            <span className="truncate">{hit.title}</span>
+          </li>
+        ))}
+      </ul>
+      <div className="flex items-center justify-between px-3 py-1.5 text-xs text-gray-500 dark:text-gray-400 border-t border-gray-200 dark:border-gray-700">
+        <span>Enter 选择 · Alt+Enter 纯文本 · Shift+Enter 仅复制 · Esc 关闭</span>
+        {settings && (
+          <label className="inline-flex items-center gap-1 cursor-pointer">
+            <input type="checkbox" checked={settings.auto_paste} onChange={toggleAutoPaste} />
+            自动粘贴
+          </label>
+        )}
+      </div>
+    </div>
+  );
+}
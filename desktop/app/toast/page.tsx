@@ -0,0 +1,87 @@
+'use client';
+
+/**
+ * 应用内通知窗口 - 系统通知不支持操作按钮时由 Rust 端创建
+ */
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Copy, ExternalLink, X } from 'lucide-react';
+
+interface ToastItem {
+  id: number;
+  content: string;
+  content_type: string;
+  device_name?: string;
+  file_name?: string;
+}
+
+export default function ToastPage() {
+  const [item, setItem] = useState<ToastItem | null>(null);
+
+  useEffect(() => {
+    invoke<ToastItem | null>('get_pending_toast').then(setItem);
+
+    const unlisten = listen<ToastItem>('toast-updated', (event) => {
+      setItem(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleAction = async (action: 'copy' | 'open' | 'dismiss') => {
+    try {
+      await invoke('toast_action', { action });
+    } catch (error) {
+      console.error('通知操作失败:', error);
+    }
+  };
+
+  if (!item) {
+    return null;
+  }
+
+  let summary = item.content;
+  if (item.content_type === 'image') {
+    summary = '图片';
+  } else if (item.content_type === 'file') {
+    summary = `文件 ${item.file_name ?? ''}`;
+  }
+
+  return (
+    <div className="h-screen p-3 bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 flex flex-col select-none">
+      <div className="flex items-start justify-between mb-1">
+        <p className="text-sm font-semibold text-gray-900 dark:text-white truncate">
+          来自 {item.device_name ?? '其他设备'} 的剪贴板内容
+        </p>
+        <button
+          onClick={() => handleAction('dismiss')}
+          className="text-gray-400 hover:text-gray-600 dark:hover:text-gray-200"
+        >
+          <X className="w-4 h-4" />
+        </button>
+      </div>
+      <p className="flex-1 text-xs text-gray-600 dark:text-gray-400 line-clamp-2 break-all">
+        {summary}
+      </p>
+      <div className="flex justify-end gap-2 mt-2">
+        <button
+          onClick={() => handleAction('copy')}
+          className="inline-flex items-center gap-1 px-3 py-1 text-xs rounded bg-blue-600 text-white hover:bg-blue-700"
+        >
+          <Copy className="w-3 h-3" />
+          复制
+        </button>
+        <button
+          onClick={() => handleAction('open')}
+          className="inline-flex items-center gap-1 px-3 py-1 text-xs rounded bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-200 hover:bg-gray-200 dark:hover:bg-gray-600"
+        >
+          <ExternalLink className="w-3 h-3" />
+          打开
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,131 @@
+/**
+ * 后端事件目录
+ * 与 Rust 端 events.rs 中的事件名和负载保持一致
+ */
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { ClipboardSyncData } from '@cloudpaste/shared/types';
+
+export interface SyncProgress {
+  page: number;
+  synced: number;
+  total: number;
+}
+
+export interface DownloadProgress {
+  url: string;
+  downloaded: number;
+  total: number | null;
+  bytes_per_sec: number | null;
+  eta_secs: number | null;
+  done: boolean;
+}
+
+export interface ClipboardCaptured {
+  content_type: string;
+  size: number;
+}
+
+export interface AuthExpired {
+  message: string;
+}
+
+export interface CacheEvicted {
+  url: string | null;
+  freed_bytes: number;
+}
+
+export interface ExportProgress {
+  url: string;
+  copied: number;
+  total: number;
+  bytes_per_sec: number | null;
+  eta_secs: number | null;
+  done: boolean;
+}
+
+export interface UploadProgress {
+  file_name: string;
+  uploaded: number;
+  total: number;
+  bytes_per_sec: number | null;
+  eta_secs: number | null;
+  done: boolean;
+}
+
+export interface CacheWarmProgress {
+  completed: number;
+  failed: number;
+  total: number;
+}
+
+export interface AppLocked {
+  reason: 'idle' | 'resume' | 'manual';
+}
+
+export interface PasteStackChanged {
+  remaining: number[];
+}
+
+export interface ScreenSharingChanged {
+  active: boolean;
+  /** 正在共享屏幕的应用 */
+  app_name: string | null;
+}
+
+export interface KeyRotationProgress {
+  completed: number;
+  total: number;
+  stage: 'started' | 'uploaded' | 'key_material' | 'history_db' | 'failed';
+  error: string | null;
+}
+
+export interface CapturePauseChanged {
+  paused: boolean;
+  /** 自动恢复的时间（RFC 3339），为空表示直到手动恢复 */
+  resumes_at: string | null;
+}
+
+export interface WebDavItemReceived {
+  item: ClipboardSyncData;
+}
+
+export interface StorageStatusChanged {
+  status: {
+    id: string;
+    synced: number;
+    failed: number;
+    last_synced_at: string | null;
+    last_error: string | null;
+  };
+}
+
+/**
+ * 事件名到负载类型的映射
+ */
+export interface BackendEvents {
+  'sync-progress': SyncProgress;
+  'download-progress': DownloadProgress;
+  'clipboard-captured': ClipboardCaptured;
+  'auth-expired': AuthExpired;
+  'cache-evicted': CacheEvicted;
+  'export-progress': ExportProgress;
+  'upload-progress': UploadProgress;
+  'cache-warm-progress': CacheWarmProgress;
+  'app-locked': AppLocked;
+  'paste-stack-changed': PasteStackChanged;
+  'capture-pause-changed': CapturePauseChanged;
+  'screen-sharing-changed': ScreenSharingChanged;
+  'key-rotation-progress': KeyRotationProgress;
+  'webdav-item-received': WebDavItemReceived;
+  'storage-status-changed': StorageStatusChanged;
+}
+
+/**
+ * 监听后端事件，返回取消监听的函数
+ */
+export function listenBackendEvent<K extends keyof BackendEvents>(
+  name: K,
+  handler: (payload: BackendEvents[K]) => void
+): Promise<UnlistenFn> {
+  return listen<BackendEvents[K]>(name, (event) => handler(event.payload));
+}
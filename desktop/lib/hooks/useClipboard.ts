@@ -15,9 +15,12 @@ import {
 import { fullName } from 'tauri-plugin-fs-pro-api';
 import { readFile } from '@tauri-apps/plugin-fs';
 import { invoke } from '@tauri-apps/api/core';
-import { cacheDir } from '@tauri-apps/api/path';
-import { fileApi } from '@cloudpaste/shared/lib/api';
-import type { ClipboardSyncData } from '@cloudpaste/shared/types';
+import { appCacheDir } from '@tauri-apps/api/path';
+import type {
+  ClipboardRepresentation,
+  ClipboardSyncData,
+  FileUploadResponse,
+} from '@cloudpaste/shared/types';
 import { getApiBaseUrl } from '@cloudpaste/shared/lib/apiConfig';
 import { useAuthStore } from '@cloudpaste/shared/store/useAuthStore';
 import { useSettingsStore } from '@cloudpaste/shared/store/useSettingsStore';
@@ -45,7 +48,7 @@ export function useClipboard(options?: UseClipboardOptions): {
   writeToClipboard: (
     content: string,
     contentType?: 'text' | 'image' | 'file',
-    metadata?: { fileName?: string; mimeType?: string }
+    metadata?: { fileName?: string; mimeType?: string; localPath?: string }
   ) => Promise<void>;
 } {
   const token = useAuthStore((state) => state.token); // 获取认证 token
@@ -66,6 +69,46 @@ export function useClipboard(options?: UseClipboardOptions): {
   // 优先使用 options 中的 autoUpload，否则使用 settings 中的配置
   const autoUpload = optionsAutoUpload !== undefined ? optionsAutoUpload : uploadSettings.autoUpload;
 
+  // 用本机设备密钥签名后再通过 WebSocket 同步，接收端据此校验来源
+  const syncPrimary = async (data: ClipboardSyncData): Promise<boolean> => {
+    // 选择 WebDAV 后端时由 Rust 写入 WebDAV（签名也在 Rust 端完成；图片和文件已由 Rust 上传到 WebDAV）
+    const backend = await invoke<string>('get_sync_backend').catch(() => 'cloud_paste');
+    if (backend === 'web_dav') {
+      try {
+        await invoke('webdav_push_item', { item: data });
+        return true;
+      } catch (error) {
+        console.error('❌ 同步到 WebDAV 失败:', error);
+        return false;
+      }
+    }
+    if (!syncClipboard) return false;
+    try {
+      const signed = await invoke<{ signature?: string; public_key?: string }>('sign_clipboard_item', {
+        content: data.content,
+        contentType: data.content_type,
+        deviceId: data.device_id ?? null,
+        representations: data.representations ?? null,
+        fileHash: data.file_hash ?? null,
+      });
+      return syncClipboard({ ...data, ...signed });
+    } catch (error) {
+      console.error('❌ 签名剪贴板内容失败:', error);
+      return syncClipboard(data);
+    }
+  };
+
+  // 写入主后端成功后，由 Rust 在后台镜像到其他已启用的存储后端
+  const syncSigned = async (data: ClipboardSyncData): Promise<boolean> => {
+    const success = await syncPrimary(data);
+    if (success) {
+      invoke('mirror_clipboard_item', { item: data }).catch((error) =>
+        console.error('❌ 镜像剪贴板内容失败:', error)
+      );
+    }
+    return success;
+  };
+
   // 构建文件完整 URL（使用登录时保存的 API 地址）
   const buildFileUrl = (fileIdOrUrl: string): string => {
     const apiBaseUrl = getApiBaseUrl();
@@ -117,6 +160,22 @@ export function useClipboard(options?: UseClipboardOptions): {
     return '';
   };
 
+  // 同步前由 Rust 端按采集设置处理文本（去除格式、跟踪参数、去重等）
+  const processTextCapture = async (
+    result: any
+  ): Promise<{
+    content: string;
+    skipped: string | null;
+    representations: ClipboardRepresentation[];
+  }> => {
+    const { html, rtf, text } = result;
+    return invoke('process_text_capture', {
+      text: text?.value ?? null,
+      html: html?.value ?? null,
+      rtf: rtf?.value ?? null,
+    });
+  };
+
   useEffect(() => {
     let isListening = false;
 
@@ -180,6 +239,14 @@ export function useClipboard(options?: UseClipboardOptions): {
             return;
           }
 
+          // 复制来源应用在排除列表中时不采集（需在来源应用仍处于前台时立即检查）
+          const sourceSkipped = await invoke<string | null>('check_capture_source');
+          if (sourceSkipped) {
+            console.log('🚫 跳过采集:', sourceSkipped);
+            lastContentRef.current = contentHash;
+            return;
+          }
+
           // 清除之前的防抖定时器
           if (debounceTimerRef.current) {
             clearTimeout(debounceTimerRef.current);
@@ -198,87 +265,39 @@ export function useClipboard(options?: UseClipboardOptions): {
           // 处理不同类型的剪贴板内容
           try {
             if (files) {
-              // 文件类型
+              // 文件类型：由 Rust 端从系统剪贴板读取文件列表（过滤、去重），上传时直接从磁盘读取
               console.log('📁 检测到文件:', files.value);
-              
-              if (showNotification) {
-                toast.success(`检测到 ${files.value.length} 个文件`);
-              }
 
-              if (autoUpload) {
-                // 遍历所有文件并上传
-                for (const filePath of files.value) {
+              const captured = await invoke<{
+                files: Array<{ path: string; name: string; size: number; is_dir: boolean }>;
+                skipped: string | null;
+              }>('process_file_capture');
+
+              if (captured.skipped) {
+                console.log('⏭️ 跳过文件:', captured.skipped);
+              } else {
+                const uploadCaptured = async () => {
                   try {
-                    console.log('📂 处理文件:', filePath);
-                    
-                    // 获取文件名和大小
-                    const fileName = filePath.split('/').pop() || 'unknown-file';
-                    
-                    // 读取文件内容
-                    const fileContent = await readFile(filePath);
-                    const fileSizeBytes = fileContent.length;
-                    console.log('✅ 文件读取成功，大小:', fileSizeBytes, 'bytes');
-                    
-                    // 检查文件是否应该上传
-                    const uploadCheck = shouldUploadFile(fileName, fileSizeBytes);
-                    if (!uploadCheck.allowed) {
-                      console.log('⏭️ 跳过文件上传:', uploadCheck.reason);
-                      if (showNotification) {
-                        toast.warning(`跳过: ${fileName} - ${uploadCheck.reason}`);
-                      }
-                      continue;
-                    }
-                    
-                    // 获取 MIME 类型（简单判断）
-                    const ext = fileName.split('.').pop()?.toLowerCase() || '';
-                    let mimeType = 'application/octet-stream';
-                    if (ext === 'pdf') mimeType = 'application/pdf';
-                    else if (ext === 'txt') mimeType = 'text/plain';
-                    else if (ext === 'json') mimeType = 'application/json';
-                    else if (ext === 'zip') mimeType = 'application/zip';
-                    else if (ext === 'png') mimeType = 'image/png';
-                    else if (ext === 'jpg' || ext === 'jpeg') mimeType = 'image/jpeg';
-                    else if (ext === 'gif') mimeType = 'image/gif';
-                    else if (ext === 'mp4') mimeType = 'video/mp4';
-                    else if (ext === 'mp3') mimeType = 'audio/mpeg';
-                    
-                    // 创建 Blob 和 File 对象
-                    const fileBlob = new Blob([fileContent], { type: mimeType });
-                    const file = new File([fileBlob], fileName, { type: mimeType });
-                    
-                    const deviceId = await invoke<string>('get_device_id_command');
-                    const deviceName = await invoke<string>('get_device_name_command');
-                    
-                    console.log('⬆️ 开始上传文件:', fileName, file.size, 'bytes');
-                    
-                    // 上传文件
-                    const uploadResponse = await fileApi.upload(file, deviceId);
-                    
-                    // 通过 WebSocket 同步到其他设备
-                    if (syncClipboard) {
-                      const success = syncClipboard({
-                        content: uploadResponse.data.file_url,
-                        content_type: uploadResponse.data.content_type,
-                        device_id: deviceId,
-                        device_name: deviceName,
-                        file_name: uploadResponse.data.file_name,
-                        file_size: uploadResponse.data.file_size,
-                        mime_type: uploadResponse.data.mime_type,
-                      });
-                      
-                      if (success && showNotification) {
-                        // toast.success(`文件 ${fileName} 已自动上传`);
-                      }
-                    }
+                    const links = await invoke<string[]>('upload_captured_files');
+                    console.log('✅ 文件已上传:', links);
                   } catch (error) {
-                    console.error('❌ 上传文件失败:', filePath, error);
+                    console.error('❌ 上传文件失败:', error);
                     if (showNotification) {
-                      toast.error(`上传文件失败: ${filePath.split('/').pop()}`);
+                      toast.error(`上传文件失败: ${(error as { message?: string })?.message ?? error}`);
                     }
                   }
+                };
+
+                const count = captured.files.filter((file) => !file.is_dir).length;
+                if (autoUpload) {
+                  await uploadCaptured();
+                } else if (showNotification) {
+                  toast.success(`检测到 ${count} 个文件`, {
+                    action: { label: '上传', onClick: () => void uploadCaptured() },
+                  });
                 }
               }
-              
+
             } else if (image) {
               // 图片类型
               console.log('🖼️ 检测到图片', {
@@ -382,28 +401,44 @@ export function useClipboard(options?: UseClipboardOptions): {
                   return;
                 }
 
-                const file = new File([imageBlob], fileName, { 
-                  type: imageBlob.type || 'image/png'
+                // 检查本机同步过滤设置
+                const filterReason = await invoke<string | null>('check_sync_filters', {
+                  contentType: 'image',
+                  fileName,
+                  mimeType: imageBlob.type || 'image/png',
+                  size: imageBlob.size,
                 });
+                if (filterReason) {
+                  console.log('⏭️ 跳过图片上传:', filterReason);
+                  if (showNotification) {
+                    toast.warning(`跳过图片上传 - ${filterReason}`);
+                  }
+                  return;
+                }
 
                 const deviceId = await invoke<string>('get_device_id_command');
                 const deviceName = await invoke<string>('get_device_name_command');
 
-                console.log('⬆️ 开始上传图片:', fileName, file.size, 'bytes');
+                console.log('⬆️ 开始上传图片:', fileName, imageBlob.size, 'bytes');
 
-                // 上传图片文件
-                const uploadResponse = await fileApi.upload(file, deviceId);
+                // 由 Rust 端上传（移除元数据、压缩、直传和受管策略大小限制都在 Rust 端处理）
+                const uploaded = await invoke<FileUploadResponse['data']>(
+                  'upload_clipboard_image',
+                  new Uint8Array(await imageBlob.arrayBuffer()),
+                  { headers: { 'file-name': encodeURIComponent(fileName) } }
+                );
                 
                 // 通过 WebSocket 同步到其他设备
                 if (syncClipboard) {
-                  const success = syncClipboard({
-                    content: uploadResponse.data.file_url,
-                    content_type: uploadResponse.data.content_type,
+                  const success = await syncSigned({
+                    content: uploaded.file_url,
+                    content_type: uploaded.content_type,
                     device_id: deviceId,
                     device_name: deviceName,
-                    file_name: uploadResponse.data.file_name,
-                    file_size: uploadResponse.data.file_size,
-                    mime_type: uploadResponse.data.mime_type,
+                    file_name: uploaded.file_name,
+                    file_size: uploaded.file_size,
+                    mime_type: uploaded.mime_type,
+                    file_hash: uploaded.file_hash,
                   });
 
                   if (success && showNotification) {
@@ -424,14 +459,23 @@ export function useClipboard(options?: UseClipboardOptions): {
               if (autoUpload) {
                 const deviceId = await invoke<string>('get_device_id_command');
                 const deviceName = await invoke<string>('get_device_name_command');
+                const captured = await processTextCapture(result);
+                if (captured.skipped) {
+                  console.log('⏭️ 跳过同步:', captured.skipped);
+                  if (captured.skipped === 'sensitive' && showNotification) {
+                    toast.warning('未同步：内容疑似敏感信息');
+                  }
+                  return;
+                }
 
                 // 通过 WebSocket 同步 HTML 内容
                 if (syncClipboard) {
-                  const success = syncClipboard({
-                    content: htmlContent,
+                  const success = await syncSigned({
+                    content: captured.content,
                     content_type: 'text',
                     device_id: deviceId,
                     device_name: deviceName,
+                    representations: captured.representations,
                   });
 
                   if (success && showNotification) {
@@ -452,14 +496,23 @@ export function useClipboard(options?: UseClipboardOptions): {
               if (autoUpload) {
                 const deviceId = await invoke<string>('get_device_id_command');
                 const deviceName = await invoke<string>('get_device_name_command');
+                const captured = await processTextCapture(result);
+                if (captured.skipped) {
+                  console.log('⏭️ 跳过同步:', captured.skipped);
+                  if (captured.skipped === 'sensitive' && showNotification) {
+                    toast.warning('未同步：内容疑似敏感信息');
+                  }
+                  return;
+                }
 
                 // 通过 WebSocket 同步 RTF 内容
                 if (syncClipboard) {
-                  const success = syncClipboard({
-                    content: rtfContent,
+                  const success = await syncSigned({
+                    content: captured.content,
                     content_type: 'text',
                     device_id: deviceId,
                     device_name: deviceName,
+                    representations: captured.representations,
                   });
 
                   if (success && showNotification) {
@@ -480,14 +533,23 @@ export function useClipboard(options?: UseClipboardOptions): {
               if (autoUpload) {
                 const deviceId = await invoke<string>('get_device_id_command');
                 const deviceName = await invoke<string>('get_device_name_command');
+                const captured = await processTextCapture(result);
+                if (captured.skipped) {
+                  console.log('⏭️ 跳过同步:', captured.skipped);
+                  if (captured.skipped === 'sensitive' && showNotification) {
+                    toast.warning('未同步：内容疑似敏感信息');
+                  }
+                  return;
+                }
 
                 // 通过 WebSocket 同步文本
                 if (syncClipboard) {
-                  const success = syncClipboard({
-                    content: textContent,
+                  const success = await syncSigned({
+                    content: captured.content,
                     content_type: 'text',
                     device_id: deviceId,
                     device_name: deviceName,
+                    representations: captured.representations,
                   });
 
                   if (success && showNotification) {
@@ -553,7 +615,7 @@ export function useClipboard(options?: UseClipboardOptions): {
   const writeToClipboard = async (
     content: string,
     contentType?: 'text' | 'image' | 'file',
-    metadata?: { fileName?: string; mimeType?: string }
+    metadata?: { fileName?: string; mimeType?: string; localPath?: string }
   ) => {
     try {
       // 设置标志，表明下一次剪贴板变化来自同步
@@ -563,108 +625,114 @@ export function useClipboard(options?: UseClipboardOptions): {
         // 处理图片类型
         console.log('🖼️ 写入图片到剪贴板:', content.substring(0, 50));
 
-        // 构建完整 URL
-        const imageUrl = buildFileUrl(content);
-        console.log('📡 完整图片 URL:', imageUrl);
-
-        // 下载图片到临时文件（带认证 token）
-        const response = await fetch(imageUrl, {
-          headers: {
-            'Authorization': `Bearer ${token}`,
-          },
-        });
+        let tempFilePath: string;
+        if (metadata?.localPath) {
+          // WebDAV 等后端的图片已由 Rust 下载到缓存（见 cache_item_file）
+          tempFilePath = metadata.localPath;
+        } else {
+          // 构建完整 URL
+          const imageUrl = buildFileUrl(content);
+          console.log('📡 完整图片 URL:', imageUrl);
+
+          // 下载图片到临时文件（带认证 token）
+          const response = await fetch(imageUrl, {
+            headers: {
+              'Authorization': `Bearer ${token}`,
+            },
+          });
 
-        console.log('📡 响应状态:', response.status, response.statusText);
-        console.log('📋 响应 Content-Type:', response.headers.get('content-type'));
+          console.log('📡 响应状态:', response.status, response.statusText);
+          console.log('📋 响应 Content-Type:', response.headers.get('content-type'));
 
-        if (!response.ok) {
-          throw new Error(`下载图片失败: ${response.status} ${response.statusText}`);
-        }
+          if (!response.ok) {
+            throw new Error(`下载图片失败: ${response.status} ${response.statusText}`);
+          }
 
-        const blob = await response.blob();
-        console.log('📦 Blob 类型:', blob.type, '大小:', blob.size, 'bytes');
+          const blob = await response.blob();
+          console.log('📦 Blob 类型:', blob.type, '大小:', blob.size, 'bytes');
 
-        const arrayBuffer = await blob.arrayBuffer();
-        const uint8Array = new Uint8Array(arrayBuffer);
+          const arrayBuffer = await blob.arrayBuffer();
+          const uint8Array = new Uint8Array(arrayBuffer);
 
-        // 验证数据不为空
-        if (uint8Array.length === 0) {
-          throw new Error('下载的图片数据为空');
-        }
+          // 验证数据不为空
+          if (uint8Array.length === 0) {
+            throw new Error('下载的图片数据为空');
+          }
 
-        // 检查是否是 HTML 或 JSON 错误响应
-        const contentType = response.headers.get('content-type') || '';
-        if (contentType.includes('text/html') || contentType.includes('application/json')) {
-          const textData = new TextDecoder().decode(uint8Array.slice(0, 200));
-          console.error('❌ 下载的不是图片数据:', textData);
-          throw new Error(`服务器返回了非图片数据: ${contentType}`);
-        }
+          // 检查是否是 HTML 或 JSON 错误响应
+          const contentType = response.headers.get('content-type') || '';
+          if (contentType.includes('text/html') || contentType.includes('application/json')) {
+            const textData = new TextDecoder().decode(uint8Array.slice(0, 200));
+            console.error('❌ 下载的不是图片数据:', textData);
+            throw new Error(`服务器返回了非图片数据: ${contentType}`);
+          }
 
-        // 检查图片文件签名
-        const signature = Array.from(uint8Array.slice(0, 8))
-          .map(b => b.toString(16).padStart(2, '0'))
-          .join(' ');
-        console.log('📸 图片文件头签名:', signature);
-
-        // PNG 签名: 89 50 4E 47 0D 0A 1A 0A
-        // JPEG 签名: FF D8 FF
-        // GIF 签名: 47 49 46 38
-        const isPNG = uint8Array[0] === 0x89 && uint8Array[1] === 0x50 && uint8Array[2] === 0x4E && uint8Array[3] === 0x47;
-        const isJPEG = uint8Array[0] === 0xFF && uint8Array[1] === 0xD8 && uint8Array[2] === 0xFF;
-        const isGIF = uint8Array[0] === 0x47 && uint8Array[1] === 0x49 && uint8Array[2] === 0x46;
-
-        console.log('🔍 图片格式检测:', { isPNG, isJPEG, isGIF });
-
-        // 生成临时文件名和路径
-        // 优先使用原始文件名，否则根据实际文件签名确定扩展名
-        let fileName = metadata?.fileName;
-        if (!fileName) {
-          // 根据实际文件签名确定扩展名
-          let ext = 'png';
-          if (isJPEG) {
-            ext = 'jpg';
-          } else if (isGIF) {
-            ext = 'gif';
-          } else if (isPNG) {
-            ext = 'png';
-          } else {
-            // 如果无法识别签名，尝试使用 mimeType
-            const mimeType = metadata?.mimeType || blob.type || 'image/png';
-            console.log('⚠️ 无法识别文件签名，使用 MIME 类型:', mimeType);
-            if (mimeType.includes('jpeg') || mimeType.includes('jpg')) {
+          // 检查图片文件签名
+          const signature = Array.from(uint8Array.slice(0, 8))
+            .map(b => b.toString(16).padStart(2, '0'))
+            .join(' ');
+          console.log('📸 图片文件头签名:', signature);
+
+          // PNG 签名: 89 50 4E 47 0D 0A 1A 0A
+          // JPEG 签名: FF D8 FF
+          // GIF 签名: 47 49 46 38
+          const isPNG = uint8Array[0] === 0x89 && uint8Array[1] === 0x50 && uint8Array[2] === 0x4E && uint8Array[3] === 0x47;
+          const isJPEG = uint8Array[0] === 0xFF && uint8Array[1] === 0xD8 && uint8Array[2] === 0xFF;
+          const isGIF = uint8Array[0] === 0x47 && uint8Array[1] === 0x49 && uint8Array[2] === 0x46;
+
+          console.log('🔍 图片格式检测:', { isPNG, isJPEG, isGIF });
+
+          // 生成临时文件名和路径
+          // 优先使用原始文件名，否则根据实际文件签名确定扩展名
+          let fileName = metadata?.fileName;
+          if (!fileName) {
+            // 根据实际文件签名确定扩展名
+            let ext = 'png';
+            if (isJPEG) {
               ext = 'jpg';
-            } else if (mimeType.includes('gif')) {
+            } else if (isGIF) {
               ext = 'gif';
-            } else if (mimeType.includes('webp')) {
-              ext = 'webp';
-            } else if (mimeType.includes('bmp')) {
-              ext = 'bmp';
+            } else if (isPNG) {
+              ext = 'png';
+            } else {
+              // 如果无法识别签名，尝试使用 mimeType
+              const mimeType = metadata?.mimeType || blob.type || 'image/png';
+              console.log('⚠️ 无法识别文件签名，使用 MIME 类型:', mimeType);
+              if (mimeType.includes('jpeg') || mimeType.includes('jpg')) {
+                ext = 'jpg';
+              } else if (mimeType.includes('gif')) {
+                ext = 'gif';
+              } else if (mimeType.includes('webp')) {
+                ext = 'webp';
+              } else if (mimeType.includes('bmp')) {
+                ext = 'bmp';
+              }
             }
+            fileName = `clipboard_image_${Date.now()}.${ext}`;
           }
-          fileName = `clipboard_image_${Date.now()}.${ext}`;
-        }
 
-        const tempDir = await cacheDir();
-        const clipboardCacheDir = `${tempDir}/cloudpaste`;
-        const tempFilePath = `${clipboardCacheDir}/${fileName}`;
+          const tempDir = await appCacheDir();
+          const clipboardCacheDir = `${tempDir}/cloudpaste`;
+          tempFilePath = `${clipboardCacheDir}/${fileName}`;
 
-        console.log('💾 图片数据大小:', uint8Array.length, 'bytes');
-        console.log('📝 保存到:', tempFilePath);
-        console.log('📋 MIME 类型:', metadata?.mimeType || blob.type);
-        console.log('📄 文件名:', fileName);
+          console.log('💾 图片数据大小:', uint8Array.length, 'bytes');
+          console.log('📝 保存到:', tempFilePath);
+          console.log('📋 MIME 类型:', metadata?.mimeType || blob.type);
+          console.log('📄 文件名:', fileName);
 
-        // 确保目录存在
-        const { writeFile, mkdir } = await import('@tauri-apps/plugin-fs');
-        try {
-          await mkdir(clipboardCacheDir, { recursive: true });
-          console.log('📁 确保目录存在:', clipboardCacheDir);
-        } catch (error) {
-          // 目录已存在，忽略错误
-          console.log('📁 目录已存在:', clipboardCacheDir);
-        }
+          // 确保目录存在
+          const { writeFile, mkdir } = await import('@tauri-apps/plugin-fs');
+          try {
+            await mkdir(clipboardCacheDir, { recursive: true });
+            console.log('📁 确保目录存在:', clipboardCacheDir);
+          } catch (error) {
+            // 目录已存在，忽略错误
+            console.log('📁 目录已存在:', clipboardCacheDir);
+          }
 
-        // 保存图片到临时文件
-        await writeFile(tempFilePath, uint8Array);
+          // 保存图片到临时文件
+          await writeFile(tempFilePath, uint8Array);
+        }
 
         // 锁定处理标志，防止剪贴板监听器触发上传
         // 在写入剪贴板期间和之后的2秒内，阻止任何自动上传
@@ -689,45 +757,51 @@ export function useClipboard(options?: UseClipboardOptions): {
         // 处理文件类型
         console.log('📁 写入文件到剪贴板:', metadata?.fileName || content.substring(0, 50));
         
-        // 构建完整 URL
-        const fileUrl = buildFileUrl(content);
-        console.log('📡 完整文件 URL:', fileUrl);
+        let tempFilePath: string;
+        if (metadata?.localPath) {
+          // WebDAV 等后端的文件已由 Rust 下载到缓存（见 cache_item_file）
+          tempFilePath = metadata.localPath;
+        } else {
+          // 构建完整 URL
+          const fileUrl = buildFileUrl(content);
+          console.log('📡 完整文件 URL:', fileUrl);
         
-        // 下载文件到临时目录（带认证 token）
-        const response = await fetch(fileUrl, {
-          headers: {
-            'Authorization': `Bearer ${token}`,
-          },
-        });
-        if (!response.ok) {
-          throw new Error(`下载文件失败: ${response.status}`);
-        }
+          // 下载文件到临时目录（带认证 token）
+          const response = await fetch(fileUrl, {
+            headers: {
+              'Authorization': `Bearer ${token}`,
+            },
+          });
+          if (!response.ok) {
+            throw new Error(`下载文件失败: ${response.status}`);
+          }
         
-        const blob = await response.blob();
-        const arrayBuffer = await blob.arrayBuffer();
-        const uint8Array = new Uint8Array(arrayBuffer);
+          const blob = await response.blob();
+          const arrayBuffer = await blob.arrayBuffer();
+          const uint8Array = new Uint8Array(arrayBuffer);
         
-        // 生成临时文件名和路径
-        const fileName = metadata?.fileName || `clipboard_file_${Date.now()}`;
-        const tempDir = await cacheDir();
-        const clipboardCacheDir = `${tempDir}/cloudpaste`;
-        const tempFilePath = `${clipboardCacheDir}/${fileName}`;
+          // 生成临时文件名和路径
+          const fileName = metadata?.fileName || `clipboard_file_${Date.now()}`;
+          const tempDir = await appCacheDir();
+          const clipboardCacheDir = `${tempDir}/cloudpaste`;
+          tempFilePath = `${clipboardCacheDir}/${fileName}`;
         
-        console.log('💾 文件数据大小:', uint8Array.length, 'bytes');
-        console.log('📝 保存到:', tempFilePath);
+          console.log('💾 文件数据大小:', uint8Array.length, 'bytes');
+          console.log('📝 保存到:', tempFilePath);
         
-        // 确保目录存在
-        const { writeFile, mkdir } = await import('@tauri-apps/plugin-fs');
-        try {
-          await mkdir(clipboardCacheDir, { recursive: true });
-          console.log('📁 确保目录存在:', clipboardCacheDir);
-        } catch (error) {
-          // 目录已存在，忽略错误
-          console.log('📁 目录已存在:', clipboardCacheDir);
-        }
+          // 确保目录存在
+          const { writeFile, mkdir } = await import('@tauri-apps/plugin-fs');
+          try {
+            await mkdir(clipboardCacheDir, { recursive: true });
+            console.log('📁 确保目录存在:', clipboardCacheDir);
+          } catch (error) {
+            // 目录已存在，忽略错误
+            console.log('📁 目录已存在:', clipboardCacheDir);
+          }
         
-        // 保存文件到临时目录
-        await writeFile(tempFilePath, uint8Array);
+          // 保存文件到临时目录
+          await writeFile(tempFilePath, uint8Array);
+        }
 
         // 锁定处理标志，防止剪贴板监听器触发上传
         processingRef.current = true;
@@ -755,7 +829,13 @@ export function useClipboard(options?: UseClipboardOptions): {
         processingRef.current = true;
         console.log('🔒 锁定剪贴板处理（同步写入）');
 
-        await writeText(content);
+        // 由 Rust 端写入，采集时带有 HTML / RTF 格式的同时恢复
+        try {
+          await invoke<boolean>('copy_text_item', { content });
+        } catch (err) {
+          console.warn('恢复富文本格式失败，只写入纯文本:', err);
+          await writeText(content);
+        }
         console.log('✅ 文本已写入剪贴板');
 
         // 延迟解锁
@@ -0,0 +1,40 @@
+/**
+ * 全局快捷键设置
+ * 快捷键由 Rust 端统一注册、保存和执行，前端只提交 {action, accelerator} 绑定
+ */
+import { invoke } from '@tauri-apps/api/core';
+
+export type HotkeyAction =
+  | { type: 'show_palette' }
+  | { type: 'paste_last_item' }
+  | { type: 'paste_plain_text' }
+  | { type: 'paste_next' }
+  | { type: 'capture_screenshot' }
+  | { type: 'paste_snippet'; id: string };
+
+export interface HotkeyBinding {
+  action: HotkeyAction;
+  /** 如 `CommandOrControl+Alt+V` */
+  accelerator: string;
+}
+
+export interface HotkeyStatus extends HotkeyBinding {
+  registered: boolean;
+  /** 注册失败的原因（通常是快捷键已被其他应用占用） */
+  error: string | null;
+}
+
+/**
+ * 获取快捷键绑定及注册状态
+ */
+export async function getHotkeys(): Promise<HotkeyStatus[]> {
+  return invoke<HotkeyStatus[]>('get_hotkeys');
+}
+
+/**
+ * 替换全部快捷键绑定
+ * 格式无效或多个操作使用同一快捷键时抛出错误；被其他应用占用的快捷键在返回状态中标记为未注册
+ */
+export async function setHotkeys(bindings: HotkeyBinding[]): Promise<HotkeyStatus[]> {
+  return invoke<HotkeyStatus[]>('set_hotkeys', { bindings });
+}
@@ -1,7 +1,6 @@
 /**
  * Tauri 文件操作工具函数
  */
-import { save } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
 import { getApiBaseUrl } from '@cloudpaste/shared/lib/apiConfig';
 
@@ -130,9 +129,9 @@ export async function downloadFileNative(fileUrl: string, fileName?: string): Pr
     // 根据文件类型获取过滤器
     const filters = getFileFilters(suggestedFileName);
 
-    // 打开系统保存对话框
-    const filePath = await save({
-      defaultPath: suggestedFileName,
+    // 打开系统保存对话框（由 Rust 端打开，选择的路径才允许写入）
+    const filePath = await invoke<string | null>('pick_save_path', {
+      defaultName: suggestedFileName,
       filters: filters,
     });
 
@@ -141,39 +140,18 @@ export async function downloadFileNative(fileUrl: string, fileName?: string): Pr
       return;
     }
 
-    let fileData: Uint8Array;
-
-    // 尝试从缓存获取文件
+    // 优先由 Rust 端从缓存直接复制到目标位置（未缓存时先下载），不经过前端传输文件内容
     try {
-      console.log('📦 尝试从缓存获取文件...');
-      const cachedPath = await invoke<string>('get_cached_file_path', {
+      await invoke<number>('export_cached_file', {
         url: apiUrl,
+        destPath: filePath,
       });
-
-      // 如果返回的是文件系统路径，说明缓存命中或已下载
-      if (cachedPath.startsWith('/') || cachedPath.includes(':\\')) {
-        console.log('✅ 从缓存读取:', cachedPath);
-        // 从缓存文件读取数据
-        const cachedData = await invoke<number[]>('read_file_bytes', {
-          filePath: cachedPath,
-        });
-        fileData = new Uint8Array(cachedData);
-      } else {
-        // 缓存失败，从网络下载
-        console.log('📥 缓存失败，从网络下载...');
-        fileData = await downloadFromNetwork(apiUrl);
-      }
     } catch (err) {
-      console.warn('从缓存读取失败，从网络下载:', err);
-      fileData = await downloadFromNetwork(apiUrl);
+      console.warn('从缓存导出失败，从网络下载:', err);
+      const fileData = await downloadFromNetwork(apiUrl);
+      await saveFileChunked(filePath, fileData);
     }
 
-    // 保存到用户选择的位置
-    await invoke('save_file_to_path', {
-      filePath: filePath,
-      data: Array.from(fileData),
-    });
-
     console.log('✅ 文件已保存到:', filePath);
   } catch (error) {
     console.error('下载文件失败:', error);
@@ -181,6 +159,91 @@ export async function downloadFileNative(fileUrl: string, fileName?: string): Pr
   }
 }
 
+/**
+ * 分块读取本地文件，避免大文件一次性经 IPC 传输
+ */
+export async function readFileChunked(filePath: string): Promise<Uint8Array> {
+  const CHUNK_SIZE = 4 * 1024 * 1024;
+  const size = await invoke<number>('get_file_size', { filePath });
+  const data = new Uint8Array(size);
+
+  let offset = 0;
+  while (offset < size) {
+    const chunk = await invoke<ArrayBuffer>('read_file_chunk', {
+      filePath,
+      offset,
+      len: CHUNK_SIZE,
+    });
+    if (chunk.byteLength === 0) break;
+    data.set(new Uint8Array(chunk), offset);
+    offset += chunk.byteLength;
+  }
+
+  return data.subarray(0, offset);
+}
+
+/**
+ * 分块写入本地文件，避免把整个文件转为数组经 IPC 传输
+ */
+export async function saveFileChunked(filePath: string, data: Uint8Array): Promise<void> {
+  const CHUNK_SIZE = 4 * 1024 * 1024;
+  const handle = await invoke<number>('open_file_writer', { filePath });
+
+  try {
+    for (let offset = 0; offset < data.length; offset += CHUNK_SIZE) {
+      await invoke('write_file_chunk', data.slice(offset, offset + CHUNK_SIZE), {
+        headers: { 'writer-handle': String(handle) },
+      });
+    }
+  } finally {
+    await invoke('close_file_writer', { handle });
+  }
+}
+
+/**
+ * 批量缓存一组文件（如历史网格当前页），返回 URL 到本地路径的映射
+ * 下载失败的 URL 不在结果中，进度通过 `cache-warm-progress` 事件获取
+ */
+export async function warmCache(urls: string[]): Promise<Map<string, string>> {
+  const results = await invoke<Array<{ url: string; path: string | null }>>('warm_cache', {
+    urls,
+  });
+  const paths = new Map<string, string>();
+  for (const { url, path } of results) {
+    if (path) paths.set(url, path);
+  }
+  return paths;
+}
+
+/**
+ * 选择本地文件并由 Rust 端直接上传（大文件从磁盘流式上传，不经过前端）
+ * 用户取消选择时返回 null，进度通过 `upload-progress` 事件获取
+ */
+export async function pickAndUploadFile<T = unknown>(): Promise<T | null> {
+  const path = await invoke<string | null>('pick_open_path', {});
+  if (!path) {
+    return null;
+  }
+  return invoke<T>('upload_file_from_path', { path });
+}
+
+/**
+ * 选择本地目录（之后可用作监听目录或本地存储目录），用户取消选择时返回 null
+ */
+export async function pickFolder(): Promise<string | null> {
+  return invoke<string | null>('pick_folder_path');
+}
+
+/** 写入剪贴板时可选的格式 */
+export type ClipboardWriteFormat = 'text' | 'html' | 'image' | 'files';
+
+/**
+ * 按指定格式将历史记录写入剪贴板（图片和文件记录由 Rust 端先下载到本地缓存）
+ */
+export async function writeClipboard(itemId: number, format: ClipboardWriteFormat): Promise<void> {
+  await invoke('write_clipboard', { itemId, format });
+}
+
 /**
  * 从网络下载文件
  */
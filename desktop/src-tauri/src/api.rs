@@ -0,0 +1,264 @@
+use crate::error::CloudPasteError;
+use crate::upload::UploadedFile;
+use crate::{api_v2, http};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// 服务器上的剪贴板项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardItem {
+    pub id: i64,
+    pub content: String,
+    pub content_type: String,
+    pub device_id: Option<String>,
+    pub device_name: Option<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    pub tags: Option<String>,
+    pub file_name: Option<String>,
+    pub file_size: Option<u64>,
+    pub mime_type: Option<String>,
+    pub updated_at: String,
+    #[serde(default)]
+    pub synced: bool,
+    /// 同一内容的多种表示格式，粘贴时由目标应用选择（旧服务器不返回）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub representations: Vec<Representation>,
+    /// 发送设备对内容的 ed25519 签名（Base64），旧版本客户端不签名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// 发送设备的签名公钥（Base64）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// 图片和文件内容的 SHA-256（十六进制），包含在签名中
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_hash: Option<String>,
+}
+
+/// 剪贴板项的一种表示格式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Representation {
+    /// 格式：text、html、rtf、image
+    pub format: String,
+    /// 该格式的内容（图片为服务器上的文件 ID）
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl Representation {
+    pub fn new(format: &str, content: String) -> Self {
+        Self {
+            format: format.to_string(),
+            content,
+            mime_type: None,
+        }
+    }
+}
+
+/// 按格式查找表示内容
+pub fn representation<'a>(representations: &'a [Representation], format: &str) -> Option<&'a str> {
+    representations
+        .iter()
+        .find(|r| r.format == format)
+        .map(|r| r.content.as_str())
+}
+
+/// 剪贴板列表分页结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipboardPage {
+    pub total: u64,
+    pub items: Vec<ClipboardItem>,
+}
+
+/// 增量同步结果：游标之后更新的项和删除的项
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClipboardChanges {
+    pub items: Vec<ClipboardItem>,
+    /// 服务器上已删除的剪贴板项 ID
+    pub deleted_ids: Vec<i64>,
+    /// 下次请求使用的游标
+    pub next_cursor: String,
+    /// 是否还有更多变更
+    pub has_more: bool,
+}
+
+/// 更新剪贴板项的请求体（只发送有值的字段）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClipboardItemUpdate {
+    /// 内容
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// 标签（逗号分隔）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+}
+
+/// 新建剪贴板项的请求体
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewClipboardItem {
+    pub content: String,
+    pub content_type: String,
+    pub device_id: Option<String>,
+    pub device_name: Option<String>,
+    pub favorite: bool,
+    pub tags: Option<String>,
+    pub file_name: Option<String>,
+    pub file_size: Option<u64>,
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub representations: Vec<Representation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_hash: Option<String>,
+}
+
+impl NewClipboardItem {
+    /// 以当前设备身份创建指定类型的剪贴板项
+    pub fn from_this_device(content: String, content_type: &str) -> Result<Self, CloudPasteError> {
+        let config = crate::GLOBAL_API_CONFIG
+            .lock()
+            .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?;
+
+        Ok(Self {
+            content,
+            content_type: content_type.to_string(),
+            device_id: Some(config.device_id.clone()),
+            device_name: Some(config.device_name.clone()),
+            ..Default::default()
+        })
+    }
+
+    /// 以当前设备身份为已上传的文件创建剪贴板项
+    pub fn from_upload(uploaded: &UploadedFile) -> Result<Self, CloudPasteError> {
+        let mut item = Self::from_this_device(uploaded.file_url.clone(), &uploaded.content_type)?;
+        item.file_name = Some(uploaded.file_name.clone());
+        item.file_size = Some(uploaded.file_size);
+        item.mime_type = Some(uploaded.mime_type.clone());
+        item.file_hash = uploaded.file_hash.clone();
+        Ok(item)
+    }
+}
+
+impl From<ClipboardItem> for NewClipboardItem {
+    fn from(item: ClipboardItem) -> Self {
+        Self {
+            content: item.content,
+            content_type: item.content_type,
+            device_id: item.device_id,
+            device_name: item.device_name,
+            favorite: item.favorite,
+            tags: item.tags,
+            file_name: item.file_name,
+            file_size: item.file_size,
+            mime_type: item.mime_type,
+            representations: item.representations,
+            signature: item.signature,
+            public_key: item.public_key,
+            file_hash: item.file_hash,
+        }
+    }
+}
+
+/// 在服务器上创建剪贴板项（服务器会广播给其他设备），发送前用本机密钥签名
+pub async fn create_clipboard_item(
+    item: &NewClipboardItem,
+) -> Result<ClipboardItem, CloudPasteError> {
+    let mut item = item.clone();
+    crate::device_trust::sign_item(&mut item);
+    if api_v2::active() {
+        return api_v2::create_clipboard_item(&item).await;
+    }
+    let request = http::api_request(Method::POST, "/clipboard/")?.json(&item);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("创建剪贴板项失败", e))?;
+
+    http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析剪贴板项失败: {}", e)))
+}
+
+/// 从服务器获取单个剪贴板项
+pub async fn get_clipboard_item(id: i64) -> Result<ClipboardItem, CloudPasteError> {
+    if api_v2::active() {
+        return api_v2::get_clipboard_item(id).await;
+    }
+    let request = http::api_request(Method::GET, &format!("/clipboard/{}", id))?;
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("获取剪贴板项失败", e))?;
+
+    http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析剪贴板项失败: {}", e)))
+}
+
+/// 从服务器分页获取剪贴板列表（按更新时间倒序，v1 页码分页；v2 使用 `api_v2::list_clipboard_items`）
+pub async fn list_clipboard_items(
+    page: u32,
+    page_size: u32,
+) -> Result<ClipboardPage, CloudPasteError> {
+    let request = http::api_request(Method::GET, "/clipboard/")?
+        .query(&[("page", page), ("page_size", page_size)]);
+
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("获取剪贴板列表失败", e))?;
+
+    http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析剪贴板列表失败: {}", e)))
+}
+
+/// 获取游标之后的增量变更（按更新时间和 ID 升序），`cursor` 为空时从头开始
+pub async fn list_clipboard_changes(
+    cursor: Option<&str>,
+    limit: u32,
+) -> Result<ClipboardChanges, CloudPasteError> {
+    let mut request =
+        http::api_request(Method::GET, "/clipboard/changes")?.query(&[("limit", limit)]);
+    if let Some(cursor) = cursor {
+        request = request.query(&[("cursor", cursor)]);
+    }
+
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("获取增量变更失败", e))?;
+
+    http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析增量变更失败: {}", e)))
+}
+
+/// 更新服务器上的剪贴板项
+pub async fn update_clipboard_item(
+    id: i64,
+    update: &ClipboardItemUpdate,
+) -> Result<ClipboardItem, CloudPasteError> {
+    if api_v2::active() {
+        return api_v2::update_clipboard_item(id, update).await;
+    }
+    let request = http::api_request(Method::PUT, &format!("/clipboard/{}", id))?.json(update);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("更新剪贴板项失败", e))?;
+
+    http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析剪贴板项失败: {}", e)))
+}
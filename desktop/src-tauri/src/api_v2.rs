@@ -0,0 +1,135 @@
+use crate::api::{ClipboardItem, ClipboardItemUpdate, NewClipboardItem};
+use crate::error::CloudPasteError;
+use crate::{capabilities, http, store};
+use once_cell::sync::Lazy;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "api_v2.json";
+
+/// 协商时服务器声明的 API 版本号
+pub const VERSION: &str = "v2";
+
+/// API v2 设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiV2Settings {
+    /// 服务器支持时自动使用 v2；关闭后始终使用 v1
+    pub enabled: bool,
+}
+
+impl Default for ApiV2Settings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<ApiV2Settings>> = Lazy::new(|| Mutex::new(ApiV2Settings::default()));
+
+/// 游标分页结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct CursorPage {
+    pub items: Vec<ClipboardItem>,
+    /// 下一页的游标，没有更多数据时为空
+    pub next_cursor: Option<String>,
+    /// 符合条件的记录总数（服务器可以不返回）
+    pub total: Option<u64>,
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<ApiV2Settings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> ApiV2Settings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 当前是否使用 v2：设置中启用且服务器在能力协商中声明支持
+pub fn active() -> bool {
+    settings().enabled && capabilities::supports_api(VERSION)
+}
+
+async fn parse<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    action: &str,
+) -> Result<T, CloudPasteError> {
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request(&format!("{}失败", action), e))?;
+
+    http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析{}结果失败: {}", action, e)))
+}
+
+/// 在服务器上创建剪贴板项（调用方负责签名）
+pub async fn create_clipboard_item(
+    item: &NewClipboardItem,
+) -> Result<ClipboardItem, CloudPasteError> {
+    let request = http::versioned_request(VERSION, Method::POST, "/clipboard/items")?.json(item);
+    parse(request, "创建剪贴板项").await
+}
+
+/// 获取单个剪贴板项
+pub async fn get_clipboard_item(id: i64) -> Result<ClipboardItem, CloudPasteError> {
+    let request =
+        http::versioned_request(VERSION, Method::GET, &format!("/clipboard/items/{}", id))?;
+    parse(request, "获取剪贴板项").await
+}
+
+/// 部分更新剪贴板项（只发送有值的字段）
+pub async fn update_clipboard_item(
+    id: i64,
+    update: &ClipboardItemUpdate,
+) -> Result<ClipboardItem, CloudPasteError> {
+    let request =
+        http::versioned_request(VERSION, Method::PATCH, &format!("/clipboard/items/{}", id))?
+            .json(update);
+    parse(request, "更新剪贴板项").await
+}
+
+/// 按游标获取剪贴板列表（按更新时间倒序）
+///
+/// `cursor` 为上一页返回的 `next_cursor`，首页传 None
+pub async fn list_clipboard_items(
+    cursor: Option<&str>,
+    limit: u32,
+) -> Result<CursorPage, CloudPasteError> {
+    let mut request = http::versioned_request(VERSION, Method::GET, "/clipboard/items")?
+        .query(&[("limit", limit)]);
+    if let Some(cursor) = cursor {
+        request = request.query(&[("cursor", cursor)]);
+    }
+    parse(request, "获取剪贴板列表").await
+}
+
+/// Tauri 命令：获取 API v2 设置
+#[tauri::command]
+pub fn get_api_v2_settings() -> ApiV2Settings {
+    settings()
+}
+
+/// Tauri 命令：更新 API v2 设置
+#[tauri::command]
+pub fn set_api_v2_settings(app: AppHandle, settings: ApiV2Settings) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
+
+/// Tauri 命令：当前使用的 API 版本
+#[tauri::command]
+pub fn get_active_api_version() -> String {
+    if active() { VERSION } else { "v1" }.to_string()
+}
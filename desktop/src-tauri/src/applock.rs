@@ -0,0 +1,284 @@
+use crate::error::CloudPasteError;
+use crate::events::{self, AppLocked};
+use crate::{idle, store};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "app_lock.json";
+
+/// 检查空闲和系统唤醒的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 两次检查之间的实际间隔超过该值时视为系统刚从睡眠中唤醒
+const RESUME_GAP: Duration = Duration::from_secs(60);
+
+/// PIN 最短长度
+const MIN_PIN_LENGTH: usize = 4;
+
+/// PIN 错误后的等待时间，减缓暴力尝试
+const FAILED_UNLOCK_DELAY: Duration = Duration::from_secs(1);
+
+/// 连续输错超过该次数后开始锁定
+const FREE_ATTEMPTS: u32 = 3;
+
+/// 首次锁定时长，之后每多错一次翻倍
+const LOCKOUT_BASE: Duration = Duration::from_secs(30);
+
+/// 锁定时长上限
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+/// 应用锁设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppLockSettings {
+    /// PIN 的 Argon2 哈希（PHC 格式），为空表示未启用应用锁
+    pin_hash: Option<String>,
+    /// 空闲多少分钟后自动锁定，0 表示不因空闲锁定
+    pub idle_minutes: u32,
+    /// 系统从睡眠中唤醒后是否锁定
+    pub lock_on_resume: bool,
+}
+
+impl Default for AppLockSettings {
+    fn default() -> Self {
+        Self {
+            pin_hash: None,
+            idle_minutes: 5,
+            lock_on_resume: true,
+        }
+    }
+}
+
+/// 返回给前端的应用锁状态（不含 PIN 哈希）
+#[derive(Debug, Clone, Serialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub idle_minutes: u32,
+    pub lock_on_resume: bool,
+}
+
+static SETTINGS: Lazy<Mutex<AppLockSettings>> =
+    Lazy::new(|| Mutex::new(AppLockSettings::default()));
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// 连续 PIN 错误的计数，同时用于串行化所有 PIN 校验
+#[derive(Default)]
+struct FailedAttempts {
+    count: u32,
+    locked_until: Option<Instant>,
+}
+
+static ATTEMPTS: Lazy<tokio::sync::Mutex<FailedAttempts>> =
+    Lazy::new(|| tokio::sync::Mutex::new(FailedAttempts::default()));
+
+/// 启动时从磁盘加载设置，已设置 PIN 时以锁定状态启动
+pub fn load(app: &AppHandle) {
+    let saved = store::load_json::<AppLockSettings>(app, SETTINGS_FILE).unwrap_or_default();
+    LOCKED.store(saved.pin_hash.is_some(), Ordering::SeqCst);
+    if let Ok(mut settings) = SETTINGS.lock() {
+        *settings = saved;
+    }
+}
+
+fn settings() -> AppLockSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 是否已设置 PIN
+pub fn is_enabled() -> bool {
+    settings().pin_hash.is_some()
+}
+
+/// 身份已通过其他方式（如生物识别）验证后解锁
+pub fn unlock_verified() {
+    idle::record_local_activity();
+    LOCKED.store(false, Ordering::SeqCst);
+    log::info!("🔓 应用已解锁");
+}
+
+fn save(app: &AppHandle, settings: AppLockSettings) -> Result<(), CloudPasteError> {
+    store::save_json(app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
+
+fn lock(reason: &'static str) {
+    if settings().pin_hash.is_none() || LOCKED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("🔒 应用已锁定: {}", reason);
+    events::emit(AppLocked { reason });
+}
+
+/// 空闲超时则锁定
+fn check_idle() {
+    let settings = settings();
+    if settings.pin_hash.is_some()
+        && settings.idle_minutes > 0
+        && idle::is_idle_for(u64::from(settings.idle_minutes) * 60)
+    {
+        lock("idle");
+    }
+}
+
+/// 历史和缓存命令调用前检查应用是否已解锁
+pub fn ensure_unlocked() -> Result<(), CloudPasteError> {
+    check_idle();
+    if LOCKED.load(Ordering::SeqCst) {
+        return Err(CloudPasteError::Locked);
+    }
+    Ok(())
+}
+
+fn hash_pin(pin: &str) -> Result<String, CloudPasteError> {
+    if pin.chars().count() < MIN_PIN_LENGTH {
+        return Err(CloudPasteError::InvalidInput(format!(
+            "PIN 至少需要 {} 位",
+            MIN_PIN_LENGTH
+        )));
+    }
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| CloudPasteError::Internal(format!("计算 PIN 哈希失败: {}", e)))
+}
+
+fn verify_pin(pin: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(pin.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// 第 `count` 次连续输错后的锁定时长
+fn lockout_duration(count: u32) -> Option<Duration> {
+    let exponent = count.checked_sub(FREE_ATTEMPTS)?;
+    let factor = 1u32.checked_shl(exponent.min(16)).unwrap_or(u32::MAX);
+    Some(LOCKOUT_BASE.saturating_mul(factor).min(MAX_LOCKOUT))
+}
+
+/// 校验当前 PIN，未设置 PIN 时直接通过
+///
+/// 所有校验持有同一把锁依次进行，连续输错超过 `FREE_ATTEMPTS` 次后按指数增长锁定
+async fn check_pin(pin: Option<&str>) -> Result<(), CloudPasteError> {
+    let Some(hash) = settings().pin_hash else {
+        return Ok(());
+    };
+    let mut attempts = ATTEMPTS.lock().await;
+    if let Some(until) = attempts.locked_until {
+        let remaining = until.saturating_duration_since(Instant::now());
+        if !remaining.is_zero() {
+            return Err(CloudPasteError::Unauthorized(format!(
+                "PIN 错误次数过多，请在 {} 秒后重试",
+                remaining.as_secs().max(1)
+            )));
+        }
+    }
+
+    let verified = match pin {
+        Some(pin) => {
+            let pin = pin.to_string();
+            tauri::async_runtime::spawn_blocking(move || verify_pin(&pin, &hash))
+                .await
+                .map_err(|e| CloudPasteError::Internal(format!("校验 PIN 失败: {}", e)))?
+        }
+        None => false,
+    };
+    if verified {
+        *attempts = FailedAttempts::default();
+        return Ok(());
+    }
+
+    attempts.count = attempts.count.saturating_add(1);
+    attempts.locked_until = lockout_duration(attempts.count).map(|d| Instant::now() + d);
+    log::warn!("🔒 PIN 校验失败（连续 {} 次）", attempts.count);
+    tokio::time::sleep(FAILED_UNLOCK_DELAY).await;
+    Err(CloudPasteError::Unauthorized("PIN 不正确".to_string()))
+}
+
+/// 后台任务：空闲超时或系统唤醒后锁定应用
+pub async fn run() {
+    let mut last_check = SystemTime::now();
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        // 睡眠期间任务不会运行，醒来后两次检查的间隔会远大于检查周期
+        let now = SystemTime::now();
+        let gap = now.duration_since(last_check).unwrap_or_default();
+        last_check = now;
+        if gap > CHECK_INTERVAL + RESUME_GAP && settings().lock_on_resume {
+            lock("resume");
+        }
+        check_idle();
+    }
+}
+
+/// Tauri 命令：获取应用锁状态
+#[tauri::command]
+pub fn get_app_lock_status() -> AppLockStatus {
+    let settings = settings();
+    AppLockStatus {
+        enabled: settings.pin_hash.is_some(),
+        locked: LOCKED.load(Ordering::SeqCst),
+        idle_minutes: settings.idle_minutes,
+        lock_on_resume: settings.lock_on_resume,
+    }
+}
+
+/// Tauri 命令：设置、修改或清除（`new_pin` 为空）应用锁 PIN，已设置 PIN 时需提供当前 PIN
+#[tauri::command]
+pub async fn set_app_lock_pin(
+    app: AppHandle,
+    current_pin: Option<String>,
+    new_pin: Option<String>,
+) -> Result<(), CloudPasteError> {
+    check_pin(current_pin.as_deref()).await?;
+
+    let mut settings = settings();
+    settings.pin_hash = new_pin.as_deref().map(hash_pin).transpose()?;
+    log::info!("🔒 应用锁: {}", settings.pin_hash.is_some());
+    save(&app, settings)?;
+    LOCKED.store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Tauri 命令：修改自动锁定设置（需处于解锁状态）
+#[tauri::command]
+pub fn set_app_lock_settings(
+    app: AppHandle,
+    idle_minutes: u32,
+    lock_on_resume: bool,
+) -> Result<(), CloudPasteError> {
+    ensure_unlocked()?;
+    let mut settings = settings();
+    settings.idle_minutes = idle_minutes;
+    settings.lock_on_resume = lock_on_resume;
+    save(&app, settings)
+}
+
+/// Tauri 命令：立即锁定应用
+#[tauri::command]
+pub fn lock_app() {
+    lock("manual");
+}
+
+/// Tauri 命令：输入 PIN 解锁应用
+#[tauri::command]
+pub async fn unlock(pin: String) -> Result<(), CloudPasteError> {
+    check_pin(Some(&pin)).await?;
+    unlock_verified();
+    Ok(())
+}
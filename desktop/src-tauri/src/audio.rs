@@ -0,0 +1,211 @@
+use crate::api::{ClipboardItem, NewClipboardItem};
+use crate::error::CloudPasteError;
+use crate::{storage, upload};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+use hound::{WavSpec, WavWriter};
+use once_cell::sync::Lazy;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// 单条语音备忘的最长录制时间，超时自动结束
+const MAX_DURATION: Duration = Duration::from_secs(5 * 60);
+
+type SharedWriter = Arc<Mutex<Option<WavWriter<BufWriter<File>>>>>;
+
+/// 正在进行的录音
+struct ActiveRecording {
+    stop: SyncSender<()>,
+    worker: JoinHandle<Result<PathBuf, String>>,
+}
+
+static RECORDING: Lazy<Mutex<Option<ActiveRecording>>> = Lazy::new(|| Mutex::new(None));
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    writer: SharedWriter,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    i16: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _| {
+                if let Ok(mut guard) = writer.lock() {
+                    if let Some(writer) = guard.as_mut() {
+                        for &sample in data {
+                            let _ = writer.write_sample(i16::from_sample(sample));
+                        }
+                    }
+                }
+            },
+            |e| log::error!("❌ 录音出错: {}", e),
+            None,
+        )
+        .map_err(|e| format!("打开麦克风失败: {}", e))
+}
+
+/// 打开默认麦克风并开始写入 WAV 文件
+fn open_stream(path: &Path) -> Result<(cpal::Stream, SharedWriter), String> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or_else(|| "未找到麦克风".to_string())?;
+    let config = device
+        .default_input_config()
+        .map_err(|e| format!("获取麦克风配置失败: {}", e))?;
+
+    let spec = WavSpec {
+        channels: config.channels(),
+        sample_rate: config.sample_rate(),
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let writer: SharedWriter = Arc::new(Mutex::new(Some(
+        WavWriter::create(path, spec).map_err(|e| format!("创建录音文件失败: {}", e))?,
+    )));
+
+    let stream_config = config.config();
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(&device, &stream_config, writer.clone()),
+        SampleFormat::I16 => build_stream::<i16>(&device, &stream_config, writer.clone()),
+        SampleFormat::U16 => build_stream::<u16>(&device, &stream_config, writer.clone()),
+        SampleFormat::I32 => build_stream::<i32>(&device, &stream_config, writer.clone()),
+        other => Err(format!("不支持的采样格式: {:?}", other)),
+    }?;
+    stream.play().map_err(|e| format!("开始录音失败: {}", e))?;
+
+    Ok((stream, writer))
+}
+
+/// 录音线程：音频流不能跨线程传递，整个生命周期都在该线程内
+fn record(
+    path: PathBuf,
+    stop: Receiver<()>,
+    ready: SyncSender<Result<(), String>>,
+) -> Result<PathBuf, String> {
+    let (stream, writer) = match open_stream(&path) {
+        Ok(opened) => {
+            let _ = ready.send(Ok(()));
+            opened
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            let _ = ready.send(Err(e.clone()));
+            return Err(e);
+        }
+    };
+
+    if stop.recv_timeout(MAX_DURATION).is_err() {
+        log::info!("⏱️ 录音达到最长时间，自动结束");
+    }
+    drop(stream);
+
+    let writer = writer
+        .lock()
+        .map_err(|e| format!("无法锁定录音文件: {}", e))?
+        .take();
+    if let Some(writer) = writer {
+        writer
+            .finalize()
+            .map_err(|e| format!("保存录音文件失败: {}", e))?;
+    }
+
+    Ok(path)
+}
+
+/// Tauri 命令：开始录制语音备忘
+#[tauri::command]
+pub fn start_audio_recording(app: AppHandle) -> Result<(), CloudPasteError> {
+    let mut recording = RECORDING
+        .lock()
+        .map_err(|e| format!("无法锁定录音状态: {}", e))?;
+    if recording.is_some() {
+        return Err(CloudPasteError::InvalidInput("已经在录音中".to_string()));
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!("voice-memo-{}.wav", timestamp));
+
+    let (stop_tx, stop_rx) = mpsc::sync_channel(1);
+    let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+    let worker = thread::spawn(move || record(path, stop_rx, ready_tx));
+
+    ready_rx
+        .recv()
+        .map_err(|_| "录音线程异常退出".to_string())??;
+
+    *recording = Some(ActiveRecording {
+        stop: stop_tx,
+        worker,
+    });
+
+    log::info!("🎙️ 开始录音");
+    let _ = app.emit("audio-recording-changed", true);
+    Ok(())
+}
+
+/// Tauri 命令：结束录音并上传为剪贴板项
+#[tauri::command]
+pub async fn stop_audio_recording(app: AppHandle) -> Result<ClipboardItem, CloudPasteError> {
+    let recording = RECORDING
+        .lock()
+        .map_err(|e| format!("无法锁定录音状态: {}", e))?
+        .take()
+        .ok_or_else(|| "当前没有进行中的录音".to_string())?;
+
+    let _ = recording.stop.send(());
+    let path = tauri::async_runtime::spawn_blocking(move || recording.worker.join())
+        .await
+        .map_err(|e| format!("录音任务失败: {}", e))?
+        .map_err(|_| "录音线程异常退出".to_string())??;
+
+    let _ = app.emit("audio-recording-changed", false);
+    log::info!("🎙️ 录音结束: {:?}", path);
+
+    let result = async {
+        let uploaded = upload::upload_file(&path).await?;
+        storage::create_item(&NewClipboardItem::from_upload(&uploaded)?).await
+    }
+    .await;
+
+    // 上传失败时保留录音文件，避免丢失语音备忘
+    match &result {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&path);
+        }
+        Err(e) => log::warn!("⚠️ 上传录音失败，录音已保留在 {:?}: {}", path, e),
+    }
+    result
+}
+
+/// 托盘菜单：开始或结束录音
+pub fn toggle_from_tray(app: &AppHandle) {
+    let is_recording = RECORDING.lock().map(|r| r.is_some()).unwrap_or(false);
+
+    if !is_recording {
+        if let Err(e) = start_audio_recording(app.clone()) {
+            log::error!("❌ {}", e);
+        }
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match stop_audio_recording(app).await {
+            Ok(item) => log::info!("✅ 语音备忘已上传: 剪贴板项={}", item.id),
+            Err(e) => log::error!("❌ 上传语音备忘失败: {}", e),
+        }
+    });
+}
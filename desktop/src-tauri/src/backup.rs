@@ -0,0 +1,301 @@
+use crate::error::CloudPasteError;
+use crate::{history, image_cache, pinned, store, ApiConfig, GLOBAL_API_CONFIG};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Webview};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 备份格式版本
+const BACKUP_VERSION: u32 = 1;
+
+/// 备份文件保存目录（位于应用数据目录下）
+const BACKUP_DIR: &str = "backups";
+
+/// 恢复时的临时解压目录（位于应用数据目录下）
+const STAGING_DIR: &str = "restore-staging";
+
+/// 备份中的清单文件
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// 备份中的连接配置（不含令牌）
+const CONFIG_FILE: &str = "config/api_config.json";
+
+/// 备份中其他设置文件所在目录
+const SETTINGS_DIR: &str = "config/settings/";
+
+/// 备份中的历史记录数据库
+const HISTORY_FILE: &str = "history.db";
+
+/// 备份中固定项缓存文件所在目录
+const CACHE_DIR: &str = "cache/";
+
+/// 不备份的设置文件：连接配置单独处理，受管策略由管理员下发
+const EXCLUDED_SETTINGS: &[&str] = &["api_config.json", "managed_policy.json", "managed.json"];
+
+/// 备份中的一个文件及其校验信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BackupFile {
+    name: String,
+    size: u64,
+    sha256: String,
+}
+
+/// 备份清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    created_at: String,
+    app_version: String,
+    files: Vec<BackupFile>,
+}
+
+/// 计算文件的大小和 SHA256
+fn digest_file(path: &Path) -> Result<(u64, String), String> {
+    let mut file = File::open(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher).map_err(|e| format!("读取文件失败: {}", e))?;
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// 收集要备份的文件：(备份中的名称, 源文件路径)
+fn collect_files(app: &AppHandle, database: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut files = vec![(HISTORY_FILE.to_string(), database.to_path_buf())];
+
+    for entry in fs::read_dir(store::data_dir(app)?)
+        .map_err(|e| format!("读取应用数据目录失败: {}", e))?
+        .flatten()
+    {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_file() && name.ends_with(".json") && !EXCLUDED_SETTINGS.contains(&name.as_str())
+        {
+            files.push((format!("{}{}", SETTINGS_DIR, name), path));
+        }
+    }
+
+    for path in pinned::protected_paths(app) {
+        if let Some(name) = path.file_name().filter(|_| path.exists()) {
+            files.push((
+                format!("{}{}", CACHE_DIR, name.to_string_lossy()),
+                path.clone(),
+            ));
+        }
+    }
+
+    Ok(files)
+}
+
+/// 写入备份归档：清单、连接配置及收集到的文件
+fn write_backup(
+    path: &Path,
+    manifest: &Manifest,
+    config: &[u8],
+    files: &[(String, PathBuf)],
+) -> Result<(), String> {
+    store::write_atomic_with(path, |file| {
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let json =
+            serde_json::to_vec_pretty(manifest).map_err(|e| format!("序列化清单失败: {}", e))?;
+        for (name, data) in [(MANIFEST_FILE, json.as_slice()), (CONFIG_FILE, config)] {
+            zip.start_file(name, options)
+                .map_err(|e| format!("写入备份失败: {}", e))?;
+            zip.write_all(data)
+                .map_err(|e| format!("写入备份失败: {}", e))?;
+        }
+
+        for (name, source) in files {
+            let mut input = File::open(source).map_err(|e| format!("读取文件失败: {}", e))?;
+            zip.start_file(name.as_str(), options)
+                .map_err(|e| format!("写入备份失败: {}", e))?;
+            std::io::copy(&mut input, &mut zip).map_err(|e| format!("写入备份失败: {}", e))?;
+        }
+
+        zip.finish().map_err(|e| format!("写入备份失败: {}", e))
+    })
+}
+
+/// 将备份解压到临时目录并逐个校验，返回清单
+///
+/// 清单中的文件缺失、大小或 SHA256 不符时返回错误，此时尚未改动任何现有数据
+fn extract_verified(path: &Path, staging: &Path) -> Result<Manifest, String> {
+    let file = File::open(path).map_err(|e| format!("打开备份文件失败: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("无效的备份文件: {}", e))?;
+
+    let manifest: Manifest = {
+        let mut json = String::new();
+        zip.by_name(MANIFEST_FILE)
+            .map_err(|_| "备份中缺少清单文件".to_string())?
+            .read_to_string(&mut json)
+            .map_err(|e| format!("读取清单失败: {}", e))?;
+        serde_json::from_str(&json).map_err(|e| format!("解析清单失败: {}", e))?
+    };
+    if manifest.version > BACKUP_VERSION {
+        return Err(format!("不支持的备份版本: {}", manifest.version));
+    }
+    if !manifest.files.iter().any(|file| file.name == HISTORY_FILE) {
+        return Err("备份中缺少历史记录数据库".to_string());
+    }
+
+    let names = manifest
+        .files
+        .iter()
+        .map(|file| file.name.as_str())
+        .chain([CONFIG_FILE]);
+    for name in names {
+        let mut input = zip
+            .by_name(name)
+            .map_err(|_| format!("备份中缺少文件: {}", name))?;
+        let relative = input
+            .enclosed_name()
+            .ok_or_else(|| format!("备份中的文件路径无效: {}", name))?;
+        let target = staging.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建临时目录失败: {}", e))?;
+        }
+        let mut output = File::create(&target).map_err(|e| format!("解压备份失败: {}", e))?;
+        std::io::copy(&mut input, &mut output).map_err(|e| format!("解压备份失败: {}", e))?;
+    }
+
+    for expected in &manifest.files {
+        let (size, sha256) = digest_file(&staging.join(&expected.name))?;
+        if size != expected.size || sha256 != expected.sha256 {
+            return Err(format!("备份文件校验失败: {}", expected.name));
+        }
+    }
+    history::verify_database(&staging.join(HISTORY_FILE))?;
+
+    Ok(manifest)
+}
+
+/// 应用已校验的备份：连接配置（保留本机令牌）、设置、历史记录和缓存文件
+fn apply_backup(app: &AppHandle, manifest: &Manifest, staging: &Path) -> Result<(), String> {
+    let content = fs::read_to_string(staging.join(CONFIG_FILE))
+        .map_err(|e| format!("读取备份配置失败: {}", e))?;
+    let (restored, _) = ApiConfig::parse(&content)?;
+    {
+        let mut config = GLOBAL_API_CONFIG
+            .lock()
+            .map_err(|e| format!("无法锁定配置: {}", e))?;
+        config.base_url = restored.base_url;
+        config.fallback_url = restored.fallback_url;
+        config.device_name = restored.device_name;
+        config.is_configured = restored.is_configured;
+        config.save_to_disk(app)?;
+    }
+
+    let data_dir = store::data_dir(app)?;
+    let cache_dir = image_cache::get_cache_dir(app)?;
+    for file in &manifest.files {
+        let source = staging.join(&file.name);
+        let target = if let Some(name) = file.name.strip_prefix(SETTINGS_DIR) {
+            data_dir.join(name)
+        } else if let Some(name) = file.name.strip_prefix(CACHE_DIR) {
+            cache_dir.join(name)
+        } else {
+            continue;
+        };
+        store::copy_atomic(&source, &target)
+            .map_err(|e| format!("恢复文件失败: {} ({})", file.name, e))?;
+    }
+
+    history::restore_from(app, &staging.join(HISTORY_FILE))?;
+    crate::load_settings(app);
+    Ok(())
+}
+
+/// Tauri 命令：创建备份（连接配置但不含令牌、各项设置、历史记录数据库及固定项缓存）
+///
+/// 备份保存在应用数据目录的 backups 目录下，返回备份文件路径
+#[tauri::command]
+pub async fn create_backup(app: AppHandle) -> Result<String, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let dir = store::data_dir(&app)?.join(BACKUP_DIR);
+    fs::create_dir_all(&dir).map_err(|e| CloudPasteError::io("创建备份目录失败", e))?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let path = dir.join(format!("cloudpaste-backup-{}.zip", timestamp));
+    let database = dir.join(format!("history-{}.db", timestamp));
+    log::info!("💾 创建备份: {:?}", path);
+
+    let config = {
+        let config = GLOBAL_API_CONFIG
+            .lock()
+            .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?;
+        let mut config = config.clone();
+        config.token = String::new();
+        serde_json::to_vec_pretty(&config).map_err(|e| format!("序列化配置失败: {}", e))?
+    };
+    let app_version = app.package_info().version.to_string();
+
+    let target = path.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        history::backup_to(&database)?;
+        let result = (|| {
+            let files = collect_files(&app, &database)?;
+            let mut entries = Vec::new();
+            for (name, source) in &files {
+                let (size, sha256) = digest_file(source)?;
+                entries.push(BackupFile {
+                    name: name.clone(),
+                    size,
+                    sha256,
+                });
+            }
+            let manifest = Manifest {
+                version: BACKUP_VERSION,
+                created_at: chrono::Utc::now().to_rfc3339(),
+                app_version,
+                files: entries,
+            };
+            write_backup(&target, &manifest, &config, &files)
+        })();
+        let _ = fs::remove_file(&database);
+        result
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("创建备份失败: {}", e)))?;
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&path);
+        return Err(e.into());
+    }
+
+    log::info!("✅ 备份已创建: {:?}", path);
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Tauri 命令：从备份恢复应用状态
+///
+/// 先完整解压并校验备份，全部通过后才替换现有数据；本机保存的令牌不会被覆盖
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    webview: Webview,
+    path: String,
+) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    log::info!("♻️ 从备份恢复: {}", path);
+    let source = crate::sandbox::check(&webview, &path)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let staging = store::data_dir(&app)?.join(STAGING_DIR);
+        let _ = fs::remove_dir_all(&staging);
+        fs::create_dir_all(&staging).map_err(|e| format!("创建临时目录失败: {}", e))?;
+
+        let result = extract_verified(&source, &staging)
+            .and_then(|manifest| apply_backup(&app, &manifest, &staging));
+        let _ = fs::remove_dir_all(&staging);
+        result
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("恢复备份失败: {}", e)))??;
+
+    log::info!("✅ 备份已恢复");
+    Ok(())
+}
@@ -0,0 +1,384 @@
+use crate::error::CloudPasteError;
+use crate::{applock, keys, store};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// 验证时系统对话框中显示的说明
+const PROMPT: &str = "解锁 CloudPaste";
+
+/// 由生物识别保护的密钥包装后的主密钥
+const BIOMETRIC_FILE: &str = "biometric_key.json";
+
+/// 生物识别可用状态
+#[derive(Debug, Clone, Serialize)]
+pub struct BiometricStatus {
+    pub available: bool,
+    /// 验证方式：`touch_id` 或 `windows_hello`，不可用时为空
+    pub kind: Option<&'static str>,
+    /// 是否已启用生物识别解锁
+    pub enabled: bool,
+}
+
+/// 保存在磁盘上的生物识别解锁材料：包装密钥只能通过系统生物识别取出
+///
+/// Windows 上由 Windows Hello 凭据的签名派生，macOS 上保存在需要 Touch ID 的钥匙串项中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BiometricKey {
+    /// 随机数 + 加密后的主密钥（Base64）
+    wrapped_master_key: String,
+}
+
+#[cfg(windows)]
+mod platform {
+    use sha2::{Digest, Sha256};
+    use windows::core::{Array, HSTRING};
+    use windows::Security::Credentials::UI::{
+        UserConsentVerifier, UserConsentVerifierAvailability,
+    };
+    use windows::Security::Credentials::{
+        KeyCredential, KeyCredentialCreationOption, KeyCredentialManager, KeyCredentialStatus,
+    };
+    use windows::Security::Cryptography::CryptographicBuffer;
+
+    pub const KIND: Option<&str> = Some("windows_hello");
+
+    /// Windows Hello 密钥凭据名称
+    const CREDENTIAL: &str = "CloudPaste";
+
+    /// 签名的固定内容：Windows Hello 的 RSA 签名是确定的，签名的哈希作为包装密钥
+    const CHALLENGE: &[u8] = b"cloudpaste-biometric-key-v1";
+
+    pub fn available() -> bool {
+        UserConsentVerifier::CheckAvailabilityAsync()
+            .and_then(|operation| operation.get())
+            .is_ok_and(|availability| availability == UserConsentVerifierAvailability::Available)
+    }
+
+    /// 用凭据签名固定内容（需通过 Windows Hello 验证），用户取消时为空
+    fn derive(credential: &KeyCredential) -> Result<Option<[u8; 32]>, String> {
+        let challenge = CryptographicBuffer::CreateFromByteArray(CHALLENGE)
+            .map_err(|e| format!("Windows Hello 签名失败: {}", e))?;
+        let result = credential
+            .RequestSignAsync(&challenge)
+            .and_then(|operation| operation.get())
+            .map_err(|e| format!("Windows Hello 签名失败: {}", e))?;
+        match result
+            .Status()
+            .map_err(|e| format!("Windows Hello 签名失败: {}", e))?
+        {
+            KeyCredentialStatus::Success => {}
+            KeyCredentialStatus::UserCanceled => return Ok(None),
+            status => return Err(format!("Windows Hello 签名失败: {:?}", status)),
+        }
+
+        let mut signature = Array::<u8>::new();
+        result
+            .Result()
+            .and_then(|buffer| CryptographicBuffer::CopyToByteArray(&buffer, &mut signature))
+            .map_err(|e| format!("读取 Windows Hello 签名失败: {}", e))?;
+        Ok(Some(Sha256::digest(&signature[..]).into()))
+    }
+
+    /// 创建新的 Windows Hello 凭据（替换原有凭据）并派生包装密钥
+    pub fn create_key(_prompt: &str) -> Result<Option<[u8; 32]>, String> {
+        let result = KeyCredentialManager::RequestCreateAsync(
+            &HSTRING::from(CREDENTIAL),
+            KeyCredentialCreationOption::ReplaceExisting,
+        )
+        .and_then(|operation| operation.get())
+        .map_err(|e| format!("创建 Windows Hello 凭据失败: {}", e))?;
+        match result
+            .Status()
+            .map_err(|e| format!("创建 Windows Hello 凭据失败: {}", e))?
+        {
+            KeyCredentialStatus::Success => {}
+            KeyCredentialStatus::UserCanceled => return Ok(None),
+            status => return Err(format!("创建 Windows Hello 凭据失败: {:?}", status)),
+        }
+        let credential = result
+            .Credential()
+            .map_err(|e| format!("创建 Windows Hello 凭据失败: {}", e))?;
+        derive(&credential)
+    }
+
+    /// 打开已有的 Windows Hello 凭据并派生包装密钥
+    pub fn open_key(_prompt: &str) -> Result<Option<[u8; 32]>, String> {
+        let result = KeyCredentialManager::OpenAsync(&HSTRING::from(CREDENTIAL))
+            .and_then(|operation| operation.get())
+            .map_err(|e| format!("打开 Windows Hello 凭据失败: {}", e))?;
+        match result
+            .Status()
+            .map_err(|e| format!("打开 Windows Hello 凭据失败: {}", e))?
+        {
+            KeyCredentialStatus::Success => {}
+            KeyCredentialStatus::NotFound => {
+                return Err("Windows Hello 凭据不存在，请重新启用生物识别解锁".to_string())
+            }
+            status => return Err(format!("打开 Windows Hello 凭据失败: {:?}", status)),
+        }
+        let credential = result
+            .Credential()
+            .map_err(|e| format!("打开 Windows Hello 凭据失败: {}", e))?;
+        derive(&credential)
+    }
+
+    pub fn delete_key() -> Result<(), String> {
+        KeyCredentialManager::DeleteAsync(&HSTRING::from(CREDENTIAL))
+            .and_then(|operation| operation.get())
+            .map_err(|e| format!("删除 Windows Hello 凭据失败: {}", e))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use core_foundation::base::{kCFAllocatorDefault, CFType, CFTypeRef, TCFType};
+    use core_foundation::boolean::CFBoolean;
+    use core_foundation::data::{CFData, CFDataRef};
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::string::{CFString, CFStringRef};
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyObject, Bool};
+    use objc2::{class, msg_send};
+    use objc2_foundation::NSString;
+    use rand_core::{OsRng, RngCore};
+    use security_framework_sys::access_control::{
+        kSecAccessControlBiometryCurrentSet, kSecAttrAccessibleWhenUnlockedThisDeviceOnly,
+        SecAccessControlCreateWithFlags,
+    };
+    use security_framework_sys::base::{errSecAuthFailed, errSecItemNotFound, errSecSuccess};
+    use security_framework_sys::item::{
+        kSecAttrAccessControl, kSecAttrAccount, kSecAttrService, kSecClass,
+        kSecClassGenericPassword, kSecReturnData, kSecUseAuthenticationContext,
+        kSecUseDataProtectionKeychain, kSecValueData,
+    };
+    use security_framework_sys::keychain_item::{SecItemAdd, SecItemCopyMatching, SecItemDelete};
+
+    #[link(name = "LocalAuthentication", kind = "framework")]
+    extern "C" {}
+
+    /// LAPolicyDeviceOwnerAuthenticationWithBiometrics
+    const POLICY_BIOMETRICS: isize = 1;
+
+    /// errSecUserCanceled
+    const ERR_USER_CANCELED: i32 = -128;
+
+    /// 钥匙串项的服务名和账户名
+    const SERVICE: &str = "CloudPaste";
+    const ACCOUNT: &str = "biometric-wrapping-key";
+
+    pub const KIND: Option<&str> = Some("touch_id");
+
+    fn context() -> Retained<AnyObject> {
+        unsafe { msg_send![class!(LAContext), new] }
+    }
+
+    pub fn available() -> bool {
+        let context = context();
+        let error: *mut *mut AnyObject = std::ptr::null_mut();
+        let available: Bool =
+            unsafe { msg_send![&*context, canEvaluatePolicy: POLICY_BIOMETRICS, error: error] };
+        available.as_bool()
+    }
+
+    fn name(key: CFStringRef) -> CFString {
+        unsafe { CFString::wrap_under_get_rule(key) }
+    }
+
+    /// 钥匙串项的查询条件（数据保护钥匙串，才能使用生物识别访问控制）
+    fn query() -> Vec<(CFString, CFType)> {
+        unsafe {
+            vec![
+                (
+                    name(kSecClass),
+                    name(kSecClassGenericPassword).into_CFType(),
+                ),
+                (name(kSecAttrService), CFString::new(SERVICE).into_CFType()),
+                (name(kSecAttrAccount), CFString::new(ACCOUNT).into_CFType()),
+                (
+                    name(kSecUseDataProtectionKeychain),
+                    CFBoolean::true_value().into_CFType(),
+                ),
+            ]
+        }
+    }
+
+    /// 生成随机包装密钥，保存到只能通过 Touch ID 读取的钥匙串项（更换指纹后失效）
+    pub fn create_key(_prompt: &str) -> Result<Option<[u8; 32]>, String> {
+        delete_key()?;
+
+        let access = unsafe {
+            SecAccessControlCreateWithFlags(
+                kCFAllocatorDefault,
+                kSecAttrAccessibleWhenUnlockedThisDeviceOnly as CFTypeRef,
+                kSecAccessControlBiometryCurrentSet,
+                std::ptr::null_mut(),
+            )
+        };
+        if access.is_null() {
+            return Err("创建钥匙串访问控制失败".to_string());
+        }
+        let access = unsafe { CFType::wrap_under_create_rule(access as CFTypeRef) };
+
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        let mut attributes = query();
+        attributes.push((name(unsafe { kSecAttrAccessControl }), access));
+        attributes.push((
+            name(unsafe { kSecValueData }),
+            CFData::from_buffer(&key).into_CFType(),
+        ));
+        let attributes = CFDictionary::from_CFType_pairs(&attributes);
+        let status = unsafe { SecItemAdd(attributes.as_concrete_TypeRef(), std::ptr::null_mut()) };
+        if status != errSecSuccess {
+            return Err(format!("保存到钥匙串失败 ({})", status));
+        }
+        Ok(Some(key))
+    }
+
+    /// 读取钥匙串中的包装密钥（系统弹出 Touch ID 验证），用户取消或验证失败时为空
+    pub fn open_key(prompt: &str) -> Result<Option<[u8; 32]>, String> {
+        let context = context();
+        let reason = NSString::from_str(prompt);
+        unsafe {
+            let _: () = msg_send![&*context, setLocalizedReason: &*reason];
+        }
+
+        let mut query = query();
+        query.push((
+            name(unsafe { kSecReturnData }),
+            CFBoolean::true_value().into_CFType(),
+        ));
+        query.push((name(unsafe { kSecUseAuthenticationContext }), unsafe {
+            CFType::wrap_under_get_rule(Retained::as_ptr(&context) as CFTypeRef)
+        }));
+        let query = CFDictionary::from_CFType_pairs(&query);
+
+        let mut result: CFTypeRef = std::ptr::null();
+        let status = unsafe { SecItemCopyMatching(query.as_concrete_TypeRef(), &mut result) };
+        match status {
+            errSecSuccess => {
+                let data = unsafe { CFData::wrap_under_create_rule(result as CFDataRef) };
+                data.bytes()
+                    .try_into()
+                    .map(Some)
+                    .map_err(|_| "钥匙串中的密钥已损坏".to_string())
+            }
+            ERR_USER_CANCELED | errSecAuthFailed => Ok(None),
+            errSecItemNotFound => Err("钥匙串中的密钥不存在，请重新启用生物识别解锁".to_string()),
+            status => Err(format!("读取钥匙串失败 ({})", status)),
+        }
+    }
+
+    pub fn delete_key() -> Result<(), String> {
+        let query = CFDictionary::from_CFType_pairs(&query());
+        match unsafe { SecItemDelete(query.as_concrete_TypeRef()) } {
+            errSecSuccess | errSecItemNotFound => Ok(()),
+            status => Err(format!("删除钥匙串项失败 ({})", status)),
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    pub const KIND: Option<&str> = None;
+
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn create_key(_prompt: &str) -> Result<Option<[u8; 32]>, String> {
+        Err("当前系统不支持生物识别".to_string())
+    }
+
+    pub fn open_key(_prompt: &str) -> Result<Option<[u8; 32]>, String> {
+        Err("当前系统不支持生物识别".to_string())
+    }
+
+    pub fn delete_key() -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn is_enrolled(app: &AppHandle) -> bool {
+    store::load_json::<BiometricKey>(app, BIOMETRIC_FILE).is_some()
+}
+
+/// 在阻塞线程中调用系统生物识别接口，用户取消或验证未通过时返回未授权错误
+async fn run_platform(
+    f: fn(&str) -> Result<Option<[u8; 32]>, String>,
+) -> Result<[u8; 32], CloudPasteError> {
+    tauri::async_runtime::spawn_blocking(move || f(PROMPT))
+        .await
+        .map_err(|e| CloudPasteError::Internal(format!("生物识别验证失败: {}", e)))??
+        .ok_or_else(|| CloudPasteError::Unauthorized("生物识别验证未通过".to_string()))
+}
+
+/// 删除生物识别保存的主密钥（停用或主密钥轮换后）
+pub fn forget(app: &AppHandle) {
+    if let Ok(path) = store::data_file_path(app, BIOMETRIC_FILE) {
+        let _ = std::fs::remove_file(path);
+    }
+    if let Err(e) = platform::delete_key() {
+        log::warn!("⚠️ {}", e);
+    }
+}
+
+/// Tauri 命令：检测本机是否可以使用生物识别（Touch ID / Windows Hello）
+#[tauri::command]
+pub async fn get_biometric_status(app: AppHandle) -> BiometricStatus {
+    let available = tauri::async_runtime::spawn_blocking(platform::available)
+        .await
+        .unwrap_or(false);
+    BiometricStatus {
+        available,
+        kind: platform::KIND.filter(|_| available),
+        enabled: is_enrolled(&app),
+    }
+}
+
+/// Tauri 命令：启用生物识别解锁，用系统生物识别保护的密钥包装已解锁的主密钥
+#[tauri::command]
+pub async fn enable_biometric_unlock(app: AppHandle) -> Result<(), CloudPasteError> {
+    applock::ensure_unlocked()?;
+    let master = keys::master_key().ok_or(CloudPasteError::Locked)?;
+
+    let key = run_platform(platform::create_key).await?;
+    let stored = BiometricKey {
+        wrapped_master_key: keys::wrap_with(&key, &master)?,
+    };
+    store::save_json(&app, BIOMETRIC_FILE, &stored)?;
+    log::info!("🔐 已启用生物识别解锁");
+    Ok(())
+}
+
+/// Tauri 命令：停用生物识别解锁
+#[tauri::command]
+pub fn disable_biometric_unlock(app: AppHandle) -> Result<(), CloudPasteError> {
+    applock::ensure_unlocked()?;
+    forget(&app);
+    log::info!("🔐 已停用生物识别解锁");
+    Ok(())
+}
+
+/// Tauri 命令：通过生物识别取出主密钥，解锁主密钥和应用锁
+#[tauri::command]
+pub async fn biometric_unlock(app: AppHandle) -> Result<(), CloudPasteError> {
+    let stored = store::load_json::<BiometricKey>(&app, BIOMETRIC_FILE)
+        .ok_or_else(|| CloudPasteError::InvalidInput("尚未启用生物识别解锁".to_string()))?;
+
+    log::info!("🔐 请求生物识别解锁");
+    let key = run_platform(platform::open_key).await?;
+    let master = keys::unwrap_with(&key, &stored.wrapped_master_key)?.ok_or_else(|| {
+        CloudPasteError::Unauthorized("生物识别密钥已失效，请使用密码解锁后重新启用".to_string())
+    })?;
+
+    if keys::master_key().is_none() {
+        tauri::async_runtime::spawn_blocking(move || keys::unlock_with_master(&app, master))
+            .await
+            .map_err(|e| CloudPasteError::Internal(format!("解锁主密钥失败: {}", e)))??;
+    }
+    if applock::is_enabled() {
+        applock::unlock_verified();
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use crate::error::CloudPasteError;
+use crate::http;
+use once_cell::sync::OnceCell;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager, State};
+
+/// 服务器支持 zstd 压缩上传
+pub const ZSTD: &str = "zstd";
+/// 服务器可以签发对象存储直传地址
+pub const PRESIGNED_UPLOAD: &str = "presigned_upload";
+/// 服务器提供 GraphQL 查询接口
+pub const GRAPHQL: &str = "graphql";
+/// 服务器提供按稳定游标返回更新和删除的增量同步接口
+pub const CHANGE_FEED: &str = "change_feed";
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+/// 协商得到的服务器能力
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerCapabilities {
+    /// 服务器版本
+    pub version: Option<String>,
+    /// 服务器支持的 API 版本，如 `["v1"]`
+    pub api_versions: Vec<String>,
+    /// 支持的功能
+    pub features: Vec<String>,
+    /// 是否已完成协商；未完成或服务器早于能力协商时为 false，各功能自行探测
+    pub negotiated: bool,
+}
+
+impl ServerCapabilities {
+    fn supports(&self, feature: &str) -> Option<bool> {
+        self.negotiated
+            .then(|| self.features.iter().any(|f| f == feature))
+    }
+}
+
+/// 应用托管状态：当前服务器的能力
+#[derive(Default)]
+pub struct CapabilityState(RwLock<ServerCapabilities>);
+
+impl CapabilityState {
+    fn get(&self) -> ServerCapabilities {
+        self.0.read().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    fn set(&self, capabilities: ServerCapabilities) {
+        if let Ok(mut current) = self.0.write() {
+            *current = capabilities;
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: Option<String>,
+    api_version: Option<String>,
+    #[serde(default)]
+    api_versions: Vec<String>,
+    /// 旧服务器不返回
+    capabilities: Option<Vec<String>>,
+}
+
+/// 注册托管状态
+pub fn init(app: &AppHandle) {
+    app.manage(CapabilityState::default());
+    let _ = APP.set(app.clone());
+}
+
+/// 服务器是否支持某项功能；尚未协商或服务器不支持协商时返回 None
+pub fn supports(feature: &str) -> Option<bool> {
+    APP.get()?
+        .state::<CapabilityState>()
+        .get()
+        .supports(feature)
+}
+
+/// 服务器是否在能力协商中声明支持某个 API 版本（如 `v2`）
+pub fn supports_api(version: &str) -> bool {
+    APP.get().is_some_and(|app| {
+        app.state::<CapabilityState>()
+            .get()
+            .api_versions
+            .iter()
+            .any(|v| v == version)
+    })
+}
+
+/// 查询服务器版本和能力并保存（切换服务器或启动时调用）
+pub async fn refresh(app: &AppHandle) -> Result<ServerCapabilities, CloudPasteError> {
+    let state = app.state::<CapabilityState>();
+    // 先清除上一个服务器的结果，协商完成前各功能按旧方式探测
+    state.set(ServerCapabilities::default());
+
+    let request = http::api_request(Method::GET, "/version")?;
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("查询服务器能力失败", e))?;
+    let body: VersionResponse = http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析服务器能力失败: {}", e)))?;
+
+    let mut api_versions = body.api_versions;
+    if api_versions.is_empty() {
+        api_versions.extend(body.api_version);
+    }
+    let capabilities = ServerCapabilities {
+        version: body.version,
+        api_versions,
+        negotiated: body.capabilities.is_some(),
+        features: body.capabilities.unwrap_or_default(),
+    };
+    log::info!(
+        "🤝 服务器能力: 版本={:?}, API={:?}, 功能={:?}",
+        capabilities.version,
+        capabilities.api_versions,
+        capabilities.features
+    );
+    state.set(capabilities.clone());
+    Ok(capabilities)
+}
+
+/// 在后台刷新服务器能力
+pub fn refresh_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh(&app).await {
+            log::warn!("⚠️ {}", e);
+        }
+    });
+}
+
+/// Tauri 命令：获取当前服务器的能力
+#[tauri::command]
+pub fn get_server_capabilities(state: State<'_, CapabilityState>) -> ServerCapabilities {
+    state.get()
+}
+
+/// Tauri 命令：重新协商服务器能力
+#[tauri::command]
+pub async fn refresh_server_capabilities(
+    app: AppHandle,
+) -> Result<ServerCapabilities, CloudPasteError> {
+    refresh(&app).await
+}
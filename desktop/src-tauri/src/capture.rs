@@ -0,0 +1,655 @@
+use crate::api::Representation;
+use crate::clipboard::{self, RichFormats};
+use crate::error::CloudPasteError;
+use crate::events::{self, ClipboardCaptured};
+use crate::sensitive::{self, SensitiveKind};
+use crate::{capture_pause, history, policy, privacy_guard, store, sync_filter};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Webview};
+use url::Url;
+
+const SETTINGS_FILE: &str = "capture.json";
+
+/// 常见的链接跟踪参数（`utm_` 开头的参数另外处理）
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc",
+    "_hsmi", "mkt_tok", "spm", "si", "ref_src", "ref_url",
+];
+
+/// 默认不采集的应用（密码管理器）
+const DEFAULT_EXCLUDED_APPS: &[&str] = &[
+    "1Password",
+    "Bitwarden",
+    "KeePass",
+    "KeePassXC",
+    "LastPass",
+    "Dashlane",
+    "Enpass",
+    "com.apple.keychainaccess",
+];
+
+/// 剪贴板采集设置：同步前对文本内容做的转换，每条规则可单独开关
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureSettings {
+    /// 去除格式（不保留 HTML / RTF 表示格式，只同步纯文本）
+    pub strip_formatting: bool,
+    /// 去除首尾空白和行尾空格
+    pub trim_whitespace: bool,
+    /// 去除链接中的跟踪参数
+    pub strip_tracking_params: bool,
+    /// 合并连续空行
+    pub collapse_newlines: bool,
+    /// 去重时间窗口（秒）：窗口内重复复制的相同内容不再同步，为 0 时不去重
+    pub dedup_window_secs: u64,
+    /// 疑似敏感内容（密码、密钥、银行卡号等）的处理方式
+    pub sensitive_action: SensitiveAction,
+    /// 不采集的应用：进程名、应用名或 macOS bundle id（不区分大小写）
+    pub excluded_apps: Vec<String>,
+}
+
+/// 疑似敏感内容的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveAction {
+    /// 不检测
+    Off,
+    /// 只保存在本地历史，不同步
+    LocalOnly,
+    /// 完全忽略
+    Skip,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            strip_formatting: false,
+            trim_whitespace: true,
+            strip_tracking_params: true,
+            collapse_newlines: false,
+            dedup_window_secs: 60,
+            sensitive_action: SensitiveAction::LocalOnly,
+            excluded_apps: DEFAULT_EXCLUDED_APPS
+                .iter()
+                .map(|app| app.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// 跳过同步的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// 与去重窗口内的内容相同
+    Duplicate,
+    /// 疑似敏感内容
+    Sensitive,
+    /// 复制自排除列表中的应用
+    ExcludedApp,
+    /// 被本机的同步过滤设置排除
+    Filtered,
+    /// 剪贴板采集已暂停
+    Paused,
+    /// 正在共享屏幕
+    ScreenSharing,
+    /// 复制自浏览器隐私窗口
+    PrivateBrowsing,
+    /// 带有密码管理器标记的内容
+    PasswordManager,
+}
+
+/// 内容未同步时发送给前端的事件（`capture-skipped`）
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureSkipped {
+    pub reason: SkipReason,
+    /// 命中的敏感内容类型
+    pub kind: Option<SensitiveKind>,
+    /// 是否已保存到本地历史
+    pub local_only: bool,
+    /// 复制来源应用
+    pub app_name: Option<String>,
+}
+
+/// 处理后的文本内容
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedText {
+    pub content: String,
+    /// 不为空时不应同步
+    pub skipped: Option<SkipReason>,
+    /// 同步时随内容发送的各种表示格式（纯文本 + 采集到的 HTML / RTF）
+    pub representations: Vec<Representation>,
+}
+
+/// 剪贴板中复制的文件（在资源管理器 / 访达中复制的文件列表）
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedFile {
+    pub path: String,
+    pub name: String,
+    pub size: u64,
+    /// 是否为目录（目录不上传）
+    pub is_dir: bool,
+}
+
+/// 处理后的文件列表
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedFiles {
+    /// 可以上传的文件（已排除不存在和被同步过滤设置排除的文件）
+    pub files: Vec<CapturedFile>,
+    /// 不为空时不应上传
+    pub skipped: Option<SkipReason>,
+}
+
+static SETTINGS: Lazy<Mutex<CaptureSettings>> =
+    Lazy::new(|| Mutex::new(CaptureSettings::default()));
+
+/// 最近一次从剪贴板采集的文件，等待用户选择上传
+static CAPTURED_FILES: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// 最近采集的内容哈希及采集时间（按时间先后）
+static RECENT: Lazy<Mutex<VecDeque<(u64, Instant)>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<CaptureSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> CaptureSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 去除 HTML 标签，保留文本（块级元素换行）
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[start + 1..start + end]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if matches!(
+            tag.as_str(),
+            "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+        ) {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+/// 去除单个链接中的跟踪参数，无需修改时返回 None
+fn clean_url(token: &str) -> Option<String> {
+    let mut url = Url::parse(token).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if !pairs.iter().any(|(key, _)| is_tracking_param(key)) {
+        return None;
+    }
+
+    let kept: Vec<_> = pairs
+        .into_iter()
+        .filter(|(key, _)| !is_tracking_param(key))
+        .collect();
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Some(url.to_string())
+}
+
+/// 去除文本中所有链接的跟踪参数
+fn strip_tracking_params(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("http") {
+        result.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>'))
+            .unwrap_or(candidate.len());
+        let token = &candidate[..end];
+
+        match clean_url(token) {
+            Some(cleaned) => result.push_str(&cleaned),
+            None => result.push_str(token),
+        }
+        rest = &candidate[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 去除首尾空白和每行末尾的空格
+fn trim_whitespace(text: &str) -> String {
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// 将连续多个空行合并为一个
+fn collapse_newlines(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+        } else {
+            blank_lines = 0;
+        }
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(line);
+    }
+    result
+}
+
+/// 按设置依次应用转换规则
+///
+/// 内容始终为纯文本（没有纯文本格式时从 HTML 提取），HTML / RTF 只作为附加的表示格式保留
+fn transform(
+    settings: &CaptureSettings,
+    text: Option<String>,
+    html: Option<&str>,
+    rtf: Option<&str>,
+) -> String {
+    let mut content = text
+        .or_else(|| html.map(html_to_text))
+        .or_else(|| rtf.map(str::to_string))
+        .unwrap_or_default();
+
+    if content.contains('\r') {
+        content = content.replace("\r\n", "\n");
+    }
+    if settings.strip_tracking_params {
+        content = strip_tracking_params(&content);
+    }
+    if settings.collapse_newlines {
+        content = collapse_newlines(&content);
+    }
+    if settings.trim_whitespace {
+        content = trim_whitespace(&content);
+    }
+    content
+}
+
+/// 检查内容是否在去重窗口内出现过，未出现时记录下来
+///
+/// 超出窗口的重复内容照常同步，由服务器更新原记录的时间戳
+fn is_duplicate(content: &str, window: Duration) -> bool {
+    if window.is_zero() {
+        return false;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    let Ok(mut recent) = RECENT.lock() else {
+        return false;
+    };
+    let now = Instant::now();
+    while recent
+        .front()
+        .is_some_and(|(_, captured_at)| now.duration_since(*captured_at) > window)
+    {
+        recent.pop_front();
+    }
+
+    if recent.iter().any(|(recent_hash, _)| *recent_hash == hash) {
+        return true;
+    }
+    recent.push_back((hash, now));
+    false
+}
+
+/// 从 macOS 应用包的 Info.plist 中读取 bundle id
+fn bundle_id(process_path: &Path) -> Option<String> {
+    let bundle = process_path
+        .ancestors()
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))?;
+    let plist = std::fs::read_to_string(bundle.join("Contents/Info.plist")).ok()?;
+
+    let after_key = &plist[plist.find("<key>CFBundleIdentifier</key>")?..];
+    let start = after_key.find("<string>")? + "<string>".len();
+    let end = after_key[start..].find("</string>")?;
+    Some(after_key[start..start + end].trim().to_string())
+}
+
+/// 检查前台应用是否在排除列表中，返回匹配到的应用名
+fn excluded_foreground_app(excluded: &[String]) -> Option<String> {
+    if excluded.is_empty() {
+        return None;
+    }
+    let window = active_win_pos_rs::get_active_window().ok()?;
+
+    let process = window
+        .process_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let bundle_id = bundle_id(&window.process_path).unwrap_or_default();
+
+    let matched = excluded.iter().any(|app| {
+        [
+            window.app_name.as_str(),
+            process.as_str(),
+            bundle_id.as_str(),
+        ]
+        .iter()
+        .any(|name| !name.is_empty() && name.eq_ignore_ascii_case(app.trim()))
+    });
+    matched.then_some(window.app_name)
+}
+
+/// Tauri 命令：检查当前复制来源及剪贴板内容的标记是否允许采集，不允许时返回原因
+///
+/// 剪贴板变化时立即调用（复制发生时来源应用仍在前台），适用于所有内容类型
+#[tauri::command]
+pub fn check_capture_source(app: AppHandle) -> Option<SkipReason> {
+    if capture_pause::is_paused() {
+        log::debug!("⏸️ 剪贴板采集已暂停，跳过采集");
+        return Some(SkipReason::Paused);
+    }
+
+    // 密码管理器复制的内容始终不记录
+    if clipboard::has_concealed_marker().unwrap_or_else(|e| {
+        log::warn!("⚠️ {}", e);
+        false
+    }) {
+        log::info!("🔒 剪贴板内容来自密码管理器，跳过采集");
+        let _ = app.emit(
+            "capture-skipped",
+            CaptureSkipped {
+                reason: SkipReason::PasswordManager,
+                kind: None,
+                local_only: false,
+                app_name: None,
+            },
+        );
+        return Some(SkipReason::PasswordManager);
+    }
+
+    if let Some((reason, app_name)) = privacy_guard::skip_reason() {
+        log::info!("🚫 正在共享屏幕或复制自隐私窗口，跳过采集: {}", app_name);
+        let _ = app.emit(
+            "capture-skipped",
+            CaptureSkipped {
+                reason,
+                kind: None,
+                local_only: false,
+                app_name: Some(app_name),
+            },
+        );
+        return Some(reason);
+    }
+
+    let app_name = excluded_foreground_app(&settings().excluded_apps)?;
+    log::info!("🚫 复制来源应用在排除列表中，跳过采集: {}", app_name);
+
+    let _ = app.emit(
+        "capture-skipped",
+        CaptureSkipped {
+            reason: SkipReason::ExcludedApp,
+            kind: None,
+            local_only: false,
+            app_name: Some(app_name),
+        },
+    );
+    Some(SkipReason::ExcludedApp)
+}
+
+/// Tauri 命令：处理剪贴板监听到的文本内容，返回同步前应使用的内容
+///
+/// 同时传入剪贴板中的各种格式，按设置选择格式并应用转换规则和受管策略的打码规则，再检查是否需要跳过同步
+#[tauri::command]
+pub fn process_text_capture(
+    app: AppHandle,
+    text: Option<String>,
+    html: Option<String>,
+    rtf: Option<String>,
+) -> CapturedText {
+    let settings = settings();
+    let content = transform(&settings, text, html.as_deref(), rtf.as_deref());
+    let (content, redacted) = match policy::redact(&content) {
+        std::borrow::Cow::Owned(redacted) => (redacted, true),
+        std::borrow::Cow::Borrowed(_) => (content, false),
+    };
+    let formats = RichFormats { html, rtf };
+
+    if let Err(reason) = sync_filter::check("text", None, None, content.len() as u64) {
+        log::info!("⏭️ 跳过同步文本: {}", reason);
+        return CapturedText {
+            content,
+            skipped: Some(SkipReason::Filtered),
+            representations: Vec::new(),
+        };
+    }
+
+    if is_duplicate(&content, Duration::from_secs(settings.dedup_window_secs)) {
+        log::info!("⏭️ 内容与最近复制的相同，跳过同步");
+        return CapturedText {
+            content,
+            skipped: Some(SkipReason::Duplicate),
+            representations: Vec::new(),
+        };
+    }
+
+    if settings.sensitive_action != SensitiveAction::Off {
+        if let Some(kind) = sensitive::detect(&content) {
+            let local_only = settings.sensitive_action == SensitiveAction::LocalOnly
+                && match history::insert_local_text(&content) {
+                    Ok(_) => true,
+                    Err(e) => {
+                        log::warn!("⚠️ 保存本地记录失败: {}", e);
+                        false
+                    }
+                };
+            log::info!("🔒 疑似敏感内容 ({:?})，不同步", kind);
+
+            let _ = app.emit(
+                "capture-skipped",
+                CaptureSkipped {
+                    reason: SkipReason::Sensitive,
+                    kind: Some(kind),
+                    local_only,
+                    app_name: None,
+                },
+            );
+            return CapturedText {
+                content,
+                skipped: Some(SkipReason::Sensitive),
+                representations: Vec::new(),
+            };
+        }
+    }
+
+    // 保留原始格式，复制回剪贴板时恢复并随内容同步（去除格式或内容被打码时只保留纯文本）
+    let representations = if settings.strip_formatting || redacted {
+        Vec::new()
+    } else {
+        if let Err(e) = clipboard::remember_rich_formats(&content, &formats) {
+            log::warn!("⚠️ 保存富文本格式失败: {}", e);
+        }
+        formats.to_representations(&content)
+    };
+
+    events::emit(ClipboardCaptured {
+        content_type: "text".to_string(),
+        size: content.len() as u64,
+    });
+    CapturedText {
+        content,
+        skipped: None,
+        representations,
+    }
+}
+
+/// Tauri 命令：处理剪贴板中复制的文件列表，记录可上传的文件
+///
+/// 文件列表（CF_HDROP / NSFilenamesPboardType / text/uri-list）由 Rust 端直接从系统剪贴板读取，
+/// 不接受前端传入的路径；记录后的文件允许前端通过 `upload_file_from_path` 读取，或调用 `upload_captured_files` 上传
+#[tauri::command]
+pub fn process_file_capture(webview: Webview) -> Result<CapturedFiles, CloudPasteError> {
+    crate::sandbox::check_window(&webview)?;
+    let paths = clipboard::read_files()?;
+
+    let mut files = Vec::new();
+    for path in &paths {
+        let Ok(metadata) = path.metadata() else {
+            log::warn!("⚠️ 剪贴板中的文件不存在: {:?}", path);
+            continue;
+        };
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if metadata.is_file() {
+            if let Err(reason) = sync_filter::check("file", Some(&name), None, metadata.len()) {
+                log::info!("⏭️ 跳过文件 {}: {}", name, reason);
+                continue;
+            }
+        }
+        files.push(CapturedFile {
+            path: path.to_string_lossy().to_string(),
+            name,
+            size: metadata.len(),
+            is_dir: metadata.is_dir(),
+        });
+    }
+
+    let skipped = if files.iter().all(|file| file.is_dir) {
+        Some(SkipReason::Filtered)
+    } else if is_duplicate(
+        &files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Duration::from_secs(settings().dedup_window_secs),
+    ) {
+        log::info!("⏭️ 文件与最近复制的相同，跳过");
+        Some(SkipReason::Duplicate)
+    } else {
+        None
+    };
+
+    let uploadable: Vec<PathBuf> = files
+        .iter()
+        .filter(|file| !file.is_dir)
+        .map(|file| PathBuf::from(&file.path))
+        .collect();
+    if skipped.is_none() {
+        for path in &uploadable {
+            crate::sandbox::grant(path);
+        }
+        log::info!("📁 剪贴板中复制了 {} 个文件", uploadable.len());
+        events::emit(ClipboardCaptured {
+            content_type: "file".to_string(),
+            size: files.iter().filter(|f| !f.is_dir).map(|f| f.size).sum(),
+        });
+        if let Ok(mut captured) = CAPTURED_FILES.lock() {
+            *captured = uploadable;
+        }
+    }
+
+    Ok(CapturedFiles { files, skipped })
+}
+
+/// Tauri 命令：上传最近一次从剪贴板采集的文件并创建剪贴板项，返回分享链接
+///
+/// 由 Rust 端直接从磁盘读取（大文件流式上传）；部分文件失败时返回成功的链接，全部失败时返回错误
+#[tauri::command]
+pub async fn upload_captured_files() -> Result<Vec<String>, CloudPasteError> {
+    let paths = CAPTURED_FILES
+        .lock()
+        .map(|mut captured| std::mem::take(&mut *captured))
+        .unwrap_or_default();
+    if paths.is_empty() {
+        return Err(CloudPasteError::InvalidInput(
+            "没有待上传的文件".to_string(),
+        ));
+    }
+
+    let mut links = Vec::new();
+    let mut last_error = None;
+    for path in &paths {
+        match crate::send::send_file(path).await {
+            Ok(link) => links.push(link),
+            Err(e) => {
+                log::error!("❌ 上传 {:?} 失败: {}", path, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if links.is_empty() => Err(e),
+        _ => Ok(links),
+    }
+}
+
+/// Tauri 命令：获取剪贴板采集设置
+#[tauri::command]
+pub fn get_capture_settings() -> CaptureSettings {
+    settings()
+}
+
+/// Tauri 命令：更新剪贴板采集设置
+#[tauri::command]
+pub fn set_capture_settings(
+    app: AppHandle,
+    settings: CaptureSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
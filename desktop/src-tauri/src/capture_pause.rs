@@ -0,0 +1,176 @@
+use crate::error::CloudPasteError;
+use crate::events::{self, CapturePauseChanged};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::AppHandle;
+
+/// 系统托盘图标 ID
+pub const TRAY_ID: &str = "main";
+
+/// 采集暂停状态（只保存在内存中，重启后恢复采集）
+#[derive(Debug, Clone, Default)]
+struct PauseState {
+    paused: bool,
+    /// 自动恢复的时间，为空表示直到手动恢复
+    resumes_at: Option<DateTime<Utc>>,
+    /// 每次暂停 / 恢复时递增，旧的自动恢复任务据此失效
+    generation: u64,
+}
+
+/// 返回给前端的暂停状态
+#[derive(Debug, Clone, Serialize)]
+pub struct PauseStatus {
+    pub paused: bool,
+    /// 自动恢复的时间（RFC 3339）
+    pub resumes_at: Option<String>,
+}
+
+static STATE: Lazy<Mutex<PauseState>> = Lazy::new(|| Mutex::new(PauseState::default()));
+
+fn status(state: &PauseState) -> PauseStatus {
+    PauseStatus {
+        paused: state.paused,
+        resumes_at: state.resumes_at.map(|at| at.to_rfc3339()),
+    }
+}
+
+/// 是否暂停了剪贴板采集（已过自动恢复时间时视为未暂停）
+pub fn is_paused() -> bool {
+    STATE
+        .lock()
+        .is_ok_and(|state| state.paused && state.resumes_at.is_none_or(|at| Utc::now() < at))
+}
+
+/// 暂停时显示的托盘图标：将默认图标转为半透明灰度
+fn paused_icon(icon: &Image<'_>) -> Image<'static> {
+    let mut rgba = icon.rgba().to_vec();
+    for pixel in rgba.chunks_exact_mut(4) {
+        let gray =
+            (u32::from(pixel[0]) * 30 + u32::from(pixel[1]) * 59 + u32::from(pixel[2]) * 11) / 100;
+        pixel[0] = gray as u8;
+        pixel[1] = gray as u8;
+        pixel[2] = gray as u8;
+        pixel[3] /= 2;
+    }
+    Image::new_owned(rgba, icon.width(), icon.height())
+}
+
+/// 按暂停状态切换托盘图标和提示文字
+fn update_tray(app: &AppHandle, status: &PauseStatus) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let Some(icon) = app.default_window_icon() else {
+        return;
+    };
+
+    let (icon, tooltip) = if status.paused {
+        (paused_icon(icon), "CloudPaste（已暂停采集）")
+    } else {
+        (icon.clone().to_owned(), "CloudPaste")
+    };
+    if let Err(e) = tray.set_icon(Some(icon)) {
+        log::warn!("⚠️ 更新托盘图标失败: {}", e);
+    }
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+fn set_state(
+    app: &AppHandle,
+    paused: bool,
+    resumes_at: Option<DateTime<Utc>>,
+) -> Result<u64, String> {
+    let (generation, status) = {
+        let mut state = STATE
+            .lock()
+            .map_err(|e| format!("无法锁定暂停状态: {}", e))?;
+        state.paused = paused;
+        state.resumes_at = resumes_at;
+        state.generation += 1;
+        (state.generation, status(&state))
+    };
+
+    update_tray(app, &status);
+    events::emit(CapturePauseChanged {
+        paused: status.paused,
+        resumes_at: status.resumes_at,
+    });
+    Ok(generation)
+}
+
+/// 暂停剪贴板采集，`minutes` 为空时直到手动恢复
+pub fn pause(app: &AppHandle, minutes: Option<u64>) -> Result<PauseStatus, String> {
+    let duration = minutes.map(|minutes| Duration::from_secs(minutes * 60));
+    let resumes_at = duration
+        .map(|duration| chrono::Duration::from_std(duration).map(|d| Utc::now() + d))
+        .transpose()
+        .map_err(|e| format!("暂停时长无效: {}", e))?;
+    let generation = set_state(app, true, resumes_at)?;
+
+    match minutes {
+        Some(minutes) => log::info!("⏸️ 已暂停剪贴板采集 {} 分钟", minutes),
+        None => log::info!("⏸️ 已暂停剪贴板采集，直到手动恢复"),
+    }
+
+    if let Some(duration) = duration {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(duration).await;
+            // 期间重新暂停或已手动恢复时不处理
+            let current = STATE.lock().map(|state| state.generation).unwrap_or(0);
+            if current == generation {
+                if let Err(e) = resume(&app) {
+                    log::warn!("⚠️ 自动恢复采集失败: {}", e);
+                }
+            }
+        });
+    }
+    Ok(get_capture_pause())
+}
+
+/// 恢复剪贴板采集
+pub fn resume(app: &AppHandle) -> Result<(), String> {
+    set_state(app, false, None)?;
+    log::info!("▶️ 已恢复剪贴板采集");
+    Ok(())
+}
+
+/// 托盘菜单：已暂停时恢复，否则暂停直到手动恢复
+pub fn toggle_from_tray(app: &AppHandle) {
+    let result = if is_paused() {
+        resume(app)
+    } else {
+        pause(app, None).map(|_| ())
+    };
+    if let Err(e) = result {
+        log::error!("❌ 切换采集暂停状态失败: {}", e);
+    }
+}
+
+/// Tauri 命令：暂停剪贴板采集 `minutes` 分钟（为空时直到手动恢复），到时自动恢复
+#[tauri::command]
+pub fn pause_capture(app: AppHandle, minutes: Option<u64>) -> Result<PauseStatus, CloudPasteError> {
+    Ok(pause(&app, minutes)?)
+}
+
+/// Tauri 命令：立即恢复剪贴板采集
+#[tauri::command]
+pub fn resume_capture(app: AppHandle) -> Result<(), CloudPasteError> {
+    Ok(resume(&app)?)
+}
+
+/// Tauri 命令：获取采集暂停状态
+#[tauri::command]
+pub fn get_capture_pause() -> PauseStatus {
+    STATE
+        .lock()
+        .map(|state| status(&state))
+        .unwrap_or(PauseStatus {
+            paused: false,
+            resumes_at: None,
+        })
+}
@@ -0,0 +1,376 @@
+use crate::api::{self, ClipboardItem, Representation};
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use crate::{http, image_cache};
+use clipboard_rs::common::RustImage;
+use clipboard_rs::{Clipboard, ClipboardContent, ClipboardContext, ContentFormat, RustImageData};
+use once_cell::sync::Lazy;
+use rusqlite::{params, OptionalExtension};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// 最多保留的富文本格式条数（超出时删除最早采集的）
+const MAX_RICH_FORMATS: i64 = 2000;
+
+/// 密码管理器写入剪贴板时附带的格式，带有这些格式的内容不应记录
+const CONCEALED_FORMATS: &[&str] = &[
+    // macOS（nspasteboard.org 约定）
+    "org.nspasteboard.ConcealedType",
+    "org.nspasteboard.TransientType",
+    "com.agilebits.onepassword",
+    // Windows
+    "ExcludeClipboardContentFromMonitorProcessing",
+];
+
+/// 写入剪贴板使用的上下文（Linux 下需在进程内持续持有剪贴板内容）
+static CONTEXT: Lazy<Mutex<Option<ClipboardContext>>> = Lazy::new(|| Mutex::new(None));
+
+/// 纯文本内容对应的 HTML / RTF 格式
+#[derive(Debug, Clone, Default)]
+pub struct RichFormats {
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+impl RichFormats {
+    pub fn is_empty(&self) -> bool {
+        self.html.is_none() && self.rtf.is_none()
+    }
+
+    /// 从剪贴板项的表示格式中取出 HTML / RTF
+    pub fn from_representations(representations: &[Representation]) -> Self {
+        Self {
+            html: api::representation(representations, "html").map(str::to_string),
+            rtf: api::representation(representations, "rtf").map(str::to_string),
+        }
+    }
+
+    /// 转为同步用的表示格式列表，没有富文本格式时为空（只有纯文本内容本身）
+    pub fn to_representations(&self, text: &str) -> Vec<Representation> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut representations = vec![Representation::new("text", text.to_string())];
+        if let Some(html) = &self.html {
+            representations.push(Representation::new("html", html.clone()));
+        }
+        if let Some(rtf) = &self.rtf {
+            representations.push(Representation::new("rtf", rtf.clone()));
+        }
+        representations
+    }
+}
+
+fn content_hash(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+fn with_context<T>(f: impl FnOnce(&ClipboardContext) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = CONTEXT
+        .lock()
+        .map_err(|e| format!("无法锁定剪贴板: {}", e))?;
+    if guard.is_none() {
+        *guard = Some(ClipboardContext::new().map_err(|e| format!("打开剪贴板失败: {}", e))?);
+    }
+    f(guard.as_ref().ok_or_else(|| "打开剪贴板失败".to_string())?)
+}
+
+/// 同时写入多种格式，粘贴时由目标应用选择合适的格式
+pub fn write(contents: Vec<ClipboardContent>) -> Result<(), String> {
+    with_context(|context| {
+        context
+            .set(contents)
+            .map_err(|e| format!("写入剪贴板失败: {}", e))
+    })
+}
+
+/// 读取剪贴板中的纯文本，没有文本时为空
+pub fn read_text() -> Result<Option<String>, String> {
+    with_context(|context| {
+        if !context.has(ContentFormat::Text) {
+            return Ok(None);
+        }
+        context
+            .get_text()
+            .map(Some)
+            .map_err(|e| format!("读取剪贴板失败: {}", e))
+    })
+}
+
+/// 读取剪贴板中复制的文件列表（CF_HDROP / NSFilenamesPboardType / text/uri-list），没有文件时为空
+pub fn read_files() -> Result<Vec<PathBuf>, String> {
+    with_context(|context| {
+        if !context.has(ContentFormat::Files) {
+            return Ok(Vec::new());
+        }
+        let files = context
+            .get_files()
+            .map_err(|e| format!("读取剪贴板失败: {}", e))?;
+        Ok(files
+            .iter()
+            .filter_map(|file| match file.strip_prefix("file://") {
+                Some(_) => url::Url::parse(file).ok()?.to_file_path().ok(),
+                None => Some(PathBuf::from(file)),
+            })
+            .collect())
+    })
+}
+
+/// 剪贴板内容是否带有密码管理器的标记（不应记录和同步）
+pub fn has_concealed_marker() -> Result<bool, String> {
+    with_context(|context| {
+        let formats = context
+            .available_formats()
+            .map_err(|e| format!("读取剪贴板格式失败: {}", e))?;
+        if formats
+            .iter()
+            .any(|format| CONCEALED_FORMATS.contains(&format.as_str()))
+        {
+            return Ok(true);
+        }
+
+        let value = |name: &str| {
+            formats
+                .iter()
+                .any(|format| format == name)
+                .then(|| context.get_buffer(name).ok())
+                .flatten()
+        };
+        // Windows：CanIncludeInClipboardHistory 为 0 表示不允许记录到剪贴板历史
+        let excluded_from_history = value("CanIncludeInClipboardHistory")
+            .is_some_and(|data| data.iter().all(|byte| *byte == 0));
+        // KDE：x-kde-passwordManagerHint 为 secret
+        let kde_secret =
+            value("x-kde-passwordManagerHint").is_some_and(|data| data.trim_ascii() == b"secret");
+        Ok(excluded_from_history || kde_secret)
+    })
+}
+
+/// 记录采集到的文本的原始 HTML / RTF 格式（以同步的纯文本内容为键），复制回剪贴板时恢复
+pub fn remember_rich_formats(content: &str, formats: &RichFormats) -> Result<(), String> {
+    if formats.is_empty() || content.is_empty() {
+        return Ok(());
+    }
+
+    let hash = content_hash(content);
+    let captured_at = chrono::Utc::now().to_rfc3339();
+    history::with_db(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO rich_formats (content_hash, html, rtf, captured_at) \
+             VALUES (?1, ?2, ?3, ?4)",
+            params![hash, formats.html, formats.rtf, captured_at],
+        )?;
+        conn.execute(
+            "DELETE FROM rich_formats WHERE content_hash NOT IN \
+             (SELECT content_hash FROM rich_formats ORDER BY captured_at DESC LIMIT ?1)",
+            params![MAX_RICH_FORMATS],
+        )
+    })?;
+    Ok(())
+}
+
+/// 记录从其他设备同步来的文本项附带的 HTML / RTF 格式，在本机复制时同样可以恢复
+pub fn remember_item_formats(items: &[ClipboardItem]) {
+    for item in items.iter().filter(|item| item.content_type == "text") {
+        let formats = RichFormats::from_representations(&item.representations);
+        if let Err(e) = remember_rich_formats(&item.content, &formats) {
+            log::warn!("⚠️ 保存富文本格式失败: {}", e);
+        }
+    }
+}
+
+/// 删除文本内容采集时记录的 HTML / RTF 格式
+pub fn forget_rich_formats(content: &str) -> Result<(), String> {
+    let hash = content_hash(content);
+    history::with_db(|conn| {
+        conn.execute(
+            "DELETE FROM rich_formats WHERE content_hash = ?1",
+            params![hash],
+        )
+    })?;
+    Ok(())
+}
+
+/// 查找文本内容采集时的 HTML / RTF 格式
+pub fn rich_formats(content: &str) -> Result<RichFormats, String> {
+    let hash = content_hash(content);
+    let formats = history::with_db(|conn| {
+        conn.query_row(
+            "SELECT html, rtf FROM rich_formats WHERE content_hash = ?1",
+            params![hash],
+            |row| {
+                Ok(RichFormats {
+                    html: row.get(0)?,
+                    rtf: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+    })?;
+    Ok(formats.unwrap_or_default())
+}
+
+/// 纯文本及其 HTML / RTF 格式的剪贴板内容
+fn text_contents(content: String, formats: RichFormats) -> Vec<ClipboardContent> {
+    let mut contents = vec![ClipboardContent::Text(content)];
+    if let Some(html) = formats.html {
+        contents.push(ClipboardContent::Html(html));
+    }
+    if let Some(rtf) = formats.rtf {
+        contents.push(ClipboardContent::Rtf(rtf));
+    }
+    contents
+}
+
+/// Tauri 命令：将文本记录复制回剪贴板，采集时带有 HTML / RTF 格式的同时恢复这些格式
+///
+/// 返回是否恢复了富文本格式
+#[tauri::command]
+pub fn copy_text_item(content: String) -> Result<bool, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let formats = rich_formats(&content).unwrap_or_else(|e| {
+        log::warn!("⚠️ 读取富文本格式失败: {}", e);
+        RichFormats::default()
+    });
+    let restored = !formats.is_empty();
+    write(text_contents(content, formats))?;
+
+    if restored {
+        log::info!("📋 已恢复富文本格式");
+    }
+    Ok(restored)
+}
+
+/// 写入剪贴板时选择的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardFormat {
+    /// 纯文本（文件记录为下载链接）
+    Text,
+    /// HTML（同时写入纯文本，供不支持 HTML 的应用使用）
+    Html,
+    /// 图片数据
+    Image,
+    /// 本地文件路径（粘贴到资源管理器 / 访达时为文件）
+    Files,
+}
+
+/// 将记录的文件下载到缓存，并以原文件名复制到临时目录（缓存文件名为 URL 哈希）
+async fn local_file(app: &AppHandle, entry: &HistoryEntry) -> Result<PathBuf, CloudPasteError> {
+    let url = http::absolute_url(&entry.item.content)?;
+    let cached = image_cache::ensure_cached(app, &url).await?;
+
+    let Some(file_name) = entry
+        .item
+        .file_name
+        .as_deref()
+        .and_then(|name| Path::new(name).file_name())
+    else {
+        return Ok(cached);
+    };
+    let dir = std::env::temp_dir()
+        .join("CloudPaste")
+        .join(entry.item.id.to_string());
+    std::fs::create_dir_all(&dir).map_err(|e| CloudPasteError::io("创建临时目录失败", e))?;
+    let path = dir.join(file_name);
+    std::fs::copy(&cached, &path).map_err(|e| CloudPasteError::io("复制文件失败", e))?;
+    Ok(path)
+}
+
+/// 按指定格式生成记录的剪贴板内容
+async fn contents_for(
+    app: &AppHandle,
+    entry: &HistoryEntry,
+    format: ClipboardFormat,
+) -> Result<Vec<ClipboardContent>, CloudPasteError> {
+    let item = &entry.item;
+    let is_text = item.content_type == "text";
+
+    match format {
+        ClipboardFormat::Text if is_text => Ok(vec![ClipboardContent::Text(item.content.clone())]),
+        ClipboardFormat::Text => Ok(vec![ClipboardContent::Text(http::absolute_url(
+            &item.content,
+        )?)]),
+        ClipboardFormat::Html => {
+            // 同步来的表示格式优先，其次是本机采集时记录的格式
+            let html = match api::representation(&item.representations, "html") {
+                Some(html) => Some(html.to_string()),
+                None if is_text => rich_formats(&item.content)?.html,
+                None => None,
+            }
+            .ok_or_else(|| CloudPasteError::NotFound("该记录没有 HTML 格式".to_string()))?;
+            Ok(vec![
+                ClipboardContent::Text(item.content.clone()),
+                ClipboardContent::Html(html),
+            ])
+        }
+        ClipboardFormat::Image => {
+            let is_image = item.content_type == "image"
+                || item
+                    .mime_type
+                    .as_deref()
+                    .is_some_and(|mime| mime.starts_with("image/"));
+            if !is_image {
+                return Err(CloudPasteError::InvalidInput("该记录不是图片".to_string()));
+            }
+            let path = local_file(app, entry).await?;
+            let image = RustImageData::from_path(&path.to_string_lossy())
+                .map_err(|e| CloudPasteError::InvalidInput(format!("读取图片失败: {}", e)))?;
+            Ok(vec![ClipboardContent::Image(image)])
+        }
+        ClipboardFormat::Files if is_text => Err(CloudPasteError::InvalidInput(
+            "文本记录没有文件".to_string(),
+        )),
+        ClipboardFormat::Files => {
+            let path = local_file(app, entry).await?;
+            Ok(vec![ClipboardContent::Files(vec![path
+                .to_string_lossy()
+                .to_string()])])
+        }
+    }
+}
+
+/// Tauri 命令：按指定格式将记录写入剪贴板
+///
+/// 由界面选择写入纯文本、HTML、图片或文件路径，而不是由 JS 插件决定写入的格式；
+/// 图片和文件记录会先下载到本地缓存
+#[tauri::command]
+pub async fn write_clipboard(
+    app: AppHandle,
+    item_id: i64,
+    format: ClipboardFormat,
+) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    write_item(&app, item_id, Some(format)).await
+}
+
+/// 按指定格式将记录写入剪贴板
+///
+/// 未指定格式时：文本写入纯文本及同步来的或本机记录的富文本格式，图片写入图片数据，文件写入文件路径
+pub async fn write_item(
+    app: &AppHandle,
+    item_id: i64,
+    format: Option<ClipboardFormat>,
+) -> Result<(), CloudPasteError> {
+    let entry = history::get_entry(item_id)?;
+    let contents = match format {
+        Some(format) => contents_for(app, &entry, format).await?,
+        None => match entry.item.content_type.as_str() {
+            "text" => {
+                let mut formats = RichFormats::from_representations(&entry.item.representations);
+                if formats.is_empty() {
+                    formats = rich_formats(&entry.item.content)?;
+                }
+                text_contents(entry.item.content.clone(), formats)
+            }
+            "image" => contents_for(app, &entry, ClipboardFormat::Image).await?,
+            _ => contents_for(app, &entry, ClipboardFormat::Files).await?,
+        },
+    };
+    write(contents)?;
+    log::info!("📋 已写入剪贴板: 剪贴板项={}, 格式={:?}", item_id, format);
+    Ok(())
+}
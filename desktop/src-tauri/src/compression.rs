@@ -0,0 +1,92 @@
+use crate::http;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// 小于该字节数的内容不压缩
+const MIN_COMPRESS_BYTES: usize = 16 * 1024;
+
+/// zstd 压缩级别（兼顾速度和压缩率）
+const ZSTD_LEVEL: i32 = 3;
+
+/// 值得压缩的文本类文件扩展名
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "log", "json", "xml", "csv", "md", "yaml", "yml", "html", "css", "js", "ts",
+];
+
+/// 各服务器是否支持 zstd（按服务器地址缓存）
+static SERVER_SUPPORT: Lazy<Mutex<Option<(String, bool)>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Deserialize)]
+struct HealthResponse {
+    #[serde(default)]
+    compression: Vec<String>,
+}
+
+/// 服务器是否声明支持 zstd 传输压缩
+///
+/// 优先使用能力协商的结果；服务器早于能力协商时查询健康检查接口，结果按服务器地址缓存
+pub async fn server_supports_zstd() -> bool {
+    if let Some(supported) = crate::capabilities::supports(crate::capabilities::ZSTD) {
+        return supported;
+    }
+    let Ok(health_url) = http::absolute_url("/health") else {
+        return false;
+    };
+
+    if let Ok(cached) = SERVER_SUPPORT.lock() {
+        if let Some((url, supported)) = cached.as_ref() {
+            if *url == health_url {
+                return *supported;
+            }
+        }
+    }
+
+    let supported = match reqwest::get(&health_url).await {
+        Ok(response) => response
+            .json::<HealthResponse>()
+            .await
+            .map(|health| health.compression.iter().any(|c| c == "zstd"))
+            .unwrap_or(false),
+        Err(e) => {
+            log::warn!("⚠️ 查询服务器压缩支持失败: {}", e);
+            return false;
+        }
+    };
+
+    log::info!("🗜️ 服务器 zstd 压缩支持: {}", supported);
+    if let Ok(mut cached) = SERVER_SUPPORT.lock() {
+        *cached = Some((health_url, supported));
+    }
+    supported
+}
+
+/// 是否值得压缩：较大的文本类文件
+pub fn should_compress(file_name: &str, len: usize) -> bool {
+    len >= MIN_COMPRESS_BYTES
+        && Path::new(file_name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 使用 zstd 压缩
+pub fn compress(data: &[u8]) -> Result<Vec<u8>, String> {
+    zstd::encode_all(data, ZSTD_LEVEL).map_err(|e| format!("压缩失败: {}", e))
+}
+
+/// 解压 zstd 数据，解压后超过 `limit` 字节时返回错误
+pub fn decompress(data: &[u8], limit: u64) -> Result<Vec<u8>, String> {
+    let decoder = zstd::Decoder::new(data).map_err(|e| format!("解压失败: {}", e))?;
+    let mut output = Vec::new();
+    decoder
+        .take(limit + 1)
+        .read_to_end(&mut output)
+        .map_err(|e| format!("解压失败: {}", e))?;
+    if output.len() as u64 > limit {
+        return Err(format!("解压后的数据超过 {} 字节", limit));
+    }
+    Ok(output)
+}
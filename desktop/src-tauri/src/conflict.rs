@@ -0,0 +1,234 @@
+use crate::api::{self, ClipboardItemUpdate, NewClipboardItem};
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry, EDIT_CONFLICT, EDIT_NONE, EDIT_PENDING};
+use crate::storage;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+/// 编辑冲突：同一剪贴板项在本地和其他设备上都被修改
+#[derive(Debug, Clone, Serialize)]
+pub struct Conflict {
+    pub id: i64,
+    pub item_id: i64,
+    pub local_content: String,
+    pub remote_content: String,
+    pub remote_updated_at: String,
+    pub detected_at: String,
+}
+
+/// 冲突处理方式
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// 保留本地版本（覆盖服务器）
+    Local,
+    /// 保留服务器版本（丢弃本地修改）
+    Remote,
+    /// 两者都保留：原记录使用服务器版本，本地版本另存为新记录
+    Both,
+}
+
+fn get_conflict(id: i64) -> Result<Conflict, String> {
+    history::with_db(|conn| {
+        conn.query_row(
+            "SELECT id, item_id, local_content, remote_content, remote_updated_at, detected_at
+             FROM conflicts WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(Conflict {
+                    id: row.get(0)?,
+                    item_id: row.get(1)?,
+                    local_content: row.get(2)?,
+                    remote_content: row.get(3)?,
+                    remote_updated_at: row.get(4)?,
+                    detected_at: row.get(5)?,
+                })
+            },
+        )
+    })
+    .map_err(|e| format!("冲突记录不存在: {} ({})", id, e))
+}
+
+/// 将本地修改推送到服务器
+///
+/// 推送前对比服务器版本，服务器在修改基准之后也被修改时记录冲突，不覆盖服务器内容
+async fn push_edit(id: i64) -> Result<(), String> {
+    let (content, base_updated_at, base_content) = history::with_db(|conn| {
+        conn.query_row(
+            "SELECT content, base_updated_at, base_content FROM history
+             WHERE id = ?1 AND edit_state = ?2",
+            params![id, EDIT_PENDING],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            },
+        )
+    })?;
+
+    let remote = api::get_clipboard_item(id).await?;
+    let remote_changed = base_updated_at.as_deref() != Some(remote.updated_at.as_str())
+        && base_content.as_deref() != Some(remote.content.as_str());
+    if remote_changed && remote.content != content {
+        // 交给同步时的冲突检测统一记录
+        history::store_items(&[remote])?;
+        return Err(format!("剪贴板项 {} 在其他设备上也被修改，已记录冲突", id));
+    }
+
+    let update = ClipboardItemUpdate {
+        content: Some(content),
+        ..Default::default()
+    };
+    let updated = api::update_clipboard_item(id, &update).await?;
+
+    history::with_db(|conn| {
+        conn.execute(
+            "UPDATE history SET edit_state = ?1, base_updated_at = NULL, base_content = NULL
+             WHERE id = ?2",
+            params![EDIT_NONE, id],
+        )
+    })?;
+    history::store_items(&[updated])?;
+
+    log::info!("✅ 本地修改已同步: 剪贴板项={}", id);
+    Ok(())
+}
+
+/// 重试推送尚未同步的本地修改
+pub async fn push_pending_edits() {
+    let pending = history::with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT id FROM history WHERE edit_state = ?1 AND id > 0")?;
+        let rows = stmt.query_map(params![EDIT_PENDING], |row| row.get::<_, i64>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    });
+
+    for id in pending.unwrap_or_default() {
+        if let Err(e) = push_edit(id).await {
+            log::warn!("⚠️ 同步本地修改失败: 剪贴板项={}, {}", id, e);
+        }
+    }
+}
+
+/// Tauri 命令：修改文本剪贴板项的内容
+///
+/// 先保存到本地，再推送到服务器；离线时保留本地修改，下次同步时推送
+#[tauri::command]
+pub async fn edit_item(id: i64, content: String) -> Result<HistoryEntry, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let updated = history::with_db(|conn| {
+        conn.execute(
+            "UPDATE history SET content = ?1,
+                 base_updated_at = CASE WHEN edit_state = ?3 THEN base_updated_at ELSE updated_at END,
+                 base_content = CASE WHEN edit_state = ?3 THEN base_content ELSE content END,
+                 edit_state = CASE WHEN edit_state = ?4 THEN ?4 ELSE ?3 END
+             WHERE id = ?2 AND content_type = 'text'",
+            params![content, id, EDIT_PENDING, EDIT_CONFLICT],
+        )
+    })?;
+    if updated == 0 {
+        return Err(CloudPasteError::NotFound(format!(
+            "文本剪贴板项不存在: {}",
+            id
+        )));
+    }
+
+    let entry = history::get_entry(id)?;
+    history::reindex(std::slice::from_ref(&entry))?;
+    log::info!("✏️ 已修改剪贴板项: {}", id);
+
+    if id > 0 {
+        if let Err(e) = push_edit(id).await {
+            log::warn!("⚠️ 修改暂未同步到服务器: {}", e);
+        }
+    }
+
+    Ok(history::get_entry(id)?)
+}
+
+/// Tauri 命令：获取所有未处理的编辑冲突
+#[tauri::command]
+pub fn list_conflicts() -> Result<Vec<Conflict>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let conflicts = history::with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, item_id, local_content, remote_content, remote_updated_at, detected_at
+             FROM conflicts ORDER BY detected_at DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Conflict {
+                id: row.get(0)?,
+                item_id: row.get(1)?,
+                local_content: row.get(2)?,
+                remote_content: row.get(3)?,
+                remote_updated_at: row.get(4)?,
+                detected_at: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    })?;
+    Ok(conflicts)
+}
+
+/// Tauri 命令：处理编辑冲突，返回受影响的记录
+#[tauri::command]
+pub async fn resolve_conflict(
+    conflict_id: i64,
+    resolution: Resolution,
+) -> Result<Vec<HistoryEntry>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let conflict = get_conflict(conflict_id)?;
+    let item_id = conflict.item_id;
+    log::info!(
+        "🔀 处理编辑冲突: 剪贴板项={}, 方式={:?}",
+        item_id,
+        resolution
+    );
+
+    history::with_db(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        match resolution {
+            // 以服务器当前版本为新的基准，重新推送本地内容
+            Resolution::Local => tx.execute(
+                "UPDATE history SET edit_state = ?1, base_updated_at = ?2, base_content = ?3
+                 WHERE id = ?4",
+                params![
+                    EDIT_PENDING,
+                    conflict.remote_updated_at,
+                    conflict.remote_content,
+                    item_id
+                ],
+            )?,
+            Resolution::Remote | Resolution::Both => tx.execute(
+                "UPDATE history SET content = ?1, updated_at = ?2, edit_state = ?3,
+                     base_updated_at = NULL, base_content = NULL WHERE id = ?4",
+                params![
+                    conflict.remote_content,
+                    conflict.remote_updated_at,
+                    EDIT_NONE,
+                    item_id
+                ],
+            )?,
+        };
+        tx.execute("DELETE FROM conflicts WHERE id = ?1", params![conflict_id])?;
+        tx.commit()
+    })?;
+
+    let mut affected = vec![history::get_entry(item_id)?];
+    history::reindex(&affected)?;
+
+    match resolution {
+        Resolution::Local => push_edit(item_id).await?,
+        Resolution::Remote => {}
+        Resolution::Both => {
+            let item = NewClipboardItem::from_this_device(conflict.local_content, "text")?;
+            let created = storage::create_item(&item).await?;
+            history::store_items(std::slice::from_ref(&created))?;
+            affected.push(history::get_entry(created.id)?);
+        }
+    }
+
+    affected[0] = history::get_entry(item_id)?;
+    Ok(affected)
+}
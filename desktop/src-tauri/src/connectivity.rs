@@ -0,0 +1,311 @@
+use crate::error::CloudPasteError;
+use crate::{http, store};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const SETTINGS_FILE: &str = "connectivity.json";
+
+/// 在线时检查服务器的间隔
+const ONLINE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 离线时检查服务器的间隔（尽快发现恢复）
+const OFFLINE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 检查服务器的超时时间
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// CloudPaste 服务端的服务名称
+const SERVICE_NAME: &str = "CloudPaste";
+
+/// 连接设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectivitySettings {
+    /// 手动离线模式：不访问服务器，只使用本地历史
+    pub offline_mode: bool,
+}
+
+/// 当前连接状态
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityStatus {
+    /// 是否可以访问服务器（手动离线模式下为 false）
+    pub online: bool,
+    /// 服务器是否可达（最近一次检查的结果）
+    pub reachable: bool,
+    /// 是否处于手动离线模式
+    pub offline_mode: bool,
+    /// 最近一次检查的延迟（毫秒）
+    pub latency_ms: Option<u64>,
+    /// 最近一次检查失败的原因
+    pub error: Option<String>,
+    /// 最近一次检查的时间
+    pub last_checked: Option<String>,
+}
+
+impl Default for ConnectivityStatus {
+    fn default() -> Self {
+        // 首次检查完成前假定在线，避免启动时误拦截请求
+        Self {
+            online: true,
+            reachable: true,
+            offline_mode: false,
+            latency_ms: None,
+            error: None,
+            last_checked: None,
+        }
+    }
+}
+
+/// 服务器预检结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServerCheck {
+    /// 规范化后的 API 地址（可直接传给 set_api_config）
+    pub api_url: String,
+    /// 服务器是否可达
+    pub reachable: bool,
+    /// 是否为 CloudPaste 服务端
+    pub is_cloudpaste: bool,
+    /// 服务名称
+    pub service: Option<String>,
+    /// 服务端版本
+    pub version: Option<String>,
+    /// 往返延迟（毫秒）
+    pub latency_ms: Option<u64>,
+    /// 检查失败的原因
+    pub error: Option<String>,
+}
+
+/// 版本接口（旧服务端没有时使用健康检查接口）的响应
+#[derive(Deserialize)]
+struct VersionResponse {
+    service: Option<String>,
+    version: Option<String>,
+}
+
+static SETTINGS: Lazy<Mutex<ConnectivitySettings>> =
+    Lazy::new(|| Mutex::new(ConnectivitySettings::default()));
+
+static STATUS: Lazy<Mutex<ConnectivityStatus>> =
+    Lazy::new(|| Mutex::new(ConnectivityStatus::default()));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<ConnectivitySettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> ConnectivitySettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn status() -> ConnectivityStatus {
+    let mut status = STATUS.lock().map(|s| s.clone()).unwrap_or_default();
+    status.offline_mode = settings().offline_mode;
+    status.online = status.reachable && !status.offline_mode;
+    status
+}
+
+/// 当前是否可以访问服务器
+pub fn is_online() -> bool {
+    status().online
+}
+
+/// 离线时返回错误，用于在发起请求前快速失败
+pub fn ensure_online() -> Result<(), CloudPasteError> {
+    let status = status();
+    if status.offline_mode {
+        return Err(CloudPasteError::Offline("当前处于离线模式".to_string()));
+    }
+    if !status.reachable {
+        return Err(CloudPasteError::Offline(
+            "无法连接到服务器，当前离线".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 记录检查结果，在线状态变化时发送 connectivity-online / connectivity-offline 事件
+fn update(app: &AppHandle, reachable: bool, latency_ms: Option<u64>, error: Option<String>) {
+    let was_online = is_online();
+    if let Ok(mut status) = STATUS.lock() {
+        status.reachable = reachable;
+        status.latency_ms = latency_ms;
+        status.error = error;
+        status.last_checked = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    let current = status();
+    if current.online != was_online {
+        emit(app, &current);
+    }
+}
+
+fn emit(app: &AppHandle, status: &ConnectivityStatus) {
+    if status.online {
+        log::info!("🌐 已连接到服务器");
+        let _ = app.emit("connectivity-online", status);
+    } else {
+        log::warn!(
+            "📴 已离线: {}",
+            status.error.as_deref().unwrap_or("离线模式")
+        );
+        let _ = app.emit("connectivity-offline", status);
+    }
+}
+
+/// 检查服务器健康检查接口是否可达
+async fn check(app: &AppHandle) {
+    if !crate::get_api_config_status().unwrap_or(false) {
+        return;
+    }
+    let Ok(health_url) = http::absolute_url("/health") else {
+        return;
+    };
+
+    let started = Instant::now();
+    let request = reqwest::Client::new()
+        .get(&health_url)
+        .timeout(CHECK_TIMEOUT);
+    match http::send(request).await {
+        Ok(response) if response.status().is_success() => {
+            let latency = started.elapsed().as_millis() as u64;
+            update(app, true, Some(latency), None);
+        }
+        Ok(response) => update(
+            app,
+            false,
+            None,
+            Some(format!("服务器返回 HTTP {}", response.status())),
+        ),
+        Err(e) => update(app, false, None, Some(e.to_string())),
+    }
+}
+
+/// 后台任务：定期检查服务器是否可达（手动离线模式下不检查）
+pub async fn monitor(app: AppHandle) {
+    loop {
+        if !settings().offline_mode {
+            check(&app).await;
+        }
+        let interval = if status().reachable {
+            ONLINE_CHECK_INTERVAL
+        } else {
+            OFFLINE_CHECK_INTERVAL
+        };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Tauri 命令：获取当前连接状态
+#[tauri::command]
+pub fn get_connectivity_status() -> ConnectivityStatus {
+    status()
+}
+
+/// Tauri 命令：立即检查服务器是否可达并返回最新状态
+#[tauri::command]
+pub async fn check_connectivity(app: AppHandle) -> ConnectivityStatus {
+    check(&app).await;
+    status()
+}
+
+/// Tauri 命令：开启或关闭手动离线模式
+#[tauri::command]
+pub async fn set_offline_mode(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<ConnectivityStatus, CloudPasteError> {
+    let was_online = is_online();
+    {
+        let mut settings = SETTINGS
+            .lock()
+            .map_err(|e| CloudPasteError::Internal(format!("无法锁定设置: {}", e)))?;
+        settings.offline_mode = enabled;
+        store::save_json(&app, SETTINGS_FILE, &*settings)?;
+    }
+    log::info!("📴 离线模式: {}", enabled);
+
+    let current = status();
+    if current.online != was_online {
+        emit(&app, &current);
+    }
+
+    // 退出离线模式时立即检查一次，而不是等到下一个周期
+    if !enabled {
+        check(&app).await;
+    }
+    Ok(status())
+}
+
+/// 请求版本信息，返回 (HTTP 状态码, 解析结果)
+async fn fetch_version(
+    url: &str,
+) -> Result<(reqwest::StatusCode, Option<VersionResponse>), CloudPasteError> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| CloudPasteError::request("无法连接到服务器", e))?;
+    let status = response.status();
+    Ok((status, response.json::<VersionResponse>().await.ok()))
+}
+
+/// Tauri 命令：保存服务器地址前检查其是否为可用的 CloudPaste 服务端
+///
+/// 请求版本接口并测量往返延迟；旧服务端没有版本接口时改用健康检查接口
+#[tauri::command]
+pub async fn check_server(url: String) -> ServerCheck {
+    let api_url = crate::normalize_api_url(url.trim());
+    let mut result = ServerCheck {
+        api_url: api_url.clone(),
+        ..Default::default()
+    };
+    if !(api_url.starts_with("http://") || api_url.starts_with("https://")) {
+        result.error = Some("服务器地址必须以 http:// 或 https:// 开头".to_string());
+        return result;
+    }
+
+    log::info!("🩺 检查服务器: {}", api_url);
+    let started = Instant::now();
+    let mut response = fetch_version(&format!("{}/version", api_url)).await;
+    if let Ok((status, _)) = &response {
+        result.latency_ms = Some(started.elapsed().as_millis() as u64);
+        if *status == reqwest::StatusCode::NOT_FOUND {
+            let origin = api_url.trim_end_matches("/api/v1");
+            response = fetch_version(&format!("{}/health", origin)).await;
+        }
+    }
+
+    match response {
+        Ok((status, body)) => {
+            result.reachable = true;
+            if let Some(body) = body.filter(|_| status.is_success()) {
+                result.is_cloudpaste = body
+                    .service
+                    .as_deref()
+                    .is_some_and(|service| service.contains(SERVICE_NAME));
+                result.service = body.service;
+                result.version = body.version;
+            }
+            if !result.is_cloudpaste {
+                result.error = Some(format!("该地址不是 CloudPaste 服务端（HTTP {}）", status));
+            }
+        }
+        Err(e) => result.error = Some(e.to_string()),
+    }
+
+    log::info!(
+        "🩺 服务器检查结果: 可达={}, CloudPaste={}, 延迟={:?}ms",
+        result.reachable,
+        result.is_cloudpaste,
+        result.latency_ms
+    );
+    result
+}
@@ -0,0 +1,211 @@
+use crate::error::CloudPasteError;
+use crate::{http, store};
+use once_cell::sync::{Lazy, OnceCell};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "crash.json";
+
+/// 崩溃报告保存目录（位于应用数据目录下）
+const CRASH_DIR: &str = "crashes";
+
+/// 崩溃报告设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashSettings {
+    /// 用户同意后才上传崩溃报告到服务器
+    pub upload_consent: bool,
+}
+
+/// 一份崩溃报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub occurred_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    /// 发生 panic 的线程
+    pub thread: String,
+    pub message: String,
+    /// panic 发生的源码位置
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+static SETTINGS: Lazy<Mutex<CrashSettings>> = Lazy::new(|| Mutex::new(CrashSettings::default()));
+
+/// 崩溃报告目录，panic hook 中使用（此时不能再访问 AppHandle）
+static REPORT_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<CrashSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> CrashSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "未知错误".to_string()
+    }
+}
+
+/// 将崩溃报告写入磁盘，返回文件路径
+fn write_report(dir: &Path, report: &CrashReport) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", report.id));
+    let json = serde_json::to_vec_pretty(report).map_err(std::io::Error::other)?;
+    store::write_atomic(&path, &json)?;
+    Ok(path)
+}
+
+/// 安装 panic hook：记录消息、位置和调用栈到本地崩溃报告，再交给默认处理
+pub fn install(app: &AppHandle) {
+    let dir = match store::data_dir(app) {
+        Ok(dir) => dir.join(CRASH_DIR),
+        Err(e) => {
+            log::warn!("⚠️ 无法安装崩溃报告: {}", e);
+            return;
+        }
+    };
+    let _ = REPORT_DIR.set(dir);
+    let app_version = app.package_info().version.to_string();
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let now = chrono::Utc::now();
+        let report = CrashReport {
+            id: format!(
+                "crash-{}-{}",
+                now.format("%Y%m%d-%H%M%S%3f"),
+                std::process::id()
+            ),
+            occurred_at: now.to_rfc3339(),
+            app_version: app_version.clone(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: std::thread::current()
+                .name()
+                .unwrap_or("unnamed")
+                .to_string(),
+            message: panic_message(info),
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+
+        if let Some(dir) = REPORT_DIR.get() {
+            match write_report(dir, &report) {
+                Ok(path) => log::error!("💥 程序崩溃: {}，报告已保存到 {:?}", report.message, path),
+                Err(e) => log::error!("💥 程序崩溃: {}，保存报告失败: {}", report.message, e),
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+/// 读取本地保存的全部崩溃报告，按时间倒序
+fn read_reports() -> Vec<(PathBuf, CrashReport)> {
+    let Some(entries) = REPORT_DIR.get().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let content = fs::read(&path).ok()?;
+            let report = serde_json::from_slice::<CrashReport>(&content).ok()?;
+            Some((path, report))
+        })
+        .collect();
+    reports.sort_by(|a, b| b.1.occurred_at.cmp(&a.1.occurred_at));
+    reports
+}
+
+async fn upload(report: &CrashReport) -> Result<(), CloudPasteError> {
+    let request = http::api_request(Method::POST, "/crash-reports")?.json(report);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("上传崩溃报告失败", e))?;
+    http::check_response(response).await?;
+    Ok(())
+}
+
+/// 用户同意时上传尚未上传的崩溃报告，上传成功的从本地删除
+pub async fn upload_pending() {
+    if !settings().upload_consent || !crate::get_api_config_status().unwrap_or(false) {
+        return;
+    }
+
+    for (path, report) in read_reports() {
+        match upload(&report).await {
+            Ok(()) => {
+                log::info!("📮 崩溃报告已上传: {}", report.id);
+                let _ = fs::remove_file(&path);
+            }
+            Err(e) => {
+                log::warn!("⚠️ {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Tauri 命令：列出本地保存的崩溃报告
+#[tauri::command]
+pub fn list_crash_reports() -> Vec<CrashReport> {
+    read_reports()
+        .into_iter()
+        .map(|(_, report)| report)
+        .collect()
+}
+
+/// Tauri 命令：删除本地保存的全部崩溃报告
+#[tauri::command]
+pub fn clear_crash_reports() -> Result<usize, CloudPasteError> {
+    let reports = read_reports();
+    for (path, _) in &reports {
+        fs::remove_file(path).map_err(|e| format!("删除崩溃报告失败: {}", e))?;
+    }
+    Ok(reports.len())
+}
+
+/// Tauri 命令：获取崩溃报告设置
+#[tauri::command]
+pub fn get_crash_settings() -> CrashSettings {
+    settings()
+}
+
+/// Tauri 命令：更新崩溃报告设置，同意上传后立即上传已有的报告
+#[tauri::command]
+pub async fn set_crash_settings(
+    app: AppHandle,
+    settings: CrashSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    log::info!("💥 崩溃报告上传: {}", settings.upload_consent);
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+
+    upload_pending().await;
+    Ok(())
+}
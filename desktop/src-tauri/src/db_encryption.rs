@@ -0,0 +1,162 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, DbKey};
+use crate::keys::{self, MasterKey};
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "history_encryption.json";
+
+/// 是否编译了 SQLCipher（`sqlcipher` 特性）
+const SUPPORTED: bool = cfg!(feature = "sqlcipher");
+
+/// 派生数据库密钥使用的子密钥用途
+const KEY_PURPOSE: &str = "history-db";
+
+/// 历史记录数据库加密设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct EncryptionSettings {
+    enabled: bool,
+}
+
+/// 返回给前端的加密状态
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEncryptionStatus {
+    /// 当前版本是否支持加密数据库
+    pub supported: bool,
+    pub enabled: bool,
+    /// 数据库是否已解锁（已打开）
+    pub unlocked: bool,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 启动时从磁盘加载设置（需在打开历史记录数据库之前）
+///
+/// 开启或取消加密时，替换数据库文件后、保存设置前退出会导致两者不一致，以文件的实际状态为准
+pub fn load(app: &AppHandle) {
+    let saved = store::load_json::<EncryptionSettings>(app, SETTINGS_FILE).unwrap_or_default();
+    ENABLED.store(saved.enabled, Ordering::SeqCst);
+
+    match history::file_is_encrypted(app) {
+        Ok(Some(encrypted)) if encrypted != saved.enabled => {
+            log::warn!(
+                "⚠️ 历史记录数据库加密状态与设置不一致，按文件实际状态修正: {}",
+                encrypted
+            );
+            if let Err(e) = save(app, encrypted) {
+                log::error!("❌ 保存历史记录数据库加密设置失败: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log::error!("❌ 检查历史记录数据库加密状态失败: {}", e),
+    }
+}
+
+fn save(app: &AppHandle, enabled: bool) -> Result<(), CloudPasteError> {
+    store::save_json(app, SETTINGS_FILE, &EncryptionSettings { enabled })?;
+    ENABLED.store(enabled, Ordering::SeqCst);
+    Ok(())
+}
+
+/// 历史记录数据库是否已加密
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+fn ensure_supported() -> Result<(), CloudPasteError> {
+    if !SUPPORTED {
+        return Err(CloudPasteError::InvalidInput(
+            "当前版本未包含 SQLCipher，无法加密历史记录数据库".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 由主密钥派生的数据库密钥，主密钥未解锁时返回错误
+fn database_key() -> Result<DbKey, CloudPasteError> {
+    keys::subkey(KEY_PURPOSE)
+        .ok_or_else(|| CloudPasteError::InvalidInput("请先设置密码或解锁主密钥".to_string()))
+}
+
+/// 主密钥解锁后打开加密的历史记录数据库（未加密或已打开时不处理）
+pub fn open_if_locked(app: &AppHandle) -> Result<(), CloudPasteError> {
+    if !is_enabled() || history::is_open() {
+        return Ok(());
+    }
+    history::open_with_key(app, Some(database_key()?))?;
+    log::info!("🔓 历史记录数据库已解锁");
+    Ok(())
+}
+
+/// 用指定主密钥派生的密钥打开历史记录数据库（恢复中断的主密钥轮换时使用原主密钥）
+pub fn open_with_master(app: &AppHandle, master: &MasterKey) -> Result<(), CloudPasteError> {
+    history::open_with_key(app, Some(keys::derive_subkey(master, KEY_PURPOSE)))?;
+    Ok(())
+}
+
+/// 主密钥轮换时用新主密钥派生的密钥重新加密历史记录数据库（未加密时不处理）
+pub fn rotate(app: &AppHandle, master: &MasterKey) -> Result<(), CloudPasteError> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    if !history::is_open() {
+        return Err(CloudPasteError::Locked);
+    }
+    history::rekey(app, Some(keys::derive_subkey(master, KEY_PURPOSE)))?;
+    Ok(())
+}
+
+/// Tauri 命令：获取历史记录数据库的加密状态
+#[tauri::command]
+pub fn get_history_encryption() -> HistoryEncryptionStatus {
+    HistoryEncryptionStatus {
+        supported: SUPPORTED,
+        enabled: is_enabled(),
+        unlocked: history::is_open(),
+    }
+}
+
+/// Tauri 命令：用主密钥派生的密钥加密历史记录数据库（需先设置密码）
+#[tauri::command]
+pub async fn enable_history_encryption(app: AppHandle) -> Result<(), CloudPasteError> {
+    ensure_supported()?;
+    crate::applock::ensure_unlocked()?;
+    if is_enabled() {
+        return Ok(());
+    }
+    let key = database_key()?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        history::rekey(&app, Some(key))?;
+        save(&app, true)
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("加密历史记录数据库失败: {}", e)))??;
+    log::info!("🔒 历史记录数据库已加密");
+    Ok(())
+}
+
+/// Tauri 命令：取消加密历史记录数据库（需已解锁）
+#[tauri::command]
+pub async fn disable_history_encryption(app: AppHandle) -> Result<(), CloudPasteError> {
+    ensure_supported()?;
+    crate::applock::ensure_unlocked()?;
+    if !is_enabled() {
+        return Ok(());
+    }
+    if !history::is_open() {
+        return Err(CloudPasteError::Locked);
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        history::rekey(&app, None)?;
+        save(&app, false)
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("取消加密历史记录数据库失败: {}", e)))??;
+    log::info!("🔓 已取消加密历史记录数据库");
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::store;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use once_cell::sync::OnceCell;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+const DEVICE_FILE: &str = "device.json";
+
+/// 本机身份信息，首次启动时生成并保存到应用数据目录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DeviceIdentity {
+    id: String,
+    /// 签名同步内容的 ed25519 私钥（Base64），旧版本生成的身份没有
+    #[serde(default)]
+    signing_key: Option<String>,
+}
+
+static DEVICE_ID: OnceCell<String> = OnceCell::new();
+
+static SIGNING_KEY: OnceCell<SigningKey> = OnceCell::new();
+
+fn decode_signing_key(encoded: &str) -> Option<SigningKey> {
+    let bytes: [u8; 32] = BASE64.decode(encoded).ok()?.try_into().ok()?;
+    Some(SigningKey::from_bytes(&bytes))
+}
+
+/// 启动时加载设备 ID 和签名密钥，不存在时生成（UUID / ed25519 密钥对）并保存
+pub fn init(app: &AppHandle) {
+    let mut identity = store::load_json::<DeviceIdentity>(app, DEVICE_FILE).unwrap_or_default();
+    let mut changed = false;
+
+    if identity.id.is_empty() {
+        identity.id = format!("desktop_{}", uuid::Uuid::new_v4());
+        log::info!("🆔 已生成设备 ID: {}", identity.id);
+        changed = true;
+    }
+
+    let signing_key = match identity.signing_key.as_deref().and_then(decode_signing_key) {
+        Some(key) => key,
+        None => {
+            let mut secret = [0u8; 32];
+            OsRng.fill_bytes(&mut secret);
+            identity.signing_key = Some(BASE64.encode(secret));
+            log::info!("🔑 已生成设备签名密钥");
+            changed = true;
+            SigningKey::from_bytes(&secret)
+        }
+    };
+
+    if changed {
+        if let Err(e) = store::save_json(app, DEVICE_FILE, &identity) {
+            log::error!("❌ 保存设备身份失败: {}", e);
+        }
+    }
+    let _ = DEVICE_ID.set(identity.id);
+    let _ = SIGNING_KEY.set(signing_key);
+}
+
+/// 本机签名公钥（Base64），初始化前为空
+pub fn public_key() -> Option<String> {
+    SIGNING_KEY
+        .get()
+        .map(|key| BASE64.encode(key.verifying_key().as_bytes()))
+}
+
+/// 用本机私钥签名，返回 Base64 签名，初始化前为空
+pub fn sign(message: &[u8]) -> Option<String> {
+    SIGNING_KEY
+        .get()
+        .map(|key| BASE64.encode(key.sign(message).to_bytes()))
+}
+
+/// 获取设备 ID（主机名变化或多台设备同名时保持唯一且不变）
+///
+/// 初始化前调用时退回到基于主机名的旧 ID
+pub fn id() -> String {
+    match DEVICE_ID.get() {
+        Some(id) => id.clone(),
+        None => format!(
+            "desktop_{}",
+            hostname::get().unwrap_or_default().to_string_lossy()
+        ),
+    }
+}
@@ -0,0 +1,339 @@
+use crate::api::{ClipboardItem, NewClipboardItem, Representation};
+use crate::error::CloudPasteError;
+use crate::{device, store};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tokio::io::AsyncReadExt;
+
+const TRUSTED_FILE: &str = "trusted_devices.json";
+
+/// 已信任的设备：设备 ID 到签名公钥（Base64）
+static TRUSTED: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 收到的剪贴板项的可信程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemTrust {
+    /// 签名有效，且来自本机或已信任的设备
+    Verified,
+    /// 签名有效，但设备未被信任或公钥与信任时不同
+    Unverified,
+    /// 签名与内容不符（内容可能被篡改）
+    Invalid,
+    /// 没有签名（旧版本客户端或其他来源）
+    Unsigned,
+}
+
+/// 本机签名身份
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceKey {
+    pub device_id: String,
+    pub public_key: String,
+    /// 公钥指纹，供在两台设备上人工比对
+    pub fingerprint: String,
+}
+
+/// 启动时从磁盘加载已信任的设备
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<HashMap<String, String>>(app, TRUSTED_FILE) {
+        if let Ok(mut trusted) = TRUSTED.lock() {
+            *trusted = saved;
+        }
+    }
+}
+
+fn save(app: &AppHandle, trusted: &HashMap<String, String>) -> Result<(), CloudPasteError> {
+    Ok(store::save_json(app, TRUSTED_FILE, trusted)?)
+}
+
+/// 签名覆盖的内容：设备 ID、内容类型、内容（文件为下载地址）、文件内容哈希和各表示格式
+///
+/// 每个字段带长度前缀，内容中的换行不会造成歧义
+fn signing_message(
+    device_id: &str,
+    content_type: &str,
+    content: &str,
+    representations: &[Representation],
+    file_hash: Option<&str>,
+) -> Vec<u8> {
+    fn push_field(message: &mut Vec<u8>, value: &str) {
+        message.extend_from_slice(format!("{}:", value.len()).as_bytes());
+        message.extend_from_slice(value.as_bytes());
+        message.push(b'\n');
+    }
+
+    let mut message = b"cloudpaste-item-v2\n".to_vec();
+    push_field(&mut message, device_id);
+    push_field(&mut message, content_type);
+    push_field(&mut message, content);
+    push_field(&mut message, file_hash.unwrap_or_default());
+    push_field(&mut message, &representations.len().to_string());
+    for representation in representations {
+        push_field(&mut message, &representation.format);
+        push_field(
+            &mut message,
+            representation.mime_type.as_deref().unwrap_or_default(),
+        );
+        push_field(&mut message, &representation.content);
+    }
+    message
+}
+
+/// 内容的 SHA-256（十六进制）
+pub fn content_hash(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// 按块读取文件计算 SHA-256（十六进制），不把整个文件读入内存
+pub async fn file_content_hash(path: &Path) -> Result<String, CloudPasteError> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 256 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// 公钥指纹：SHA-256 的前 16 字节，按 4 位十六进制分组
+pub fn fingerprint(public_key: &str) -> String {
+    let digest = Sha256::digest(public_key.as_bytes());
+    digest[..16]
+        .chunks(2)
+        .map(|pair| format!("{:02X}{:02X}", pair[0], pair[1]))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 剪贴板项的签名及对应公钥（均为 Base64）
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemSignature {
+    pub signature: Option<String>,
+    pub public_key: Option<String>,
+}
+
+fn sign(
+    device_id: &str,
+    content_type: &str,
+    content: &str,
+    representations: &[Representation],
+    file_hash: Option<&str>,
+) -> ItemSignature {
+    let message = signing_message(device_id, content_type, content, representations, file_hash);
+    ItemSignature {
+        signature: device::sign(&message),
+        public_key: device::public_key(),
+    }
+}
+
+/// 用本机密钥签名要发送的剪贴板项
+pub fn sign_item(item: &mut NewClipboardItem) {
+    let device_id = item.device_id.clone().unwrap_or_else(device::id);
+    let signed = sign(
+        &device_id,
+        &item.content_type,
+        &item.content,
+        &item.representations,
+        item.file_hash.as_deref(),
+    );
+    item.signature = signed.signature;
+    item.public_key = signed.public_key;
+}
+
+/// 校验 Base64 编码的 ed25519 签名
+pub fn verify_signature(public_key: &str, signature: &str, message: &[u8]) -> bool {
+    let key = BASE64
+        .decode(public_key)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok());
+    let signature = BASE64
+        .decode(signature)
+        .ok()
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+        .map(|bytes| Signature::from_bytes(&bytes));
+    match (key, signature) {
+        (Some(key), Some(signature)) => key.verify(message, &signature).is_ok(),
+        _ => false,
+    }
+}
+
+/// 设备是否已被信任，且公钥与信任时相同
+pub fn is_trusted(device_id: &str, public_key: &str) -> bool {
+    TRUSTED
+        .lock()
+        .is_ok_and(|trusted| trusted.get(device_id).map(String::as_str) == Some(public_key))
+}
+
+/// 校验收到的剪贴板项的签名及发送设备是否可信
+///
+/// 服务器被攻破时可以伪造内容，但无法伪造已信任设备的签名
+pub fn verify(item: &ClipboardItem) -> ItemTrust {
+    let (Some(signature), Some(public_key), Some(device_id)) = (
+        item.signature.as_deref(),
+        item.public_key.as_deref(),
+        item.device_id.as_deref(),
+    ) else {
+        return ItemTrust::Unsigned;
+    };
+
+    let message = signing_message(
+        device_id,
+        &item.content_type,
+        &item.content,
+        &item.representations,
+        item.file_hash.as_deref(),
+    );
+    if !verify_signature(public_key, signature, &message) {
+        log::warn!(
+            "⚠️ 剪贴板项签名无效: 剪贴板项={}, 设备={}",
+            item.id,
+            device_id
+        );
+        return ItemTrust::Invalid;
+    }
+
+    let from_this_device =
+        device_id == device::id() && device::public_key().as_deref() == Some(public_key);
+    if matches!(item.content_type.as_str(), "image" | "file") && item.file_hash.is_none() {
+        // 签名未覆盖文件内容，服务器可以替换下载到的文件
+        log::warn!("⚠️ 剪贴板项的签名不包含文件哈希: {}", item.id);
+        ItemTrust::Unverified
+    } else if from_this_device || is_trusted(device_id, public_key) {
+        ItemTrust::Verified
+    } else {
+        log::warn!("⚠️ 剪贴板项来自未验证的设备: {}", device_id);
+        ItemTrust::Unverified
+    }
+}
+
+/// Tauri 命令：获取本机签名公钥及指纹
+#[tauri::command]
+pub fn get_device_key() -> Result<DeviceKey, CloudPasteError> {
+    let public_key = device::public_key().ok_or_else(|| "设备签名密钥尚未初始化".to_string())?;
+    Ok(DeviceKey {
+        device_id: device::id(),
+        fingerprint: fingerprint(&public_key),
+        public_key,
+    })
+}
+
+/// 下载图片或文件并与签名中的文件哈希比对，不一致时删除缓存
+async fn verify_file(app: &AppHandle, url: &str, expected: &str) -> Result<bool, CloudPasteError> {
+    let path = crate::storage::ensure_cached(app, url).await?;
+    if file_content_hash(&path).await? == expected {
+        return Ok(true);
+    }
+    crate::storage::evict(app, url)?;
+    Ok(false)
+}
+
+/// Tauri 命令：签名要通过 WebSocket 同步的剪贴板内容
+#[tauri::command]
+pub fn sign_clipboard_item(
+    content: String,
+    content_type: String,
+    device_id: Option<String>,
+    representations: Option<Vec<Representation>>,
+    file_hash: Option<String>,
+) -> ItemSignature {
+    let device_id = device_id.unwrap_or_else(device::id);
+    sign(
+        &device_id,
+        &content_type,
+        &content,
+        &representations.unwrap_or_default(),
+        file_hash.as_deref(),
+    )
+}
+
+/// Tauri 命令：校验收到的剪贴板项；签名可信的图片和文件会下载并校验文件哈希
+#[tauri::command]
+pub async fn verify_clipboard_item(app: AppHandle, item: ClipboardItem) -> ItemTrust {
+    let trust = verify(&item);
+    let Some(expected) = item.file_hash.as_deref() else {
+        return trust;
+    };
+    if trust != ItemTrust::Verified {
+        return trust;
+    }
+    match verify_file(&app, &item.content, expected).await {
+        Ok(true) => ItemTrust::Verified,
+        Ok(false) => {
+            log::warn!("⚠️ 下载的文件与签名中的哈希不符: 剪贴板项={}", item.id);
+            ItemTrust::Invalid
+        }
+        Err(e) => {
+            log::warn!("⚠️ 无法校验文件哈希: {}", e);
+            ItemTrust::Unverified
+        }
+    }
+}
+
+/// Tauri 命令：获取公钥指纹（信任设备前与该设备上显示的指纹比对）
+#[tauri::command]
+pub fn get_key_fingerprint(public_key: String) -> String {
+    fingerprint(&public_key)
+}
+
+/// Tauri 命令：列出已信任的设备（设备 ID 到公钥）
+#[tauri::command]
+pub fn list_trusted_devices() -> Result<HashMap<String, String>, CloudPasteError> {
+    Ok(TRUSTED
+        .lock()
+        .map_err(|e| format!("无法锁定信任列表: {}", e))?
+        .clone())
+}
+
+/// Tauri 命令：信任设备的签名公钥（设备更换密钥后需重新信任）
+#[tauri::command]
+pub fn trust_device(
+    app: AppHandle,
+    device_id: String,
+    public_key: String,
+) -> Result<(), CloudPasteError> {
+    let mut trusted = TRUSTED
+        .lock()
+        .map_err(|e| format!("无法锁定信任列表: {}", e))?;
+    trusted.insert(device_id.clone(), public_key);
+    save(&app, &trusted)?;
+    log::info!("🤝 已信任设备: {}", device_id);
+    Ok(())
+}
+
+/// Tauri 命令：取消信任设备
+#[tauri::command]
+pub fn untrust_device(app: AppHandle, device_id: String) -> Result<(), CloudPasteError> {
+    let mut trusted = TRUSTED
+        .lock()
+        .map_err(|e| format!("无法锁定信任列表: {}", e))?;
+    if trusted.remove(&device_id).is_some() {
+        save(&app, &trusted)?;
+        log::info!("🚫 已取消信任设备: {}", device_id);
+    }
+    Ok(())
+}
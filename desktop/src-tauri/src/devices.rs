@@ -0,0 +1,103 @@
+use crate::error::CloudPasteError;
+use crate::{http, GLOBAL_API_CONFIG};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// 服务器上登记的设备
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceInfo {
+    pub id: i64,
+    pub device_id: String,
+    pub device_name: String,
+    pub device_type: Option<String>,
+    pub last_sync: Option<String>,
+    pub created_at: String,
+    /// 是否为本机
+    #[serde(default)]
+    pub is_current: bool,
+}
+
+/// 修改设备信息的请求体
+#[derive(Debug, Clone, Serialize)]
+struct DeviceUpdate<'a> {
+    device_name: &'a str,
+}
+
+fn current_device_id() -> Result<String, CloudPasteError> {
+    GLOBAL_API_CONFIG
+        .lock()
+        .map(|config| config.device_id.clone())
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))
+}
+
+/// Tauri 命令：获取服务器上登记的全部设备
+#[tauri::command]
+pub async fn list_devices() -> Result<Vec<DeviceInfo>, CloudPasteError> {
+    let request = http::api_request(Method::GET, "/devices/")?;
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("获取设备列表失败", e))?;
+
+    let mut devices: Vec<DeviceInfo> = http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析设备列表失败: {}", e)))?;
+
+    let current = current_device_id()?;
+    for device in &mut devices {
+        device.is_current = device.device_id == current;
+    }
+    Ok(devices)
+}
+
+/// Tauri 命令：重命名本机，同时更新服务器上的记录和本地配置
+#[tauri::command]
+pub async fn rename_device(app: AppHandle, name: String) -> Result<(), CloudPasteError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CloudPasteError::InvalidInput(
+            "设备名称不能为空".to_string(),
+        ));
+    }
+
+    let device_id = current_device_id()?;
+    log::info!("🖥️ 重命名设备: {} -> {}", device_id, name);
+
+    let request = http::api_request(Method::PATCH, &format!("/devices/{}", device_id))?
+        .json(&DeviceUpdate { device_name: &name });
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("重命名设备失败", e))?;
+    http::check_response(response).await?;
+
+    let mut config = GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?;
+    config.device_name = name;
+    config.save_to_disk(&app)?;
+
+    log::info!("✅ 设备已重命名");
+    Ok(())
+}
+
+/// Tauri 命令：撤销其他设备的访问权限（从服务器删除该设备并断开其连接）
+#[tauri::command]
+pub async fn revoke_device(id: String) -> Result<(), CloudPasteError> {
+    if id == current_device_id()? {
+        return Err(CloudPasteError::InvalidInput(
+            "不能撤销本机，请改为清除配置".to_string(),
+        ));
+    }
+
+    log::info!("🖥️ 撤销设备: {}", id);
+    let request = http::api_request(Method::DELETE, &format!("/devices/{}", id))?;
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("撤销设备失败", e))?;
+    http::check_response(response).await?;
+
+    log::info!("✅ 设备已撤销: {}", id);
+    Ok(())
+}
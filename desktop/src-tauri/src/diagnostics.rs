@@ -0,0 +1,211 @@
+use crate::error::CloudPasteError;
+use crate::{
+    connectivity, history, http, image_cache, logging, metered, startup, store, GLOBAL_API_CONFIG,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Webview};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 最多打包的日志文件数（按修改时间取最近的）
+const MAX_LOG_FILES: usize = 3;
+
+/// 诊断包中的摘要文件
+const SUMMARY_FILE: &str = "diagnostics.json";
+
+/// 诊断包中日志文件所在目录
+const LOGS_DIR: &str = "logs/";
+
+/// 连接配置摘要（不含令牌、设备名称和服务器地址原文）
+#[derive(Debug, Serialize)]
+struct ConfigSummary {
+    is_configured: bool,
+    has_token: bool,
+    /// 服务器地址的协议
+    scheme: Option<String>,
+    /// 服务器主机名的 SHA256 前 12 位，用于判断是否为同一服务器
+    host_hash: Option<String>,
+    has_fallback: bool,
+    version: u32,
+}
+
+/// 缓存统计
+#[derive(Debug, Default, Serialize)]
+struct CacheStats {
+    files: u64,
+    bytes: u64,
+    history_items: Option<i64>,
+}
+
+/// 平台信息
+#[derive(Debug, Serialize)]
+struct PlatformInfo {
+    os: &'static str,
+    arch: &'static str,
+    family: &'static str,
+    app_version: String,
+    tauri_version: &'static str,
+    portable: bool,
+}
+
+/// 诊断摘要
+#[derive(Debug, Serialize)]
+struct Summary {
+    generated_at: String,
+    platform: PlatformInfo,
+    config: ConfigSummary,
+    cache: CacheStats,
+    log_level: String,
+    connectivity: connectivity::ConnectivityStatus,
+    throttle: http::ThrottleState,
+    metered: metered::MeteredStatus,
+    startup: startup::StartupReport,
+    /// 应用数据目录下的设置文件名（不含内容）
+    settings_files: Vec<String>,
+}
+
+fn config_summary() -> Result<ConfigSummary, String> {
+    let config = GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| format!("无法锁定配置: {}", e))?;
+    let url = url::Url::parse(&config.base_url).ok();
+
+    Ok(ConfigSummary {
+        is_configured: config.is_configured,
+        has_token: !config.token.is_empty(),
+        scheme: url.as_ref().map(|url| url.scheme().to_string()),
+        host_hash: url.as_ref().and_then(|url| url.host_str()).map(|host| {
+            let hash = format!("{:x}", Sha256::digest(host.as_bytes()));
+            hash[..12].to_string()
+        }),
+        has_fallback: config.fallback_url.is_some(),
+        version: config.version,
+    })
+}
+
+fn cache_stats(app: &AppHandle) -> CacheStats {
+    let mut stats = CacheStats::default();
+    if let Ok(dir) = store::cache_dir(app) {
+        let usage = image_cache::dir_usage(&dir);
+        stats.files = usage.files;
+        stats.bytes = usage.bytes;
+    }
+    stats.history_items = history::with_db(|conn| {
+        conn.query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+    })
+    .ok();
+    stats
+}
+
+fn settings_files(app: &AppHandle) -> Vec<String> {
+    let Ok(entries) = store::data_dir(app)
+        .and_then(|dir| fs::read_dir(dir).map_err(|e| format!("读取应用数据目录失败: {}", e)))
+    else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".json"))
+        .collect();
+    names.sort();
+    names
+}
+
+/// 最近修改的日志文件
+fn recent_logs(app: &AppHandle) -> Vec<PathBuf> {
+    let Ok(entries) = logging::log_dir(app)
+        .and_then(|dir| fs::read_dir(dir).map_err(|e| format!("读取日志目录失败: {}", e)))
+    else {
+        return Vec::new();
+    };
+    let mut logs: Vec<_> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    logs.into_iter()
+        .take(MAX_LOG_FILES)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+fn write_bundle(path: &Path, summary: &[u8], logs: &[PathBuf]) -> Result<(), CloudPasteError> {
+    store::write_atomic_with(path, |file| {
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let zip_error =
+            |e: zip::result::ZipError| CloudPasteError::Io(format!("写入诊断包失败: {}", e));
+
+        zip.start_file(SUMMARY_FILE, options).map_err(zip_error)?;
+        zip.write_all(summary)
+            .map_err(|e| CloudPasteError::io("写入诊断包失败", e))?;
+
+        for log in logs {
+            let Some(name) = log.file_name() else {
+                continue;
+            };
+            // 日志正在写入时也可读取，读取失败的跳过
+            let Ok(mut input) = File::open(log) else {
+                continue;
+            };
+            zip.start_file(format!("{}{}", LOGS_DIR, name.to_string_lossy()), options)
+                .map_err(zip_error)?;
+            std::io::copy(&mut input, &mut zip)
+                .map_err(|e| CloudPasteError::io("写入诊断包失败", e))?;
+        }
+
+        zip.finish().map_err(zip_error)
+    })
+}
+
+/// Tauri 命令：导出诊断包（最近的日志、匿名化的配置摘要、缓存统计和平台信息），用于反馈问题
+///
+/// 诊断包不含令牌、剪贴板内容和服务器地址
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: AppHandle,
+    webview: Webview,
+    path: String,
+) -> Result<(), CloudPasteError> {
+    log::info!("🩺 导出诊断包: {}", path);
+    let target = crate::sandbox::check(&webview, &path)?;
+
+    let summary = Summary {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        platform: PlatformInfo {
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            family: std::env::consts::FAMILY,
+            app_version: app.package_info().version.to_string(),
+            tauri_version: tauri::VERSION,
+            portable: store::portable_dir().is_some(),
+        },
+        config: config_summary()?,
+        cache: cache_stats(&app),
+        log_level: logging::get_log_level(),
+        connectivity: connectivity::get_connectivity_status(),
+        throttle: http::get_throttle_state(),
+        metered: metered::get_metered_status(),
+        startup: startup::get_startup_report(),
+        settings_files: settings_files(&app),
+    };
+    let summary = serde_json::to_vec_pretty(&summary)
+        .map_err(|e| CloudPasteError::Internal(format!("序列化诊断信息失败: {}", e)))?;
+    let logs = recent_logs(&app);
+
+    tauri::async_runtime::spawn_blocking(move || write_bundle(&target, &summary, &logs))
+        .await
+        .map_err(|e| CloudPasteError::Internal(format!("导出诊断包失败: {}", e)))??;
+
+    log::info!("✅ 诊断包已导出: {}", path);
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// 局域网服务类型
+const SERVICE_TYPE: &str = "_cloudpaste._tcp.local.";
+
+/// 局域网直连端口（用于设备间直接传输）
+pub const LAN_PORT: u16 = 45127;
+
+/// 局域网中发现的其他 CloudPaste 设备
+#[derive(Debug, Clone, Serialize)]
+pub struct LanPeer {
+    pub device_id: String,
+    pub device_name: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    /// 最近一次发现的时间（Unix 秒）
+    pub last_seen: u64,
+}
+
+/// 已发现的设备（key 为 mDNS 服务全名）
+static PEERS: Lazy<Mutex<HashMap<String, LanPeer>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 保持 mDNS 守护进程存活
+static DAEMON: Lazy<Mutex<Option<ServiceDaemon>>> = Lazy::new(|| Mutex::new(None));
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// 启动局域网设备发现：广播本机服务并浏览其他设备
+pub fn start(app: AppHandle) -> Result<(), String> {
+    let (device_id, device_name) = {
+        let config = crate::GLOBAL_API_CONFIG
+            .lock()
+            .map_err(|e| format!("无法锁定配置: {}", e))?;
+        (config.device_id.clone(), config.device_name.clone())
+    };
+
+    let daemon = ServiceDaemon::new().map_err(|e| format!("启动 mDNS 服务失败: {}", e))?;
+
+    let host_name = format!("{}.local.", crate::get_device_name());
+    let version = app.package_info().version.to_string();
+    let properties = [
+        ("device_id", device_id.as_str()),
+        ("device_name", device_name.as_str()),
+        ("version", version.as_str()),
+    ];
+    let service = ServiceInfo::new(
+        SERVICE_TYPE,
+        &device_id,
+        &host_name,
+        "",
+        LAN_PORT,
+        &properties[..],
+    )
+    .map_err(|e| format!("创建 mDNS 服务信息失败: {}", e))?
+    .enable_addr_auto();
+
+    daemon
+        .register(service)
+        .map_err(|e| format!("注册 mDNS 服务失败: {}", e))?;
+
+    let receiver = daemon
+        .browse(SERVICE_TYPE)
+        .map_err(|e| format!("浏览 mDNS 服务失败: {}", e))?;
+
+    if let Ok(mut guard) = DAEMON.lock() {
+        *guard = Some(daemon);
+    }
+
+    log::info!("📡 局域网设备发现已启动: {}", device_id);
+
+    std::thread::spawn(move || {
+        while let Ok(event) = receiver.recv() {
+            let changed = match event {
+                ServiceEvent::ServiceResolved(info) => {
+                    let peer_id = info
+                        .get_property_val_str("device_id")
+                        .unwrap_or_default()
+                        .to_string();
+                    if peer_id.is_empty() || peer_id == device_id {
+                        continue;
+                    }
+
+                    let peer = LanPeer {
+                        device_name: info
+                            .get_property_val_str("device_name")
+                            .unwrap_or(&peer_id)
+                            .to_string(),
+                        device_id: peer_id,
+                        addresses: info
+                            .get_addresses()
+                            .iter()
+                            .map(|addr| addr.to_string())
+                            .collect(),
+                        port: info.get_port(),
+                        last_seen: now_secs(),
+                    };
+                    log::info!(
+                        "📡 发现局域网设备: {} ({}) {:?}",
+                        peer.device_name,
+                        peer.device_id,
+                        peer.addresses
+                    );
+                    PEERS
+                        .lock()
+                        .map(|mut peers| peers.insert(info.get_fullname().to_string(), peer))
+                        .is_ok()
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    log::info!("📡 局域网设备已离线: {}", fullname);
+                    PEERS
+                        .lock()
+                        .map(|mut peers| peers.remove(&fullname).is_some())
+                        .unwrap_or(false)
+                }
+                _ => false,
+            };
+
+            if changed {
+                let _ = app.emit("lan-peers-changed", list_peers());
+            }
+        }
+        log::info!("📡 局域网设备发现已停止");
+    });
+
+    Ok(())
+}
+
+/// 当前已发现的设备列表
+pub fn list_peers() -> Vec<LanPeer> {
+    PEERS
+        .lock()
+        .map(|peers| peers.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Tauri 命令：获取局域网中发现的其他设备
+#[tauri::command]
+pub fn list_lan_peers() -> Vec<LanPeer> {
+    list_peers()
+}
@@ -0,0 +1,235 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use hyper::client::connect::dns::Name;
+use once_cell::sync::Lazy;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "dns.json";
+
+/// DoH 查询的超时时间
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 服务器未返回 TTL 时缓存解析结果的时间
+const DEFAULT_TTL: u64 = 300;
+
+/// DNS 设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DnsSettings {
+    /// 是否使用 DNS-over-HTTPS 解析域名（否则使用系统 DNS）
+    pub doh_enabled: bool,
+    /// DoH 服务地址（JSON 格式接口），使用 IP 地址可避免解析 DoH 服务本身的域名
+    pub doh_url: String,
+    /// DoH 查询失败时是否回退到系统 DNS
+    pub fallback_to_system: bool,
+}
+
+impl Default for DnsSettings {
+    fn default() -> Self {
+        Self {
+            doh_enabled: false,
+            doh_url: "https://1.1.1.1/dns-query".to_string(),
+            fallback_to_system: true,
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<DnsSettings>> = Lazy::new(|| Mutex::new(DnsSettings::default()));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<DnsSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> DnsSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// DoH JSON 接口的响应（application/dns-json）
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    #[serde(rename = "TTL", default)]
+    ttl: Option<u64>,
+    data: String,
+}
+
+/// 使用 DNS-over-HTTPS 解析域名，按 TTL 缓存结果
+pub struct DohResolver {
+    inner: Arc<DohClient>,
+}
+
+struct DohClient {
+    url: String,
+    fallback_to_system: bool,
+    /// 查询 DoH 服务使用的客户端（系统 DNS 解析 DoH 服务本身）
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, (Vec<IpAddr>, Instant)>>,
+}
+
+impl DohClient {
+    fn new(settings: &DnsSettings) -> Self {
+        Self {
+            url: settings.doh_url.clone(),
+            fallback_to_system: settings.fallback_to_system,
+            client: reqwest::Client::builder()
+                .timeout(QUERY_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 查询一种记录类型（A / AAAA），返回地址和最短 TTL
+    async fn query(
+        &self,
+        host: &str,
+        record_type: &str,
+    ) -> Result<(Vec<IpAddr>, u64), CloudPasteError> {
+        let response = self
+            .client
+            .get(&self.url)
+            .query(&[("name", host), ("type", record_type)])
+            .header(reqwest::header::ACCEPT, "application/dns-json")
+            .send()
+            .await
+            .map_err(|e| CloudPasteError::request("DoH 查询失败", e))?
+            .error_for_status()
+            .map_err(|e| CloudPasteError::request("DoH 查询失败", e))?
+            .json::<DohResponse>()
+            .await
+            .map_err(|e| CloudPasteError::request("DoH 响应无效", e))?;
+
+        if response.status != 0 {
+            return Err(CloudPasteError::Network(format!(
+                "DoH 查询失败，状态码: {}",
+                response.status
+            )));
+        }
+
+        let mut ttl = DEFAULT_TTL;
+        let addrs = response
+            .answer
+            .into_iter()
+            // 1 = A，28 = AAAA（跳过 CNAME 等记录）
+            .filter(|answer| answer.record_type == 1 || answer.record_type == 28)
+            .filter_map(|answer| {
+                ttl = ttl.min(answer.ttl.unwrap_or(DEFAULT_TTL));
+                answer.data.parse::<IpAddr>().ok()
+            })
+            .collect();
+        Ok((addrs, ttl))
+    }
+
+    async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, CloudPasteError> {
+        if let Some(addrs) = self.cache.lock().ok().and_then(|cache| {
+            cache
+                .get(host)
+                .filter(|(_, expires)| *expires > Instant::now())
+                .map(|(addrs, _)| addrs.clone())
+        }) {
+            return Ok(addrs);
+        }
+
+        let (mut addrs, mut ttl) = self.query(host, "A").await?;
+        match self.query(host, "AAAA").await {
+            Ok((v6, v6_ttl)) => {
+                addrs.extend(v6);
+                ttl = ttl.min(v6_ttl);
+            }
+            Err(e) => log::debug!("AAAA 查询失败: {} ({})", host, e),
+        }
+        if addrs.is_empty() {
+            return Err(CloudPasteError::Network(format!(
+                "DoH 未返回 {} 的地址",
+                host
+            )));
+        }
+
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(
+                host.to_string(),
+                (addrs.clone(), Instant::now() + Duration::from_secs(ttl)),
+            );
+        }
+        Ok(addrs)
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let doh = self.inner.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs = match doh.lookup(host).await {
+                Ok(addrs) => addrs,
+                Err(e) if doh.fallback_to_system => {
+                    log::warn!("⚠️ {}，回退到系统 DNS", e);
+                    // 端口由连接器在解析后设置
+                    let addrs = tokio::net::lookup_host(format!("{}:0", host)).await?;
+                    return Ok(Box::new(addrs) as Addrs);
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let addrs = addrs.into_iter().map(|ip| SocketAddr::new(ip, 0));
+            Ok(Box::new(addrs) as Addrs)
+        })
+    }
+}
+
+/// 按当前设置创建 DoH 解析器，未启用时返回 None（使用系统 DNS）
+pub fn resolver() -> Option<Arc<DohResolver>> {
+    let settings = settings();
+    settings.doh_enabled.then(|| {
+        Arc::new(DohResolver {
+            inner: Arc::new(DohClient::new(&settings)),
+        })
+    })
+}
+
+/// Tauri 命令：获取 DNS 设置
+#[tauri::command]
+pub fn get_dns_settings() -> DnsSettings {
+    settings()
+}
+
+/// Tauri 命令：更新 DNS 设置，立即按新设置重建共享客户端
+#[tauri::command]
+pub fn set_dns_settings(app: AppHandle, settings: DnsSettings) -> Result<(), CloudPasteError> {
+    if settings.doh_enabled && !settings.doh_url.starts_with("https://") {
+        return Err(CloudPasteError::InvalidInput(
+            "DoH 地址必须以 https:// 开头".to_string(),
+        ));
+    }
+
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    log::info!(
+        "🌐 DNS-over-HTTPS: {}（{}）",
+        settings.doh_enabled,
+        settings.doh_url
+    );
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    crate::http::rebuild_client();
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// 命令返回给前端的错误
+///
+/// 序列化为 `{ code, message }`，`code` 取值稳定，前端据此区分错误类型，`message` 用于展示
+#[derive(Debug, thiserror::Error)]
+pub enum CloudPasteError {
+    /// 尚未配置服务器
+    #[error("API 尚未配置")]
+    NotConfigured,
+    /// 令牌无效或无权访问（HTTP 401/403）
+    #[error("认证失败: {0}")]
+    Unauthorized(String),
+    /// 无法连接服务器或请求超时
+    #[error("网络错误: {0}")]
+    Network(String),
+    /// 服务器不可达或处于手动离线模式
+    #[error("{0}")]
+    Offline(String),
+    /// 服务器限流
+    #[error("服务器请求过于频繁，请在 {retry_after_secs} 秒后重试")]
+    RateLimited { retry_after_secs: u64 },
+    /// 服务器返回错误状态码
+    #[error("请求失败，HTTP 状态码: {status} {message}")]
+    Server { status: u16, message: String },
+    /// 服务器响应无法解析
+    #[error("{0}")]
+    InvalidResponse(String),
+    /// 磁盘空间不足
+    #[error("磁盘空间不足: {0}")]
+    DiskFull(String),
+    /// 没有读写权限
+    #[error("没有权限: {0}")]
+    PermissionDenied(String),
+    /// 文件或记录不存在
+    #[error("{0}")]
+    NotFound(String),
+    /// 其他文件读写错误
+    #[error("{0}")]
+    Io(String),
+    /// 参数无效
+    #[error("{0}")]
+    InvalidInput(String),
+    /// 应用已锁定，需先输入 PIN 解锁
+    #[error("应用已锁定，请输入 PIN 解锁")]
+    Locked,
+    /// 用户取消了操作（如取消下载）
+    #[error("操作已取消")]
+    Cancelled,
+    /// 其他错误
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl CloudPasteError {
+    /// 稳定的错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotConfigured => "not_configured",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Network(_) => "network",
+            Self::Offline(_) => "offline",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Server { .. } => "server",
+            Self::InvalidResponse(_) => "invalid_response",
+            Self::DiskFull(_) => "disk_full",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::NotFound(_) => "not_found",
+            Self::Io(_) => "io",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::Locked => "locked",
+            Self::Cancelled => "cancelled",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    /// 文件读写错误，按错误类型区分磁盘已满、无权限和不存在
+    pub fn io(context: &str, e: std::io::Error) -> Self {
+        let message = format!("{}: {}", context, e);
+        match e.kind() {
+            std::io::ErrorKind::StorageFull => Self::DiskFull(message),
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied(message),
+            std::io::ErrorKind::NotFound => Self::NotFound(message),
+            _ => Self::Io(message),
+        }
+    }
+
+    /// 发送请求失败：连接失败或超时视为网络错误
+    pub fn request(context: &str, e: reqwest::Error) -> Self {
+        let message = format!("{}: {}", context, e);
+        if e.is_decode() {
+            Self::InvalidResponse(message)
+        } else {
+            Self::Network(message)
+        }
+    }
+}
+
+impl Serialize for CloudPasteError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CloudPasteError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// 尚未迁移的内部函数仍返回 String，统一视为其他错误
+impl From<String> for CloudPasteError {
+    fn from(message: String) -> Self {
+        Self::Internal(message)
+    }
+}
+
+/// 供仍返回 String 的函数使用 `?`
+impl From<CloudPasteError> for String {
+    fn from(error: CloudPasteError) -> Self {
+        error.to_string()
+    }
+}
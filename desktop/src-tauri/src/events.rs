@@ -0,0 +1,227 @@
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+/// 发往前端的事件，每种负载类型对应一个固定的事件名
+pub trait AppEvent: Serialize + Clone {
+    /// 事件名
+    const NAME: &'static str;
+}
+
+/// 同步历史的进度（每同步完一页发送一次）
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgress {
+    /// 已完成的页数
+    pub page: u32,
+    /// 已同步的记录数
+    pub synced: u64,
+    /// 需要同步的记录总数
+    pub total: u64,
+}
+
+impl AppEvent for SyncProgress {
+    const NAME: &'static str = "sync-progress";
+}
+
+/// 文件下载进度
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub url: String,
+    /// 已下载的字节数
+    pub downloaded: u64,
+    /// 文件总大小，服务器未返回时为空
+    pub total: Option<u64>,
+    /// 最近几秒的平均下载速度（字节/秒），刚开始下载时为空
+    pub bytes_per_sec: Option<u64>,
+    /// 预计剩余秒数，总大小未知时为空
+    pub eta_secs: Option<u64>,
+    /// 是否已下载完成
+    pub done: bool,
+}
+
+impl AppEvent for DownloadProgress {
+    const NAME: &'static str = "download-progress";
+}
+
+/// 采集到新的剪贴板内容（已通过过滤规则，即将同步）
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipboardCaptured {
+    /// 内容类型：text、image、file 等
+    pub content_type: String,
+    /// 内容大小（字节）
+    pub size: u64,
+}
+
+impl AppEvent for ClipboardCaptured {
+    const NAME: &'static str = "clipboard-captured";
+}
+
+/// 服务器拒绝了令牌（HTTP 401），需要重新登录
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthExpired {
+    pub message: String,
+}
+
+impl AppEvent for AuthExpired {
+    const NAME: &'static str = "auth-expired";
+}
+
+/// 缓存文件被删除
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheEvicted {
+    /// 被删除缓存的 URL，清空全部缓存时为空
+    pub url: Option<String>,
+    /// 释放的字节数
+    pub freed_bytes: u64,
+}
+
+impl AppEvent for CacheEvicted {
+    const NAME: &'static str = "cache-evicted";
+}
+
+/// 将缓存文件导出到用户选择位置的进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub url: String,
+    pub copied: u64,
+    pub total: u64,
+    pub bytes_per_sec: Option<u64>,
+    pub eta_secs: Option<u64>,
+    pub done: bool,
+}
+
+impl AppEvent for ExportProgress {
+    const NAME: &'static str = "export-progress";
+}
+
+/// 文件上传进度
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgress {
+    pub file_name: String,
+    /// 已发送的字节数
+    pub uploaded: u64,
+    pub total: u64,
+    /// 最近几秒的平均上传速度（字节/秒），刚开始上传时为空
+    pub bytes_per_sec: Option<u64>,
+    /// 预计剩余秒数
+    pub eta_secs: Option<u64>,
+    /// 是否已全部发送
+    pub done: bool,
+}
+
+impl AppEvent for UploadProgress {
+    const NAME: &'static str = "upload-progress";
+}
+
+/// 批量预缓存的进度（每完成一个文件发送一次）
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheWarmProgress {
+    /// 已处理的数量（包括失败的）
+    pub completed: usize,
+    /// 下载失败的数量
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl AppEvent for CacheWarmProgress {
+    const NAME: &'static str = "cache-warm-progress";
+}
+
+/// 应用已锁定，前端应显示解锁界面
+#[derive(Debug, Clone, Serialize)]
+pub struct AppLocked {
+    /// 锁定原因：`idle`（空闲超时）、`resume`（系统唤醒）或 `manual`（手动锁定）
+    pub reason: &'static str,
+}
+
+impl AppEvent for AppLocked {
+    const NAME: &'static str = "app-locked";
+}
+
+/// 粘贴队列发生变化（加入、粘贴或清空）
+#[derive(Debug, Clone, Serialize)]
+pub struct PasteStackChanged {
+    /// 队列中剩余的剪贴板项 ID（按粘贴顺序）
+    pub remaining: Vec<i64>,
+}
+
+impl AppEvent for PasteStackChanged {
+    const NAME: &'static str = "paste-stack-changed";
+}
+
+/// 剪贴板采集暂停或恢复
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturePauseChanged {
+    pub paused: bool,
+    /// 自动恢复的时间（RFC 3339），为空表示直到手动恢复
+    pub resumes_at: Option<String>,
+}
+
+impl AppEvent for CapturePauseChanged {
+    const NAME: &'static str = "capture-pause-changed";
+}
+
+/// 检测到屏幕共享开始或结束（共享期间暂停采集）
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenSharingChanged {
+    pub active: bool,
+    /// 正在共享屏幕的应用
+    pub app_name: Option<String>,
+}
+
+impl AppEvent for ScreenSharingChanged {
+    const NAME: &'static str = "screen-sharing-changed";
+}
+
+/// 主密钥轮换进度
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyRotationProgress {
+    /// 已完成的步骤数
+    pub completed: u32,
+    pub total: u32,
+    /// 当前阶段：started、uploaded、key_material、history_db、failed
+    pub stage: &'static str,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+impl AppEvent for KeyRotationProgress {
+    const NAME: &'static str = "key-rotation-progress";
+}
+
+/// 从 WebDAV 收到其他设备的剪贴板项
+#[derive(Debug, Clone, Serialize)]
+pub struct WebDavItemReceived {
+    pub item: crate::api::NewClipboardItem,
+}
+
+impl AppEvent for WebDavItemReceived {
+    const NAME: &'static str = "webdav-item-received";
+}
+
+/// 某个存储后端的同步状态变化
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageStatusChanged {
+    pub status: crate::storage::BackendStatus,
+}
+
+impl AppEvent for StorageStatusChanged {
+    const NAME: &'static str = "storage-status-changed";
+}
+
+/// 保存应用句柄，供没有 AppHandle 的模块发送事件
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 发送事件（应用尚未初始化时忽略）
+pub fn emit<E: AppEvent>(event: E) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    if let Err(e) = app.emit(E::NAME, event) {
+        log::debug!("发送事件 {} 失败: {}", E::NAME, e);
+    }
+}
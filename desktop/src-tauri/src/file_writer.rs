@@ -0,0 +1,137 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::ipc::{InvokeBody, Request};
+use tauri::Webview;
+
+/// 分块写入时用于传递句柄的请求头
+const HANDLE_HEADER: &str = "writer-handle";
+
+/// 打开中的文件写入器
+struct Writer {
+    path: PathBuf,
+    /// 覆盖写入时实际写入的临时文件，关闭时重命名为目标文件；追加写入时为空
+    temp: Option<PathBuf>,
+    file: BufWriter<File>,
+    written: u64,
+}
+
+static WRITERS: Lazy<Mutex<HashMap<u32, Writer>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_HANDLE: AtomicU32 = AtomicU32::new(1);
+
+fn lock_writers() -> Result<std::sync::MutexGuard<'static, HashMap<u32, Writer>>, CloudPasteError> {
+    WRITERS
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定文件写入器: {}", e)))
+}
+
+fn unknown_handle(handle: u32) -> CloudPasteError {
+    CloudPasteError::NotFound(format!("文件写入器不存在: {}", handle))
+}
+
+/// 打开文件准备分块写入，返回句柄；`append` 为 true 时追加到已有内容之后
+///
+/// 覆盖写入时先写入临时文件，关闭时才替换目标文件，中途崩溃不会留下不完整的文件
+pub fn open(path: PathBuf, append: bool) -> Result<u32, CloudPasteError> {
+    let temp = (!append).then(|| store::atomic_temp_path(&path));
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(temp.as_ref().unwrap_or(&path))
+        .map_err(|e| CloudPasteError::io("创建文件失败", e))?;
+
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    lock_writers()?.insert(
+        handle,
+        Writer {
+            path: path.clone(),
+            temp,
+            file: BufWriter::new(file),
+            written: 0,
+        },
+    );
+    log::info!("💾 开始分块写入文件: {:?} (句柄 {})", path, handle);
+    Ok(handle)
+}
+
+/// 写入一块数据，返回该句柄累计写入的字节数
+pub fn write(handle: u32, data: &[u8]) -> Result<u64, CloudPasteError> {
+    let mut writers = lock_writers()?;
+    let writer = writers
+        .get_mut(&handle)
+        .ok_or_else(|| unknown_handle(handle))?;
+    writer
+        .file
+        .write_all(data)
+        .map_err(|e| CloudPasteError::io("写入文件失败", e))?;
+    writer.written += data.len() as u64;
+    Ok(writer.written)
+}
+
+/// 写完后关闭文件并刷新到磁盘，返回写入的总字节数
+pub fn close(handle: u32) -> Result<u64, CloudPasteError> {
+    let writer = lock_writers()?
+        .remove(&handle)
+        .ok_or_else(|| unknown_handle(handle))?;
+    let file = writer
+        .file
+        .into_inner()
+        .map_err(|e| CloudPasteError::io("写入文件失败", e.into_error()))?;
+    match &writer.temp {
+        Some(temp) => store::commit_atomic(file, temp, &writer.path),
+        None => file.sync_all(),
+    }
+    .map_err(|e| CloudPasteError::io("写入文件失败", e))?;
+
+    log::info!("✅ 文件已保存: {:?} ({} 字节)", writer.path, writer.written);
+    Ok(writer.written)
+}
+
+/// Tauri 命令：打开文件准备分块写入，返回写入器句柄
+#[tauri::command]
+pub fn open_file_writer(
+    webview: Webview,
+    file_path: String,
+    append: Option<bool>,
+) -> Result<u32, CloudPasteError> {
+    let path = crate::sandbox::check(&webview, &file_path)?;
+    open(path, append.unwrap_or(false))
+}
+
+/// Tauri 命令：向写入器写入一块数据，返回累计写入的字节数
+///
+/// 数据以二进制请求体传入，句柄放在 `writer-handle` 请求头中：
+/// `invoke('write_file_chunk', bytes, { headers: { 'writer-handle': String(handle) } })`
+#[tauri::command]
+pub fn write_file_chunk(webview: Webview, request: Request<'_>) -> Result<u64, CloudPasteError> {
+    crate::sandbox::check_window(&webview)?;
+    let handle = request
+        .headers()
+        .get(HANDLE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u32>().ok())
+        .ok_or_else(|| CloudPasteError::InvalidInput("缺少文件写入器句柄".to_string()))?;
+
+    match request.body() {
+        InvokeBody::Raw(data) => write(handle, data),
+        InvokeBody::Json(_) => Err(CloudPasteError::InvalidInput(
+            "数据需以二进制形式传入".to_string(),
+        )),
+    }
+}
+
+/// Tauri 命令：关闭写入器，返回写入的总字节数
+#[tauri::command]
+pub fn close_file_writer(webview: Webview, handle: u32) -> Result<u64, CloudPasteError> {
+    crate::sandbox::check_window(&webview)?;
+    close(handle)
+}
@@ -0,0 +1,128 @@
+use crate::error::CloudPasteError;
+use crate::{idle, store};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "focus_follow.json";
+
+/// 焦点跟随同步设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusFollowSettings {
+    /// 是否启用：启用后只在本机空闲时自动应用远程剪贴板
+    pub enabled: bool,
+    /// 本机无操作多少秒后视为空闲
+    pub idle_seconds: u64,
+    /// 本设备优先级，来源设备优先级更高时忽略空闲判断直接应用
+    pub device_priority: i32,
+}
+
+impl Default for FocusFollowSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_seconds: 10,
+            device_priority: 0,
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<FocusFollowSettings>> =
+    Lazy::new(|| Mutex::new(FocusFollowSettings::default()));
+
+/// 远程剪贴板应用判定结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteApplyDecision {
+    pub apply: bool,
+    pub reason: String,
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<FocusFollowSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+            log::info!("✅ 已加载焦点跟随同步设置: enabled={}", settings.enabled);
+        }
+    }
+}
+
+/// 根据当前设置和空闲状态判断是否应用远程剪贴板
+pub fn decide(source_priority: Option<i32>) -> RemoteApplyDecision {
+    let settings = SETTINGS.lock().map(|s| s.clone()).unwrap_or_default();
+
+    if !settings.enabled {
+        return RemoteApplyDecision {
+            apply: true,
+            reason: "未启用焦点跟随".to_string(),
+        };
+    }
+
+    if let Some(priority) = source_priority {
+        if priority > settings.device_priority {
+            return RemoteApplyDecision {
+                apply: true,
+                reason: "来源设备优先级更高".to_string(),
+            };
+        }
+    }
+
+    if idle::is_idle_for(settings.idle_seconds) {
+        RemoteApplyDecision {
+            apply: true,
+            reason: "本机处于空闲状态".to_string(),
+        }
+    } else {
+        RemoteApplyDecision {
+            apply: false,
+            reason: format!("本机 {} 秒内有操作", settings.idle_seconds),
+        }
+    }
+}
+
+/// Tauri 命令：获取焦点跟随同步设置
+#[tauri::command]
+pub fn get_focus_follow_settings() -> Result<FocusFollowSettings, CloudPasteError> {
+    SETTINGS
+        .lock()
+        .map(|s| s.clone())
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定设置: {}", e)))
+}
+
+/// Tauri 命令：更新焦点跟随同步设置
+#[tauri::command]
+pub fn set_focus_follow_settings(
+    app: AppHandle,
+    settings: FocusFollowSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+
+    let mut current = SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))?;
+    *current = settings;
+
+    log::info!(
+        "✅ 焦点跟随同步设置已更新: enabled={}, idle_seconds={}, priority={}",
+        current.enabled,
+        current.idle_seconds,
+        current.device_priority
+    );
+    Ok(())
+}
+
+/// Tauri 命令：收到远程剪贴板时判断是否自动应用
+///
+/// `source_priority` 为来源设备的优先级（可选）
+#[tauri::command]
+pub fn should_apply_remote_clipboard(source_priority: Option<i32>) -> RemoteApplyDecision {
+    let decision = decide(source_priority);
+    log::info!(
+        "🔁 远程剪贴板应用判定: apply={}, reason={}",
+        decision.apply,
+        decision.reason
+    );
+    decision
+}
@@ -0,0 +1,220 @@
+use crate::error::CloudPasteError;
+use crate::{send, store};
+use notify::event::{CreateKind, ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Webview};
+
+const SETTINGS_FILE: &str = "watched_folders.json";
+
+/// 检查新文件是否写入完成的间隔（两次检查大小不变视为写入完成）
+const SETTLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 最多等待新文件写入完成的检查次数
+const MAX_SETTLE_CHECKS: u32 = 120;
+
+/// 下载或写入中的临时文件扩展名，不上传
+const PARTIAL_EXTENSIONS: &[&str] = &["tmp", "part", "crdownload", "download"];
+
+/// 自动上传新文件的监听目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedFolder {
+    pub path: String,
+    /// 是否包含子目录
+    #[serde(default)]
+    pub recursive: bool,
+    /// 上传后是否将分享链接复制到剪贴板
+    #[serde(default)]
+    pub copy_link: bool,
+}
+
+static FOLDERS: Lazy<Mutex<Vec<WatchedFolder>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+static WATCHER: Lazy<Mutex<Option<RecommendedWatcher>>> = Lazy::new(|| Mutex::new(None));
+
+/// 正在等待写入完成或上传中的文件，避免同一文件的多个事件重复上传
+static PENDING: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 启动时从磁盘加载监听目录
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<Vec<WatchedFolder>>(app, SETTINGS_FILE) {
+        if let Ok(mut folders) = FOLDERS.lock() {
+            *folders = saved;
+        }
+    }
+}
+
+fn folders() -> Vec<WatchedFolder> {
+    FOLDERS.lock().map(|f| f.clone()).unwrap_or_default()
+}
+
+/// 文件所属的监听目录
+fn folder_for(path: &Path) -> Option<WatchedFolder> {
+    let path = path.canonicalize().ok()?;
+    folders().into_iter().find(|folder| {
+        let Ok(root) = Path::new(&folder.path).canonicalize() else {
+            return false;
+        };
+        if folder.recursive {
+            path.starts_with(&root)
+        } else {
+            path.parent() == Some(root.as_path())
+        }
+    })
+}
+
+/// 跳过隐藏文件和写入中的临时文件
+fn should_upload(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    let partial = path.extension().is_some_and(|ext| {
+        PARTIAL_EXTENSIONS
+            .iter()
+            .any(|partial| ext.eq_ignore_ascii_case(partial))
+    });
+    !hidden && !partial
+}
+
+/// 等待文件写入完成（大小不再变化），文件消失或超时返回 false
+async fn wait_settled(path: &Path) -> bool {
+    let mut last_size = None;
+    for _ in 0..MAX_SETTLE_CHECKS {
+        tokio::time::sleep(SETTLE_INTERVAL).await;
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            return false;
+        };
+        let size = metadata.len();
+        if size > 0 && last_size == Some(size) {
+            return true;
+        }
+        last_size = Some(size);
+    }
+    false
+}
+
+async fn upload_new_file(path: PathBuf) {
+    if wait_settled(&path).await {
+        if let Some(folder) = folder_for(&path) {
+            log::info!("📂 监听目录中的新文件: {:?}", path);
+            match send::send_file(&path).await {
+                Ok(link) if folder.copy_link => {
+                    if let Err(e) = tauri_plugin_clipboard_x::write_text(link).await {
+                        log::warn!("⚠️ 复制分享链接失败: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("❌ 自动上传 {:?} 失败: {}", path, e),
+            }
+        }
+    }
+
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.remove(&path);
+    }
+}
+
+fn handle_event(event: notify::Result<Event>) {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            log::warn!("⚠️ 监听目录出错: {}", e);
+            return;
+        }
+    };
+    if !matches!(
+        event.kind,
+        EventKind::Create(CreateKind::File | CreateKind::Any)
+            | EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Both))
+    ) {
+        return;
+    }
+
+    // 重命名事件中最后一个路径为新文件名
+    let Some(path) = event.paths.last().cloned() else {
+        return;
+    };
+    if !should_upload(&path) || !path.is_file() {
+        return;
+    }
+    let Ok(mut pending) = PENDING.lock() else {
+        return;
+    };
+    if pending.insert(path.clone()) {
+        tauri::async_runtime::spawn(upload_new_file(path));
+    }
+}
+
+/// 按当前设置重新监听全部目录
+fn rewatch() -> Result<(), String> {
+    let mut guard = WATCHER
+        .lock()
+        .map_err(|e| format!("无法锁定目录监听: {}", e))?;
+    *guard = None;
+
+    let folders = folders();
+    if folders.is_empty() {
+        return Ok(());
+    }
+
+    let mut watcher = notify::recommended_watcher(handle_event)
+        .map_err(|e| format!("创建目录监听失败: {}", e))?;
+    for folder in &folders {
+        let mode = if folder.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        match watcher.watch(Path::new(&folder.path), mode) {
+            Ok(()) => log::info!("📂 开始监听目录: {}", folder.path),
+            Err(e) => log::warn!("⚠️ 监听目录 {} 失败: {}", folder.path, e),
+        }
+    }
+    *guard = Some(watcher);
+    Ok(())
+}
+
+/// 开始监听已保存的目录
+pub fn start() -> Result<(), String> {
+    rewatch()
+}
+
+/// Tauri 命令：获取自动上传的监听目录
+#[tauri::command]
+pub fn get_watched_folders() -> Vec<WatchedFolder> {
+    folders()
+}
+
+/// Tauri 命令：设置自动上传的监听目录，立即按新设置重新监听
+///
+/// 新增的目录须是用户通过对话框选择过的目录（见 `sandbox::pick_folder_path`）
+#[tauri::command]
+pub fn set_watched_folders(
+    app: AppHandle,
+    webview: Webview,
+    folders: Vec<WatchedFolder>,
+) -> Result<(), CloudPasteError> {
+    crate::sandbox::check_window(&webview)?;
+    let current = self::folders();
+    for folder in &folders {
+        if !current.iter().any(|existing| existing.path == folder.path) {
+            crate::sandbox::check(&webview, &folder.path)?;
+        }
+        if !Path::new(&folder.path).is_dir() {
+            return Err(CloudPasteError::NotFound(format!(
+                "目录不存在: {}",
+                folder.path
+            )));
+        }
+    }
+
+    store::save_json(&app, SETTINGS_FILE, &folders)?;
+    log::info!("📂 监听目录: {} 个", folders.len());
+    *FOLDERS.lock().map_err(|e| format!("无法锁定设置: {}", e))? = folders;
+    Ok(rewatch()?)
+}
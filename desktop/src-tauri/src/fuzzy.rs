@@ -0,0 +1,214 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::sync::Mutex;
+
+/// 参与匹配的标题最大字符数（越短匹配越快）
+const TITLE_CHARS: usize = 120;
+
+/// 默认返回的结果数
+const DEFAULT_LIMIT: usize = 20;
+
+/// 候选项
+struct Candidate {
+    id: i64,
+    title: String,
+    content_type: String,
+    updated_at: String,
+}
+
+/// 候选列表及上一次查询的匹配结果
+#[derive(Default)]
+struct CandidateCache {
+    /// 按更新时间倒序
+    candidates: Vec<Candidate>,
+    last_query: String,
+    /// 上一次查询匹配到的候选下标
+    last_matches: Vec<usize>,
+}
+
+/// 快速粘贴面板的候选列表，首次使用时从本地历史加载
+static CACHE: Lazy<Mutex<Option<CandidateCache>>> = Lazy::new(|| Mutex::new(None));
+
+/// 候选数超过该值时并行匹配
+const PARALLEL_THRESHOLD: usize = 2000;
+
+static MATCHER: Lazy<SkimMatcherV2> = Lazy::new(|| SkimMatcherV2::default().smart_case());
+
+/// 模糊匹配结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FuzzyHit {
+    pub id: i64,
+    pub title: String,
+    pub content_type: String,
+    pub score: i64,
+    /// 标题中匹配到的字符下标（按字符计），用于高亮
+    pub indices: Vec<usize>,
+}
+
+/// 生成候选标题：文本取首段内容，图片取识别文字，文件取文件名
+fn title_of(entry: &HistoryEntry) -> String {
+    let item = &entry.item;
+    let source = match item.content_type.as_str() {
+        "text" => item.content.as_str(),
+        "image" => entry.ocr_text.as_deref().unwrap_or("图片"),
+        _ => item.file_name.as_deref().unwrap_or(&item.content),
+    };
+
+    source
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(TITLE_CHARS)
+        .collect()
+}
+
+fn candidate_of(entry: &HistoryEntry) -> Candidate {
+    Candidate {
+        id: entry.item.id,
+        title: title_of(entry),
+        content_type: entry.item.content_type.clone(),
+        updated_at: entry.item.updated_at.clone(),
+    }
+}
+
+/// 本地历史变化时更新候选列表（尚未加载时无需处理）
+pub fn update(entries: &[HistoryEntry]) {
+    let Ok(mut guard) = CACHE.lock() else {
+        return;
+    };
+    let Some(cache) = guard.as_mut() else {
+        return;
+    };
+
+    let candidates = &mut cache.candidates;
+    candidates.retain(|c| !entries.iter().any(|entry| entry.item.id == c.id));
+    candidates.extend(entries.iter().map(candidate_of));
+    candidates.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+    cache.last_query.clear();
+    cache.last_matches.clear();
+}
+
+/// 本地历史删除记录时移除对应候选
+pub fn remove(ids: &[i64]) {
+    let Ok(mut guard) = CACHE.lock() else {
+        return;
+    };
+    let Some(cache) = guard.as_mut() else {
+        return;
+    };
+
+    cache.candidates.retain(|c| !ids.contains(&c.id));
+    cache.last_query.clear();
+    cache.last_matches.clear();
+}
+
+/// 对指定下标的候选计算匹配分数，返回 (分数, 下标)，保持传入顺序
+fn score_all(candidates: &[Candidate], indices: &[usize], query: &str) -> Vec<(i64, usize)> {
+    let score = |chunk: &[usize]| -> Vec<(i64, usize)> {
+        chunk
+            .iter()
+            .filter_map(|&i| {
+                MATCHER
+                    .fuzzy_match(&candidates[i].title, query)
+                    .map(|score| (score, i))
+            })
+            .collect()
+    };
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    if indices.len() < PARALLEL_THRESHOLD || threads == 1 {
+        return score(indices);
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = indices
+            .chunks(indices.len().div_ceil(threads))
+            .map(|chunk| scope.spawn(move || score(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+/// Tauri 命令：快速粘贴面板的模糊搜索
+///
+/// 查询为空时返回最近的记录
+#[tauri::command]
+pub fn fuzzy_find(query: String, limit: Option<usize>) -> Result<Vec<FuzzyHit>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    let mut guard = CACHE
+        .lock()
+        .map_err(|e| format!("无法锁定候选列表: {}", e))?;
+    if guard.is_none() {
+        *guard = Some(CandidateCache {
+            candidates: history::all_entries()?.iter().map(candidate_of).collect(),
+            ..Default::default()
+        });
+    }
+    let Some(cache) = guard.as_mut() else {
+        return Ok(Vec::new());
+    };
+
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(cache
+            .candidates
+            .iter()
+            .take(limit)
+            .map(|c| FuzzyHit {
+                id: c.id,
+                title: c.title.clone(),
+                content_type: c.content_type.clone(),
+                score: 0,
+                indices: Vec::new(),
+            })
+            .collect());
+    }
+
+    // 逐字输入时新查询以上一次查询开头，只需在上一次的匹配结果中继续筛选
+    let indices: Vec<usize> =
+        if !cache.last_query.is_empty() && query.starts_with(&cache.last_query) {
+            std::mem::take(&mut cache.last_matches)
+        } else {
+            (0..cache.candidates.len()).collect()
+        };
+
+    // 结果保持候选顺序（时间倒序），排序稳定因此同分时较新的在前
+    let mut scored = score_all(&cache.candidates, &indices, query);
+    cache.last_query = query.to_string();
+    cache.last_matches = scored.iter().map(|(_, i)| *i).collect();
+
+    // 只为前 limit 个结果计算高亮位置
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.truncate(limit);
+
+    let candidates = &cache.candidates;
+    Ok(scored
+        .into_iter()
+        .map(|(score, i)| {
+            let c = &candidates[i];
+            FuzzyHit {
+                id: c.id,
+                title: c.title.clone(),
+                content_type: c.content_type.clone(),
+                score,
+                indices: MATCHER
+                    .fuzzy_indices(&c.title, query)
+                    .map(|(_, indices)| indices)
+                    .unwrap_or_default(),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,92 @@
+use crate::api::{ClipboardItem, ClipboardPage, Representation};
+use crate::error::CloudPasteError;
+use crate::{capabilities, http};
+use graphql_client::{GraphQLQuery, Response};
+use reqwest::Method;
+
+/// 对应 schema 中的 `DateTime` 标量（ISO 8601 字符串，与 REST 接口一致）
+type DateTime = String;
+
+/// 历史列表查询：只取本地历史需要保存的字段
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "graphql/schema.graphql",
+    query_path = "graphql/history_list.graphql",
+    response_derives = "Debug"
+)]
+pub struct HistoryList;
+
+/// 服务器是否提供 GraphQL 接口（在能力协商中声明）
+pub fn available() -> bool {
+    capabilities::supports(capabilities::GRAPHQL) == Some(true)
+}
+
+impl From<history_list::HistoryListClipboardItemsItems> for ClipboardItem {
+    fn from(item: history_list::HistoryListClipboardItemsItems) -> Self {
+        Self {
+            id: item.id,
+            content: item.content,
+            content_type: item.content_type,
+            device_id: item.device_id,
+            device_name: item.device_name,
+            favorite: item.favorite,
+            tags: item.tags,
+            file_name: item.file_name,
+            file_size: item.file_size.map(|size| size as u64),
+            mime_type: item.mime_type,
+            updated_at: item.updated_at,
+            synced: item.synced,
+            representations: item
+                .representations
+                .into_iter()
+                .map(|r| Representation {
+                    format: r.format,
+                    content: r.content,
+                    mime_type: r.mime_type,
+                })
+                .collect(),
+            signature: None,
+            public_key: None,
+            file_hash: None,
+        }
+    }
+}
+
+/// 通过 GraphQL 分页获取剪贴板列表（按更新时间倒序），参数与 `api::list_clipboard_items` 相同
+///
+/// 只请求列表需要的字段，比 REST 列表接口的响应更小
+pub async fn list_clipboard_items(
+    page: u32,
+    page_size: u32,
+) -> Result<ClipboardPage, CloudPasteError> {
+    let query = HistoryList::build_query(history_list::Variables {
+        page: page.into(),
+        page_size: page_size.into(),
+    });
+    let request = http::api_request(Method::POST, "/graphql")?.json(&query);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("查询剪贴板列表失败", e))?;
+    let body: Response<history_list::ResponseData> = http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析剪贴板列表失败: {}", e)))?;
+
+    if let Some(errors) = body.errors.filter(|errors| !errors.is_empty()) {
+        let messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+        return Err(CloudPasteError::InvalidResponse(format!(
+            "查询剪贴板列表失败: {}",
+            messages.join("; ")
+        )));
+    }
+    let data = body
+        .data
+        .ok_or_else(|| CloudPasteError::InvalidResponse("查询剪贴板列表没有返回数据".to_string()))?
+        .clipboard_items;
+
+    Ok(ClipboardPage {
+        total: data.total as u64,
+        items: data.items.into_iter().map(ClipboardItem::from).collect(),
+    })
+}
@@ -0,0 +1,59 @@
+use crate::error::CloudPasteError;
+use crate::http;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// 跨设备接力指令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandoffDirective {
+    pub item_id: i64,
+    pub source_device_id: Option<String>,
+    pub source_device_name: Option<String>,
+}
+
+/// Tauri 命令：将当前查看的剪贴板项接力到其他设备
+///
+/// 通过服务器事件通道通知目标设备立即打开该项
+#[tauri::command]
+pub async fn handoff_current(item_id: i64, device_id: String) -> Result<(), CloudPasteError> {
+    let directive = {
+        let config = crate::GLOBAL_API_CONFIG
+            .lock()
+            .map_err(|e| format!("无法锁定配置: {}", e))?;
+        HandoffDirective {
+            item_id,
+            source_device_id: Some(config.device_id.clone()),
+            source_device_name: Some(config.device_name.clone()),
+        }
+    };
+
+    log::info!("🤝 发送接力请求: 剪贴板项={} -> {}", item_id, device_id);
+
+    let request = http::api_request(Method::POST, &format!("/devices/{}/handoff", device_id))?
+        .json(&directive);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("发送接力请求失败", e))?;
+    http::check_response(response).await?;
+
+    log::info!("✅ 接力请求已送达: {}", device_id);
+    Ok(())
+}
+
+/// Tauri 命令：处理收到的接力指令
+///
+/// 前端从 WebSocket 收到 `handoff` 消息后调用，将主窗口带到前台并导航到对应项
+#[tauri::command]
+pub fn receive_handoff(app: AppHandle, directive: HandoffDirective) -> Result<(), CloudPasteError> {
+    log::info!(
+        "🤝 收到接力请求: 剪贴板项={}, 来源={:?}",
+        directive.item_id,
+        directive.source_device_name
+    );
+
+    crate::show_main_window(&app);
+
+    app.emit("handoff-navigate", &directive)
+        .map_err(|e| CloudPasteError::Internal(format!("发送导航事件失败: {}", e)))
+}
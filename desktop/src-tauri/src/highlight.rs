@@ -0,0 +1,66 @@
+use crate::error::CloudPasteError;
+use once_cell::sync::Lazy;
+use syntect::highlighting::ThemeSet;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+/// 未指定主题时使用的默认主题
+const DEFAULT_THEME: &str = "InspiredGitHub";
+
+/// 超过该字节数的内容不做高亮，直接转义输出
+const MAX_HIGHLIGHT_BYTES: usize = 512 * 1024;
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// 高亮代码（阻塞调用）
+fn highlight(text: &str, lang: Option<&str>, theme: Option<&str>) -> Result<String, String> {
+    if text.len() > MAX_HIGHLIGHT_BYTES {
+        return Ok(format!("<pre>{}</pre>", escape_html(text)));
+    }
+
+    let syntax_set = &*SYNTAX_SET;
+    let syntax = lang
+        .filter(|lang| !lang.is_empty())
+        .and_then(|lang| syntax_set.find_syntax_by_token(lang))
+        .or_else(|| syntax_set.find_syntax_by_first_line(text))
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+
+    let theme_name = theme.unwrap_or(DEFAULT_THEME);
+    let theme = THEME_SET
+        .themes
+        .get(theme_name)
+        .ok_or_else(|| format!("未知的高亮主题: {}", theme_name))?;
+
+    highlighted_html_for_string(text, syntax_set, syntax, theme)
+        .map_err(|e| format!("代码高亮失败: {}", e))
+}
+
+/// Tauri 命令：代码语法高亮，返回带内联样式的 HTML
+///
+/// `lang` 为语言名或扩展名（如 `rust`、`py`），为空时根据首行自动识别
+#[tauri::command]
+pub async fn highlight_code(
+    text: String,
+    lang: Option<String>,
+    theme: Option<String>,
+) -> Result<String, CloudPasteError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        highlight(&text, lang.as_deref(), theme.as_deref())
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("代码高亮任务失败: {}", e)))?
+    .map_err(CloudPasteError::from)
+}
+
+/// Tauri 命令：获取可用的高亮主题
+#[tauri::command]
+pub fn list_highlight_themes() -> Vec<String> {
+    THEME_SET.themes.keys().cloned().collect()
+}
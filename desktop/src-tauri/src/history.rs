@@ -0,0 +1,1108 @@
+use crate::api::{self, ClipboardItem, ClipboardItemUpdate};
+use crate::error::CloudPasteError;
+use crate::events::{self, SyncProgress};
+use crate::retention::TypeLimits;
+use crate::{api_v2, capabilities, fuzzy, graphql, http, search, store, sync_filter};
+use once_cell::sync::Lazy;
+use rusqlite::{params, params_from_iter, Connection, OpenFlags, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const DB_FILE: &str = "history.db";
+
+/// 未加密的 SQLite 数据库文件头
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// 从服务器同步历史时每页的数量
+const SYNC_PAGE_SIZE: u32 = 100;
+
+/// 数据库结构迁移，按顺序执行，已执行的版本记录在 `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    "
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY,
+    content TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    device_id TEXT,
+    device_name TEXT,
+    favorite INTEGER NOT NULL DEFAULT 0,
+    tags TEXT,
+    file_name TEXT,
+    file_size INTEGER,
+    mime_type TEXT,
+    updated_at TEXT NOT NULL,
+    synced INTEGER NOT NULL DEFAULT 0,
+    ocr_text TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_history_updated_at ON history (updated_at);
+",
+    "
+ALTER TABLE history ADD COLUMN folder TEXT;
+ALTER TABLE history ADD COLUMN tags_pending INTEGER NOT NULL DEFAULT 0;
+",
+    "
+ALTER TABLE history ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+",
+    "
+ALTER TABLE history ADD COLUMN edit_state INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE history ADD COLUMN base_updated_at TEXT;
+ALTER TABLE history ADD COLUMN base_content TEXT;
+CREATE TABLE IF NOT EXISTS conflicts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id INTEGER NOT NULL,
+    local_content TEXT NOT NULL,
+    remote_content TEXT NOT NULL,
+    remote_updated_at TEXT NOT NULL,
+    detected_at TEXT NOT NULL
+);
+",
+    "
+CREATE TABLE IF NOT EXISTS sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+",
+    "
+CREATE TABLE IF NOT EXISTS rich_formats (
+    content_hash TEXT PRIMARY KEY,
+    html TEXT,
+    rtf TEXT,
+    captured_at TEXT NOT NULL
+);
+",
+    "
+ALTER TABLE history ADD COLUMN representations TEXT;
+",
+];
+
+/// 增量同步游标：服务器增量变更接口返回的 `next_cursor`
+///
+/// 早期版本在 `cursor` 中保存的是更新时间，格式不同，换用新的键
+const SYNC_CURSOR_KEY: &str = "changes_cursor";
+
+/// 本地编辑状态：无本地修改
+pub const EDIT_NONE: i64 = 0;
+/// 本地编辑状态：本地修改尚未推送到服务器
+pub const EDIT_PENDING: i64 = 1;
+/// 本地编辑状态：本地修改与服务器上的修改冲突，等待用户处理
+pub const EDIT_CONFLICT: i64 = 2;
+
+const SELECT_COLUMNS: &str = "id, content, content_type, device_id, device_name, favorite, tags, \
+     file_name, file_size, mime_type, updated_at, synced, ocr_text, folder, pinned, representations";
+
+/// 本地历史记录数据库连接
+static DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+/// 本地历史记录项：服务器剪贴板项及本地附加信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    #[serde(flatten)]
+    pub item: ClipboardItem,
+    /// 图片的文字识别结果
+    pub ocr_text: Option<String>,
+    /// 所在文件夹（仅本地）
+    pub folder: Option<String>,
+    /// 是否固定（文件内容始终保留在本地缓存）
+    pub pinned: bool,
+}
+
+/// 标签及使用次数
+#[derive(Debug, Clone, Serialize)]
+pub struct TagCount {
+    pub name: String,
+    pub count: u64,
+}
+
+/// SQLCipher 加密数据库的原始密钥（256 位）
+pub type DbKey = [u8; 32];
+
+/// 当前数据库使用的密钥，未加密时为空
+static DB_KEY: Mutex<Option<DbKey>> = Mutex::new(None);
+
+/// 启动时打开（必要时创建）历史记录数据库，数据库已加密时等待解锁后再打开
+pub fn open(app: &AppHandle) -> Result<(), String> {
+    if crate::db_encryption::is_enabled() {
+        log::info!("🔒 历史记录数据库已加密，解锁后打开");
+        return Ok(());
+    }
+    open_with_key(app, None)
+}
+
+/// 使用指定密钥（未加密时为空）打开历史记录数据库
+pub fn open_with_key(app: &AppHandle, key: Option<DbKey>) -> Result<(), String> {
+    let path = store::data_file_path(app, DB_FILE)?;
+    let conn = open_connection(&path, key.as_ref())?;
+
+    *DB.lock()
+        .map_err(|e| format!("无法锁定历史记录数据库: {}", e))? = Some(conn);
+    set_key(key)?;
+
+    log::info!("✅ 历史记录数据库已打开: {:?}", path);
+    Ok(())
+}
+
+/// 根据文件头判断数据库文件是否已加密，文件不存在或为空时返回 None
+///
+/// `rekey` 在原文件改名备份后、新文件就位前退出时，先恢复备份的原文件
+pub fn file_is_encrypted(app: &AppHandle) -> Result<Option<bool>, String> {
+    let path = store::data_file_path(app, DB_FILE)?;
+    let mut backup = path.clone().into_os_string();
+    backup.push(".old");
+    let backup = PathBuf::from(backup);
+    if !path.exists() && backup.exists() {
+        std::fs::rename(&backup, &path).map_err(|e| format!("恢复历史记录数据库失败: {}", e))?;
+        log::warn!("⚠️ 已恢复重新加密中断前的历史记录数据库");
+    }
+
+    let file = match std::fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("读取历史记录数据库失败: {}", e)),
+    };
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    file.take(SQLITE_HEADER.len() as u64)
+        .read_to_end(&mut header)
+        .map_err(|e| format!("读取历史记录数据库失败: {}", e))?;
+    if header.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(header != SQLITE_HEADER))
+}
+
+/// 数据库是否已打开
+pub fn is_open() -> bool {
+    DB.lock().is_ok_and(|guard| guard.is_some())
+}
+
+/// 当前数据库使用的密钥，未加密时为空
+pub fn current_key() -> Option<DbKey> {
+    DB_KEY.lock().ok().and_then(|key| *key)
+}
+
+fn set_key(key: Option<DbKey>) -> Result<(), String> {
+    *DB_KEY
+        .lock()
+        .map_err(|e| format!("无法锁定数据库密钥: {}", e))? = key;
+    Ok(())
+}
+
+/// SQLCipher 的原始密钥写法（`x'…'`），不再经过 SQLCipher 自身的密钥派生
+fn key_literal(key: &DbKey) -> String {
+    let hex: String = key.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("x'{}'", hex)
+}
+
+/// 在连接上设置密钥，并通过读取数据库结构确认密钥正确
+fn apply_key(conn: &Connection, key: Option<&DbKey>) -> Result<(), String> {
+    if let Some(key) = key {
+        conn.execute_batch(&format!("PRAGMA key = \"{}\";", key_literal(key)))
+            .map_err(|e| format!("设置数据库密钥失败: {}", e))?;
+    }
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map_err(|_| "无法读取历史记录数据库（密码不正确或文件已损坏）".to_string())?;
+    Ok(())
+}
+
+/// 打开数据库文件：设置密钥、开启安全删除并执行迁移
+fn open_connection(path: &Path, key: Option<&DbKey>) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| format!("打开历史记录数据库失败: {}", e))?;
+    apply_key(&conn, key)?;
+    enable_secure_delete(&conn)?;
+    migrate(&conn).map_err(|e| format!("初始化历史记录数据库失败: {}", e))?;
+    Ok(conn)
+}
+
+/// 将数据库导出为使用指定密钥（为空时不加密）的新文件（需要 SQLCipher）
+fn export_to(conn: &Connection, path: &Path, key: Option<&DbKey>) -> rusqlite::Result<()> {
+    let target = path.to_string_lossy().to_string();
+    let key = key.map(key_literal).unwrap_or_default();
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    conn.execute("ATTACH DATABASE ?1 AS export KEY ?2", params![target, key])?;
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('export')", [], |_| Ok(()))
+        .and_then(|_| conn.execute_batch(&format!("PRAGMA export.user_version = {};", version)));
+    conn.execute_batch("DETACH DATABASE export;")?;
+    exported
+}
+
+/// 用新密钥（为空时取消加密）重写数据库文件并重新打开
+pub fn rekey(app: &AppHandle, key: Option<DbKey>) -> Result<(), String> {
+    let path = store::data_file_path(app, DB_FILE)?;
+    let mut staging = path.clone().into_os_string();
+    staging.push(".rekey");
+    let staging = PathBuf::from(staging);
+    let _ = std::fs::remove_file(&staging);
+
+    let mut guard = DB
+        .lock()
+        .map_err(|e| format!("无法锁定历史记录数据库: {}", e))?;
+    let conn = guard
+        .as_ref()
+        .ok_or_else(|| "历史记录数据库尚未打开".to_string())?;
+    export_to(conn, &staging, key.as_ref()).map_err(|e| {
+        let _ = std::fs::remove_file(&staging);
+        format!("导出历史记录数据库失败: {}", e)
+    })?;
+
+    // 关闭当前连接后用导出的文件替换，原文件先改名备份，任一步失败都恢复原文件并重新打开
+    let previous_key = current_key();
+    let mut backup = path.clone().into_os_string();
+    backup.push(".old");
+    let backup = PathBuf::from(backup);
+    guard.take();
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let mut sidecar = path.clone().into_os_string();
+        sidecar.push(suffix);
+        let _ = std::fs::remove_file(sidecar);
+    }
+
+    let replaced = std::fs::rename(&path, &backup)
+        .map_err(|e| format!("备份历史记录数据库失败: {}", e))
+        .and_then(|_| {
+            std::fs::rename(&staging, &path).map_err(|e| {
+                let _ = std::fs::rename(&backup, &path);
+                format!("替换历史记录数据库失败: {}", e)
+            })
+        })
+        .and_then(|_| {
+            open_connection(&path, key.as_ref()).inspect_err(|_| {
+                let _ = std::fs::remove_file(&path);
+                let _ = std::fs::rename(&backup, &path);
+            })
+        });
+
+    match replaced {
+        Ok(conn) => {
+            *guard = Some(conn);
+            let _ = std::fs::remove_file(&backup);
+            set_key(key)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&staging);
+            match open_connection(&path, previous_key.as_ref()) {
+                Ok(conn) => *guard = Some(conn),
+                Err(reopen) => log::error!("❌ 重新打开历史记录数据库失败: {}", reopen),
+            }
+            Err(e)
+        }
+    }
+}
+
+/// 删除记录时用零覆盖其内容，被删除的内容不会残留在数据库文件的空闲页中
+fn enable_secure_delete(conn: &Connection) -> Result<(), String> {
+    conn.pragma_update(None, "secure_delete", true)
+        .map_err(|e| format!("初始化历史记录数据库失败: {}", e))
+}
+
+/// 执行尚未执行的数据库迁移
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index as i64 + 1)?;
+        tx.commit()?;
+        log::info!("🗄️ 历史记录数据库已迁移到版本 {}", index + 1);
+    }
+    Ok(())
+}
+
+/// 退出前关闭数据库（合并预写日志后释放连接）
+pub fn close() -> Result<(), String> {
+    let mut guard = DB
+        .lock()
+        .map_err(|e| format!("无法锁定历史记录数据库: {}", e))?;
+    if let Some(conn) = guard.take() {
+        let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)");
+        conn.close()
+            .map_err(|(_, e)| format!("关闭历史记录数据库失败: {}", e))?;
+        log::info!("✅ 历史记录数据库已关闭");
+    }
+    Ok(())
+}
+
+/// 在数据库连接上执行操作
+pub fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let guard = DB
+        .lock()
+        .map_err(|e| format!("无法锁定历史记录数据库: {}", e))?;
+    let conn = guard
+        .as_ref()
+        .ok_or_else(|| "历史记录数据库尚未打开".to_string())?;
+    f(conn).map_err(|e| format!("历史记录数据库操作失败: {}", e))
+}
+
+/// 将数据库一致地复制到指定文件（目标文件不能已存在），加密的数据库使用相同的密钥导出
+pub fn backup_to(path: &Path) -> Result<(), String> {
+    if let Some(key) = current_key() {
+        with_db(|conn| export_to(conn, path, Some(&key)))?;
+        return Ok(());
+    }
+    let target = path.to_string_lossy().to_string();
+    with_db(|conn| conn.execute("VACUUM INTO ?1", params![target]))?;
+    Ok(())
+}
+
+/// 检查数据库文件是否完整且可读（使用当前数据库的密钥）
+pub fn verify_database(path: &Path) -> Result<(), String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("打开数据库失败: {}", e))?;
+    apply_key(&conn, current_key().as_ref())?;
+    let result: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("检查数据库失败: {}", e))?;
+    if result != "ok" {
+        return Err(format!("数据库已损坏: {}", result));
+    }
+    Ok(())
+}
+
+/// 用指定的数据库文件替换当前历史记录，并更新索引
+pub fn restore_from(app: &AppHandle, source: &Path) -> Result<(), String> {
+    let old_ids = with_db(|conn| {
+        let mut stmt = conn.prepare("SELECT id FROM history")?;
+        let rows = stmt.query_map([], |row| row.get::<_, i64>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    let path = store::data_file_path(app, DB_FILE)?;
+    {
+        let mut guard = DB
+            .lock()
+            .map_err(|e| format!("无法锁定历史记录数据库: {}", e))?;
+        // 先关闭当前连接再替换文件
+        guard.take();
+
+        for suffix in ["-wal", "-shm"] {
+            let mut sidecar = path.clone().into_os_string();
+            sidecar.push(suffix);
+            let _ = std::fs::remove_file(sidecar);
+        }
+        store::copy_atomic(source, &path).map_err(|e| format!("恢复历史记录数据库失败: {}", e))?;
+
+        *guard = Some(open_connection(&path, current_key().as_ref())?);
+    }
+
+    let entries = all_entries()?;
+    let removed: Vec<i64> = old_ids
+        .into_iter()
+        .filter(|id| !entries.iter().any(|entry| entry.item.id == *id))
+        .collect();
+    fuzzy::remove(&removed);
+    search::remove_entries(&removed)?;
+    reindex(&entries)?;
+
+    log::info!("✅ 历史记录数据库已恢复: {} 条记录", entries.len());
+    Ok(())
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        item: ClipboardItem {
+            id: row.get(0)?,
+            content: row.get(1)?,
+            content_type: row.get(2)?,
+            device_id: row.get(3)?,
+            device_name: row.get(4)?,
+            favorite: row.get(5)?,
+            tags: row.get(6)?,
+            file_name: row.get(7)?,
+            file_size: row.get::<_, Option<i64>>(8)?.map(|size| size as u64),
+            mime_type: row.get(9)?,
+            updated_at: row.get(10)?,
+            synced: row.get(11)?,
+            representations: row
+                .get::<_, Option<String>>(15)?
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            signature: None,
+            public_key: None,
+            file_hash: None,
+        },
+        ocr_text: row.get(12)?,
+        folder: row.get(13)?,
+        pinned: row.get(14)?,
+    })
+}
+
+/// 写入或更新剪贴板项（保留本地附加信息和尚未同步的标签），返回更新后的记录
+///
+/// 有未推送的本地修改时保留本地内容；服务器版本在修改基准之后也被改动（版本号和内容都变了）时记录冲突
+pub fn upsert_items(items: &[ClipboardItem]) -> Result<Vec<HistoryEntry>, String> {
+    let detected_at = chrono::Utc::now()
+        .format("%Y-%m-%dT%H:%M:%S%.6f")
+        .to_string();
+
+    with_db(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut new_conflict = tx.prepare_cached(
+                "INSERT INTO conflicts (item_id, local_content, remote_content, remote_updated_at, detected_at)
+                 SELECT id, content, ?2, ?3, ?4 FROM history
+                 WHERE id = ?1 AND edit_state = ?5 AND base_updated_at IS NOT ?3
+                     AND base_content IS NOT ?2 AND content != ?2",
+            )?;
+            let mut mark_conflict = tx.prepare_cached(
+                "UPDATE history SET edit_state = ?4
+                 WHERE id = ?1 AND edit_state = ?5 AND base_updated_at IS NOT ?3
+                     AND base_content IS NOT ?2 AND content != ?2",
+            )?;
+            let mut refresh_conflict = tx.prepare_cached(
+                "UPDATE conflicts SET remote_content = ?2, remote_updated_at = ?3
+                 WHERE item_id = ?1 AND remote_updated_at != ?3",
+            )?;
+            for item in items {
+                let inserted = new_conflict.execute(params![
+                    item.id,
+                    item.content,
+                    item.updated_at,
+                    detected_at,
+                    EDIT_PENDING
+                ])?;
+                if inserted > 0 {
+                    mark_conflict.execute(params![
+                        item.id,
+                        item.content,
+                        item.updated_at,
+                        EDIT_CONFLICT,
+                        EDIT_PENDING
+                    ])?;
+                    log::warn!("⚠️ 检测到编辑冲突: 剪贴板项={}", item.id);
+                } else {
+                    refresh_conflict.execute(params![item.id, item.content, item.updated_at])?;
+                }
+            }
+        }
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO history (id, content, content_type, device_id, device_name, favorite, \
+                     tags, file_name, file_size, mime_type, updated_at, synced, representations)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                 ON CONFLICT(id) DO UPDATE SET
+                     content = CASE WHEN history.edit_state != 0 THEN history.content ELSE excluded.content END,
+                     content_type = excluded.content_type,
+                     device_id = excluded.device_id,
+                     device_name = excluded.device_name,
+                     favorite = excluded.favorite,
+                     tags = CASE WHEN history.tags_pending THEN history.tags ELSE excluded.tags END,
+                     file_name = excluded.file_name,
+                     file_size = excluded.file_size,
+                     mime_type = excluded.mime_type,
+                     updated_at = excluded.updated_at,
+                     synced = excluded.synced,
+                     representations = excluded.representations",
+            )?;
+            for item in items {
+                stmt.execute(params![
+                    item.id,
+                    item.content,
+                    item.content_type,
+                    item.device_id,
+                    item.device_name,
+                    item.favorite,
+                    item.tags,
+                    item.file_name,
+                    item.file_size.map(|size| size as i64),
+                    item.mime_type,
+                    item.updated_at,
+                    item.synced,
+                    (!item.representations.is_empty())
+                        .then(|| serde_json::to_string(&item.representations).ok())
+                        .flatten(),
+                ])?;
+            }
+        }
+        tx.commit()
+    })?;
+
+    get_entries(&items.iter().map(|item| item.id).collect::<Vec<_>>())
+}
+
+/// 按 ID 获取记录（保持传入顺序，不存在的 ID 被忽略）
+pub fn get_entries(ids: &[i64]) -> Result<Vec<HistoryEntry>, String> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; ids.len()].join(",");
+    let mut entries = with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM history WHERE id IN ({})",
+            SELECT_COLUMNS, placeholders
+        ))?;
+        let rows = stmt.query_map(params_from_iter(ids), entry_from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    entries.sort_by_key(|entry| ids.iter().position(|id| *id == entry.item.id));
+    Ok(entries)
+}
+
+/// 按 ID 获取单条记录
+pub fn get_entry(id: i64) -> Result<HistoryEntry, String> {
+    get_entries(&[id])?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("剪贴板项不存在: {}", id))
+}
+
+/// 获取全部记录（用于重建搜索索引）
+pub fn all_entries() -> Result<Vec<HistoryEntry>, String> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM history ORDER BY updated_at DESC",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], entry_from_row)?;
+        rows.collect()
+    })
+}
+
+/// 获取最近更新的若干条记录
+pub fn recent_entries(limit: usize) -> Result<Vec<HistoryEntry>, String> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM history ORDER BY updated_at DESC LIMIT ?1",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![limit as i64], entry_from_row)?;
+        rows.collect()
+    })
+}
+
+/// 获取固定或收藏的记录（需要保证离线可用）
+pub fn pinned_entries() -> Result<Vec<HistoryEntry>, String> {
+    with_db(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM history WHERE pinned = 1 OR favorite = 1 ORDER BY updated_at DESC",
+            SELECT_COLUMNS
+        ))?;
+        let rows = stmt.query_map([], entry_from_row)?;
+        rows.collect()
+    })
+}
+
+/// 按保留策略删除记录（固定和收藏的记录除外），返回被删除的记录
+///
+/// 记录按更新时间倒序计算：超出条数（总数或该类型的条数）、早于天数或累计大小超出上限的记录被删除
+pub fn prune(
+    max_items: Option<usize>,
+    type_limits: &TypeLimits,
+    max_days: Option<u32>,
+    max_bytes: Option<u64>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let cutoff = match max_days {
+        Some(days) => Some(with_db(|conn| {
+            conn.query_row(
+                "SELECT strftime('%Y-%m-%dT%H:%M:%S', 'now', ?1)",
+                params![format!("-{} days", days)],
+                |row| row.get::<_, String>(0),
+            )
+        })?),
+        None => None,
+    };
+
+    let mut total_bytes = 0u64;
+    let mut type_counts: HashMap<String, usize> = HashMap::new();
+    let expired: Vec<HistoryEntry> = all_entries()?
+        .into_iter()
+        .enumerate()
+        .filter(|(index, entry)| {
+            let item = &entry.item;
+            total_bytes += item.content.len() as u64 + item.file_size.unwrap_or(0);
+            // 非文本、非图片的记录都按文件计数
+            let kind = match item.content_type.as_str() {
+                "text" | "image" => item.content_type.as_str(),
+                _ => "file",
+            };
+            let type_count = type_counts.entry(kind.to_string()).or_default();
+            *type_count += 1;
+
+            if entry.pinned || item.favorite {
+                return false;
+            }
+            max_items.is_some_and(|max| *index >= max)
+                || type_limits
+                    .limit_for(&item.content_type)
+                    .is_some_and(|max| *type_count > max)
+                || cutoff
+                    .as_deref()
+                    .is_some_and(|cutoff| item.updated_at.as_str() < cutoff)
+                || max_bytes.is_some_and(|max| total_bytes > max)
+        })
+        .map(|(_, entry)| entry)
+        .collect();
+
+    if expired.is_empty() {
+        return Ok(expired);
+    }
+
+    let ids: Vec<i64> = expired.iter().map(|entry| entry.item.id).collect();
+    remove_rows(&ids)?;
+    Ok(expired)
+}
+
+/// 从数据库及搜索索引中删除记录
+fn remove_rows(ids: &[i64]) -> Result<(), String> {
+    with_db(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare_cached("DELETE FROM history WHERE id = ?1")?;
+            for id in ids {
+                stmt.execute(params![id])?;
+            }
+        }
+        tx.commit()
+    })?;
+
+    fuzzy::remove(ids);
+    search::remove_entries(ids)?;
+    Ok(())
+}
+
+/// 删除指定记录，返回被删除的记录（不存在的 ID 被忽略）
+///
+/// 数据库开启了 `secure_delete`，删除的内容会被覆盖；文本记录采集时保存的 HTML / RTF 格式一并删除
+pub fn delete_entries(ids: &[i64]) -> Result<Vec<HistoryEntry>, String> {
+    let entries = get_entries(ids)?;
+    if entries.is_empty() {
+        return Ok(entries);
+    }
+
+    let ids: Vec<i64> = entries.iter().map(|entry| entry.item.id).collect();
+    remove_rows(&ids)?;
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.item.content_type == "text")
+    {
+        crate::clipboard::forget_rich_formats(&entry.item.content)?;
+    }
+    Ok(entries)
+}
+
+/// 设置记录的固定状态
+pub fn set_pinned(id: i64, pinned: bool) -> Result<HistoryEntry, String> {
+    let updated = with_db(|conn| {
+        conn.execute(
+            "UPDATE history SET pinned = ?1 WHERE id = ?2",
+            params![pinned, id],
+        )
+    })?;
+    if updated == 0 {
+        return Err(format!("剪贴板项不存在: {}", id));
+    }
+
+    get_entry(id)
+}
+
+/// 保存图片的文字识别结果并更新索引
+pub fn attach_ocr_text(url: &str, text: &str) -> Result<(), String> {
+    // 记录中保存的可能是相对路径，也可能是完整 URL
+    let origin = http::absolute_url("")?;
+    let relative = url.strip_prefix(&origin).unwrap_or(url);
+
+    let ids = with_db(|conn| {
+        conn.execute(
+            "UPDATE history SET ocr_text = ?1 WHERE content IN (?2, ?3)",
+            params![text, url, relative],
+        )?;
+        let mut stmt = conn.prepare("SELECT id FROM history WHERE content IN (?1, ?2)")?;
+        let rows = stmt.query_map(params![url, relative], |row| row.get::<_, i64>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    reindex(&get_entries(&ids)?)
+}
+
+/// 记录变化后更新全文索引和模糊搜索候选
+pub fn reindex(entries: &[HistoryEntry]) -> Result<(), String> {
+    fuzzy::update(entries);
+    search::index_entries(entries)
+}
+
+/// 写入本地历史并更新索引
+pub fn store_items(items: &[ClipboardItem]) -> Result<(), String> {
+    crate::clipboard::remember_item_formats(items);
+    reindex(&upsert_items(items)?)
+}
+
+/// 保存仅本地的文本项（不同步到服务器）
+pub fn insert_local_text(content: &str) -> Result<HistoryEntry, String> {
+    let item = api::NewClipboardItem::from_this_device(content.to_string(), "text")?;
+    insert_local_item(item)
+}
+
+/// 保存不在服务器上的剪贴板项（仅本地或写入其他主后端），使用负数 ID 避免与服务器记录冲突
+pub fn insert_local_item(item: api::NewClipboardItem) -> Result<HistoryEntry, String> {
+    let id = with_db(|conn| {
+        conn.query_row(
+            "SELECT MIN(0, COALESCE(MIN(id), 0)) - 1 FROM history",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+    })?;
+
+    let item = ClipboardItem {
+        id,
+        content: item.content,
+        content_type: item.content_type,
+        device_id: item.device_id,
+        device_name: item.device_name,
+        favorite: item.favorite,
+        tags: item.tags,
+        file_name: item.file_name,
+        file_size: item.file_size,
+        mime_type: item.mime_type,
+        updated_at: chrono::Utc::now()
+            .format("%Y-%m-%dT%H:%M:%S%.6f")
+            .to_string(),
+        synced: false,
+        representations: item.representations,
+        signature: item.signature,
+        public_key: item.public_key,
+        file_hash: item.file_hash,
+    };
+    store_items(std::slice::from_ref(&item))?;
+
+    get_entry(id)
+}
+
+/// 合并导入的记录，返回实际写入的条数
+///
+/// 本地已有同一记录且不旧于导入版本时跳过；仅本地的记录（负数 ID）重新分配 ID，内容相同的不重复导入
+pub fn import_entries(entries: &[HistoryEntry]) -> Result<usize, String> {
+    let mut imported = Vec::new();
+    for entry in entries {
+        let mut item = entry.item.clone();
+        let skip = with_db(|conn| {
+            if item.id < 0 {
+                return conn
+                    .query_row(
+                        "SELECT 1 FROM history WHERE id < 0 AND content = ?1 AND updated_at = ?2",
+                        params![item.content, item.updated_at],
+                        |_| Ok(()),
+                    )
+                    .optional()
+                    .map(|found| found.is_some());
+            }
+            conn.query_row(
+                "SELECT updated_at >= ?2 FROM history WHERE id = ?1",
+                params![item.id, item.updated_at],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()
+            .map(|newer| newer.unwrap_or(false))
+        })?;
+        if skip {
+            continue;
+        }
+
+        if item.id < 0 {
+            item.id = with_db(|conn| {
+                conn.query_row(
+                    "SELECT MIN(0, COALESCE(MIN(id), 0)) - 1 FROM history",
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
+            })?;
+        }
+        upsert_items(std::slice::from_ref(&item))?;
+        with_db(|conn| {
+            conn.execute(
+                "UPDATE history SET ocr_text = COALESCE(?2, ocr_text), folder = COALESCE(?3, folder),
+                     pinned = pinned OR ?4
+                 WHERE id = ?1",
+                params![item.id, entry.ocr_text, entry.folder, entry.pinned],
+            )
+        })?;
+        imported.push(item.id);
+    }
+
+    reindex(&get_entries(&imported)?)?;
+    Ok(imported.len())
+}
+
+/// Tauri 命令：记录前端收到的剪贴板项（列表加载或实时同步）
+#[tauri::command]
+pub fn record_history_items(items: Vec<ClipboardItem>) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    Ok(store_items(&accepted(items))?)
+}
+
+/// 按本机的同步过滤设置筛选从服务器收到的剪贴板项
+fn accepted(items: Vec<ClipboardItem>) -> Vec<ClipboardItem> {
+    items.into_iter().filter(sync_filter::accepts).collect()
+}
+
+/// 规范化标签：去除空白、逗号和重复项
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.replace(',', " ").trim().to_string();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// 将本地标签推送到服务器，成功后清除待同步标记
+async fn push_tags(id: i64, tags: Option<String>) -> Result<(), String> {
+    let update = ClipboardItemUpdate {
+        tags: Some(tags.unwrap_or_default()),
+        ..Default::default()
+    };
+    api::update_clipboard_item(id, &update).await?;
+
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE history SET tags_pending = 0 WHERE id = ?1",
+            params![id],
+        )
+    })?;
+    Ok(())
+}
+
+/// 重试推送之前未能同步到服务器的标签
+pub async fn push_pending_tags() {
+    let pending = with_db(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT id, tags FROM history WHERE tags_pending = 1 AND id > 0")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<Vec<(i64, Option<String>)>>>()
+    });
+
+    for (id, tags) in pending.unwrap_or_default() {
+        if let Err(e) = push_tags(id, tags).await {
+            log::warn!("⚠️ 同步标签失败，稍后重试: 剪贴板项={}, {}", id, e);
+            return;
+        }
+    }
+}
+
+/// Tauri 命令：设置剪贴板项的标签
+///
+/// 先保存到本地，再同步到服务器；服务器不可用或不支持时保留本地标签，下次同步历史时重试
+#[tauri::command]
+pub async fn tag_item(id: i64, tags: Vec<String>) -> Result<HistoryEntry, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let tags = normalize_tags(tags);
+    let joined = (!tags.is_empty()).then(|| tags.join(","));
+
+    let updated = with_db(|conn| {
+        conn.execute(
+            "UPDATE history SET tags = ?1, tags_pending = 1 WHERE id = ?2",
+            params![joined, id],
+        )
+    })?;
+    if updated == 0 {
+        return Err(CloudPasteError::NotFound(format!("剪贴板项不存在: {}", id)));
+    }
+
+    let entry = get_entry(id)?;
+    reindex(std::slice::from_ref(&entry))?;
+
+    log::info!("🏷️ 设置标签: 剪贴板项={}, 标签={:?}", id, tags);
+    // 仅本地的记录（负数 ID）不同步
+    if id > 0 {
+        if let Err(e) = push_tags(id, joined).await {
+            log::warn!("⚠️ 标签暂未同步到服务器: {}", e);
+        }
+    }
+
+    Ok(entry)
+}
+
+/// Tauri 命令：列出所有标签及使用次数（按次数倒序）
+#[tauri::command]
+pub fn list_tags() -> Result<Vec<TagCount>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let rows = with_db(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT tags FROM history WHERE tags IS NOT NULL AND tags != ''")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    let mut counts: Vec<TagCount> = Vec::new();
+    for tag in rows.iter().flat_map(|tags| tags.split(',')) {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            continue;
+        }
+        match counts.iter_mut().find(|c| c.name == tag) {
+            Some(count) => count.count += 1,
+            None => counts.push(TagCount {
+                name: tag.to_string(),
+                count: 1,
+            }),
+        }
+    }
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    Ok(counts)
+}
+
+/// Tauri 命令：将剪贴板项移动到文件夹，`folder` 为空时移出文件夹
+#[tauri::command]
+pub fn move_to_folder(id: i64, folder: Option<String>) -> Result<HistoryEntry, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let folder = folder
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty());
+
+    let updated = with_db(|conn| {
+        conn.execute(
+            "UPDATE history SET folder = ?1 WHERE id = ?2",
+            params![folder, id],
+        )
+    })?;
+    if updated == 0 {
+        return Err(CloudPasteError::NotFound(format!("剪贴板项不存在: {}", id)));
+    }
+
+    log::info!("📁 移动到文件夹: 剪贴板项={}, 文件夹={:?}", id, folder);
+    Ok(get_entry(id)?)
+}
+
+/// Tauri 命令：列出所有文件夹
+#[tauri::command]
+pub fn list_folders() -> Result<Vec<String>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let folders = with_db(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT folder FROM history WHERE folder IS NOT NULL ORDER BY folder",
+        )?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect()
+    })?;
+    Ok(folders)
+}
+
+fn sync_cursor() -> Result<Option<String>, String> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT value FROM sync_state WHERE key = ?1",
+            params![SYNC_CURSOR_KEY],
+            |row| row.get(0),
+        )
+        .optional()
+    })
+}
+
+fn save_sync_cursor(cursor: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![SYNC_CURSOR_KEY, cursor],
+        )
+    })?;
+    Ok(())
+}
+
+/// Tauri 命令：从服务器同步剪贴板历史到本地，返回同步的数量
+///
+/// 服务器提供增量变更接口时只拉取上次同步之后的更新和删除，`full` 为 true 时从头拉取；
+/// 否则分页拉取全部
+#[tauri::command]
+pub async fn sync_history(app: AppHandle, full: Option<bool>) -> Result<u64, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    push_pending_tags().await;
+    crate::conflict::push_pending_edits().await;
+
+    if capabilities::supports(capabilities::CHANGE_FEED) == Some(true) {
+        let cursor = if full.unwrap_or(false) {
+            None
+        } else {
+            sync_cursor()?
+        };
+        return sync_changes(&app, cursor).await;
+    }
+
+    log::info!("🔄 开始同步剪贴板历史 (全部)");
+    // 服务器支持 v2 时按游标分页，否则按页码分页（提供 GraphQL 时只查询需要的字段）
+    let use_v2 = api_v2::active();
+    let use_graphql = !use_v2 && graphql::available();
+    let mut page = 1;
+    let mut next: Option<String> = None;
+    let mut synced = 0u64;
+    loop {
+        let (items, total, next_cursor) = if use_v2 {
+            let result = api_v2::list_clipboard_items(next.as_deref(), SYNC_PAGE_SIZE).await?;
+            let total = result.total.unwrap_or(synced + result.items.len() as u64);
+            (result.items, total, result.next_cursor)
+        } else if use_graphql {
+            let result = graphql::list_clipboard_items(page, SYNC_PAGE_SIZE).await?;
+            (result.items, result.total, None)
+        } else {
+            let result = api::list_clipboard_items(page, SYNC_PAGE_SIZE).await?;
+            (result.items, result.total, None)
+        };
+        if items.is_empty() {
+            break;
+        }
+
+        synced += items.len() as u64;
+        store_items(&accepted(items))?;
+        events::emit(SyncProgress {
+            page,
+            synced,
+            total,
+        });
+
+        let done = if use_v2 {
+            next_cursor.is_none()
+        } else {
+            synced >= total
+        };
+        if done {
+            break;
+        }
+        next = next_cursor;
+        page += 1;
+    }
+
+    log::info!("✅ 剪贴板历史同步完成: {} 条", synced);
+    Ok(synced)
+}
+
+/// 按增量变更接口的游标同步更新和删除，每页完成后保存游标（中途失败下次从该页继续）
+async fn sync_changes(app: &AppHandle, mut cursor: Option<String>) -> Result<u64, CloudPasteError> {
+    log::info!("🔄 开始增量同步剪贴板历史 (游标: {:?})", cursor);
+    let mut page = 1;
+    let mut synced = 0u64;
+    let mut removed = 0usize;
+    loop {
+        let changes = api::list_clipboard_changes(cursor.as_deref(), SYNC_PAGE_SIZE).await?;
+
+        synced += changes.items.len() as u64;
+        store_items(&accepted(changes.items))?;
+        removed += crate::retention::apply_remote_deletions(app, &changes.deleted_ids)?;
+        save_sync_cursor(&changes.next_cursor)?;
+        events::emit(SyncProgress {
+            page,
+            synced,
+            total: synced,
+        });
+
+        if !changes.has_more {
+            break;
+        }
+        cursor = Some(changes.next_cursor);
+        page += 1;
+    }
+
+    log::info!(
+        "✅ 剪贴板历史增量同步完成: 更新 {} 条，删除 {} 条",
+        synced,
+        removed
+    );
+    Ok(synced)
+}
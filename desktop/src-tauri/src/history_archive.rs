@@ -0,0 +1,228 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use crate::{http, image_cache};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Webview};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 归档格式版本
+const ARCHIVE_VERSION: u32 = 1;
+
+/// 归档中的清单文件
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// 归档中存放文件内容的目录
+const FILES_DIR: &str = "files/";
+
+/// 归档中的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchivedEntry {
+    #[serde(flatten)]
+    entry: HistoryEntry,
+    /// 文件内容在归档中的路径（文本或未能缓存的文件为空）
+    #[serde(default)]
+    file: Option<String>,
+}
+
+/// 归档清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    exported_at: String,
+    app_version: String,
+    entries: Vec<ArchivedEntry>,
+}
+
+/// 导出或导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveSummary {
+    /// 记录条数
+    pub items: usize,
+    /// 文件内容数
+    pub files: usize,
+}
+
+/// 记录对应的文件 URL（文本内容没有文件）
+fn file_url(entry: &HistoryEntry) -> Option<String> {
+    if entry.item.content_type == "text" {
+        return None;
+    }
+    http::absolute_url(&entry.item.content).ok()
+}
+
+/// 写入 zip 归档：清单及文件内容
+fn write_archive(
+    path: &Path,
+    manifest: &Manifest,
+    payloads: &[(String, PathBuf)],
+) -> Result<(), String> {
+    crate::store::write_atomic_with(path, |file| {
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let json =
+            serde_json::to_vec_pretty(manifest).map_err(|e| format!("序列化清单失败: {}", e))?;
+        zip.start_file(MANIFEST_FILE, options)
+            .map_err(|e| format!("写入归档失败: {}", e))?;
+        zip.write_all(&json)
+            .map_err(|e| format!("写入归档失败: {}", e))?;
+
+        for (name, source) in payloads {
+            let mut input = File::open(source).map_err(|e| format!("读取缓存文件失败: {}", e))?;
+            zip.start_file(name.as_str(), options)
+                .map_err(|e| format!("写入归档失败: {}", e))?;
+            std::io::copy(&mut input, &mut zip).map_err(|e| format!("写入归档失败: {}", e))?;
+        }
+
+        zip.finish().map_err(|e| format!("写入归档失败: {}", e))
+    })
+}
+
+/// 读取 zip 归档：返回清单，并将文件内容解压到 `targets` 给出的位置（已存在的跳过）
+fn read_archive(
+    path: &Path,
+    targets: impl Fn(&ArchivedEntry) -> Option<PathBuf>,
+) -> Result<(Manifest, usize), String> {
+    let file = File::open(path).map_err(|e| format!("打开归档文件失败: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("无效的归档文件: {}", e))?;
+
+    let manifest: Manifest = {
+        let mut json = String::new();
+        zip.by_name(MANIFEST_FILE)
+            .map_err(|_| "归档中缺少清单文件".to_string())?
+            .read_to_string(&mut json)
+            .map_err(|e| format!("读取清单失败: {}", e))?;
+        serde_json::from_str(&json).map_err(|e| format!("解析清单失败: {}", e))?
+    };
+    if manifest.version > ARCHIVE_VERSION {
+        return Err(format!("不支持的归档版本: {}", manifest.version));
+    }
+
+    let mut files = 0;
+    for archived in &manifest.entries {
+        // 只解压清单中引用的 files/ 目录下的文件
+        let Some(name) = archived
+            .file
+            .as_deref()
+            .filter(|name| name.starts_with(FILES_DIR) && !name.contains(".."))
+        else {
+            continue;
+        };
+        let Some(target) = targets(archived).filter(|target| !target.exists()) else {
+            continue;
+        };
+
+        let mut input = match zip.by_name(name) {
+            Ok(input) => input,
+            Err(e) => {
+                log::warn!("⚠️ 归档中缺少文件: {} ({})", name, e);
+                continue;
+            }
+        };
+        let mut output = File::create(&target).map_err(|e| format!("写入缓存文件失败: {}", e))?;
+        std::io::copy(&mut input, &mut output).map_err(|e| format!("写入缓存文件失败: {}", e))?;
+        files += 1;
+    }
+
+    Ok((manifest, files))
+}
+
+/// Tauri 命令：将全部本地历史导出为 zip 归档（JSON 清单及文件内容）
+///
+/// 尚未缓存的文件会先下载，下载失败的只导出记录
+#[tauri::command]
+pub async fn export_history(
+    app: AppHandle,
+    webview: Webview,
+    path: String,
+) -> Result<ArchiveSummary, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    log::info!("📦 导出历史记录: {}", path);
+    let target = crate::sandbox::check(&webview, &path)?;
+
+    let mut entries = Vec::new();
+    let mut payloads = Vec::new();
+    for entry in history::all_entries()? {
+        let mut file = None;
+        if let Some(url) = file_url(&entry) {
+            match image_cache::ensure_cached(&app, &url).await {
+                Ok(cache_path) => {
+                    if let Some(name) = cache_path.file_name() {
+                        let name = format!("{}{}", FILES_DIR, name.to_string_lossy());
+                        if !payloads.iter().any(|(existing, _)| *existing == name) {
+                            payloads.push((name.clone(), cache_path));
+                        }
+                        file = Some(name);
+                    }
+                }
+                Err(e) => log::warn!("⚠️ 文件未能导出: {} ({})", url, e),
+            }
+        }
+        entries.push(ArchivedEntry { entry, file });
+    }
+
+    let manifest = Manifest {
+        version: ARCHIVE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        entries,
+    };
+    let summary = ArchiveSummary {
+        items: manifest.entries.len(),
+        files: payloads.len(),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || write_archive(&target, &manifest, &payloads))
+        .await
+        .map_err(|e| CloudPasteError::Internal(format!("导出历史记录失败: {}", e)))??;
+
+    log::info!(
+        "✅ 历史记录已导出: {} 条记录，{} 个文件",
+        summary.items,
+        summary.files
+    );
+    Ok(summary)
+}
+
+/// Tauri 命令：从 zip 归档导入历史记录并与本地历史合并
+///
+/// 返回实际导入的记录数和恢复到缓存的文件数
+#[tauri::command]
+pub async fn import_history(
+    app: AppHandle,
+    webview: Webview,
+    path: String,
+) -> Result<ArchiveSummary, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    log::info!("📦 导入历史记录: {}", path);
+    let source = crate::sandbox::check(&webview, &path)?;
+
+    let resolver = app.clone();
+    let (manifest, files) = tauri::async_runtime::spawn_blocking(move || {
+        read_archive(&source, |archived| {
+            file_url(&archived.entry)
+                .and_then(|url| image_cache::cache_path_for_url(&resolver, &url).ok())
+        })
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("导入历史记录失败: {}", e)))??;
+
+    let entries: Vec<HistoryEntry> = manifest
+        .entries
+        .into_iter()
+        .map(|archived| archived.entry)
+        .collect();
+    let items = history::import_entries(&entries)?;
+
+    log::info!(
+        "✅ 历史记录已导入: {} 条记录（共 {} 条），{} 个文件",
+        items,
+        entries.len(),
+        files
+    );
+    Ok(ArchiveSummary { items, files })
+}
@@ -0,0 +1,352 @@
+use crate::error::CloudPasteError;
+use crate::{clipboard, history, paste_stack, quick_paste, screenshot, snippets, store};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+const HOTKEYS_FILE: &str = "hotkeys.json";
+
+/// 快捷键触发的操作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// 打开快速粘贴面板
+    ShowPalette,
+    /// 将最近一条记录粘贴到当前窗口
+    PasteLastItem,
+    /// 将剪贴板内容转为纯文本并粘贴到当前窗口
+    PastePlainText,
+    /// 粘贴粘贴队列中的下一项
+    PasteNext,
+    /// 按截图设置截图
+    CaptureScreenshot,
+    /// 展开文本片段并写入剪贴板
+    PasteSnippet { id: String },
+}
+
+/// 快捷键绑定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub action: HotkeyAction,
+    /// 快捷键，如 `CommandOrControl+Alt+V`
+    pub accelerator: String,
+}
+
+/// 快捷键的注册状态
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyStatus {
+    #[serde(flatten)]
+    pub binding: HotkeyBinding,
+    pub registered: bool,
+    /// 注册失败的原因（通常是快捷键已被其他应用占用）
+    pub error: Option<String>,
+}
+
+/// 已保存的绑定及各自的注册状态
+static HOTKEYS: Lazy<Mutex<Vec<HotkeyStatus>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn default_bindings() -> Vec<HotkeyBinding> {
+    vec![
+        HotkeyBinding {
+            action: HotkeyAction::ShowPalette,
+            accelerator: "CommandOrControl+Alt+V".to_string(),
+        },
+        HotkeyBinding {
+            action: HotkeyAction::PastePlainText,
+            accelerator: "CommandOrControl+Alt+Shift+V".to_string(),
+        },
+        HotkeyBinding {
+            action: HotkeyAction::CaptureScreenshot,
+            accelerator: "CommandOrControl+Alt+S".to_string(),
+        },
+    ]
+}
+
+/// 从旧版本分散保存的快捷键设置（截图、快速粘贴、文本片段）迁移绑定
+fn legacy_bindings(app: &AppHandle) -> Option<Vec<HotkeyBinding>> {
+    let screenshot = store::load_json::<serde_json::Value>(app, "screenshot.json");
+    let snippets = store::load_json::<serde_json::Value>(app, "snippets.json");
+    let quick_paste = store::load_json::<serde_json::Value>(app, "quick_paste.json");
+    if screenshot.is_none() && snippets.is_none() && quick_paste.is_none() {
+        return None;
+    }
+
+    // 设置文件中没有该项时使用旧版本的默认快捷键，为 null 时表示未设置
+    fn accelerator(
+        settings: &Option<serde_json::Value>,
+        key: &str,
+        default: &str,
+    ) -> Option<String> {
+        match settings.as_ref().and_then(|settings| settings.get(key)) {
+            Some(value) => value.as_str().map(str::to_string),
+            None => Some(default.to_string()),
+        }
+    }
+
+    let mut bindings = Vec::new();
+    let mut push = |action: HotkeyAction, accelerator: Option<String>| {
+        if let Some(accelerator) = accelerator {
+            bindings.push(HotkeyBinding {
+                action,
+                accelerator,
+            });
+        }
+    };
+    push(
+        HotkeyAction::ShowPalette,
+        accelerator(&quick_paste, "shortcut", "CommandOrControl+Alt+V"),
+    );
+    push(
+        HotkeyAction::PastePlainText,
+        accelerator(
+            &quick_paste,
+            "plain_paste_shortcut",
+            "CommandOrControl+Alt+Shift+V",
+        ),
+    );
+    push(
+        HotkeyAction::CaptureScreenshot,
+        accelerator(&screenshot, "shortcut", "CommandOrControl+Alt+S"),
+    );
+    for snippet in snippets
+        .as_ref()
+        .and_then(|value| value.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let id = snippet.get("id").and_then(|id| id.as_str());
+        let shortcut = snippet.get("shortcut").and_then(|s| s.as_str());
+        if let (Some(id), Some(shortcut)) = (id, shortcut) {
+            push(
+                HotkeyAction::PasteSnippet { id: id.to_string() },
+                Some(shortcut.to_string()),
+            );
+        }
+    }
+    Some(bindings)
+}
+
+/// 启动时从磁盘加载快捷键绑定（首次启动时迁移旧设置）
+pub fn load(app: &AppHandle) {
+    let bindings = match store::load_json::<Vec<HotkeyBinding>>(app, HOTKEYS_FILE) {
+        Some(bindings) => bindings,
+        None => {
+            let bindings = legacy_bindings(app).unwrap_or_else(default_bindings);
+            if let Err(e) = store::save_json(app, HOTKEYS_FILE, &bindings) {
+                log::warn!("⚠️ 保存快捷键设置失败: {}", e);
+            }
+            bindings
+        }
+    };
+
+    if let Ok(mut hotkeys) = HOTKEYS.lock() {
+        *hotkeys = bindings
+            .into_iter()
+            .map(|binding| HotkeyStatus {
+                binding,
+                registered: false,
+                error: None,
+            })
+            .collect();
+    }
+}
+
+fn bindings() -> Vec<HotkeyBinding> {
+    HOTKEYS
+        .lock()
+        .map(|hotkeys| {
+            hotkeys
+                .iter()
+                .map(|status| status.binding.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 执行快捷键对应的操作
+fn dispatch(app: &AppHandle, action: &HotkeyAction) {
+    match action {
+        HotkeyAction::ShowPalette => {
+            if let Err(e) = quick_paste::show(app) {
+                log::error!("❌ 打开快速粘贴面板失败: {}", e);
+            }
+        }
+        HotkeyAction::PasteSnippet { id } => snippets::paste_in_background(app, id.clone()),
+        HotkeyAction::PastePlainText => {
+            tauri::async_runtime::spawn(async {
+                if let Err(e) = quick_paste::paste_clipboard_as_plain_text(true).await {
+                    log::error!("❌ 纯文本粘贴失败: {}", e);
+                }
+            });
+        }
+        HotkeyAction::PasteLastItem => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = paste_last_item(&app).await {
+                    log::error!("❌ 粘贴最近记录失败: {}", e);
+                }
+            });
+        }
+        HotkeyAction::PasteNext => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = paste_stack::paste_next(&app).await {
+                    log::error!("❌ 粘贴队列中的下一项失败: {}", e);
+                }
+            });
+        }
+        HotkeyAction::CaptureScreenshot => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = screenshot::capture_with_settings(app).await {
+                    log::error!("❌ 快捷键截图失败: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// 将最近一条记录写入剪贴板并粘贴到当前窗口
+async fn paste_last_item(app: &AppHandle) -> Result<(), String> {
+    crate::applock::ensure_unlocked()?;
+    let entry = history::recent_entries(1)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "没有历史记录".to_string())?;
+    clipboard::write_item(app, entry.item.id, None).await?;
+    quick_paste::paste_now().await
+}
+
+/// 检查绑定：快捷键格式无效或多个操作使用同一快捷键时返回错误
+fn validate(bindings: &[HotkeyBinding]) -> Result<Vec<Shortcut>, String> {
+    let mut seen: HashMap<Shortcut, &HotkeyAction> = HashMap::new();
+    let mut shortcuts = Vec::with_capacity(bindings.len());
+    for binding in bindings {
+        let shortcut = Shortcut::from_str(&binding.accelerator)
+            .map_err(|e| format!("快捷键格式无效 ({}): {}", binding.accelerator, e))?;
+        if let Some(existing) = seen.insert(shortcut, &binding.action) {
+            return Err(format!(
+                "快捷键 {} 同时绑定了 {:?} 和 {:?}",
+                binding.accelerator, existing, binding.action
+            ));
+        }
+        shortcuts.push(shortcut);
+    }
+    Ok(shortcuts)
+}
+
+/// 注册一组已检查过的绑定，返回各自的注册状态（已被其他应用占用的快捷键注册失败，不影响其他绑定）
+fn register_all(
+    app: &AppHandle,
+    bindings: Vec<HotkeyBinding>,
+    shortcuts: Vec<Shortcut>,
+) -> Vec<HotkeyStatus> {
+    bindings
+        .into_iter()
+        .zip(shortcuts)
+        .map(|(binding, shortcut)| {
+            let action = binding.action.clone();
+            let handler = move |app: &AppHandle, _: &Shortcut, event: ShortcutEvent| {
+                if event.state == ShortcutState::Pressed {
+                    dispatch(app, &action);
+                }
+            };
+            match app.global_shortcut().on_shortcut(shortcut, handler) {
+                Ok(()) => {
+                    log::info!(
+                        "⌨️ 已注册快捷键: {} -> {:?}",
+                        binding.accelerator,
+                        binding.action
+                    );
+                    HotkeyStatus {
+                        binding,
+                        registered: true,
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    log::warn!("⚠️ 注册快捷键失败 ({}): {}", binding.accelerator, e);
+                    HotkeyStatus {
+                        binding,
+                        registered: false,
+                        error: Some(e.to_string()),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// 注销当前已注册的所有绑定
+fn unregister_all(app: &AppHandle) {
+    let registered: Vec<String> = HOTKEYS
+        .lock()
+        .map(|hotkeys| {
+            hotkeys
+                .iter()
+                .filter(|status| status.registered)
+                .map(|status| status.binding.accelerator.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    for accelerator in registered {
+        if let Err(e) = app.global_shortcut().unregister(accelerator.as_str()) {
+            log::warn!("⚠️ 注销快捷键失败 ({}): {}", accelerator, e);
+        }
+    }
+}
+
+/// 替换全部绑定：注销旧绑定、注册并保存新绑定
+fn apply(app: &AppHandle, bindings: Vec<HotkeyBinding>) -> Result<Vec<HotkeyStatus>, String> {
+    let shortcuts = validate(&bindings)?;
+    unregister_all(app);
+    let statuses = register_all(app, bindings.clone(), shortcuts);
+
+    store::save_json(app, HOTKEYS_FILE, &bindings)?;
+    *HOTKEYS
+        .lock()
+        .map_err(|e| format!("无法锁定快捷键: {}", e))? = statuses.clone();
+    Ok(statuses)
+}
+
+/// 注册已保存的快捷键
+pub fn register_saved(app: &AppHandle) {
+    if let Err(e) = apply(app, bindings()) {
+        log::warn!("⚠️ 注册快捷键失败: {}", e);
+    }
+}
+
+/// 删除文本片段的快捷键绑定（片段被删除时调用）
+pub fn remove_snippet_bindings(app: &AppHandle, snippet_id: &str) -> Result<(), String> {
+    let mut bindings = bindings();
+    let before = bindings.len();
+    bindings.retain(
+        |binding| !matches!(&binding.action, HotkeyAction::PasteSnippet { id } if id == snippet_id),
+    );
+    if bindings.len() != before {
+        apply(app, bindings)?;
+    }
+    Ok(())
+}
+
+/// Tauri 命令：获取快捷键绑定及注册状态
+#[tauri::command]
+pub fn get_hotkeys() -> Vec<HotkeyStatus> {
+    HOTKEYS.lock().map(|h| h.clone()).unwrap_or_default()
+}
+
+/// Tauri 命令：替换全部快捷键绑定，返回各绑定的注册状态
+///
+/// 快捷键格式无效或多个操作使用同一快捷键时不做任何修改并返回错误；
+/// 被其他应用占用的快捷键仍会保存（下次启动时重试），在返回状态中标记为未注册
+#[tauri::command]
+pub fn set_hotkeys(
+    app: AppHandle,
+    bindings: Vec<HotkeyBinding>,
+) -> Result<Vec<HotkeyStatus>, CloudPasteError> {
+    Ok(apply(&app, bindings)?)
+}
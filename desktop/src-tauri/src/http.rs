@@ -0,0 +1,658 @@
+use crate::error::CloudPasteError;
+use once_cell::sync::{Lazy, OnceCell};
+use reqwest::{Client, Method, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const SETTINGS_FILE: &str = "http_timeouts.json";
+
+const NETWORK_SETTINGS_FILE: &str = "network.json";
+
+/// 网络超时设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutSettings {
+    /// 建立连接的超时时间（秒）
+    pub connect_secs: u64,
+    /// 下载文件时两次收到数据之间的最长等待时间（秒），超过视为连接已断开
+    pub read_secs: u64,
+}
+
+impl Default for TimeoutSettings {
+    fn default() -> Self {
+        Self {
+            connect_secs: 10,
+            read_secs: 30,
+        }
+    }
+}
+
+static TIMEOUTS: Lazy<Mutex<TimeoutSettings>> =
+    Lazy::new(|| Mutex::new(TimeoutSettings::default()));
+
+/// 连接服务器时使用的 IP 协议
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpPreference {
+    /// 同时尝试 IPv6 和 IPv4，先连上的为准（happy eyeballs）
+    #[default]
+    Auto,
+    /// 只使用 IPv4（适用于 AAAA 记录错误导致连接卡住的服务器）
+    Ipv4,
+    /// 只使用 IPv6
+    Ipv6,
+}
+
+/// 网络连接设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub ip_preference: IpPreference,
+}
+
+static NETWORK: Lazy<Mutex<NetworkSettings>> = Lazy::new(|| Mutex::new(NetworkSettings::default()));
+
+/// 共享 HTTP 客户端（复用连接池），超时设置变化时重建
+static CLIENT: Lazy<RwLock<Client>> =
+    Lazy::new(|| RwLock::new(build_client(&TimeoutSettings::default())));
+
+/// 用于发送切换事件的应用句柄
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+/// 当前是否在使用备用服务器地址
+static USING_FALLBACK: AtomicBool = AtomicBool::new(false);
+
+/// 使用备用地址时检查主地址是否恢复的间隔
+const PRIMARY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 检查主地址时的超时时间
+const PRIMARY_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 空闲连接在连接池中保留的时间
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// 每个主机最多保留的空闲连接数
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+
+/// TCP keep-alive 探测间隔
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// HTTP/2 连接的 PING 间隔，及时发现已断开的连接
+const HTTP2_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 等待 HTTP/2 PING 响应的超时时间
+const HTTP2_KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 服务器未给出 Retry-After 时的初始退避时间，连续限流时翻倍
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(5);
+
+/// 退避时间上限
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// 服务器限流状态
+struct Throttle {
+    /// 在此之前不发送 API 请求
+    until: Option<Instant>,
+    /// 连续被限流的次数
+    strikes: u32,
+}
+
+static THROTTLE: Mutex<Throttle> = Mutex::new(Throttle {
+    until: None,
+    strikes: 0,
+});
+
+/// 限流状态（作为 throttle-changed 事件的内容）
+#[derive(Debug, Clone, Serialize)]
+pub struct ThrottleState {
+    /// 是否正在等待服务器限流结束
+    pub throttled: bool,
+    /// 剩余等待秒数
+    pub retry_after_secs: u64,
+}
+
+/// 当前使用的服务器地址（也作为地址切换事件的内容）
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveEndpoint {
+    /// API 地址
+    pub base_url: String,
+    /// 是否为备用地址
+    pub fallback: bool,
+}
+
+fn build_client(settings: &TimeoutSettings) -> Client {
+    // 历史视图会同时发起大量小文件请求：复用空闲连接，TLS 下通过 ALPN 协商 HTTP/2 多路复用
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(settings.connect_secs))
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .http2_adaptive_window(true)
+        .http2_keep_alive_interval(HTTP2_KEEPALIVE_INTERVAL)
+        .http2_keep_alive_timeout(HTTP2_KEEPALIVE_TIMEOUT)
+        .http2_keep_alive_while_idle(true);
+    if let Some(resolver) = crate::dns::resolver() {
+        builder = builder.dns_resolver(resolver);
+    }
+    // 绑定到对应协议的本地地址后，连接器只会尝试该协议的服务器地址
+    match network().ip_preference {
+        IpPreference::Auto => {}
+        IpPreference::Ipv4 => builder = builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        IpPreference::Ipv6 => builder = builder.local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+    }
+    builder.build().unwrap_or_default()
+}
+
+/// 按当前超时、网络和 DNS 设置重建共享客户端
+pub fn rebuild_client() {
+    let client = build_client(&timeouts());
+    if let Ok(mut current) = CLIENT.write() {
+        *current = client;
+    }
+}
+
+pub fn client() -> Client {
+    CLIENT.read().map(|c| c.clone()).unwrap_or_default()
+}
+
+fn timeouts() -> TimeoutSettings {
+    TIMEOUTS.lock().map(|t| t.clone()).unwrap_or_default()
+}
+
+fn network() -> NetworkSettings {
+    NETWORK.lock().map(|n| n.clone()).unwrap_or_default()
+}
+
+/// 下载时等待下一块数据的超时时间
+pub fn read_timeout() -> Duration {
+    Duration::from_secs(timeouts().read_secs)
+}
+
+/// API 请求的总超时时间（连接超时加读取超时），避免服务器不响应时请求一直挂起
+pub fn request_timeout() -> Duration {
+    let settings = timeouts();
+    Duration::from_secs(settings.connect_secs + settings.read_secs)
+}
+
+/// 启动时从磁盘加载超时和网络设置，并按这些设置及 DNS 设置创建共享客户端
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = crate::store::load_json::<TimeoutSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut timeouts) = TIMEOUTS.lock() {
+            *timeouts = saved;
+        }
+    }
+    if let Some(saved) = crate::store::load_json::<NetworkSettings>(app, NETWORK_SETTINGS_FILE) {
+        if let Ok(mut network) = NETWORK.lock() {
+            *network = saved;
+        }
+    }
+    rebuild_client();
+}
+
+/// 启动时保存应用句柄，用于发送服务器地址切换事件
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 当前使用的 API 地址：主地址不可达且配置了备用地址时使用备用地址
+fn active_base_url(config: &crate::ApiConfig) -> String {
+    match config.fallback_url.as_ref() {
+        Some(fallback) if USING_FALLBACK.load(Ordering::Relaxed) => fallback.clone(),
+        _ => config.base_url.clone(),
+    }
+}
+
+/// 切换主/备用地址并发送事件，返回 (切换前, 切换后) 的 API 地址；没有备用地址时返回 None
+fn switch_endpoint(fallback: bool) -> Option<(String, String)> {
+    let (from, to) = {
+        let config = crate::GLOBAL_API_CONFIG.lock().ok()?;
+        let fallback_url = config.fallback_url.clone()?;
+        if fallback {
+            (config.base_url.clone(), fallback_url)
+        } else {
+            (fallback_url, config.base_url.clone())
+        }
+    };
+
+    if USING_FALLBACK.swap(fallback, Ordering::Relaxed) == fallback {
+        return Some((from, to));
+    }
+
+    if fallback {
+        log::warn!("⚠️ 主服务器不可达，切换到备用地址: {}", to);
+    } else {
+        log::info!("✅ 主服务器已恢复，切换回主地址: {}", to);
+    }
+    if let Some(app) = APP.get() {
+        let _ = app.emit(
+            "endpoint-switched",
+            ActiveEndpoint {
+                base_url: to.clone(),
+                fallback,
+            },
+        );
+    }
+    Some((from, to))
+}
+
+/// 配置变化后重新从主地址开始
+pub fn reset_endpoint() {
+    USING_FALLBACK.store(false, Ordering::Relaxed);
+}
+
+/// 将主地址下的 URL 改写为备用地址下的同一路径；URL 不属于主地址（如对象存储、第三方图片）或没有备用地址时返回 None
+fn fallback_url(url: &reqwest::Url) -> Option<reqwest::Url> {
+    let (primary, fallback) = {
+        let config = crate::GLOBAL_API_CONFIG.lock().ok()?;
+        (config.base_url.clone(), config.fallback_url.clone()?)
+    };
+    let rest = url
+        .as_str()
+        .strip_prefix(primary.trim_end_matches("/api/v1"))?;
+    if !(rest.is_empty() || rest.starts_with('/') || rest.starts_with('?')) {
+        return None;
+    }
+    format!("{}{}", fallback.trim_end_matches("/api/v1"), rest)
+        .parse()
+        .ok()
+}
+
+/// 执行请求；主地址连接失败且配置了备用地址时切换到备用地址并重试一次
+///
+/// 只有发往主地址的请求失败才会切换，第三方地址（对象存储、外部图片）不可达不影响服务器地址
+async fn execute_with_failover(request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    let request = request.build()?;
+    let retry = request.try_clone();
+    let url = request.url().clone();
+
+    let error = match client().execute(request).await {
+        Ok(response) => return Ok(response),
+        Err(e) if e.is_connect() || e.is_timeout() => e,
+        Err(e) => return Err(e),
+    };
+
+    if USING_FALLBACK.load(Ordering::Relaxed) {
+        return Err(error);
+    }
+    let Some(rewritten) = fallback_url(&url) else {
+        return Err(error);
+    };
+    if switch_endpoint(true).is_none() {
+        return Err(error);
+    }
+    let Some(mut retry) = retry else {
+        return Err(error);
+    };
+    *retry.url_mut() = rewritten;
+    client().execute(retry).await
+}
+
+/// 发送请求；主地址连接失败且配置了备用地址时切换到备用地址并重试一次
+///
+/// 流式请求体（如分块上传）无法重试，此时只切换地址，由调用方重试。
+/// 服务器返回 429/503 时按 Retry-After 全局退避，期间 `api_request` 直接返回错误
+pub async fn send(request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    let response = execute_with_failover(request).await?;
+    observe_rate_limit(&response);
+    Ok(response)
+}
+
+/// 解析 Retry-After 响应头（秒数或 HTTP 日期）
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let seconds = (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+    Some(Duration::from_secs(seconds.max(0) as u64))
+}
+
+/// 发送限流状态变化事件
+fn emit_throttle(state: ThrottleState) {
+    if let Some(app) = APP.get() {
+        let _ = app.emit("throttle-changed", state);
+    }
+}
+
+/// 根据响应更新限流状态：429/503 时开始退避，成功时清除连续限流计数
+fn observe_rate_limit(response: &Response) {
+    // 对象存储等第三方地址的限流不影响 API 请求
+    if !is_own_server(response.url().as_str()) {
+        return;
+    }
+    let status = response.status();
+    let limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || status == reqwest::StatusCode::SERVICE_UNAVAILABLE;
+    let Ok(mut throttle) = THROTTLE.lock() else {
+        return;
+    };
+
+    if !limited {
+        if status.is_success() {
+            throttle.strikes = 0;
+        }
+        return;
+    }
+
+    let backoff = retry_after(response)
+        .unwrap_or_else(|| DEFAULT_BACKOFF * 2u32.saturating_pow(throttle.strikes.min(10)))
+        .min(MAX_BACKOFF);
+    let until = Instant::now() + backoff;
+    throttle.strikes += 1;
+    throttle.until = Some(throttle.until.map_or(until, |current| current.max(until)));
+    drop(throttle);
+
+    log::warn!(
+        "⏳ 服务器限流 (HTTP {})，{} 秒内暂停请求",
+        status.as_u16(),
+        backoff.as_secs()
+    );
+    emit_throttle(ThrottleState {
+        throttled: true,
+        retry_after_secs: backoff.as_secs(),
+    });
+}
+
+/// 当前限流状态（退避结束时清除并发送事件）
+fn throttle_state() -> ThrottleState {
+    let Ok(mut throttle) = THROTTLE.lock() else {
+        return ThrottleState {
+            throttled: false,
+            retry_after_secs: 0,
+        };
+    };
+
+    match throttle.until {
+        Some(until) if until > Instant::now() => ThrottleState {
+            throttled: true,
+            retry_after_secs: (until - Instant::now()).as_secs().max(1),
+        },
+        Some(_) => {
+            throttle.until = None;
+            drop(throttle);
+            log::info!("✅ 服务器限流已结束");
+            let state = ThrottleState {
+                throttled: false,
+                retry_after_secs: 0,
+            };
+            emit_throttle(state.clone());
+            state
+        }
+        None => ThrottleState {
+            throttled: false,
+            retry_after_secs: 0,
+        },
+    }
+}
+
+/// 后台任务：使用备用地址期间定期检查主地址，恢复后切换回主地址
+pub async fn watch_primary() {
+    loop {
+        tokio::time::sleep(PRIMARY_CHECK_INTERVAL).await;
+        if !USING_FALLBACK.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        let health_url = match crate::GLOBAL_API_CONFIG.lock() {
+            Ok(config) => format!("{}/health", config.base_url.trim_end_matches("/api/v1")),
+            Err(_) => continue,
+        };
+        let reachable = client()
+            .get(&health_url)
+            .timeout(PRIMARY_CHECK_TIMEOUT)
+            .send()
+            .await
+            .is_ok_and(|response| response.status().is_success());
+        if reachable {
+            switch_endpoint(false);
+        }
+    }
+}
+
+/// 构造带认证信息的 API 请求
+///
+/// `path` 为相对于 `/api/v1` 的路径，例如 `/devices/`
+///
+/// 离线（服务器不可达或手动离线模式）或服务器限流期间直接返回错误，不等待请求超时
+pub fn api_request(method: Method, path: &str) -> Result<RequestBuilder, CloudPasteError> {
+    versioned_request("v1", method, path)
+}
+
+/// 构造指定 API 版本的带认证请求，`path` 为相对于 `/api/<version>` 的路径
+pub fn versioned_request(
+    version: &str,
+    method: Method,
+    path: &str,
+) -> Result<RequestBuilder, CloudPasteError> {
+    Ok(build_request(version, method, path)?.timeout(request_timeout()))
+}
+
+/// 构造传输文件内容的 API 请求，不设置总超时（大文件的传输时间取决于文件大小和网速）
+pub fn transfer_request(method: Method, path: &str) -> Result<RequestBuilder, CloudPasteError> {
+    build_request("v1", method, path)
+}
+
+fn build_request(
+    version: &str,
+    method: Method,
+    path: &str,
+) -> Result<RequestBuilder, CloudPasteError> {
+    crate::connectivity::ensure_online()?;
+
+    let throttle = throttle_state();
+    if throttle.throttled {
+        return Err(CloudPasteError::RateLimited {
+            retry_after_secs: throttle.retry_after_secs,
+        });
+    }
+
+    let (base_url, token) = {
+        let config = crate::GLOBAL_API_CONFIG
+            .lock()
+            .map_err(|e| format!("无法锁定配置: {}", e))?;
+
+        if !config.is_configured {
+            return Err(CloudPasteError::NotConfigured);
+        }
+
+        (active_base_url(&config), config.token.clone())
+    };
+
+    let base_url = format!("{}/api/{}", base_url.trim_end_matches("/api/v1"), version);
+    Ok(client()
+        .request(method, format!("{}{}", base_url, path))
+        .bearer_auth(token))
+}
+
+/// 两个 URL 是否属于同一来源（协议、主机和端口均相同）
+fn same_origin(a: &str, b: &str) -> bool {
+    match (reqwest::Url::parse(a), reqwest::Url::parse(b)) {
+        (Ok(a), Ok(b)) => a.origin() == b.origin(),
+        _ => false,
+    }
+}
+
+/// URL 是否指向本服务器（主地址或备用地址）
+fn is_own_server(url: &str) -> bool {
+    let Ok(config) = crate::GLOBAL_API_CONFIG.lock() else {
+        return false;
+    };
+    std::iter::once(&config.base_url)
+        .chain(config.fallback_url.as_ref())
+        .any(|base| same_origin(url, base))
+}
+
+/// 构建文件下载请求（使用共享客户端）；指向本服务器主地址或备用地址的 URL 附带登录令牌
+///
+/// 其他来源（如对象存储的预签名链接）不附带令牌，避免令牌泄露给第三方
+pub fn download_request(url: &str) -> RequestBuilder {
+    let request = client().get(url);
+    let Ok(config) = crate::GLOBAL_API_CONFIG.lock() else {
+        return request;
+    };
+    if !config.is_configured || config.token.is_empty() {
+        return request;
+    }
+
+    let own_server = std::iter::once(&config.base_url)
+        .chain(config.fallback_url.as_ref())
+        .any(|base| same_origin(url, base));
+    if own_server {
+        request.bearer_auth(&config.token)
+    } else {
+        request
+    }
+}
+
+/// 将服务器返回的相对路径（如 `/api/v1/files/download/x`）转换为完整 URL
+pub fn absolute_url(path: &str) -> Result<String, CloudPasteError> {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return Ok(path.to_string());
+    }
+
+    let config = crate::GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?;
+
+    Ok(format!(
+        "{}{}",
+        active_base_url(&config).trim_end_matches("/api/v1"),
+        path
+    ))
+}
+
+/// 检查响应状态码，失败时附带服务器返回的错误信息
+pub async fn check_response(response: Response) -> Result<Response, CloudPasteError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(CloudPasteError::RateLimited {
+            retry_after_secs: retry_after(&response).unwrap_or(DEFAULT_BACKOFF).as_secs(),
+        });
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        log::warn!("🔑 令牌已失效，需要重新登录");
+        crate::events::emit(crate::events::AuthExpired {
+            message: body.clone(),
+        });
+    }
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(CloudPasteError::Unauthorized(body));
+    }
+    Err(CloudPasteError::Server {
+        status: status.as_u16(),
+        message: body,
+    })
+}
+
+/// Tauri 命令：获取当前使用的服务器地址
+#[tauri::command]
+pub fn get_active_endpoint() -> Result<ActiveEndpoint, CloudPasteError> {
+    let config = crate::GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| format!("无法锁定配置: {}", e))?;
+    Ok(ActiveEndpoint {
+        base_url: active_base_url(&config),
+        fallback: config.fallback_url.is_some() && USING_FALLBACK.load(Ordering::Relaxed),
+    })
+}
+
+/// Tauri 命令：获取服务器限流状态
+#[tauri::command]
+pub fn get_throttle_state() -> ThrottleState {
+    throttle_state()
+}
+
+/// Tauri 命令：获取网络超时设置
+#[tauri::command]
+pub fn get_timeout_settings() -> TimeoutSettings {
+    timeouts()
+}
+
+/// Tauri 命令：更新网络超时设置，立即重建共享客户端
+#[tauri::command]
+pub fn set_timeout_settings(
+    app: AppHandle,
+    settings: TimeoutSettings,
+) -> Result<(), CloudPasteError> {
+    if settings.connect_secs == 0 || settings.read_secs == 0 {
+        return Err(CloudPasteError::InvalidInput(
+            "超时时间必须大于 0".to_string(),
+        ));
+    }
+
+    crate::store::save_json(&app, SETTINGS_FILE, &settings)?;
+    log::info!(
+        "⏱️ 网络超时: 连接 {} 秒，读取 {} 秒",
+        settings.connect_secs,
+        settings.read_secs
+    );
+    *TIMEOUTS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    rebuild_client();
+    Ok(())
+}
+
+/// Tauri 命令：获取网络连接设置
+#[tauri::command]
+pub fn get_network_settings() -> NetworkSettings {
+    network()
+}
+
+/// Tauri 命令：更新网络连接设置（IPv4 / IPv6 / 自动），立即重建共享客户端
+#[tauri::command]
+pub fn set_network_settings(
+    app: AppHandle,
+    settings: NetworkSettings,
+) -> Result<(), CloudPasteError> {
+    crate::store::save_json(&app, NETWORK_SETTINGS_FILE, &settings)?;
+    log::info!("🌐 连接协议: {:?}", settings.ip_preference);
+    *NETWORK.lock().map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    rebuild_client();
+    Ok(())
+}
+
+/// 与服务器协商的连接协议（诊断用）
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtocolInfo {
+    /// 检查的地址
+    pub url: String,
+    /// 协商的 HTTP 版本，如 "HTTP/2.0"
+    pub version: String,
+    /// 请求耗时（毫秒）
+    pub latency_ms: u64,
+}
+
+/// Tauri 命令：请求服务器健康检查接口，返回协商的 HTTP 版本
+#[tauri::command]
+pub async fn get_http_protocol() -> Result<ProtocolInfo, CloudPasteError> {
+    let url = absolute_url("/health")?;
+    let started = Instant::now();
+    let response = send(client().get(&url))
+        .await
+        .map_err(|e| CloudPasteError::request("检查连接协议失败", e))?;
+    let info = ProtocolInfo {
+        url,
+        version: format!("{:?}", response.version()),
+        latency_ms: started.elapsed().as_millis() as u64,
+    };
+    log::info!("🔌 连接协议: {} ({} ms)", info.version, info.latency_ms);
+    Ok(info)
+}
@@ -0,0 +1,48 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 最近一次本地操作（复制、输入、窗口获得焦点）的时间
+static LAST_LOCAL_ACTIVITY: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 记录一次本地活动
+pub fn record_local_activity() {
+    if let Ok(mut last) = LAST_LOCAL_ACTIVITY.lock() {
+        *last = Some(Instant::now());
+    }
+}
+
+/// 主窗口焦点变化（获得焦点视为一次本地活动）
+pub fn set_window_focused(focused: bool) {
+    if focused {
+        record_local_activity();
+    }
+}
+
+/// 距离最近一次本地活动的时长，从未有过活动时返回 None
+pub fn idle_duration() -> Option<Duration> {
+    LAST_LOCAL_ACTIVITY
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .map(|instant| instant.elapsed())
+}
+
+/// 本机是否已空闲至少指定秒数
+pub fn is_idle_for(seconds: u64) -> bool {
+    match idle_duration() {
+        Some(duration) => duration >= Duration::from_secs(seconds),
+        None => true,
+    }
+}
+
+/// Tauri 命令：前端上报本地活动（本地复制、键盘输入等）
+#[tauri::command]
+pub fn notify_local_activity() {
+    record_local_activity();
+}
+
+/// Tauri 命令：获取本机空闲秒数，从未有过活动时返回 None
+#[tauri::command]
+pub fn get_idle_seconds() -> Option<u64> {
+    idle_duration().map(|duration| duration.as_secs())
+}
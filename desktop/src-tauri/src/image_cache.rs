@@ -1,140 +1,512 @@
+use crate::error::CloudPasteError;
+use crate::events::{self, CacheEvicted, CacheWarmProgress, DownloadProgress, ExportProgress};
+use crate::transfer::RateMeter;
 use log::{info, warn};
-use reqwest;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Webview};
+use tokio::task::AbortHandle;
 
-/// 获取缓存目录路径
-fn get_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let cache_dir = app
-        .path()
-        .app_cache_dir()
-        .map_err(|e| format!("获取缓存目录失败: {}", e))?;
+/// 分块读取文件时单块的大小上限
+const MAX_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// 按服务器声明的 Content-Length 预分配内存的上限（声明的长度不可信）
+const MAX_PREALLOCATE: u64 = 8 * 1024 * 1024;
+
+/// 单个缓存文件的最大字节数，下载数据超过时中止
+const MAX_CACHED_FILE_SIZE: u64 = 512 * 1024 * 1024;
+
+/// `no-store` 内容的临时文件目录（相对于缓存目录），文件使用后删除
+const TRANSIENT_DIR: &str = "transient";
+
+/// `no-store` 内容的临时文件保留时间（足够前端加载一次）
+const TRANSIENT_TTL: Duration = Duration::from_secs(60);
+
+/// 下载进度事件的最短发送间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 导出缓存文件时每次复制的块大小
+const EXPORT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 批量预缓存时的最大并发下载数
+const WARM_CONCURRENCY: usize = 4;
+
+/// 缓存占用
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CacheUsage {
+    pub bytes: u64,
+    pub files: u64,
+}
+
+impl CacheUsage {
+    fn add_file(&mut self, size: u64) {
+        self.files += 1;
+        self.bytes += size;
+    }
 
-    let image_cache_dir = cache_dir.join("images");
+    fn merge(&mut self, other: CacheUsage) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+    }
+}
+
+/// 与缓存文件一同保存的 HTTP 缓存信息（来自 `Cache-Control` / `Expires`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheMeta {
+    /// 缓存时间（Unix 秒）
+    cached_at: u64,
+    /// 过期时间（Unix 秒），`no-cache` 时等于缓存时间
+    expires_at: u64,
+}
+
+/// 进行中的下载任务（同一 URL 可能同时有多个），用于取消下载
+static DOWNLOADS: Lazy<Mutex<HashMap<String, Vec<AbortHandle>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 批量预缓存中单个 URL 的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmedFile {
+    pub url: String,
+    /// 本地缓存路径，下载失败时为空
+    pub path: Option<String>,
+}
+
+/// 获取缓存目录路径
+pub fn get_cache_dir(app: &AppHandle) -> Result<PathBuf, CloudPasteError> {
+    let image_cache_dir = crate::store::cache_dir(app)?.join("images");
 
     // 确保缓存目录存在
-    fs::create_dir_all(&image_cache_dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+    fs::create_dir_all(&image_cache_dir).map_err(|e| CloudPasteError::io("创建缓存目录失败", e))?;
 
     Ok(image_cache_dir)
 }
 
-/// 根据 URL 生成缓存文件名（使用 SHA256 哈希）
-fn get_cache_filename(url: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(url.as_bytes());
-    let result = hasher.finalize();
+/// 支持的缓存文件扩展名
+const CACHE_EXTENSIONS: &[&str] = &[
+    "jpg", "png", "gif", "webp", "bmp", "svg", "ico", // 图片
+    "mp4", "avi", "mov", "mkv", "webm", "flv", "wmv", "m4v", // 视频
+    "mp3", "wav", "ogg", "flac", "m4a", "aac", "wma", // 音频
+    "pdf", "txt", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "csv", "json", "xml", // 文档
+    "zip", "rar", "7z", "tar", "gz", // 压缩文件
+    "js", "ts", "jsx", "tsx", "py", "java", "cpp", "c", "go", "rs", "html",
+    "css", // 代码文件
+];
+
+/// 未知类型使用的扩展名
+const UNKNOWN_EXTENSION: &str = "bin";
+
+/// 将扩展名规范化为支持的缓存扩展名
+fn normalize_extension(ext: &str) -> Option<&'static str> {
+    let ext_lower = ext.to_lowercase();
+    let ext_lower = match ext_lower.as_str() {
+        "jpeg" => "jpg",
+        "htm" => "html",
+        "plain" => "txt",
+        other => other,
+    };
+    CACHE_EXTENSIONS.iter().copied().find(|&e| e == ext_lower)
+}
 
-    // 从 URL 中提取文件扩展名（移除查询参数）
+/// 从 URL 中提取文件扩展名（移除查询参数）
+fn url_extension(url: &str) -> Option<&'static str> {
     let url_without_query = url.split('?').next().unwrap_or(url);
-
-    let extension = url_without_query
+    url_without_query
         .rsplit('/')
         .next()
-        .and_then(|filename| filename.rsplit('.').next())
-        .and_then(|ext| {
-            let ext_lower = ext.to_lowercase();
-
-            // 支持的文件扩展名列表
-            match ext_lower.as_str() {
-                // 图片
-                "jpg" | "jpeg" => Some("jpg"),
-                "png" => Some("png"),
-                "gif" => Some("gif"),
-                "webp" => Some("webp"),
-                "bmp" => Some("bmp"),
-                "svg" => Some("svg"),
-                "ico" => Some("ico"),
-
-                // 视频
-                "mp4" => Some("mp4"),
-                "avi" => Some("avi"),
-                "mov" => Some("mov"),
-                "mkv" => Some("mkv"),
-                "webm" => Some("webm"),
-                "flv" => Some("flv"),
-                "wmv" => Some("wmv"),
-                "m4v" => Some("m4v"),
-
-                // 音频
-                "mp3" => Some("mp3"),
-                "wav" => Some("wav"),
-                "ogg" => Some("ogg"),
-                "flac" => Some("flac"),
-                "m4a" => Some("m4a"),
-                "aac" => Some("aac"),
-                "wma" => Some("wma"),
-
-                // 文档
-                "pdf" => Some("pdf"),
-                "txt" => Some("txt"),
-                "doc" => Some("doc"),
-                "docx" => Some("docx"),
-                "xls" => Some("xls"),
-                "xlsx" => Some("xlsx"),
-                "ppt" => Some("ppt"),
-                "pptx" => Some("pptx"),
-                "csv" => Some("csv"),
-                "json" => Some("json"),
-                "xml" => Some("xml"),
-
-                // 压缩文件
-                "zip" => Some("zip"),
-                "rar" => Some("rar"),
-                "7z" => Some("7z"),
-                "tar" => Some("tar"),
-                "gz" => Some("gz"),
-
-                // 代码文件
-                "js" => Some("js"),
-                "ts" => Some("ts"),
-                "jsx" => Some("jsx"),
-                "tsx" => Some("tsx"),
-                "py" => Some("py"),
-                "java" => Some("java"),
-                "cpp" => Some("cpp"),
-                "c" => Some("c"),
-                "go" => Some("go"),
-                "rs" => Some("rs"),
-                "html" => Some("html"),
-                "css" => Some("css"),
-
-                _ => None,
-            }
-        })
-        .unwrap_or("bin"); // 未知类型使用 .bin
+        .and_then(|filename| filename.rsplit_once('.'))
+        .and_then(|(_, ext)| normalize_extension(ext))
+}
+
+/// 根据 URL 生成缓存文件名前缀（使用 SHA256 哈希）
+fn url_hash(url: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// 根据文件内容（magic bytes）或响应的 Content-Type 判断扩展名
+fn detect_extension(bytes: &[u8], content_type: Option<&str>) -> Option<&'static str> {
+    if let Some(ext) = infer::get(bytes).and_then(|kind| normalize_extension(kind.extension())) {
+        return Some(ext);
+    }
+
+    // 文本类文件没有 magic bytes，使用 Content-Type（如 text/csv、application/json）
+    let mime = content_type?.split(';').next()?.trim().to_lowercase();
+    let subtype = mime.rsplit('/').next()?;
+    match subtype {
+        "svg+xml" => Some("svg"),
+        "javascript" => Some("js"),
+        "quicktime" => Some("mov"),
+        "mpeg" if mime.starts_with("audio/") => Some("mp3"),
+        _ => normalize_extension(subtype.rsplit('+').next().unwrap_or(subtype)),
+    }
+}
+
+/// 根据 URL 计算缓存文件路径
+///
+/// URL 没有可识别的扩展名时，文件下载后按内容识别的扩展名保存，这里查找已按识别结果保存的文件
+pub fn cache_path_for_url(app: &AppHandle, url: &str) -> Result<PathBuf, CloudPasteError> {
+    let cache_dir = get_cache_dir(app)?;
+    let hash = url_hash(url);
+    if let Some(ext) = url_extension(url) {
+        return Ok(cache_dir.join(format!("{}.{}", hash, ext)));
+    }
+
+    let sniffed = CACHE_EXTENSIONS
+        .iter()
+        .map(|ext| cache_dir.join(format!("{}.{}", hash, ext)))
+        .find(|path| path.exists());
+    Ok(sniffed.unwrap_or_else(|| cache_dir.join(format!("{}.{}", hash, UNKNOWN_EXTENSION))))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// 缓存文件对应的 HTTP 缓存信息文件路径
+fn meta_path(cache_path: &Path) -> PathBuf {
+    let mut path = cache_path.as_os_str().to_owned();
+    path.push(".meta.json");
+    PathBuf::from(path)
+}
+
+fn cache_control(headers: &reqwest::header::HeaderMap) -> Vec<String> {
+    headers
+        .get_all(reqwest::header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim().to_lowercase())
+        .collect()
+}
 
-    format!("{:x}.{}", result, extension)
+/// 服务器是否禁止保存响应（`Cache-Control: no-store`）
+fn is_no_store(headers: &reqwest::header::HeaderMap) -> bool {
+    cache_control(headers)
+        .iter()
+        .any(|directive| directive == "no-store")
 }
 
-/// 下载图片并缓存
+/// 根据响应头计算缓存过期时间，服务器未声明缓存策略时返回 None（长期缓存）
+///
+/// `Cache-Control` 优先于 `Expires`；`no-cache` 视为立即过期，
+/// 本次仍可使用下载的文件，但下次访问会重新下载，定期清理时删除
+fn expires_at(headers: &reqwest::header::HeaderMap, now: u64) -> Option<u64> {
+    let cache_control = cache_control(headers);
+
+    if cache_control
+        .iter()
+        .any(|directive| directive == "no-cache")
+    {
+        return Some(now);
+    }
+    if let Some(max_age) = cache_control.iter().find_map(|directive| {
+        directive
+            .strip_prefix("max-age=")
+            .and_then(|age| age.trim_matches('"').parse::<u64>().ok())
+    }) {
+        return Some(now.saturating_add(max_age));
+    }
+
+    // 无法解析的 Expires（如 "0"）按已过期处理
+    let expires = headers.get(reqwest::header::EXPIRES)?.to_str().ok()?;
+    Some(
+        chrono::DateTime::parse_from_rfc2822(expires)
+            .map(|time| time.timestamp().max(0) as u64)
+            .unwrap_or(now),
+    )
+}
+
+/// 缓存文件是否仍在服务器允许的缓存期内（没有缓存信息的文件始终有效）
+fn is_fresh(cache_path: &Path) -> bool {
+    let Ok(data) = fs::read(meta_path(cache_path)) else {
+        return true;
+    };
+    serde_json::from_slice::<CacheMeta>(&data).map_or(true, |meta| meta.expires_at > now_secs())
+}
+
+/// 缓存文件是否存在且未过期
+pub fn is_cached(cache_path: &Path) -> bool {
+    cache_path.exists() && is_fresh(cache_path)
+}
+
+/// 确保文件已下载到缓存，返回缓存路径（下载失败时返回错误）
+pub async fn ensure_cached(app: &AppHandle, url: &str) -> Result<PathBuf, CloudPasteError> {
+    let cache_path = cache_path_for_url(app, url)?;
+    if is_cached(&cache_path) {
+        return Ok(cache_path);
+    }
+    download_and_cache(app, url, &cache_path).await
+}
+
+/// 先用零覆盖文件内容再删除，避免删除的敏感内容残留在磁盘上
+fn wipe_file(path: &Path, len: u64) -> std::io::Result<()> {
+    const CHUNK: usize = 64 * 1024;
+
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = [0u8; CHUNK];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(CHUNK as u64) as usize;
+        file.write_all(&zeros[..n])?;
+        remaining -= n as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)
+}
+
+/// 删除 URL 对应的缓存文件（及生成的视频封面和识别文字），返回释放的字节数
+///
+/// 文件内容在删除前被覆盖
+pub fn evict(app: &AppHandle, url: &str) -> Result<u64, CloudPasteError> {
+    let cache_path = cache_path_for_url(app, url)?;
+    let mut freed = 0u64;
+
+    for path in [
+        crate::video_poster::poster_path(&cache_path),
+        crate::ocr::text_path(&cache_path),
+        meta_path(&cache_path),
+        cache_path,
+    ] {
+        if let Ok(metadata) = fs::metadata(&path) {
+            wipe_file(&path, metadata.len())
+                .map_err(|e| CloudPasteError::io("删除缓存文件失败", e))?;
+            freed += metadata.len();
+        }
+    }
+
+    if freed > 0 {
+        events::emit(CacheEvicted {
+            url: Some(url.to_string()),
+            freed_bytes: freed,
+        });
+    }
+    Ok(freed)
+}
+
+/// 下载图片并缓存，返回实际保存的路径（扩展名可能按内容识别结果调整）
+///
+/// 下载在单独的任务中进行，可通过 `cancel_cache_download` 中止
 async fn download_and_cache(
     _app: &AppHandle,
     url: &str,
-    cache_path: &PathBuf,
-) -> Result<(), String> {
+    cache_path: &Path,
+) -> Result<PathBuf, CloudPasteError> {
+    let task = tauri::async_runtime::spawn({
+        let url = url.to_string();
+        let cache_path = cache_path.to_path_buf();
+        async move { download(&url, &cache_path).await }
+    });
+    let abort = task.inner().abort_handle();
+    if let Ok(mut downloads) = DOWNLOADS.lock() {
+        downloads
+            .entry(url.to_string())
+            .or_default()
+            .push(abort.clone());
+    }
+
+    let result = match task.await {
+        Ok(result) => result,
+        Err(tauri::Error::JoinError(e)) if e.is_cancelled() => {
+            info!("🚫 已取消下载: {}", url);
+            Err(CloudPasteError::Cancelled)
+        }
+        Err(e) => Err(CloudPasteError::Internal(format!("下载任务失败: {}", e))),
+    };
+
+    if let Ok(mut downloads) = DOWNLOADS.lock() {
+        if let Some(handles) = downloads.get_mut(url) {
+            handles.retain(|handle| handle.id() != abort.id());
+            if handles.is_empty() {
+                downloads.remove(url);
+            }
+        }
+    }
+
+    crate::telemetry::record("download", &result);
+    result
+}
+
+async fn download(url: &str, cache_path: &Path) -> Result<PathBuf, CloudPasteError> {
     info!("📥 开始下载图片: {}", url);
 
-    let response = reqwest::get(url)
+    // 服务器可能对文本类文件使用 zstd 压缩传输
+    let request =
+        crate::http::download_request(url).header(reqwest::header::ACCEPT_ENCODING, "zstd");
+    let read_timeout = crate::http::read_timeout();
+    let timed_out = || {
+        CloudPasteError::Network(format!(
+            "下载超时（{} 秒未收到数据）",
+            read_timeout.as_secs()
+        ))
+    };
+    let mut response = tokio::time::timeout(read_timeout, crate::http::send(request))
         .await
-        .map_err(|e| format!("下载图片失败: {}", e))?;
+        .map_err(|_| timed_out())?
+        .map_err(|e| CloudPasteError::request("下载图片失败", e))?;
 
     if !response.status().is_success() {
-        return Err(format!("下载失败，HTTP 状态码: {}", response.status()));
+        return Err(CloudPasteError::Server {
+            status: response.status().as_u16(),
+            message: "下载失败".to_string(),
+        });
     }
 
-    let bytes = response
-        .bytes()
+    let zstd_encoded = response
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .is_some_and(|encoding| encoding == "zstd");
+    let expires_at = expires_at(response.headers(), now_secs());
+    let no_store = is_no_store(response.headers());
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    let too_large = || {
+        CloudPasteError::InvalidResponse(format!(
+            "文件超过缓存大小上限（{} MB）",
+            MAX_CACHED_FILE_SIZE / 1024 / 1024
+        ))
+    };
+    let total = response.content_length();
+    if total.is_some_and(|total| total > MAX_CACHED_FILE_SIZE) {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::with_capacity(total.unwrap_or(0).min(MAX_PREALLOCATE) as usize);
+    let mut last_progress = Instant::now();
+    let mut meter = RateMeter::new();
+    while let Some(chunk) = tokio::time::timeout(read_timeout, response.chunk())
         .await
-        .map_err(|e| format!("读取图片数据失败: {}", e))?;
+        .map_err(|_| timed_out())?
+        .map_err(|e| CloudPasteError::request("读取图片数据失败", e))?
+    {
+        if (bytes.len() + chunk.len()) as u64 > MAX_CACHED_FILE_SIZE {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+        meter.record(bytes.len() as u64);
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            events::emit(DownloadProgress {
+                url: url.to_string(),
+                downloaded: bytes.len() as u64,
+                total,
+                bytes_per_sec: meter.bytes_per_sec(),
+                eta_secs: meter.eta_secs(bytes.len() as u64, total),
+                done: false,
+            });
+        }
+    }
+    events::emit(DownloadProgress {
+        url: url.to_string(),
+        downloaded: bytes.len() as u64,
+        total,
+        bytes_per_sec: meter.bytes_per_sec(),
+        eta_secs: Some(0),
+        done: true,
+    });
+    if zstd_encoded {
+        bytes = crate::compression::decompress(&bytes, MAX_CACHED_FILE_SIZE)
+            .map_err(CloudPasteError::InvalidResponse)?;
+    }
+
+    // URL 中没有扩展名（如预签名链接）时按内容识别，便于按类型预览
+    let mut cache_path = cache_path.to_path_buf();
+    if cache_path
+        .extension()
+        .is_some_and(|ext| ext == UNKNOWN_EXTENSION)
+    {
+        if let Some(ext) = detect_extension(&bytes, content_type.as_deref()) {
+            cache_path.set_extension(ext);
+        }
+    }
 
-    fs::write(cache_path, bytes).map_err(|e| format!("保存图片到缓存失败: {}", e))?;
+    if no_store {
+        return store_transient(&cache_path, &bytes);
+    }
+
+    crate::store::write_atomic(&cache_path, &bytes)
+        .map_err(|e| CloudPasteError::io("保存图片到缓存失败", e))?;
+
+    // 保存服务器声明的缓存期限；没有声明时删除旧的缓存信息
+    let meta_path = meta_path(&cache_path);
+    match expires_at {
+        Some(expires_at) => {
+            let meta = CacheMeta {
+                cached_at: now_secs(),
+                expires_at,
+            };
+            let data = serde_json::to_vec(&meta)
+                .map_err(|e| CloudPasteError::Internal(format!("序列化缓存信息失败: {}", e)))?;
+            crate::store::write_atomic(&meta_path, &data)
+                .map_err(|e| CloudPasteError::io("保存缓存信息失败", e))?;
+        }
+        None => {
+            let _ = fs::remove_file(&meta_path);
+        }
+    }
 
     info!("✅ 图片已缓存到: {:?}", cache_path);
 
-    Ok(())
+    Ok(cache_path)
+}
+
+/// 保存 `no-store` 内容：不写入缓存，只写入临时文件供本次使用，过一段时间后删除
+///
+/// 之前缓存的同一 URL 的文件一并删除，下次访问重新下载
+fn store_transient(cache_path: &Path, bytes: &[u8]) -> Result<PathBuf, CloudPasteError> {
+    let (Some(cache_dir), Some(file_name)) = (cache_path.parent(), cache_path.file_name()) else {
+        return Err(CloudPasteError::Internal("无效的缓存路径".to_string()));
+    };
+    for path in [cache_path.to_path_buf(), meta_path(cache_path)] {
+        if let Ok(metadata) = fs::metadata(&path) {
+            let _ = wipe_file(&path, metadata.len());
+        }
+    }
+
+    let transient_dir = cache_dir.join(TRANSIENT_DIR);
+    fs::create_dir_all(&transient_dir).map_err(|e| CloudPasteError::io("创建临时目录失败", e))?;
+    let path = transient_dir.join(file_name);
+    crate::store::write_atomic(&path, bytes)
+        .map_err(|e| CloudPasteError::io("保存临时文件失败", e))?;
+
+    let transient = path.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(TRANSIENT_TTL).await;
+        remove_transient(&transient);
+    });
+
+    info!("✅ 内容不允许缓存，已保存为临时文件: {:?}", path);
+    Ok(path)
+}
+
+/// 覆盖并删除临时文件及其生成的视频封面和识别文字
+fn remove_transient(path: &Path) {
+    for path in [
+        crate::video_poster::poster_path(path),
+        crate::ocr::text_path(path),
+        path.to_path_buf(),
+    ] {
+        if let Ok(metadata) = fs::metadata(&path) {
+            if let Err(e) = wipe_file(&path, metadata.len()) {
+                warn!("⚠️ 删除临时文件失败: {:?} ({})", path, e);
+            }
+        }
+    }
 }
 
 /// Tauri 命令：获取文件缓存路径（通用版本，支持所有文件类型）
@@ -143,32 +515,35 @@ async fn download_and_cache(
 /// 如果未缓存，下载并缓存后返回本地文件路径
 /// 如果下载失败，返回原始 URL
 #[tauri::command]
-pub async fn get_cached_file_path(app: AppHandle, url: String) -> Result<String, String> {
+pub async fn get_cached_file_path(app: AppHandle, url: String) -> Result<String, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
     // 如果不是 HTTP/HTTPS URL，直接返回（可能是本地文件）
     if !url.starts_with("http://") && !url.starts_with("https://") {
         return Ok(url);
     }
 
-    let cache_dir = get_cache_dir(&app)?;
-    let filename = get_cache_filename(&url);
-    let cache_path = cache_dir.join(&filename);
+    let cache_path = cache_path_for_url(&app, &url)?;
 
-    // 检查缓存是否存在
-    if cache_path.exists() {
+    // 检查缓存是否存在且未过期
+    if is_cached(&cache_path) {
         info!("✅ 使用缓存的文件: {:?}", cache_path);
+        crate::ocr::spawn_auto_ocr(&app, &url, &cache_path);
         // 返回文件系统路径（前端会使用 convertFileSrc 转换）
         return cache_path
             .to_str()
             .map(|s| s.to_string())
-            .ok_or_else(|| "路径转换失败".to_string());
+            .ok_or_else(|| CloudPasteError::Internal("路径转换失败".to_string()));
     }
 
     // 下载并缓存
     match download_and_cache(&app, &url, &cache_path).await {
-        Ok(_) => cache_path
-            .to_str()
-            .map(|s| s.to_string())
-            .ok_or_else(|| "路径转换失败".to_string()),
+        Ok(cache_path) => {
+            crate::ocr::spawn_auto_ocr(&app, &url, &cache_path);
+            cache_path
+                .to_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| CloudPasteError::Internal("路径转换失败".to_string()))
+        }
         Err(e) => {
             warn!("⚠️ 下载失败，使用原始 URL: {}", e);
             // 下载失败时返回原始 URL
@@ -177,62 +552,374 @@ pub async fn get_cached_file_path(app: AppHandle, url: String) -> Result<String,
     }
 }
 
-/// Tauri 命令：获取图片缓存路径（保留向后兼容）
+/// 缓存单个 URL，返回本地路径（非 HTTP 地址原样返回）
+async fn warm_one(app: &AppHandle, url: &str) -> Option<String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Some(url.to_string());
+    }
+    match ensure_cached(app, url).await {
+        Ok(path) => {
+            crate::ocr::spawn_auto_ocr(app, url, &path);
+            path.to_str().map(|s| s.to_string())
+        }
+        Err(e) => {
+            warn!("⚠️ 预缓存失败: {} ({})", url, e);
+            None
+        }
+    }
+}
+
+/// Tauri 命令：批量缓存一组 URL（如历史网格当前页），按输入顺序返回本地路径
+///
+/// 以有限并发下载，每完成一个发送 `cache-warm-progress` 事件
 #[tauri::command]
-pub async fn get_cached_image_path(app: AppHandle, url: String) -> Result<String, String> {
-    get_cached_file_path(app, url).await
+pub async fn warm_cache(
+    app: AppHandle,
+    urls: Vec<String>,
+) -> Result<Vec<WarmedFile>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let total = urls.len();
+    let queue = Arc::new(Mutex::new(
+        urls.iter().cloned().enumerate().collect::<VecDeque<_>>(),
+    ));
+    let paths = Arc::new(Mutex::new(vec![None; total]));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let failed = Arc::new(AtomicUsize::new(0));
+
+    let workers: Vec<_> = (0..WARM_CONCURRENCY.min(total))
+        .map(|_| {
+            let (app, queue, paths) = (app.clone(), queue.clone(), paths.clone());
+            let (completed, failed) = (completed.clone(), failed.clone());
+            tauri::async_runtime::spawn(async move {
+                while let Some((index, url)) = queue.lock().ok().and_then(|mut q| q.pop_front()) {
+                    let path = warm_one(&app, &url).await;
+                    if path.is_none() {
+                        failed.fetch_add(1, Ordering::SeqCst);
+                    }
+                    if let Ok(mut paths) = paths.lock() {
+                        paths[index] = path;
+                    }
+                    events::emit(CacheWarmProgress {
+                        completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                        failed: failed.load(Ordering::SeqCst),
+                        total,
+                    });
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker
+            .await
+            .map_err(|e| CloudPasteError::Internal(format!("预缓存失败: {}", e)))?;
+    }
+
+    let paths = paths
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("预缓存失败: {}", e)))?
+        .clone();
+    info!(
+        "✅ 批量缓存完成: {} 个，失败 {} 个",
+        total,
+        failed.load(Ordering::SeqCst)
+    );
+    Ok(urls
+        .into_iter()
+        .zip(paths)
+        .map(|(url, path)| WarmedFile { url, path })
+        .collect())
 }
 
-/// Tauri 命令：清除所有图片缓存
+/// Tauri 命令：删除单个 URL 的缓存文件（删除剪贴板项时回收磁盘空间），返回释放的字节数
 #[tauri::command]
-pub async fn clear_image_cache(app: AppHandle) -> Result<(), String> {
-    let cache_dir = get_cache_dir(&app)?;
+pub async fn evict_cache_entry(app: AppHandle, url: String) -> Result<u64, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let freed = evict(&app, &url)?;
+    info!("🗑️ 已删除缓存: {} ({} 字节)", url, freed);
+    Ok(freed)
+}
 
-    if cache_dir.exists() {
-        fs::remove_dir_all(&cache_dir).map_err(|e| format!("清除缓存失败: {}", e))?;
+/// 将缓存文件复制到目标位置，按块复制并发送进度事件，返回复制的字节数
+fn copy_with_progress(url: &str, source: &Path, dest: &Path) -> Result<u64, CloudPasteError> {
+    let mut input =
+        fs::File::open(source).map_err(|e| CloudPasteError::io("读取缓存文件失败", e))?;
+    let total = input
+        .metadata()
+        .map_err(|e| CloudPasteError::io("读取缓存文件失败", e))?
+        .len();
+
+    crate::store::write_atomic_with::<CloudPasteError>(dest, |mut output| {
+        let mut buffer = vec![0u8; EXPORT_CHUNK_SIZE];
+        let mut copied = 0u64;
+        let mut last_progress = Instant::now();
+        let mut meter = RateMeter::new();
+        loop {
+            let read = input
+                .read(&mut buffer)
+                .map_err(|e| CloudPasteError::io("读取缓存文件失败", e))?;
+            if read == 0 {
+                break;
+            }
+            output
+                .write_all(&buffer[..read])
+                .map_err(|e| CloudPasteError::io("写入文件失败", e))?;
+            copied += read as u64;
+            meter.record(copied);
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                last_progress = Instant::now();
+                events::emit(ExportProgress {
+                    url: url.to_string(),
+                    copied,
+                    total,
+                    bytes_per_sec: meter.bytes_per_sec(),
+                    eta_secs: meter.eta_secs(copied, Some(total)),
+                    done: false,
+                });
+            }
+        }
+        Ok(output)
+    })?;
+
+    events::emit(ExportProgress {
+        url: url.to_string(),
+        copied: total,
+        total,
+        bytes_per_sec: None,
+        eta_secs: Some(0),
+        done: true,
+    });
+    Ok(total)
+}
+
+/// Tauri 命令：将缓存文件导出到用户选择的位置（未缓存时先下载），返回文件大小
+///
+/// 直接在本地复制，不经过前端传输文件内容；下载和复制进度分别通过
+/// `download-progress` 和 `export-progress` 事件获取
+#[tauri::command]
+pub async fn export_cached_file(
+    app: AppHandle,
+    webview: Webview,
+    url: String,
+    dest_path: String,
+) -> Result<u64, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let dest = crate::sandbox::check(&webview, &dest_path)?;
+    let source = ensure_cached(&app, &url).await?;
+
+    info!("💾 导出缓存文件: {} -> {:?}", url, dest);
+    let size =
+        tauri::async_runtime::spawn_blocking(move || copy_with_progress(&url, &source, &dest))
+            .await
+            .map_err(|e| CloudPasteError::Internal(format!("导出文件失败: {}", e)))??;
+    info!("✅ 文件已导出: {} ({} 字节)", dest_path, size);
+    Ok(size)
+}
+
+/// 删除超过服务器声明缓存期限的文件（如短期有效的预签名链接、`no-cache` 内容），返回释放的字节数
+///
+/// 同时删除上次运行时遗留的 `no-store` 临时文件
+pub fn purge_expired(app: &AppHandle) -> Result<u64, CloudPasteError> {
+    let cache_dir = get_cache_dir(app)?;
+    let now = now_secs();
+    let mut freed = 0u64;
+
+    if let Ok(entries) = fs::read_dir(cache_dir.join(TRANSIENT_DIR)) {
+        for entry in entries.flatten() {
+            let stale = entry
+                .metadata()
+                .ok()
+                .and_then(|metadata| metadata.modified().ok())
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age >= TRANSIENT_TTL);
+            if stale {
+                remove_transient(&entry.path());
+            }
+        }
+    }
+
+    for entry in fs::read_dir(&cache_dir)
+        .map_err(|e| CloudPasteError::io("读取缓存目录失败", e))?
+        .flatten()
+    {
+        let path = entry.path();
+        let Some(cache_path) = path
+            .to_str()
+            .and_then(|p| p.strip_suffix(".meta.json"))
+            .map(PathBuf::from)
+        else {
+            continue;
+        };
+        let expired = fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<CacheMeta>(&data).ok())
+            .is_some_and(|meta| meta.expires_at <= now);
+        if !expired {
+            continue;
+        }
 
-        // 重新创建缓存目录
-        fs::create_dir_all(&cache_dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+        for path in [
+            crate::video_poster::poster_path(&cache_path),
+            crate::ocr::text_path(&cache_path),
+            cache_path,
+            path,
+        ] {
+            if let Ok(metadata) = fs::metadata(&path) {
+                fs::remove_file(&path).map_err(|e| CloudPasteError::io("删除缓存文件失败", e))?;
+                freed += metadata.len();
+            }
+        }
+    }
 
-        info!("✅ 图片缓存已清除");
+    if freed > 0 {
+        info!("🧹 已删除过期的缓存文件，释放 {} 字节", freed);
+        events::emit(CacheEvicted {
+            url: None,
+            freed_bytes: freed,
+        });
     }
+    Ok(freed)
+}
 
-    Ok(())
+/// Tauri 命令：取消 URL 正在进行的缓存下载，返回是否有下载被取消
+///
+/// 等待该下载的调用（如 `get_cached_file_path`）会收到取消错误或回退到原始 URL
+#[tauri::command]
+pub fn cancel_cache_download(url: String) -> bool {
+    let handles = DOWNLOADS
+        .lock()
+        .ok()
+        .and_then(|mut downloads| downloads.remove(&url))
+        .unwrap_or_default();
+    for handle in &handles {
+        handle.abort();
+    }
+    !handles.is_empty()
+}
+
+/// Tauri 命令：获取图片缓存路径（保留向后兼容）
+#[tauri::command]
+pub async fn get_cached_image_path(app: AppHandle, url: String) -> Result<String, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    get_cached_file_path(app, url).await
 }
 
-/// Tauri 命令：获取缓存大小（字节）
+/// Tauri 命令：清除所有图片缓存（固定的文件除外）
 #[tauri::command]
-pub async fn get_cache_size(app: AppHandle) -> Result<u64, String> {
+pub async fn clear_image_cache(app: AppHandle) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
     let cache_dir = get_cache_dir(&app)?;
 
-    if !cache_dir.exists() {
-        return Ok(0);
+    if cache_dir.exists() {
+        let pinned = crate::pinned::protected_paths(&app);
+        let keep: HashSet<PathBuf> = pinned
+            .iter()
+            .flat_map(|path| [path.clone(), meta_path(path)])
+            .collect();
+        let mut freed = 0u64;
+
+        for entry in fs::read_dir(&cache_dir)
+            .map_err(|e| CloudPasteError::io("清除缓存失败", e))?
+            .flatten()
+        {
+            let path = entry.path();
+            if keep.contains(&path) {
+                continue;
+            }
+            let result = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                freed += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                fs::remove_file(&path)
+            };
+            result.map_err(|e| CloudPasteError::io("清除缓存失败", e))?;
+        }
+
+        info!("✅ 图片缓存已清除（保留 {} 个固定文件）", pinned.len());
+        events::emit(CacheEvicted {
+            url: None,
+            freed_bytes: freed,
+        });
     }
 
-    let mut total_size = 0u64;
+    Ok(())
+}
 
-    if let Ok(entries) = fs::read_dir(&cache_dir) {
-        for entry in entries.flatten() {
-            if let Ok(metadata) = entry.metadata() {
-                if metadata.is_file() {
-                    total_size += metadata.len();
-                }
+/// 递归统计目录下的文件数和总大小，各子目录在单独的线程中并行统计
+pub fn dir_usage(dir: &Path) -> CacheUsage {
+    let mut usage = CacheUsage::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return usage;
+    };
+
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            subdirs.push(entry.path());
+        } else {
+            usage.add_file(metadata.len());
+        }
+    }
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = subdirs
+            .iter()
+            .map(|subdir| scope.spawn(|| dir_usage_serial(subdir)))
+            .collect();
+        for handle in handles {
+            if let Ok(sub) = handle.join() {
+                usage.merge(sub);
             }
         }
+    });
+    usage
+}
+
+fn dir_usage_serial(dir: &Path) -> CacheUsage {
+    let mut usage = CacheUsage::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return usage;
+    };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            usage.merge(dir_usage_serial(&entry.path()));
+        } else {
+            usage.add_file(metadata.len());
+        }
     }
+    usage
+}
 
-    Ok(total_size)
+/// Tauri 命令：获取缓存占用（包括子目录中的视频封面和未完成的下载）
+///
+/// 在后台线程中统计，缓存较大时也不会阻塞界面
+#[tauri::command]
+pub async fn get_cache_size(app: AppHandle) -> Result<CacheUsage, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let cache_dir = get_cache_dir(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || dir_usage(&cache_dir))
+        .await
+        .map_err(|e| CloudPasteError::Internal(format!("统计缓存大小失败: {}", e)))
 }
 
 /// Tauri 命令：保存文件到指定路径
 #[tauri::command]
-pub async fn save_file_to_path(file_path: String, data: Vec<u8>) -> Result<(), String> {
+pub async fn save_file_to_path(
+    webview: Webview,
+    file_path: String,
+    data: Vec<u8>,
+) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
     info!("💾 保存文件到: {}", file_path);
+    let path = crate::sandbox::check(&webview, &file_path)?;
 
-    let mut file = fs::File::create(&file_path).map_err(|e| format!("创建文件失败: {}", e))?;
-
-    file.write_all(&data)
-        .map_err(|e| format!("写入文件失败: {}", e))?;
+    crate::store::write_atomic(&path, &data).map_err(|e| CloudPasteError::io("写入文件失败", e))?;
 
     info!("✅ 文件已保存: {}", file_path);
 
@@ -241,12 +928,51 @@ pub async fn save_file_to_path(file_path: String, data: Vec<u8>) -> Result<(), S
 
 /// Tauri 命令：读取文件字节数据
 #[tauri::command]
-pub async fn read_file_bytes(file_path: String) -> Result<Vec<u8>, String> {
+pub async fn read_file_bytes(
+    webview: Webview,
+    file_path: String,
+) -> Result<Vec<u8>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
     info!("📖 读取文件: {}", file_path);
+    let path = crate::sandbox::check(&webview, &file_path)?;
 
-    let data = fs::read(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let data = fs::read(&path).map_err(|e| CloudPasteError::io("读取文件失败", e))?;
 
     info!("✅ 文件已读取: {} 字节", data.len());
 
     Ok(data)
 }
+
+/// Tauri 命令：获取文件大小（字节），配合 read_file_chunk 分块读取
+#[tauri::command]
+pub async fn get_file_size(webview: Webview, file_path: String) -> Result<u64, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let path = crate::sandbox::check(&webview, &file_path)?;
+    let metadata = fs::metadata(&path).map_err(|e| CloudPasteError::io("读取文件信息失败", e))?;
+    Ok(metadata.len())
+}
+
+/// Tauri 命令：读取文件的一段（最多 4 MB），以二进制返回
+///
+/// 大文件应分块读取，避免一次性载入内存并序列化整个文件；超出文件末尾时返回的数据较短或为空
+#[tauri::command]
+pub async fn read_file_chunk(
+    webview: Webview,
+    file_path: String,
+    offset: u64,
+    len: u64,
+) -> Result<tauri::ipc::Response, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let path = crate::sandbox::check(&webview, &file_path)?;
+    let len = len.min(MAX_CHUNK_SIZE);
+    let mut file = fs::File::open(&path).map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+
+    let mut data = Vec::with_capacity(len as usize);
+    file.take(len)
+        .read_to_end(&mut data)
+        .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+
+    Ok(tauri::ipc::Response::new(data))
+}
@@ -0,0 +1,218 @@
+use crate::error::CloudPasteError;
+use crate::{metadata_strip, store};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ExtendedColorType, ImageDecoder, ImageFormat, ImageReader};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "image_processing.json";
+
+/// 上传前图片处理设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageProcessingSettings {
+    /// 是否在上传前压缩图片
+    pub enabled: bool,
+    /// 最长边的最大像素数
+    pub max_dimension: u32,
+    /// JPEG 质量（1-100）
+    pub jpeg_quality: u8,
+    /// 是否将不含透明通道的 PNG 转为 JPEG
+    pub convert_png_to_jpeg: bool,
+    /// 是否在上传前移除 EXIF/XMP 元数据（如 GPS 位置）
+    pub strip_metadata: bool,
+}
+
+impl Default for ImageProcessingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_dimension: 2560,
+            jpeg_quality: 85,
+            convert_png_to_jpeg: true,
+            strip_metadata: false,
+        }
+    }
+}
+
+/// 各服务器配置（profile）的设置，key 为 base_url
+static SETTINGS: Lazy<Mutex<HashMap<String, ImageProcessingSettings>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 当前服务器配置对应的 key
+fn profile_key() -> String {
+    crate::GLOBAL_API_CONFIG
+        .lock()
+        .map(|config| config.base_url.clone())
+        .unwrap_or_default()
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) =
+        store::load_json::<HashMap<String, ImageProcessingSettings>>(app, SETTINGS_FILE)
+    {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+/// 当前服务器配置的图片处理设置
+fn settings() -> ImageProcessingSettings {
+    let key = profile_key();
+    SETTINGS
+        .lock()
+        .ok()
+        .and_then(|settings| settings.get(&key).cloned())
+        .unwrap_or_default()
+}
+
+/// 当前服务器配置是否要求上传前移除图片元数据
+pub fn strip_metadata_enabled() -> bool {
+    settings().strip_metadata
+}
+
+/// 根据扩展名判断图片格式
+fn image_format(file_name: &str) -> Option<ImageFormat> {
+    let ext = file_name.rsplit('.').next()?.to_lowercase();
+    match ext.as_str() {
+        "png" => Some(ImageFormat::Png),
+        "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+        "bmp" => Some(ImageFormat::Bmp),
+        "webp" => Some(ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// 替换文件扩展名
+fn with_extension(file_name: &str, extension: &str) -> String {
+    match file_name.rsplit_once('.') {
+        Some((stem, _)) => format!("{}.{}", stem, extension),
+        None => format!("{}.{}", file_name, extension),
+    }
+}
+
+/// 缩放并重新编码图片，结果不比原图小时返回 None
+///
+/// 重新编码不保留 EXIF，解码时先按 EXIF 方向旋转，避免图片转向
+fn compress(
+    file_name: &str,
+    data: &[u8],
+    format: ImageFormat,
+    settings: &ImageProcessingSettings,
+) -> Result<Option<(String, Vec<u8>)>, String> {
+    let mut decoder = ImageReader::with_format(Cursor::new(data), format)
+        .into_decoder()
+        .map_err(|e| format!("解码图片失败: {}", e))?;
+    let orientation = decoder
+        .orientation()
+        .map_err(|e| format!("读取图片方向失败: {}", e))?;
+    let mut img =
+        DynamicImage::from_decoder(decoder).map_err(|e| format!("解码图片失败: {}", e))?;
+    img.apply_orientation(orientation);
+
+    let (width, height) = (img.width(), img.height());
+    if width.max(height) > settings.max_dimension {
+        img = img.resize(
+            settings.max_dimension,
+            settings.max_dimension,
+            FilterType::Lanczos3,
+        );
+    }
+
+    let to_jpeg =
+        format == ImageFormat::Jpeg || (settings.convert_png_to_jpeg && !img.color().has_alpha());
+
+    let mut output = Vec::new();
+    let output_name = if to_jpeg {
+        let rgb = img.to_rgb8();
+        JpegEncoder::new_with_quality(&mut output, settings.jpeg_quality.clamp(1, 100))
+            .encode(&rgb, rgb.width(), rgb.height(), ExtendedColorType::Rgb8)
+            .map_err(|e| format!("编码 JPEG 失败: {}", e))?;
+        with_extension(file_name, "jpg")
+    } else {
+        img.write_to(&mut Cursor::new(&mut output), ImageFormat::Png)
+            .map_err(|e| format!("编码 PNG 失败: {}", e))?;
+        with_extension(file_name, "png")
+    };
+
+    if output.len() >= data.len() {
+        return Ok(None);
+    }
+
+    log::info!(
+        "🗜️ 图片已压缩: {}x{} -> {}x{}, {} -> {} 字节",
+        width,
+        height,
+        img.width(),
+        img.height(),
+        data.len(),
+        output.len()
+    );
+    Ok(Some((output_name, output)))
+}
+
+/// 上传前的图片预处理步骤：移除元数据、压缩
+///
+/// 压缩失败时使用原图；元数据移除失败时拒绝上传，避免隐私数据外泄
+pub async fn preprocess(file_name: String, data: Vec<u8>) -> Result<(String, Vec<u8>), String> {
+    let settings = settings();
+    if !settings.enabled && !settings.strip_metadata {
+        return Ok((file_name, data));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut data = data;
+
+        if settings.strip_metadata {
+            match metadata_strip::strip_metadata(&data) {
+                Ok(Some(stripped)) => {
+                    log::info!("🧹 已移除图片元数据: {}", file_name);
+                    data = stripped;
+                }
+                Ok(None) => {}
+                Err(e) => return Err(format!("移除图片元数据失败，已取消上传: {}", e)),
+            }
+        }
+
+        let Some(format) = image_format(&file_name).filter(|_| settings.enabled) else {
+            return Ok((file_name, data));
+        };
+
+        match compress(&file_name, &data, format, &settings) {
+            Ok(Some(processed)) => Ok(processed),
+            Ok(None) => Ok((file_name, data)),
+            Err(e) => {
+                log::warn!("⚠️ 图片压缩失败，使用原图上传: {}", e);
+                Ok((file_name, data))
+            }
+        }
+    })
+    .await
+    .map_err(|e| format!("图片预处理任务失败: {}", e))?
+}
+
+/// Tauri 命令：获取当前服务器配置的图片处理设置
+#[tauri::command]
+pub fn get_image_processing_settings() -> ImageProcessingSettings {
+    settings()
+}
+
+/// Tauri 命令：更新当前服务器配置的图片处理设置
+#[tauri::command]
+pub fn set_image_processing_settings(
+    app: AppHandle,
+    settings: ImageProcessingSettings,
+) -> Result<(), CloudPasteError> {
+    let mut all = SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))?;
+    all.insert(profile_key(), settings);
+    Ok(store::save_json(&app, SETTINGS_FILE, &*all)?)
+}
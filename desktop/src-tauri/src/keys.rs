@@ -0,0 +1,565 @@
+use crate::error::CloudPasteError;
+use crate::events::{self, KeyRotationProgress};
+use crate::{http, store};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use once_cell::sync::Lazy;
+use rand_core::{OsRng, RngCore};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const KEYS_FILE: &str = "keys.json";
+
+/// 替换密钥材料期间保存原来的密钥材料，中途退出时历史记录数据库可能仍由它加密
+const PENDING_KEYS_FILE: &str = "keys.pending.json";
+
+/// 密码最短长度
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+/// 主密钥（256 位），各加密功能使用由它派生的子密钥
+pub type MasterKey = [u8; 32];
+
+/// 密钥派生参数（Argon2id），随包装后的主密钥一起保存，之后调整默认参数不影响已有密钥
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    /// 内存开销（KiB）
+    m_cost: u32,
+    /// 迭代次数
+    t_cost: u32,
+    /// 并行度
+    p_cost: u32,
+    /// 盐（Base64）
+    salt: String,
+}
+
+impl KdfParams {
+    /// 使用默认参数和新的随机盐
+    fn generate() -> Self {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+            salt: BASE64.encode(salt),
+        }
+    }
+
+    /// 从密码派生用于包装主密钥的密钥
+    fn derive(&self, passphrase: &str) -> Result<[u8; 32], CloudPasteError> {
+        let salt = BASE64
+            .decode(&self.salt)
+            .map_err(|e| CloudPasteError::Internal(format!("读取密钥盐失败: {}", e)))?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|e| CloudPasteError::Internal(format!("密钥派生参数无效: {}", e)))?;
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| CloudPasteError::Internal(format!("派生密钥失败: {}", e)))?;
+        Ok(key)
+    }
+}
+
+/// 保存在磁盘上的密钥材料：主密钥由密码派生的密钥加密（包装）后保存
+///
+/// 同时上传到服务器（服务器无法解开），其他设备据此跟随密码修改和密钥轮换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeyFile {
+    kdf: KdfParams,
+    /// 随机数 + 加密后的主密钥（Base64）
+    wrapped_master_key: String,
+    /// 对应的服务器版本，尚未上传时为 0
+    #[serde(default)]
+    version: u64,
+}
+
+/// 返回给前端的密钥状态
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyStatus {
+    /// 是否已设置密码
+    pub configured: bool,
+    /// 主密钥是否已解锁
+    pub unlocked: bool,
+}
+
+static KEY_FILE: Lazy<Mutex<Option<KeyFile>>> = Lazy::new(|| Mutex::new(None));
+
+/// 是否正在轮换主密钥
+static ROTATING: AtomicBool = AtomicBool::new(false);
+
+/// 轮换主密钥的步骤数（上传新密钥材料、保存新密钥材料、重新加密历史记录数据库）
+const ROTATION_STEPS: u32 = 3;
+
+/// 已解锁的主密钥（只保存在内存中）
+static MASTER_KEY: Mutex<Option<MasterKey>> = Mutex::new(None);
+
+/// 启动时从磁盘加载密钥材料
+pub fn load(app: &AppHandle) {
+    let saved = store::load_json::<KeyFile>(app, KEYS_FILE);
+    if let Ok(mut key_file) = KEY_FILE.lock() {
+        *key_file = saved;
+    }
+}
+
+fn key_file() -> Option<KeyFile> {
+    KEY_FILE.lock().ok().and_then(|key_file| key_file.clone())
+}
+
+fn save(app: &AppHandle, key_file: KeyFile) -> Result<(), CloudPasteError> {
+    store::save_json(app, KEYS_FILE, &key_file)?;
+    *KEY_FILE
+        .lock()
+        .map_err(|e| format!("无法锁定密钥: {}", e))? = Some(key_file);
+    Ok(())
+}
+
+/// 保存新的密钥材料并用新主密钥重新加密历史记录数据库，失败时恢复原来的密钥材料
+///
+/// 原密钥材料先写入 `PENDING_KEYS_FILE`，两步之间退出时下次解锁据此打开数据库（见 `open_history`）
+fn replace_key_file(
+    app: &AppHandle,
+    old: KeyFile,
+    new_file: KeyFile,
+    master: &MasterKey,
+) -> Result<(), CloudPasteError> {
+    store::save_json(app, PENDING_KEYS_FILE, &old)?;
+    save(app, new_file)?;
+    let result = crate::db_encryption::rotate(app, master);
+    if result.is_err() {
+        save(app, old)?;
+    } else {
+        // 生物识别保存的是原主密钥，轮换后需要重新启用
+        crate::biometric::forget(app);
+    }
+    clear_pending(app);
+    result
+}
+
+fn clear_pending(app: &AppHandle) {
+    if let Ok(path) = store::data_file_path(app, PENDING_KEYS_FILE) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// 主密钥解锁后打开加密的历史记录数据库
+///
+/// 上次替换密钥材料未完成时数据库仍由原主密钥加密：用原密钥材料打开后重新加密为当前主密钥
+fn open_history(app: &AppHandle, passphrase: &str) -> Result<(), CloudPasteError> {
+    let Some(pending) = store::load_json::<KeyFile>(app, PENDING_KEYS_FILE) else {
+        return crate::db_encryption::open_if_locked(app);
+    };
+    let opened = crate::db_encryption::open_if_locked(app);
+    let Err(e) = opened else {
+        clear_pending(app);
+        return Ok(());
+    };
+
+    let Ok(previous) = unwrap(&pending, passphrase) else {
+        return Err(e);
+    };
+    crate::db_encryption::open_with_master(app, &previous).map_err(|_| e)?;
+    log::warn!("⚠️ 上次替换主密钥未完成，已用原主密钥打开历史记录数据库，重新加密");
+    let master = master_key().ok_or(CloudPasteError::Locked)?;
+    crate::db_encryption::rotate(app, &master)?;
+    clear_pending(app);
+    Ok(())
+}
+
+fn set_master_key(key: Option<MasterKey>) -> Result<(), CloudPasteError> {
+    *MASTER_KEY
+        .lock()
+        .map_err(|e| format!("无法锁定密钥: {}", e))? = key;
+    Ok(())
+}
+
+/// 已解锁的主密钥
+pub fn master_key() -> Option<MasterKey> {
+    MASTER_KEY.lock().ok().and_then(|key| *key)
+}
+
+/// 用已解开的主密钥解锁（如生物识别解锁），并打开加密的历史记录数据库
+///
+/// 上次替换密钥材料未完成时需要密码解开原主密钥，只能用密码解锁
+pub fn unlock_with_master(app: &AppHandle, master: MasterKey) -> Result<(), CloudPasteError> {
+    if key_file().is_none() {
+        return Err(CloudPasteError::InvalidInput("尚未设置密码".to_string()));
+    }
+    if store::load_json::<KeyFile>(app, PENDING_KEYS_FILE).is_some() {
+        return Err(CloudPasteError::Unauthorized(
+            "上次替换主密钥未完成，请使用密码解锁".to_string(),
+        ));
+    }
+
+    set_master_key(Some(master))?;
+    if let Err(e) = crate::db_encryption::open_if_locked(app) {
+        set_master_key(None)?;
+        return Err(e);
+    }
+    log::info!("🔓 主密钥已解锁");
+    Ok(())
+}
+
+/// 由主密钥派生指定用途的子密钥（如 `history-db`），主密钥未解锁时为空
+///
+/// 各功能使用不同的子密钥，更换密码只需重新包装主密钥，子密钥保持不变
+pub fn subkey(purpose: &str) -> Option<[u8; 32]> {
+    master_key().map(|master| derive_subkey(&master, purpose))
+}
+
+/// 由指定主密钥派生子密钥
+pub fn derive_subkey(master: &MasterKey, purpose: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("cloudpaste-{}-v1", purpose).as_bytes());
+    hasher.update(master);
+    hasher.finalize().into()
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), CloudPasteError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(CloudPasteError::InvalidInput(format!(
+            "密码至少需要 {} 位",
+            MIN_PASSPHRASE_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// 用指定密钥包装主密钥，返回随机数 + 加密后的主密钥（Base64）
+pub fn wrap_with(key: &[u8; 32], master: &MasterKey) -> Result<String, CloudPasteError> {
+    let cipher = ChaCha20Poly1305::new(&(*key).into());
+
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(&Nonce::from(nonce), master.as_slice())
+        .map_err(|e| CloudPasteError::Internal(format!("加密主密钥失败: {}", e)))?;
+
+    let mut wrapped = nonce.to_vec();
+    wrapped.extend(ciphertext);
+    Ok(BASE64.encode(wrapped))
+}
+
+/// 用指定密钥解开 `wrap_with` 包装的主密钥，密钥不正确时为空
+pub fn unwrap_with(key: &[u8; 32], wrapped: &str) -> Result<Option<MasterKey>, CloudPasteError> {
+    let wrapped = BASE64
+        .decode(wrapped)
+        .map_err(|e| CloudPasteError::Internal(format!("读取主密钥失败: {}", e)))?;
+    let (nonce, ciphertext) = wrapped
+        .split_first_chunk::<12>()
+        .ok_or_else(|| CloudPasteError::Internal("主密钥已损坏".to_string()))?;
+
+    let cipher = ChaCha20Poly1305::new(&(*key).into());
+    let Ok(plaintext) = cipher.decrypt(&Nonce::from(*nonce), ciphertext) else {
+        return Ok(None);
+    };
+    plaintext
+        .try_into()
+        .map(Some)
+        .map_err(|_| CloudPasteError::Internal("主密钥已损坏".to_string()))
+}
+
+/// 用新的盐从密码派生密钥并包装主密钥
+fn wrap(master: &MasterKey, passphrase: &str) -> Result<KeyFile, CloudPasteError> {
+    let kdf = KdfParams::generate();
+    let wrapped_master_key = wrap_with(&kdf.derive(passphrase)?, master)?;
+    Ok(KeyFile {
+        kdf,
+        wrapped_master_key,
+        version: key_file().map(|current| current.version).unwrap_or(0),
+    })
+}
+
+/// 用密码解开包装的主密钥，密码错误时返回未授权错误
+fn unwrap(key_file: &KeyFile, passphrase: &str) -> Result<MasterKey, CloudPasteError> {
+    unwrap_with(
+        &key_file.kdf.derive(passphrase)?,
+        &key_file.wrapped_master_key,
+    )?
+    .ok_or_else(|| CloudPasteError::Unauthorized("密码不正确".to_string()))
+}
+
+/// 上传密钥材料，`key_file.version` 为所基于的服务器版本（服务器版本不同时拒绝），返回新版本
+async fn upload(key_file: &KeyFile) -> Result<u64, CloudPasteError> {
+    let request = http::api_request(Method::PUT, "/keys/")?.json(key_file);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("上传主密钥失败", e))?;
+    let uploaded: KeyFile = http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析主密钥版本失败: {}", e)))?;
+    Ok(uploaded.version)
+}
+
+/// 获取服务器上的密钥材料，尚未上传时为空
+async fn fetch_remote() -> Result<Option<KeyFile>, CloudPasteError> {
+    let request = http::api_request(Method::GET, "/keys/")?;
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("获取主密钥失败", e))?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map(Some)
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析主密钥失败: {}", e)))
+}
+
+/// 上传密钥材料并记录服务器版本；失败只记录日志（未配置服务器时只保存在本机）
+async fn publish(app: &AppHandle) {
+    let Some(mut key_file) = key_file() else {
+        return;
+    };
+    match upload(&key_file).await {
+        Ok(version) => {
+            key_file.version = version;
+            if let Err(e) = save(app, key_file) {
+                log::warn!("⚠️ 保存主密钥版本失败: {}", e);
+            }
+        }
+        Err(CloudPasteError::NotConfigured) => {}
+        Err(e) => log::warn!("⚠️ 上传主密钥失败: {}", e),
+    }
+}
+
+/// 服务器上有其他设备更新的密钥材料时改用它：主密钥已轮换时用新主密钥重新加密本地数据
+///
+/// 其他设备修改了密码、当前密码解不开时保留本机密钥材料
+fn follow_remote(
+    app: &AppHandle,
+    remote: KeyFile,
+    passphrase: &str,
+    current: &MasterKey,
+) -> Result<MasterKey, CloudPasteError> {
+    let master = match unwrap(&remote, passphrase) {
+        Ok(master) => master,
+        Err(e) => {
+            log::warn!("⚠️ 服务器上的主密钥无法用当前密码解开，保留本机密钥: {}", e);
+            return Ok(*current);
+        }
+    };
+    if master == *current {
+        save(app, remote)?;
+        return Ok(master);
+    }
+
+    let local =
+        key_file().ok_or_else(|| CloudPasteError::InvalidInput("尚未设置密码".to_string()))?;
+    let version = remote.version;
+    replace_key_file(app, local, remote, &master)?;
+    log::info!("🔑 已跟随其他设备轮换主密钥 (版本 {})", version);
+    Ok(master)
+}
+
+/// 密钥派生较慢，放到阻塞线程中执行
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, CloudPasteError> + Send + 'static,
+) -> Result<T, CloudPasteError> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| CloudPasteError::Internal(format!("密钥操作失败: {}", e)))?
+}
+
+/// Tauri 命令：获取密钥状态
+#[tauri::command]
+pub fn get_key_status() -> KeyStatus {
+    KeyStatus {
+        configured: key_file().is_some(),
+        unlocked: master_key().is_some(),
+    }
+}
+
+/// Tauri 命令：首次设置密码，生成随机主密钥并用密码包装后保存（保持解锁状态）
+#[tauri::command]
+pub async fn set_passphrase(app: AppHandle, passphrase: String) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    validate_passphrase(&passphrase)?;
+    if key_file().is_some() {
+        return Err(CloudPasteError::InvalidInput(
+            "已设置密码，请使用修改密码".to_string(),
+        ));
+    }
+
+    {
+        let app = app.clone();
+        run_blocking(move || {
+            let mut master = [0u8; 32];
+            OsRng.fill_bytes(&mut master);
+            save(&app, wrap(&master, &passphrase)?)?;
+            set_master_key(Some(master))
+        })
+        .await?;
+    }
+    log::info!("🔑 已设置密码并生成主密钥");
+    publish(&app).await;
+    Ok(())
+}
+
+/// Tauri 命令：修改密码，用新密码重新包装主密钥（主密钥及已加密的数据不变）
+#[tauri::command]
+pub async fn change_passphrase(
+    app: AppHandle,
+    current_passphrase: String,
+    new_passphrase: String,
+) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    validate_passphrase(&new_passphrase)?;
+    let key_file =
+        key_file().ok_or_else(|| CloudPasteError::InvalidInput("尚未设置密码".to_string()))?;
+
+    {
+        let app = app.clone();
+        run_blocking(move || {
+            let master = unwrap(&key_file, &current_passphrase)?;
+            save(&app, wrap(&master, &new_passphrase)?)?;
+            set_master_key(Some(master))
+        })
+        .await?;
+    }
+    log::info!("🔑 已修改密码");
+    publish(&app).await;
+    Ok(())
+}
+
+/// Tauri 命令：用密码解锁主密钥（启动后调用一次），并打开依赖主密钥的数据
+#[tauri::command]
+pub async fn unlock_keys(app: AppHandle, passphrase: String) -> Result<(), CloudPasteError> {
+    let key_file =
+        key_file().ok_or_else(|| CloudPasteError::InvalidInput("尚未设置密码".to_string()))?;
+
+    // 其他设备修改过密码或轮换过主密钥时，服务器上的版本更新
+    let remote = match fetch_remote().await {
+        Ok(remote) => remote.filter(|remote| remote.version > key_file.version),
+        Err(e) => {
+            log::debug!("获取服务器上的主密钥失败: {}", e);
+            None
+        }
+    };
+
+    run_blocking(move || {
+        let master = unwrap(&key_file, &passphrase).inspect_err(|_| {
+            log::warn!("🔑 解锁主密钥失败");
+        })?;
+        set_master_key(Some(master))?;
+        log::info!("🔓 主密钥已解锁");
+        open_history(&app, &passphrase)?;
+
+        if let Some(remote) = remote {
+            set_master_key(Some(follow_remote(&app, remote, &passphrase, &master)?))?;
+        }
+        Ok(())
+    })
+    .await
+}
+
+fn emit_rotation_progress(completed: u32, stage: &'static str, error: Option<String>) {
+    events::emit(KeyRotationProgress {
+        completed,
+        total: ROTATION_STEPS,
+        stage,
+        error,
+    });
+}
+
+/// 生成新的主密钥，用同一密码包装后先上传到服务器，再保存到本机并用新密钥重新加密本地数据
+///
+/// 上传成功后本机步骤失败时恢复原来的密钥材料（本机和服务器）；未配置服务器时只在本机轮换
+async fn rotate(app: &AppHandle, old: KeyFile, passphrase: String) -> Result<(), CloudPasteError> {
+    let (master, mut new_file) = run_blocking(move || {
+        let mut master = [0u8; 32];
+        OsRng.fill_bytes(&mut master);
+        let new_file = wrap(&master, &passphrase)?;
+        Ok((master, new_file))
+    })
+    .await?;
+
+    let uploaded = match upload(&new_file).await {
+        Ok(version) => {
+            new_file.version = version;
+            true
+        }
+        Err(CloudPasteError::NotConfigured) => {
+            log::info!("ℹ️ 未配置服务器，只在本机轮换主密钥");
+            false
+        }
+        Err(e) => return Err(e),
+    };
+    emit_rotation_progress(1, "uploaded", None);
+
+    let result = {
+        let app = app.clone();
+        let old = old.clone();
+        let new_file = new_file.clone();
+        run_blocking(move || {
+            emit_rotation_progress(2, "key_material", None);
+            replace_key_file(&app, old, new_file, &master)?;
+            set_master_key(Some(master))
+        })
+        .await
+    };
+
+    if let Err(e) = result {
+        if uploaded {
+            // 服务器上已是新密钥，重新上传原来的密钥材料，其他设备不会跟随这次失败的轮换
+            let mut restored = old;
+            restored.version = new_file.version;
+            match upload(&restored).await {
+                Ok(version) => {
+                    restored.version = version;
+                    save(app, restored)?;
+                }
+                Err(restore) => log::error!("❌ 恢复服务器上的主密钥失败: {}", restore),
+            }
+        }
+        return Err(e);
+    }
+    emit_rotation_progress(ROTATION_STEPS, "history_db", None);
+    Ok(())
+}
+
+/// Tauri 命令：轮换主密钥（怀疑密钥泄露时使用）
+///
+/// 校验密码后在后台生成新的主密钥并重新加密本地数据，进度通过 key-rotation-progress 事件通知
+#[tauri::command]
+pub async fn rotate_keys(app: AppHandle, passphrase: String) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let key_file =
+        key_file().ok_or_else(|| CloudPasteError::InvalidInput("尚未设置密码".to_string()))?;
+    if ROTATING.swap(true, Ordering::SeqCst) {
+        return Err(CloudPasteError::InvalidInput("密钥正在轮换中".to_string()));
+    }
+
+    let checked = {
+        let key_file = key_file.clone();
+        let passphrase = passphrase.clone();
+        run_blocking(move || unwrap(&key_file, &passphrase)).await
+    };
+    if let Err(e) = checked {
+        ROTATING.store(false, Ordering::SeqCst);
+        return Err(e);
+    }
+
+    emit_rotation_progress(0, "started", None);
+    tauri::async_runtime::spawn(async move {
+        match rotate(&app, key_file, passphrase).await {
+            Ok(()) => log::info!("🔑 主密钥已轮换"),
+            Err(e) => {
+                log::error!("❌ 轮换主密钥失败: {}", e);
+                emit_rotation_progress(0, "failed", Some(e.to_string()));
+            }
+        }
+        ROTATING.store(false, Ordering::SeqCst);
+    });
+    Ok(())
+}
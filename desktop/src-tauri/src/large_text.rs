@@ -0,0 +1,126 @@
+use crate::api::{ClipboardItem, NewClipboardItem};
+use crate::error::CloudPasteError;
+use crate::{storage, store, upload};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "large_text.json";
+
+/// 超长文本处理设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LargeTextSettings {
+    /// 超过该字节数的文本改为附件上传
+    pub inline_limit_bytes: usize,
+    /// 截断预览保留的字符数
+    pub preview_chars: usize,
+}
+
+impl Default for LargeTextSettings {
+    fn default() -> Self {
+        Self {
+            inline_limit_bytes: 64 * 1024,
+            preview_chars: 2000,
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<LargeTextSettings>> =
+    Lazy::new(|| Mutex::new(LargeTextSettings::default()));
+
+/// 文本粘贴创建结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TextPasteResult {
+    pub item: ClipboardItem,
+    /// 是否被截断并附带完整内容附件
+    pub truncated: bool,
+    /// 完整内容附件的下载地址
+    pub attachment_url: Option<String>,
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<LargeTextSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> LargeTextSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Tauri 命令：创建文本粘贴
+///
+/// 文本超过内联限制时，完整内容作为 .txt 附件上传，粘贴内容为截断预览并附带附件链接
+#[tauri::command]
+pub async fn create_text_paste(text: String) -> Result<TextPasteResult, CloudPasteError> {
+    let settings = settings();
+
+    if text.len() <= settings.inline_limit_bytes {
+        let item = NewClipboardItem::from_this_device(text, "text")?;
+        return Ok(TextPasteResult {
+            item: storage::create_item(&item).await?,
+            truncated: false,
+            attachment_url: None,
+        });
+    }
+
+    let total_chars = text.chars().count();
+    let total_bytes = text.len() as u64;
+    let preview: String = text.chars().take(settings.preview_chars).collect();
+
+    log::info!(
+        "✂️ 文本超过内联限制 ({} > {} 字节)，上传完整内容为附件",
+        total_bytes,
+        settings.inline_limit_bytes
+    );
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let file_name = format!("clipboard-{}.txt", timestamp);
+    let uploaded = upload::upload_bytes(file_name.clone(), text.into_bytes()).await?;
+    let attachment_url = storage::file_link(&uploaded.file_url)?;
+
+    let mut item = NewClipboardItem::from_this_device(
+        format!(
+            "{}\n\n…（内容过长已截断，共 {} 字符，完整内容: {}）",
+            preview, total_chars, attachment_url
+        ),
+        "text",
+    )?;
+    item.file_name = Some(file_name);
+    item.file_size = Some(total_bytes);
+    item.mime_type = Some("text/plain".to_string());
+
+    Ok(TextPasteResult {
+        item: storage::create_item(&item).await?,
+        truncated: true,
+        attachment_url: Some(attachment_url),
+    })
+}
+
+/// Tauri 命令：获取超长文本处理设置
+#[tauri::command]
+pub fn get_large_text_settings() -> LargeTextSettings {
+    settings()
+}
+
+/// Tauri 命令：更新超长文本处理设置
+#[tauri::command]
+pub fn set_large_text_settings(
+    app: AppHandle,
+    settings: LargeTextSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
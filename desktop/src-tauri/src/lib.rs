@@ -1,16 +1,94 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+use error::CloudPasteError;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tauri::menu::{Menu, MenuItem};
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem, Submenu};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::webview::PageLoadEvent;
 use tauri::{AppHandle, Manager, WindowEvent};
 use tauri_plugin_autostart::MacosLauncher;
-use tauri_plugin_log::{Target, TargetKind};
-
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+mod api;
+mod api_v2;
+mod applock;
+mod audio;
+mod backup;
+mod biometric;
+mod capabilities;
+mod capture;
+mod capture_pause;
+mod clipboard;
+mod compression;
+mod conflict;
+mod connectivity;
+mod crash;
+mod db_encryption;
+mod device;
+mod device_trust;
+mod devices;
+mod diagnostics;
+mod discovery;
+mod dns;
+mod error;
+mod events;
+mod file_writer;
+mod focus_sync;
+mod folder_watch;
+mod fuzzy;
+mod graphql;
+mod handoff;
+mod highlight;
+mod history;
+mod history_archive;
+mod hotkeys;
+mod http;
+mod idle;
 mod image_cache;
+mod image_processing;
+mod keys;
+mod large_text;
+mod logging;
+mod markdown_export;
+mod metadata_strip;
+mod metered;
+mod notification;
+mod ocr;
+mod p2p;
+mod paste_stack;
+mod pinned;
+mod policy;
+mod prefetch;
+mod presigned;
+mod privacy_guard;
+mod qr;
+mod quick_paste;
+mod remote_export;
+mod retention;
+mod s3;
+mod sandbox;
+mod screenshot;
+mod search;
+mod send;
+mod sensitive;
+mod share;
+mod shutdown;
+mod snippets;
+mod speed_test;
+mod startup;
+mod storage;
+mod store;
+mod sync_filter;
+mod sync_schedule;
+mod telemetry;
+mod transfer;
+mod upload;
+mod video_poster;
+mod webdav;
 // 全局 API 配置
 static GLOBAL_API_CONFIG: Lazy<Arc<Mutex<ApiConfig>>> = Lazy::new(|| {
     Arc::new(Mutex::new(ApiConfig {
@@ -19,9 +97,57 @@ static GLOBAL_API_CONFIG: Lazy<Arc<Mutex<ApiConfig>>> = Lazy::new(|| {
         device_id: get_device_id(),
         device_name: get_device_name(),
         is_configured: false,
+        fallback_url: None,
+        version: CONFIG_VERSION,
     }))
 });
 
+/// 当前配置文件结构版本
+const CONFIG_VERSION: u32 = 1;
+
+/// 配置迁移链：第 i 项将版本 i 的配置升级到版本 i + 1
+const CONFIG_MIGRATIONS: &[fn(&mut serde_json::Map<String, serde_json::Value>)] =
+    &[migrate_config_v0];
+
+/// 版本 0（无 version 字段）：补齐缺失字段，规范化服务器地址
+fn migrate_config_v0(config: &mut serde_json::Map<String, serde_json::Value>) {
+    use serde_json::Value;
+
+    let base_url = config
+        .get("base_url")
+        .or_else(|| config.get("api_url"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .trim_end_matches("/api/v1")
+        .to_string();
+    let is_configured = config
+        .get("is_configured")
+        .and_then(Value::as_bool)
+        .unwrap_or(!base_url.is_empty());
+    config.remove("api_url");
+    config.insert(
+        "base_url".to_string(),
+        Value::from(if base_url.is_empty() {
+            String::new()
+        } else {
+            format!("{}/api/v1", base_url)
+        }),
+    );
+    config.insert("is_configured".to_string(), Value::from(is_configured));
+
+    let defaults = [
+        ("token", String::new()),
+        ("device_id", get_device_id()),
+        ("device_name", get_device_name()),
+    ];
+    for (key, value) in defaults {
+        if !config.get(key).is_some_and(Value::is_string) {
+            config.insert(key.to_string(), Value::from(value));
+        }
+    }
+}
+
 // API 配置（添加序列化支持）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ApiConfig {
@@ -30,57 +156,110 @@ struct ApiConfig {
     device_id: String,
     device_name: String,
     is_configured: bool,
+    /// 备用 API 地址（主地址不可达时自动切换）
+    #[serde(default)]
+    fallback_url: Option<String>,
+    /// 配置文件结构版本，旧文件加载时按迁移链升级
+    #[serde(default)]
+    version: u32,
 }
 
 impl ApiConfig {
     fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
-        // 使用 Tauri 提供的跨平台 API 获取应用数据目录
-        let app_data_dir = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+        // 返回配置文件完整路径
+        store::data_file_path(app, "api_config.json")
+    }
 
-        // 确保目录存在
-        std::fs::create_dir_all(&app_data_dir)
-            .map_err(|e| format!("创建应用数据目录失败: {}", e))?;
+    /// 解析配置文件内容，旧版本按迁移链升级；返回配置及是否发生了迁移
+    fn parse(content: &str) -> Result<(Self, bool), String> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(content).map_err(|e| format!("解析配置失败: {}", e))?;
+        let map = value
+            .as_object_mut()
+            .ok_or_else(|| "配置格式无效".to_string())?;
+
+        let version = map
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0) as u32;
+        if version > CONFIG_VERSION {
+            log::warn!(
+                "⚠️ 配置文件版本 {} 高于当前支持的版本 {}，尝试按当前结构读取",
+                version,
+                CONFIG_VERSION
+            );
+        }
+        for (index, migrate) in CONFIG_MIGRATIONS.iter().enumerate().skip(version as usize) {
+            migrate(map);
+            map.insert("version".to_string(), serde_json::Value::from(index + 1));
+            log::info!("🔧 配置已从版本 {} 迁移到版本 {}", index, index + 1);
+        }
 
-        // 返回配置文件完整路径
-        let mut config_path = app_data_dir;
-        config_path.push("api_config.json");
-        Ok(config_path)
+        let config =
+            serde_json::from_value::<Self>(value).map_err(|e| format!("解析配置失败: {}", e))?;
+        Ok((config, version < CONFIG_VERSION))
     }
 
     fn load_from_disk(app: &AppHandle) -> Option<Self> {
         let path = Self::config_path(app).ok()?;
-        if path.exists() {
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(config) = serde_json::from_str::<Self>(&content) {
-                    log::info!(
-                        "✅ 从磁盘加载配置: base_url={}, is_configured={}",
-                        config.base_url, config.is_configured
-                    );
-                    return Some(config);
-                }
+        if !path.exists() {
+            return None;
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::error!("❌ 读取配置文件失败: {}", e);
+                return None;
+            }
+        };
+        let (config, migrated) = match Self::parse(&content) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                // 保留原文件，避免下次保存时覆盖用户配置
+                let mut corrupt = path.clone().into_os_string();
+                corrupt.push(".invalid");
+                let _ = fs::copy(&path, &corrupt);
+                log::error!("❌ {}，原文件已另存为 {:?}", e, corrupt);
+                return None;
+            }
+        };
+
+        if migrated {
+            // 迁移前的文件另存一份，升级后的配置写回磁盘
+            let mut previous = path.clone().into_os_string();
+            previous.push(".bak");
+            if let Err(e) = fs::copy(&path, &previous) {
+                log::warn!("⚠️ 备份旧配置文件失败: {}", e);
+            }
+            if let Err(e) = config.save_to_disk(app) {
+                log::warn!("⚠️ 保存迁移后的配置失败: {}", e);
             }
         }
-        None
+
+        log::info!(
+            "✅ 从磁盘加载配置: base_url={}, is_configured={}",
+            config.base_url, config.is_configured
+        );
+        Some(config)
     }
 
-    fn save_to_disk(&self, app: &AppHandle) -> Result<(), String> {
+    fn save_to_disk(&self, app: &AppHandle) -> Result<(), CloudPasteError> {
         let path = Self::config_path(app)?;
         let content =
             serde_json::to_string_pretty(self).map_err(|e| format!("序列化失败: {}", e))?;
 
-        fs::write(&path, content).map_err(|e| format!("写入文件失败: {}", e))?;
+        store::write_atomic(&path, content.as_bytes())
+            .map_err(|e| CloudPasteError::io("写入文件失败", e))?;
 
         log::info!("✅ 配置已保存到磁盘: {:?}", path);
         Ok(())
     }
 
-    fn delete_from_disk(app: &AppHandle) -> Result<(), String> {
+    fn delete_from_disk(app: &AppHandle) -> Result<(), CloudPasteError> {
         let path = Self::config_path(app)?;
         if path.exists() {
-            fs::remove_file(&path).map_err(|e| format!("删除文件失败: {}", e))?;
+            fs::remove_file(&path).map_err(|e| CloudPasteError::io("删除文件失败", e))?;
             log::info!("✅ 配置文件已删除: {:?}", path);
         }
         Ok(())
@@ -89,15 +268,7 @@ impl ApiConfig {
 
 // 获取设备唯一 ID（内部函数）
 fn get_device_id() -> String {
-    // 简单实现：使用机器名 + 时间戳
-    // 实际应该保存到配置文件中
-    format!(
-        "desktop_{}",
-        hostname::get()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string()
-    )
+    device::id()
 }
 
 // 获取设备名称（内部函数）
@@ -108,6 +279,15 @@ fn get_device_name() -> String {
         .to_string()
 }
 
+// 显示主窗口并置于前台
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.unminimize();
+    }
+}
+
 // Tauri 命令：获取设备 ID
 #[tauri::command]
 fn get_device_id_command() -> String {
@@ -127,7 +307,11 @@ fn greet(name: &str) -> String {
 
 // 设置 API 配置
 #[tauri::command]
-fn set_api_config(app: AppHandle, api_url: String, token: String) -> Result<(), String> {
+fn set_api_config(
+    app: AppHandle,
+    api_url: String,
+    token: String,
+) -> Result<(), CloudPasteError> {
     log::info!(
         "🔧 set_api_config 被调用: api_url={}, token_len={}",
         api_url,
@@ -136,17 +320,12 @@ fn set_api_config(app: AppHandle, api_url: String, token: String) -> Result<(),
 
     let mut config = GLOBAL_API_CONFIG
         .lock()
-        .map_err(|e| format!("无法锁定配置: {}", e))?;
-
-    // 移除 /api/v1 后缀（如果有）
-    let base_url = api_url
-        .trim_end_matches('/')
-        .trim_end_matches("/api/v1")
-        .to_string();
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?;
 
-    config.base_url = format!("{}/api/v1", base_url);
+    config.base_url = normalize_api_url(&api_url);
     config.token = token;
     config.is_configured = true;
+    http::reset_endpoint();
 
     log::info!(
         "✅ API 配置已更新: base_url={}, device_id={}, is_configured={}",
@@ -158,12 +337,38 @@ fn set_api_config(app: AppHandle, api_url: String, token: String) -> Result<(),
 
     log::info!("💾 配置已保存到磁盘");
 
+    // 切换服务器后重新协商服务器能力
+    capabilities::refresh_in_background(&app);
+
+    Ok(())
+}
+
+/// 统一 API 地址格式：移除末尾的 / 和 /api/v1（如果有）后补上 /api/v1
+fn normalize_api_url(url: &str) -> String {
+    let base_url = url.trim_end_matches('/').trim_end_matches("/api/v1");
+    format!("{}/api/v1", base_url)
+}
+
+/// Tauri 命令：设置备用服务器地址（主地址不可达时自动切换），传入空值时清除
+#[tauri::command]
+fn set_fallback_url(app: AppHandle, url: Option<String>) -> Result<(), CloudPasteError> {
+    let mut config = GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?;
+
+    config.fallback_url = url
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| normalize_api_url(url.trim()));
+    http::reset_endpoint();
+    config.save_to_disk(&app)?;
+
+    log::info!("✅ 备用服务器地址已更新: {:?}", config.fallback_url);
     Ok(())
 }
 
 // 获取当前 API 配置状态
 #[tauri::command]
-fn get_api_config_status() -> Result<bool, String> {
+fn get_api_config_status() -> Result<bool, CloudPasteError> {
     let config = GLOBAL_API_CONFIG
         .lock()
         .map_err(|e| format!("无法锁定配置: {}", e))?;
@@ -173,14 +378,16 @@ fn get_api_config_status() -> Result<bool, String> {
 
 // 清除 API 配置
 #[tauri::command]
-fn clear_api_config(app: AppHandle) -> Result<(), String> {
+fn clear_api_config(app: AppHandle) -> Result<(), CloudPasteError> {
     let mut config = GLOBAL_API_CONFIG
         .lock()
-        .map_err(|e| format!("无法锁定配置: {}", e))?;
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?;
 
     config.base_url = String::new();
     config.token = String::new();
     config.is_configured = false;
+    config.fallback_url = None;
+    http::reset_endpoint();
 
     log::info!("✅ API 配置已清除");
 
@@ -190,8 +397,194 @@ fn clear_api_config(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// 从磁盘加载各项设置（启动时及恢复备份后调用）
+fn load_settings(app: &AppHandle) {
+    applock::load(app);
+    capture::load(app);
+    connectivity::load(app);
+    crash::load(app);
+    device_trust::load(app);
+    dns::load(app);
+    focus_sync::load(app);
+    folder_watch::load(app);
+    http::load(app);
+    hotkeys::load(app);
+    ocr::load(app);
+    large_text::load(app);
+    metered::load(app);
+    image_processing::load(app);
+    notification::load(app);
+    screenshot::load(app);
+    policy::load(app);
+    prefetch::load(app);
+    privacy_guard::load(app);
+    quick_paste::load(app);
+    remote_export::load(app);
+    retention::load(app);
+    snippets::load(app);
+    storage::load(app);
+    sync_filter::load(app);
+    sync_schedule::load(app);
+    telemetry::load(app);
+    webdav::load(app);
+    api_v2::load(app);
+}
+
+/// 首帧未加载时开始延迟初始化的超时时间
+const DEFERRED_INIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 创建系统托盘
+fn create_tray(app: &tauri::App) -> tauri::Result<()> {
+    // 创建托盘菜单项
+    let show_item = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
+    let hide_item = MenuItem::with_id(app, "hide", "隐藏窗口", true, None::<&str>)?;
+    let record_item = MenuItem::with_id(app, "record", "开始/结束录音", true, None::<&str>)?;
+    let pause_item = MenuItem::with_id(app, "pause", "暂停/恢复采集", true, None::<&str>)?;
+    let snippets_menu = Submenu::with_id(app, "snippets", "文本片段", true)?;
+    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+
+    // 构建菜单
+    let menu = Menu::with_items(
+        app,
+        &[
+            &show_item,
+            &hide_item,
+            &record_item,
+            &pause_item,
+            &snippets_menu,
+            &quit_item,
+        ],
+    )?;
+    // 片段加载后填充子菜单
+    snippets::attach_tray_menu(snippets_menu);
+
+    // 创建系统托盘图标
+    let _tray = TrayIconBuilder::with_id(capture_pause::TRAY_ID)
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "show" => {
+                show_main_window(app);
+            }
+            "hide" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
+            "record" => {
+                audio::toggle_from_tray(app);
+            }
+            "pause" => {
+                capture_pause::toggle_from_tray(app);
+            }
+            "quit" => {
+                // 完成进行中的上传并推送本地修改后再退出
+                tauri::async_runtime::spawn(shutdown::shutdown(app.clone()));
+            }
+            id => {
+                if let Some(snippet_id) = id.strip_prefix(snippets::TRAY_ID_PREFIX) {
+                    snippets::paste_in_background(app, snippet_id.to_string());
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                let app = tray.app_handle();
+                show_main_window(app);
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// 初始化非关键子系统（首帧之后执行，只执行一次）
+fn init_deferred(app: &AppHandle) {
+    if !startup::begin_deferred() {
+        return;
+    }
+
+    startup::stage("hotkeys", || hotkeys::register_saved(app));
+
+    // 后台刷新受管策略和服务器能力
+    if get_api_config_status().unwrap_or(false) {
+        capabilities::refresh_in_background(app);
+        let policy_app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = policy::fetch(&policy_app).await {
+                log::warn!("⚠️ 刷新受管策略失败: {}", e);
+            }
+        });
+    }
+
+    startup::stage("search_index", || {
+        if let Err(e) = search::open(app) {
+            log::error!("❌ {}", e);
+        }
+    });
+
+    // 启动局域网设备发现
+    startup::stage("discovery", || {
+        if let Err(e) = discovery::start(app.clone()) {
+            log::warn!("⚠️ 局域网设备发现启动失败: {}", e);
+        }
+    });
+    tauri::async_runtime::spawn(p2p::serve(app.clone()));
+
+    // 监听目录中的新文件并自动上传
+    startup::stage("folder_watch", || {
+        if let Err(e) = folder_watch::start() {
+            log::warn!("⚠️ {}", e);
+        }
+    });
+
+    // 确保固定项始终可离线使用
+    tauri::async_runtime::spawn(pinned::keep_cached(app.clone()));
+
+    // 按保留策略定期清理本地历史
+    tauri::async_runtime::spawn(retention::run_periodic(app.clone()));
+
+    // 使用备用服务器地址期间检查主地址是否恢复
+    tauri::async_runtime::spawn(http::watch_primary());
+
+    // 定期检查服务器是否可达
+    tauri::async_runtime::spawn(connectivity::monitor(app.clone()));
+
+    // 检测按流量计费的网络
+    tauri::async_runtime::spawn(metered::watch(app.clone()));
+
+    // 屏幕共享期间暂停采集
+    tauri::async_runtime::spawn(privacy_guard::watch());
+
+    // 主后端不是 CloudPaste 服务器（如 WebDAV）时轮询其他设备写入的内容
+    tauri::async_runtime::spawn(storage::watch());
+
+    // 按同步计划定期同步历史
+    tauri::async_runtime::spawn(sync_schedule::run(app.clone()));
+
+    // 用户同意时上传之前的崩溃报告
+    tauri::async_runtime::spawn(crash::upload_pending());
+
+    // 开启使用统计时定期上传
+    tauri::async_runtime::spawn(telemetry::run(app.clone()));
+
+    // 启动参数中带有文件时上传并复制分享链接
+    if let Ok(cwd) = std::env::current_dir() {
+        send::handle_args(app, std::env::args().collect(), &cwd);
+    }
+
+    startup::mark_deferred_completed();
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    startup::mark_launch();
     log::info!("🚀 启动 Tauri 应用");
     tauri::Builder::default()
         .plugin(tauri_plugin_notification::init())
@@ -199,15 +592,28 @@ pub fn run() {
             tauri_plugin_log::Builder::new()
                 .targets([
                     Target::new(TargetKind::Stdout),
-                    Target::new(TargetKind::LogDir { file_name: None }),
+                    // 便携模式下日志也写入可执行文件旁的 data 目录
+                    Target::new(match store::portable_dir() {
+                        Some(dir) => TargetKind::Folder {
+                            path: dir.join("logs"),
+                            file_name: None,
+                        },
+                        None => TargetKind::LogDir { file_name: None },
+                    }),
                     Target::new(TargetKind::Webview),
                 ])
-                .level(log::LevelFilter::Info)
+                // 实际输出级别由 logging 模块控制，可在运行时调整
+                .level(log::LevelFilter::Trace)
+                .filter(logging::enabled)
+                .max_file_size(logging::MAX_LOG_FILE_SIZE)
+                .rotation_strategy(RotationStrategy::KeepSome(logging::KEPT_LOG_FILES))
                 .build(),
         )
         .plugin(tauri_plugin_single_instance::init(
-            |_app_handle, _argv, _cwd| {
+            |app_handle, argv, cwd| {
                 log::info!("🚀 启动单实例应用");
+                // 再次启动时传入的文件交给已运行的实例发送
+                send::handle_args(app_handle, argv, Path::new(&cwd));
             },
         ))
         .plugin(tauri_plugin_autostart::init(
@@ -232,61 +638,34 @@ pub fn run() {
 
                 api.prevent_close();
             }
+            WindowEvent::Focused(focused) => {
+                idle::set_window_focused(*focused);
+            }
             _ => {}
         })
+        .on_page_load(|webview, payload| {
+            // 首帧加载完成后再初始化非关键子系统
+            if webview.label() == "main" && payload.event() == PageLoadEvent::Finished {
+                startup::mark_first_frame();
+                init_deferred(webview.app_handle());
+            }
+        })
         .setup(|app| {
+            startup::record_since_launch("plugins");
+            http::init(app.handle());
+            events::init(app.handle());
+            capabilities::init(app.handle());
+            logging::load(app.handle());
+            crash::install(app.handle());
             log::info!("=====================================");
             log::info!("平台: {}", std::env::consts::OS);
+            if let Some(dir) = store::portable_dir() {
+                log::info!("便携模式: 数据目录 {:?}", dir);
+            }
             log::info!("应用数据目录: {:?}", app.path().app_data_dir());
             log::info!("应用日志目录: {:?}", app.path().app_log_dir());
             log::info!("=====================================");
-            // 创建托盘菜单项
-            let show_item = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
-            let hide_item = MenuItem::with_id(app, "hide", "隐藏窗口", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-
-            // 构建菜单
-            let menu = Menu::with_items(app, &[&show_item, &hide_item, &quit_item])?;
-
-            // 创建系统托盘图标
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
-                .menu(&menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                            let _ = window.unminimize();
-                        }
-                    }
-                    "hide" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.hide();
-                        }
-                    }
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                            let _ = window.unminimize();
-                        }
-                    }
-                })
-                .build(app)?;
+            startup::stage("tray", || create_tray(app))?;
 
             // 监听窗口关闭事件：点击关闭按钮时隐藏窗口而不是退出
             if let Some(window) = app.get_webview_window("main") {
@@ -300,12 +679,40 @@ pub fn run() {
             }
 
             // 从磁盘加载配置（如果存在）
-            if let Some(saved_config) = ApiConfig::load_from_disk(app.handle()) {
+            startup::stage("config", || {
+                device::init(app.handle());
+                let saved_config = ApiConfig::load_from_disk(app.handle());
                 if let Ok(mut config) = GLOBAL_API_CONFIG.lock() {
-                    *config = saved_config;
-                    log::info!("✅ 应用启动时已加载保存的配置");
+                    if let Some(saved_config) = saved_config {
+                        *config = saved_config;
+                        log::info!("✅ 应用启动时已加载保存的配置");
+                    }
+                    // 旧版本配置中保存的是基于主机名的设备 ID，统一使用持久化的设备 ID
+                    config.device_id = device::id();
                 }
-            }
+            });
+
+            startup::stage("history", || {
+                keys::load(app.handle());
+                db_encryption::load(app.handle());
+                if let Err(e) = history::open(app.handle()) {
+                    log::error!("❌ {}", e);
+                }
+            });
+
+            startup::stage("settings", || load_settings(app.handle()));
+
+            // 空闲超时或系统唤醒后锁定应用
+            tauri::async_runtime::spawn(applock::run());
+
+            startup::mark_tray_ready();
+
+            // 主窗口隐藏启动（如开机自启）时可能迟迟不加载页面，超时后也开始延迟初始化
+            let deferred_app = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(DEFERRED_INIT_TIMEOUT).await;
+                init_deferred(&deferred_app);
+            });
 
             Ok(())
         })
@@ -321,7 +728,195 @@ pub fn run() {
             image_cache::clear_image_cache,
             image_cache::get_cache_size,
             image_cache::save_file_to_path,
-            image_cache::read_file_bytes
+            image_cache::read_file_bytes,
+            image_cache::get_file_size,
+            image_cache::read_file_chunk,
+            idle::notify_local_activity,
+            idle::get_idle_seconds,
+            focus_sync::get_focus_follow_settings,
+            focus_sync::set_focus_follow_settings,
+            focus_sync::should_apply_remote_clipboard,
+            discovery::list_lan_peers,
+            handoff::handoff_current,
+            handoff::receive_handoff,
+            p2p::send_file_to_device,
+            qr::generate_qr,
+            policy::get_effective_policy,
+            policy::refresh_policy,
+            policy::apply_pushed_policy,
+            speed_test::run_speed_test,
+            speed_test::get_speed_test_history,
+            ocr::ocr_image,
+            ocr::get_ocr_text,
+            ocr::get_ocr_settings,
+            ocr::set_ocr_settings,
+            large_text::create_text_paste,
+            large_text::get_large_text_settings,
+            large_text::set_large_text_settings,
+            image_processing::get_image_processing_settings,
+            image_processing::set_image_processing_settings,
+            notification::notify_incoming_paste,
+            notification::get_pending_toast,
+            notification::toast_action,
+            notification::get_notification_settings,
+            notification::set_notification_settings,
+            screenshot::capture_screenshot,
+            screenshot::get_screenshot_settings,
+            screenshot::set_screenshot_settings,
+            startup::get_startup_report,
+            video_poster::get_video_poster,
+            audio::start_audio_recording,
+            audio::stop_audio_recording,
+            markdown_export::export_markdown,
+            highlight::highlight_code,
+            highlight::list_highlight_themes,
+            history::record_history_items,
+            history::sync_history,
+            history::tag_item,
+            history::list_tags,
+            history::move_to_folder,
+            history::list_folders,
+            search::search_history,
+            fuzzy::fuzzy_find,
+            pinned::pin_item,
+            pinned::list_pinned,
+            retention::run_retention_cleanup,
+            retention::delete_history_items,
+            retention::get_retention_settings,
+            retention::set_retention_settings,
+            snippets::list_snippets,
+            snippets::save_snippet,
+            snippets::delete_snippet,
+            snippets::expand_snippet,
+            snippets::paste_snippet,
+            capture::process_text_capture,
+            capture::check_capture_source,
+            capture::get_capture_settings,
+            capture::set_capture_settings,
+            sync_filter::check_sync_filters,
+            sync_filter::get_sync_filters,
+            sync_filter::set_sync_filters,
+            conflict::edit_item,
+            conflict::list_conflicts,
+            conflict::resolve_conflict,
+            history_archive::export_history,
+            history_archive::import_history,
+            backup::create_backup,
+            backup::restore_backup,
+            set_fallback_url,
+            http::get_active_endpoint,
+            connectivity::get_connectivity_status,
+            connectivity::check_connectivity,
+            connectivity::set_offline_mode,
+            connectivity::check_server,
+            metered::get_metered_status,
+            metered::get_metered_settings,
+            metered::set_metered_settings,
+            sync_schedule::sync_now,
+            sync_schedule::get_sync_schedule,
+            sync_schedule::set_sync_schedule,
+            http::get_throttle_state,
+            logging::get_log_level,
+            logging::set_log_level,
+            diagnostics::export_diagnostics,
+            crash::list_crash_reports,
+            crash::clear_crash_reports,
+            crash::get_crash_settings,
+            crash::set_crash_settings,
+            telemetry::get_telemetry_settings,
+            telemetry::set_telemetry_settings,
+            telemetry::get_telemetry_snapshot,
+            file_writer::open_file_writer,
+            file_writer::write_file_chunk,
+            file_writer::close_file_writer,
+            sandbox::pick_save_path,
+            sandbox::pick_open_path,
+            sandbox::pick_folder_path,
+            devices::list_devices,
+            devices::rename_device,
+            devices::revoke_device,
+            applock::get_app_lock_status,
+            applock::set_app_lock_pin,
+            applock::set_app_lock_settings,
+            applock::lock_app,
+            applock::unlock,
+            biometric::get_biometric_status,
+            biometric::enable_biometric_unlock,
+            biometric::disable_biometric_unlock,
+            biometric::biometric_unlock,
+            folder_watch::get_watched_folders,
+            folder_watch::set_watched_folders,
+            prefetch::get_prefetch_settings,
+            prefetch::set_prefetch_settings,
+            image_cache::warm_cache,
+            image_cache::evict_cache_entry,
+            image_cache::export_cached_file,
+            http::get_timeout_settings,
+            http::set_timeout_settings,
+            image_cache::cancel_cache_download,
+            dns::get_dns_settings,
+            dns::set_dns_settings,
+            http::get_network_settings,
+            http::set_network_settings,
+            http::get_http_protocol,
+            upload::upload_file_from_path,
+            upload::upload_clipboard_image,
+            capture::process_file_capture,
+            capture::upload_captured_files,
+            clipboard::copy_text_item,
+            clipboard::write_clipboard,
+            quick_paste::show_quick_paste,
+            quick_paste::hide_quick_paste,
+            quick_paste::quick_paste_item,
+            quick_paste::paste_plain_text,
+            quick_paste::get_quick_paste_settings,
+            quick_paste::set_quick_paste_settings,
+            hotkeys::get_hotkeys,
+            hotkeys::set_hotkeys,
+            paste_stack::paste_stack_push,
+            paste_stack::get_paste_stack,
+            paste_stack::clear_paste_stack,
+            capture_pause::pause_capture,
+            capture_pause::resume_capture,
+            capture_pause::get_capture_pause,
+            privacy_guard::get_privacy_guard_settings,
+            privacy_guard::set_privacy_guard_settings,
+            db_encryption::get_history_encryption,
+            db_encryption::enable_history_encryption,
+            db_encryption::disable_history_encryption,
+            keys::get_key_status,
+            keys::set_passphrase,
+            keys::change_passphrase,
+            keys::unlock_keys,
+            keys::rotate_keys,
+            share::create_encrypted_share,
+            share::open_encrypted_share,
+            device_trust::get_device_key,
+            device_trust::sign_clipboard_item,
+            device_trust::verify_clipboard_item,
+            device_trust::get_key_fingerprint,
+            device_trust::list_trusted_devices,
+            device_trust::trust_device,
+            device_trust::untrust_device,
+            webdav::get_webdav_settings,
+            webdav::set_webdav_settings,
+            webdav::test_webdav_connection,
+            webdav::webdav_push_item,
+            webdav::get_sync_backend,
+            remote_export::get_remote_export_settings,
+            remote_export::set_remote_export_settings,
+            remote_export::test_remote_export,
+            remote_export::export_to_remote,
+            storage::get_storage_settings,
+            storage::set_storage_settings,
+            storage::get_storage_status,
+            storage::mirror_clipboard_item,
+            storage::cache_item_file,
+            capabilities::get_server_capabilities,
+            capabilities::refresh_server_capabilities,
+            api_v2::get_api_v2_settings,
+            api_v2::set_api_v2_settings,
+            api_v2::get_active_api_version
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
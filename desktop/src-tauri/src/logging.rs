@@ -0,0 +1,95 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "logging.json";
+
+/// 单个日志文件的大小上限，超过后轮转
+pub const MAX_LOG_FILE_SIZE: u128 = 5 * 1024 * 1024;
+
+/// 轮转后保留的日志文件数
+pub const KEPT_LOG_FILES: usize = 5;
+
+/// 日志设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    /// 日志级别：error、warn、info、debug、trace
+    pub level: String,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+        }
+    }
+}
+
+/// 当前日志级别（LevelFilter 的数值），日志插件按此过滤
+static LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Info as usize);
+
+/// 日志插件的过滤条件：只输出不高于当前级别的日志
+pub fn enabled(metadata: &log::Metadata) -> bool {
+    metadata.level() as usize <= LEVEL.load(Ordering::Relaxed)
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level.trim()).map_err(|_| format!("无效的日志级别: {}", level))
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<LogSettings>(app, SETTINGS_FILE) {
+        if let Ok(level) = parse_level(&saved.level) {
+            LEVEL.store(level as usize, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 日志文件所在目录（便携模式下位于可执行文件旁的 data/logs）
+pub fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match store::portable_dir() {
+        Some(dir) => Ok(dir.join("logs")),
+        None => app
+            .path()
+            .app_log_dir()
+            .map_err(|e| format!("获取日志目录失败: {}", e)),
+    }
+}
+
+fn current_level() -> LevelFilter {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Tauri 命令：获取当前日志级别
+#[tauri::command]
+pub fn get_log_level() -> String {
+    current_level().as_str().to_lowercase()
+}
+
+/// Tauri 命令：修改日志级别，立即生效并在重启后保留
+#[tauri::command]
+pub fn set_log_level(app: AppHandle, level: String) -> Result<(), CloudPasteError> {
+    let filter = parse_level(&level)?;
+    let settings = LogSettings {
+        level: filter.as_str().to_lowercase(),
+    };
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+
+    LEVEL.store(filter as usize, Ordering::Relaxed);
+    log::info!("📝 日志级别已修改为: {}", settings.level);
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use crate::api;
+use crate::error::CloudPasteError;
+use pulldown_cmark::{html, Event, Options, Parser};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use tokio::process::Command;
+
+/// 导出的 HTML 使用的样式
+const EXPORT_STYLE: &str = r#"
+body { max-width: 820px; margin: 2em auto; padding: 0 1em; font-family: -apple-system, "Segoe UI", "PingFang SC", "Microsoft YaHei", sans-serif; line-height: 1.6; color: #1f2937; }
+pre { background: #f3f4f6; padding: 0.8em 1em; border-radius: 6px; overflow-x: auto; }
+code { font-family: "Cascadia Code", Menlo, Consolas, monospace; font-size: 0.9em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d1d5db; padding: 0.3em 0.6em; }
+blockquote { margin: 0; padding-left: 1em; border-left: 4px solid #d1d5db; color: #6b7280; }
+img { max-width: 100%; }
+@media print { body { margin: 0; max-width: none; } }
+"#;
+
+/// 导出文档的内容安全策略：禁止脚本和外部资源，只允许内联样式和 data: 图片
+///
+/// PDF 由无头浏览器打印，剪贴板内容不能在打印时执行脚本或访问网络
+const EXPORT_CSP: &str = "default-src 'none'; img-src data:; style-src 'unsafe-inline'";
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Html,
+    Pdf,
+}
+
+/// 将 Markdown 渲染为完整的 HTML 文档
+///
+/// Markdown 中的原始 HTML 按文本显示，不作为标签输出
+fn render_html(markdown: &str, title: &str) -> String {
+    let events = Parser::new_ext(markdown, Options::all()).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        event => event,
+    });
+    let mut body = String::new();
+    html::push_html(&mut body, events);
+
+    let title = title
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta http-equiv=\"Content-Security-Policy\" content=\"{}\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        EXPORT_CSP, title, EXPORT_STYLE, body
+    )
+}
+
+/// 可用于无头打印的浏览器（Windows 自带 Edge）
+fn browser_candidates() -> Vec<PathBuf> {
+    if cfg!(target_os = "windows") {
+        ["ProgramFiles(x86)", "ProgramFiles", "LocalAppData"]
+            .iter()
+            .filter_map(std::env::var_os)
+            .flat_map(|dir| {
+                let dir = PathBuf::from(dir);
+                [
+                    dir.join("Microsoft/Edge/Application/msedge.exe"),
+                    dir.join("Google/Chrome/Application/chrome.exe"),
+                ]
+            })
+            .collect()
+    } else if cfg!(target_os = "macos") {
+        vec![
+            PathBuf::from("/Applications/Google Chrome.app/Contents/MacOS/Google Chrome"),
+            PathBuf::from("/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge"),
+            PathBuf::from("/Applications/Chromium.app/Contents/MacOS/Chromium"),
+        ]
+    } else {
+        [
+            "google-chrome",
+            "chromium",
+            "chromium-browser",
+            "microsoft-edge",
+        ]
+        .iter()
+        .flat_map(|name| {
+            ["/usr/bin", "/usr/local/bin", "/snap/bin"]
+                .iter()
+                .map(move |dir| Path::new(dir).join(name))
+        })
+        .collect()
+    }
+}
+
+/// 使用无头浏览器将 HTML 打印为 PDF
+async fn print_pdf(html: &str, output: &Path) -> Result<(), CloudPasteError> {
+    let browser = browser_candidates()
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or_else(|| {
+            CloudPasteError::NotFound(
+                "未找到可用于导出 PDF 的浏览器（需要 Edge 或 Chrome）".to_string(),
+            )
+        })?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let source = std::env::temp_dir().join(format!("cloudpaste-export-{}.html", timestamp));
+    std::fs::write(&source, html).map_err(|e| CloudPasteError::io("写入临时文件失败", e))?;
+
+    let mut print_arg = std::ffi::OsString::from("--print-to-pdf=");
+    print_arg.push(output);
+
+    let mut command = Command::new(&browser);
+    command
+        .args(["--headless", "--disable-gpu", "--no-pdf-header-footer"])
+        .arg(print_arg)
+        .arg(&source);
+
+    // Windows 上不弹出控制台窗口
+    #[cfg(windows)]
+    command.creation_flags(0x0800_0000);
+
+    let result = command.output().await;
+    let _ = std::fs::remove_file(&source);
+
+    let output_status = result.map_err(|e| CloudPasteError::io("启动浏览器失败", e))?;
+    if !output_status.status.success() || !output.exists() {
+        return Err(CloudPasteError::Internal(format!(
+            "导出 PDF 失败: {}",
+            String::from_utf8_lossy(&output_status.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Tauri 命令：将 Markdown 剪贴板项导出为 HTML 或 PDF
+///
+/// 弹出保存对话框选择位置，返回保存路径；用户取消时返回 None
+#[tauri::command]
+pub async fn export_markdown(
+    app: AppHandle,
+    id: i64,
+    format: ExportFormat,
+) -> Result<Option<String>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let item = api::get_clipboard_item(id).await?;
+    if item.content_type != "text" {
+        return Err(CloudPasteError::InvalidInput(
+            "只能导出文本内容".to_string(),
+        ));
+    }
+
+    let title = item
+        .content
+        .lines()
+        .map(|line| line.trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .map(|line| line.chars().take(60).collect::<String>())
+        .unwrap_or_else(|| format!("CloudPaste {}", id));
+
+    let (filter_name, extension) = match format {
+        ExportFormat::Html => ("HTML", "html"),
+        ExportFormat::Pdf => ("PDF", "pdf"),
+    };
+
+    let dialog = app
+        .dialog()
+        .file()
+        .set_title("导出 Markdown")
+        .set_file_name(format!("cloudpaste-{}.{}", id, extension))
+        .add_filter(filter_name, &[extension]);
+
+    let Some(selected) = tauri::async_runtime::spawn_blocking(move || dialog.blocking_save_file())
+        .await
+        .map_err(|e| format!("打开保存对话框失败: {}", e))?
+    else {
+        return Ok(None);
+    };
+    let path = selected
+        .into_path()
+        .map_err(|e| format!("无效的保存路径: {}", e))?;
+
+    log::info!("📝 导出 Markdown: 剪贴板项={} -> {:?}", id, path);
+
+    let html = render_html(&item.content, &title);
+    match format {
+        ExportFormat::Html => crate::store::write_atomic(&path, html.as_bytes())
+            .map_err(|e| format!("保存文件失败: {}", e))?,
+        ExportFormat::Pdf => print_pdf(&html, &path).await?,
+    }
+
+    log::info!("✅ 导出完成: {:?}", path);
+    Ok(Some(path.to_string_lossy().to_string()))
+}
@@ -0,0 +1,367 @@
+use image::metadata::Orientation;
+
+/// PNG 文件签名
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// JPEG APP1 段中 EXIF 数据的前缀
+const EXIF_PREFIX: &[u8] = b"Exif\0\0";
+
+/// 从 EXIF（TIFF 格式）中读取需要保留的方向，无需旋转时返回 None
+fn kept_orientation(tiff: &[u8]) -> Option<u8> {
+    Orientation::from_exif_chunk(tiff)
+        .map(Orientation::to_exif)
+        .filter(|orientation| *orientation != Orientation::NoTransforms.to_exif())
+}
+
+/// 只包含方向标签（0x0112）的最小 EXIF（大端 TIFF 格式）
+fn orientation_exif(orientation: u8) -> Vec<u8> {
+    let mut tiff = b"MM\0\x2a".to_vec();
+    // IFD0 紧跟在 8 字节的 TIFF 头之后
+    tiff.extend_from_slice(&8u32.to_be_bytes());
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    // 方向：SHORT 类型，1 个值，值左对齐存放在 4 字节中
+    tiff.extend_from_slice(&0x0112u16.to_be_bytes());
+    tiff.extend_from_slice(&3u16.to_be_bytes());
+    tiff.extend_from_slice(&1u32.to_be_bytes());
+    tiff.extend_from_slice(&[0, orientation, 0, 0]);
+    // 没有下一个 IFD
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff
+}
+
+/// 移除图片中的 EXIF/XMP 等元数据（不重新编码，图像数据保持不变）
+///
+/// 根据文件头识别格式，不支持的格式返回 None
+pub fn strip_metadata(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(data).map(Some)
+    } else if data.starts_with(&PNG_SIGNATURE) {
+        strip_png(data).map(Some)
+    } else if is_heif(data) {
+        strip_heif(data).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// 根据文件头判断是否为支持移除元数据的格式（只需文件开头 12 字节）
+pub fn is_supported(header: &[u8]) -> bool {
+    header.starts_with(&[0xFF, 0xD8]) || header.starts_with(&PNG_SIGNATURE) || is_heif(header)
+}
+
+/// JPEG：移除 APP1（EXIF/XMP）、APP3-APP13、APP15 和注释段
+///
+/// 保留 APP0（JFIF）、APP2（ICC 色彩配置）和 APP14（Adobe 色彩变换）；
+/// EXIF 中的方向改写为只含方向标签的 APP1 段，移除后图片不会转向
+fn strip_jpeg(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..2]);
+
+    let mut pos = 2;
+    while pos + 2 <= data.len() {
+        if data[pos] != 0xFF {
+            return Err("JPEG 段结构无效".to_string());
+        }
+
+        let marker = data[pos + 1];
+
+        // 填充字节
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+
+        // 无长度字段的标记
+        if (0xD0..=0xD9).contains(&marker) || marker == 0x01 {
+            output.extend_from_slice(&data[pos..pos + 2]);
+            pos += 2;
+            continue;
+        }
+
+        // SOS 之后是压缩图像数据，原样保留
+        if marker == 0xDA {
+            output.extend_from_slice(&data[pos..]);
+            return Ok(output);
+        }
+
+        if pos + 4 > data.len() {
+            return Err("JPEG 段长度无效".to_string());
+        }
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let end = pos + 2 + length;
+        if length < 2 || end > data.len() {
+            return Err("JPEG 段长度无效".to_string());
+        }
+
+        let is_metadata =
+            marker == 0xE1 || (0xE3..=0xED).contains(&marker) || marker == 0xEF || marker == 0xFE;
+        if !is_metadata {
+            output.extend_from_slice(&data[pos..end]);
+        } else if marker == 0xE1 {
+            let orientation = data[pos + 4..end]
+                .strip_prefix(EXIF_PREFIX)
+                .and_then(kept_orientation);
+            if let Some(orientation) = orientation {
+                let tiff = orientation_exif(orientation);
+                let length = (2 + EXIF_PREFIX.len() + tiff.len()) as u16;
+                output.extend_from_slice(&[0xFF, 0xE1]);
+                output.extend_from_slice(&length.to_be_bytes());
+                output.extend_from_slice(EXIF_PREFIX);
+                output.extend_from_slice(&tiff);
+            }
+        }
+
+        pos = end;
+    }
+
+    Err("JPEG 缺少图像数据".to_string())
+}
+
+/// PNG：移除 eXIf、文本（tEXt/zTXt/iTXt，XMP 存放于 iTXt）和 tIME 块
+///
+/// eXIf 中的方向改写为只含方向标签的 eXIf 块
+fn strip_png(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&PNG_SIGNATURE);
+
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 12 <= data.len() {
+        let length =
+            u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let chunk_type = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let end = pos + 12 + length;
+        if end > data.len() {
+            return Err("PNG 块长度无效".to_string());
+        }
+
+        if !matches!(&chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            output.extend_from_slice(&data[pos..end]);
+        } else if &chunk_type == b"eXIf" {
+            if let Some(orientation) = kept_orientation(&data[pos + 8..end - 4]) {
+                let tiff = orientation_exif(orientation);
+                let mut crc = crc32fast::Hasher::new();
+                crc.update(b"eXIf");
+                crc.update(&tiff);
+                output.extend_from_slice(&(tiff.len() as u32).to_be_bytes());
+                output.extend_from_slice(b"eXIf");
+                output.extend_from_slice(&tiff);
+                output.extend_from_slice(&crc.finalize().to_be_bytes());
+            }
+        }
+
+        if &chunk_type == b"IEND" {
+            return Ok(output);
+        }
+        pos = end;
+    }
+
+    Err("PNG 缺少 IEND 块".to_string())
+}
+
+/// 是否为 HEIF/HEIC 文件
+fn is_heif(data: &[u8]) -> bool {
+    data.len() >= 12
+        && &data[4..8] == b"ftyp"
+        && matches!(
+            &data[8..12],
+            b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx" | b"mif1" | b"msf1"
+        )
+}
+
+/// ISOBMFF box：(类型, 内容起始位置, 结束位置)
+type BoxRange = ([u8; 4], usize, usize);
+
+fn read_uint(data: &[u8], pos: usize, size: usize) -> Result<u64, String> {
+    if pos.checked_add(size).is_none_or(|end| end > data.len()) {
+        return Err("HEIF 数据越界".to_string());
+    }
+    Ok(data[pos..pos + size]
+        .iter()
+        .fold(0u64, |value, byte| (value << 8) | *byte as u64))
+}
+
+/// 文件中的位置加上从文件读出的偏移，溢出时返回错误
+fn add_offset(pos: usize, offset: u64) -> Result<usize, String> {
+    usize::try_from(offset)
+        .ok()
+        .and_then(|offset| pos.checked_add(offset))
+        .ok_or_else(|| "HEIF 偏移量溢出".to_string())
+}
+
+/// 列出 [start, end) 范围内的子 box
+fn child_boxes(data: &[u8], start: usize, end: usize) -> Result<Vec<BoxRange>, String> {
+    let mut boxes = Vec::new();
+    let mut pos = start;
+    while pos + 8 <= end {
+        let size = read_uint(data, pos, 4)?;
+        let box_type = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let (header, box_end) = match size {
+            0 => (8, end),
+            1 => (16, add_offset(pos, read_uint(data, pos + 8, 8)?)?),
+            _ => (8, add_offset(pos, size)?),
+        };
+        if box_end > end || box_end < pos + header {
+            return Err("HEIF box 长度无效".to_string());
+        }
+        boxes.push((box_type, pos + header, box_end));
+        pos = box_end;
+    }
+    Ok(boxes)
+}
+
+fn read_cstring(data: &[u8], pos: usize, end: usize) -> (String, usize) {
+    let terminator = data[pos..end]
+        .iter()
+        .position(|byte| *byte == 0)
+        .map(|offset| pos + offset)
+        .unwrap_or(end);
+    (
+        String::from_utf8_lossy(&data[pos..terminator]).to_string(),
+        (terminator + 1).min(end),
+    )
+}
+
+/// 从 iinf 中找出 EXIF 和 XMP 元数据项的 ID
+fn metadata_item_ids(data: &[u8], start: usize, end: usize) -> Result<Vec<u32>, String> {
+    let version = read_uint(data, start, 1)?;
+    let entries_start = start + 4 + if version == 0 { 2 } else { 4 };
+
+    let mut ids = Vec::new();
+    for (box_type, content_start, box_end) in child_boxes(data, entries_start, end)? {
+        if &box_type != b"infe" {
+            continue;
+        }
+
+        let infe_version = read_uint(data, content_start, 1)?;
+        if infe_version < 2 {
+            continue;
+        }
+
+        let mut pos = content_start + 4;
+        let id_size = if infe_version == 2 { 2 } else { 4 };
+        let item_id = read_uint(data, pos, id_size)? as u32;
+        pos += id_size + 2;
+
+        if pos + 4 > box_end {
+            return Err("HEIF infe 长度无效".to_string());
+        }
+        let item_type = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+        pos += 4;
+
+        let (_, pos) = read_cstring(data, pos, box_end);
+        let is_xmp = &item_type == b"mime" && {
+            let (content_type, _) = read_cstring(data, pos, box_end);
+            content_type.contains("rdf+xml") || content_type.contains("xmp")
+        };
+
+        if &item_type == b"Exif" || is_xmp {
+            ids.push(item_id);
+        }
+    }
+    Ok(ids)
+}
+
+/// 根据 iloc 计算指定项在文件中的数据范围
+fn item_ranges(
+    data: &[u8],
+    start: usize,
+    item_ids: &[u32],
+    idat_start: Option<usize>,
+) -> Result<Vec<(usize, usize)>, String> {
+    let version = read_uint(data, start, 1)?;
+    let mut pos = start + 4;
+
+    let sizes = read_uint(data, pos, 2)?;
+    let offset_size = ((sizes >> 12) & 0xF) as usize;
+    let length_size = ((sizes >> 8) & 0xF) as usize;
+    let base_offset_size = ((sizes >> 4) & 0xF) as usize;
+    let index_size = if version == 1 || version == 2 {
+        (sizes & 0xF) as usize
+    } else {
+        0
+    };
+    pos += 2;
+
+    let count_size = if version < 2 { 2 } else { 4 };
+    let item_count = read_uint(data, pos, count_size)?;
+    pos += count_size;
+
+    let mut ranges = Vec::new();
+    for _ in 0..item_count {
+        let item_id = read_uint(data, pos, count_size)? as u32;
+        pos += count_size;
+
+        let mut construction_method = 0;
+        if version == 1 || version == 2 {
+            construction_method = read_uint(data, pos, 2)? & 0xF;
+            pos += 2;
+        }
+
+        // data_reference_index
+        pos += 2;
+
+        let base_offset = read_uint(data, pos, base_offset_size)?;
+        pos += base_offset_size;
+
+        let extent_count = read_uint(data, pos, 2)?;
+        pos += 2;
+
+        for _ in 0..extent_count {
+            pos += index_size;
+            let extent_offset = read_uint(data, pos, offset_size)?;
+            pos += offset_size;
+            let extent_length = read_uint(data, pos, length_size)?;
+            pos += length_size;
+
+            if !item_ids.contains(&item_id) {
+                continue;
+            }
+
+            let origin = match (construction_method, idat_start) {
+                (0, _) => 0,
+                (1, Some(idat)) => idat,
+                _ => return Err("不支持的 HEIF 元数据存储方式".to_string()),
+            };
+            let offset = add_offset(add_offset(origin, base_offset)?, extent_offset)?;
+            let length = add_offset(0, extent_length)?;
+            ranges.push((offset, length));
+        }
+    }
+    Ok(ranges)
+}
+
+/// HEIF/HEIC：将 EXIF 和 XMP 元数据项的内容原地清零
+///
+/// 不改变文件结构和各 box 的偏移，图像数据保持不变
+fn strip_heif(data: &[u8]) -> Result<Vec<u8>, String> {
+    let top_level = child_boxes(data, 0, data.len())?;
+    let Some((_, meta_start, meta_end)) = top_level.iter().find(|(t, _, _)| t == b"meta") else {
+        return Ok(data.to_vec());
+    };
+
+    // meta 是 FullBox，跳过 version/flags
+    let meta_children = child_boxes(data, meta_start + 4, *meta_end)?;
+    let find = |name: &[u8; 4]| meta_children.iter().find(|(t, _, _)| t == name);
+
+    let Some((_, iinf_start, iinf_end)) = find(b"iinf") else {
+        return Ok(data.to_vec());
+    };
+    let item_ids = metadata_item_ids(data, *iinf_start, *iinf_end)?;
+    if item_ids.is_empty() {
+        return Ok(data.to_vec());
+    }
+
+    let (_, iloc_start, _) = find(b"iloc").ok_or_else(|| "HEIF 缺少 iloc".to_string())?;
+    let idat_start = find(b"idat").map(|(_, start, _)| *start);
+
+    let mut output = data.to_vec();
+    for (offset, length) in item_ranges(data, *iloc_start, &item_ids, idat_start)? {
+        let end = offset
+            .checked_add(length)
+            .filter(|end| *end <= output.len())
+            .ok_or_else(|| "HEIF 元数据范围无效".to_string())?;
+        output[offset..end].fill(0);
+    }
+
+    Ok(output)
+}
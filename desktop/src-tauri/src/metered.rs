@@ -0,0 +1,242 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
+
+const SETTINGS_FILE: &str = "metered.json";
+
+/// 检测网络计费类型的间隔
+const DETECT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 手动指定网络是否按流量计费（覆盖自动检测结果）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteredOverride {
+    /// 使用自动检测结果
+    #[default]
+    Auto,
+    /// 始终视为按流量计费
+    Metered,
+    /// 始终视为不限流量
+    Unmetered,
+}
+
+/// 按流量计费网络的同步设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeteredSettings {
+    /// 按流量计费时暂停同步大文件
+    pub pause_large_files: bool,
+    /// 超过该大小（MB）的文件视为大文件
+    pub large_file_mb: u64,
+    /// 手动指定网络类型
+    pub connection_override: MeteredOverride,
+}
+
+impl Default for MeteredSettings {
+    fn default() -> Self {
+        Self {
+            pause_large_files: true,
+            large_file_mb: 5,
+            connection_override: MeteredOverride::Auto,
+        }
+    }
+}
+
+/// 当前网络计费状态
+#[derive(Debug, Clone, Serialize)]
+pub struct MeteredStatus {
+    /// 是否视为按流量计费（已考虑手动设置）
+    pub metered: bool,
+    /// 自动检测结果，无法检测时为空
+    pub detected: Option<bool>,
+    /// 是否正在暂停大文件同步
+    pub paused: bool,
+}
+
+static SETTINGS: Lazy<Mutex<MeteredSettings>> =
+    Lazy::new(|| Mutex::new(MeteredSettings::default()));
+
+/// 最近一次自动检测的结果
+static DETECTED: Mutex<Option<bool>> = Mutex::new(None);
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<MeteredSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> MeteredSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn status() -> MeteredStatus {
+    let settings = settings();
+    let detected = DETECTED.lock().ok().and_then(|d| *d);
+    let metered = match settings.connection_override {
+        MeteredOverride::Auto => detected.unwrap_or(false),
+        MeteredOverride::Metered => true,
+        MeteredOverride::Unmetered => false,
+    };
+
+    MeteredStatus {
+        metered,
+        detected,
+        paused: metered && settings.pause_large_files,
+    }
+}
+
+/// 检查文件是否可以在当前网络下同步，按流量计费且文件较大时返回原因
+pub fn check(size: u64) -> Result<(), String> {
+    if !status().paused {
+        return Ok(());
+    }
+
+    let limit_mb = settings().large_file_mb;
+    if size > limit_mb * 1024 * 1024 {
+        return Err(format!(
+            "当前网络按流量计费，已暂停同步大于 {} MB 的文件",
+            limit_mb
+        ));
+    }
+    Ok(())
+}
+
+/// 执行命令并返回标准输出，失败时返回 None
+async fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+
+    // Windows 上不弹出控制台窗口
+    #[cfg(windows)]
+    command.creation_flags(0x0800_0000);
+
+    let output = command.output().await.ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 检测当前网络是否按流量计费，无法检测时返回 None
+///
+/// - Windows：系统的网络计费类型（Fixed/Variable 或漫游视为按流量计费）
+/// - macOS：默认网络为手机热点（iPhone 热点网段或 Android 热点的 DHCP 标记）
+/// - Linux：NetworkManager 的 Metered 属性
+async fn detect() -> Option<bool> {
+    if cfg!(target_os = "windows") {
+        let script = "$p = [Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]::GetInternetConnectionProfile(); \
+             if ($p) { $c = $p.GetConnectionCost(); \"$($c.NetworkCostType) $($c.Roaming)\" }";
+        let output = command_output(
+            "powershell",
+            &["-NoProfile", "-NonInteractive", "-Command", script],
+        )
+        .await?;
+        let mut parts = output.split_whitespace();
+        let cost = parts.next()?;
+        let roaming = parts.next() == Some("True");
+        Some(cost == "Fixed" || cost == "Variable" || roaming)
+    } else if cfg!(target_os = "macos") {
+        let route = command_output("route", &["-n", "get", "default"]).await?;
+        let interface = route
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("interface:"))?
+            .trim()
+            .to_string();
+        let packet = command_output("ipconfig", &["getpacket", &interface])
+            .await
+            .unwrap_or_default();
+        Some(packet.contains("ANDROID_METERED") || packet.contains("yiaddr = 172.20.10."))
+    } else if cfg!(target_os = "linux") {
+        let output = command_output(
+            "gdbus",
+            &[
+                "call",
+                "--system",
+                "--dest",
+                "org.freedesktop.NetworkManager",
+                "--object-path",
+                "/org/freedesktop/NetworkManager",
+                "--method",
+                "org.freedesktop.DBus.Properties.Get",
+                "org.freedesktop.NetworkManager",
+                "Metered",
+            ],
+        )
+        .await?;
+        // NMMetered：1 = 是，3 = 推测是，2/4 = 否，0 = 未知
+        let value = output
+            .trim_matches(|c: char| !c.is_ascii_digit())
+            .rsplit(' ')
+            .next()?
+            .parse::<u32>()
+            .ok()?;
+        match value {
+            1 | 3 => Some(true),
+            2 | 4 => Some(false),
+            _ => None,
+        }
+    } else {
+        // 移动端没有可用的检测方式，由用户手动设置
+        None
+    }
+}
+
+/// 后台任务：定期检测网络计费类型，变化时发送 metered-changed 事件
+pub async fn watch(app: AppHandle) {
+    loop {
+        let detected = detect().await;
+        let changed = match DETECTED.lock() {
+            Ok(mut current) => std::mem::replace(&mut *current, detected) != detected,
+            Err(_) => false,
+        };
+        if changed {
+            let status = status();
+            log::info!(
+                "📶 网络计费类型: {}",
+                match detected {
+                    Some(true) => "按流量计费",
+                    Some(false) => "不限流量",
+                    None => "未知",
+                }
+            );
+            let _ = app.emit("metered-changed", status);
+        }
+        tokio::time::sleep(DETECT_INTERVAL).await;
+    }
+}
+
+/// Tauri 命令：获取当前网络计费状态
+#[tauri::command]
+pub fn get_metered_status() -> MeteredStatus {
+    status()
+}
+
+/// Tauri 命令：获取按流量计费网络的同步设置
+#[tauri::command]
+pub fn get_metered_settings() -> MeteredSettings {
+    settings()
+}
+
+/// Tauri 命令：更新按流量计费网络的同步设置
+#[tauri::command]
+pub fn set_metered_settings(
+    app: AppHandle,
+    settings: MeteredSettings,
+) -> Result<MeteredStatus, CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+
+    let status = status();
+    let _ = app.emit("metered-changed", &status);
+    Ok(status)
+}
@@ -0,0 +1,240 @@
+use crate::api::ClipboardItem;
+use crate::error::CloudPasteError;
+use crate::{http, image_cache, store};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+use tauri_plugin_notification::NotificationExt;
+
+const SETTINGS_FILE: &str = "notifications.json";
+
+/// 应用内通知窗口标签
+const TOAST_LABEL: &str = "toast";
+
+/// 应用内通知窗口尺寸（逻辑像素）
+const TOAST_WIDTH: f64 = 360.0;
+const TOAST_HEIGHT: f64 = 120.0;
+
+/// 通知窗口与屏幕边缘的距离
+const TOAST_MARGIN: f64 = 16.0;
+
+/// 通知设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// 系统通知不支持操作按钮时，改用应用内通知窗口
+    pub toast_fallback: bool,
+    /// 应用内通知自动关闭的秒数
+    pub toast_duration_secs: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            toast_fallback: true,
+            toast_duration_secs: 8,
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<NotificationSettings>> =
+    Lazy::new(|| Mutex::new(NotificationSettings::default()));
+
+/// 当前在通知窗口中展示的剪贴板项
+static PENDING_TOAST: Lazy<Mutex<Option<ClipboardItem>>> = Lazy::new(|| Mutex::new(None));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<NotificationSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> NotificationSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 系统通知插件是否支持操作按钮
+///
+/// 通知插件仅在移动端实现了操作按钮，桌面端（包括便携版 Windows，
+/// 未注册 AUMID 时系统通知可能完全不显示）都需要使用应用内通知窗口
+fn native_actions_available() -> bool {
+    cfg!(mobile)
+}
+
+/// 通知正文摘要
+fn summary(item: &ClipboardItem) -> String {
+    match item.content_type.as_str() {
+        "image" => "图片".to_string(),
+        "file" => format!("文件 {}", item.file_name.as_deref().unwrap_or("")),
+        _ => item.content.chars().take(80).collect(),
+    }
+}
+
+/// 将通知窗口放到主显示器右下角
+fn position_toast(window: &WebviewWindow) {
+    let Ok(Some(monitor)) = window.primary_monitor() else {
+        return;
+    };
+
+    let scale = monitor.scale_factor();
+    let size = monitor.size().to_logical::<f64>(scale);
+    let origin = monitor.position().to_logical::<f64>(scale);
+
+    let _ = window.set_position(LogicalPosition::new(
+        origin.x + size.width - TOAST_WIDTH - TOAST_MARGIN,
+        origin.y + size.height - TOAST_HEIGHT - TOAST_MARGIN * 4.0,
+    ));
+}
+
+/// 显示应用内通知窗口，已存在时更新内容
+fn show_toast(app: &AppHandle, item: ClipboardItem) -> Result<(), String> {
+    let item_id = item.id;
+    *PENDING_TOAST
+        .lock()
+        .map_err(|e| format!("无法锁定通知: {}", e))? = Some(item.clone());
+
+    if let Some(window) = app.get_webview_window(TOAST_LABEL) {
+        let _ = window.emit("toast-updated", &item);
+        let _ = window.show();
+    } else {
+        let window = WebviewWindowBuilder::new(app, TOAST_LABEL, WebviewUrl::App("toast".into()))
+            .title("CloudPaste")
+            .inner_size(TOAST_WIDTH, TOAST_HEIGHT)
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(false)
+            .build()
+            .map_err(|e| format!("创建通知窗口失败: {}", e))?;
+        position_toast(&window);
+    }
+
+    // 到时自动关闭（期间若已换成新的通知则不处理）
+    let duration = Duration::from_secs(settings().toast_duration_secs.max(1));
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(duration).await;
+        let still_showing = PENDING_TOAST
+            .lock()
+            .map(|pending| pending.as_ref().is_some_and(|item| item.id == item_id))
+            .unwrap_or(false);
+        if still_showing {
+            close_toast(&app);
+        }
+    });
+
+    Ok(())
+}
+
+fn close_toast(app: &AppHandle) {
+    if let Ok(mut pending) = PENDING_TOAST.lock() {
+        *pending = None;
+    }
+    if let Some(window) = app.get_webview_window(TOAST_LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// 将剪贴板项写入本地剪贴板
+async fn copy_item(app: &AppHandle, item: &ClipboardItem) -> Result<(), String> {
+    match item.content_type.as_str() {
+        "image" | "file" => {
+            let url = http::absolute_url(&item.content)?;
+            let path = image_cache::get_cached_file_path(app.clone(), url.clone()).await?;
+            if path == url {
+                return Err("下载文件失败，无法复制".to_string());
+            }
+
+            if item.content_type == "image" {
+                tauri_plugin_clipboard_x::write_image(path).await
+            } else {
+                tauri_plugin_clipboard_x::write_files(vec![path]).await
+            }
+        }
+        _ => tauri_plugin_clipboard_x::write_text(item.content.clone()).await,
+    }
+}
+
+/// Tauri 命令：提示收到新的剪贴板内容
+///
+/// 系统通知支持操作按钮时使用系统通知，否则显示带「复制」「打开」按钮的应用内通知窗口
+#[tauri::command]
+pub fn notify_incoming_paste(app: AppHandle, item: ClipboardItem) -> Result<(), CloudPasteError> {
+    let from = item
+        .device_name
+        .clone()
+        .unwrap_or_else(|| "其他设备".to_string());
+
+    if !native_actions_available() && settings().toast_fallback {
+        log::info!("🔔 显示应用内通知: 剪贴板项={}", item.id);
+        return Ok(show_toast(&app, item)?);
+    }
+
+    app.notification()
+        .builder()
+        .title(format!("来自 {} 的剪贴板内容", from))
+        .body(summary(&item))
+        .show()
+        .map_err(|e| CloudPasteError::Internal(format!("发送通知失败: {}", e)))
+}
+
+/// Tauri 命令：获取通知窗口当前展示的剪贴板项
+#[tauri::command]
+pub fn get_pending_toast() -> Option<ClipboardItem> {
+    PENDING_TOAST
+        .lock()
+        .ok()
+        .and_then(|pending| pending.clone())
+}
+
+/// Tauri 命令：处理通知窗口中的按钮
+///
+/// `copy` 将内容写入本地剪贴板，`open` 打开主窗口并定位到该项，其他值仅关闭通知
+#[tauri::command]
+pub async fn toast_action(app: AppHandle, action: String) -> Result<(), CloudPasteError> {
+    let item = get_pending_toast();
+    close_toast(&app);
+
+    let Some(item) = item else {
+        return Ok(());
+    };
+
+    log::info!("🔔 通知操作: {} (剪贴板项={})", action, item.id);
+
+    match action.as_str() {
+        "copy" => Ok(copy_item(&app, &item).await?),
+        "open" => {
+            crate::show_main_window(&app);
+            app.emit("open-clipboard-item", &item)
+                .map_err(|e| CloudPasteError::Internal(format!("发送导航事件失败: {}", e)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Tauri 命令：获取通知设置
+#[tauri::command]
+pub fn get_notification_settings() -> NotificationSettings {
+    settings()
+}
+
+/// Tauri 命令：更新通知设置
+#[tauri::command]
+pub fn set_notification_settings(
+    app: AppHandle,
+    settings: NotificationSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Webview};
+use tokio::process::Command;
+
+const SETTINGS_FILE: &str = "ocr_settings.json";
+
+/// 支持 OCR 的图片扩展名
+const OCR_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "gif", "webp", "tif", "tiff"];
+
+/// Tesseract 可执行文件名
+const TESSERACT_EXECUTABLE: &str = if cfg!(windows) {
+    "tesseract.exe"
+} else {
+    "tesseract"
+};
+
+/// PATH 中找不到时依次查找的常见安装目录
+#[cfg(windows)]
+const INSTALL_DIRS: &[&str] = &[
+    r"C:\Program Files\Tesseract-OCR",
+    r"C:\Program Files (x86)\Tesseract-OCR",
+];
+#[cfg(target_os = "macos")]
+const INSTALL_DIRS: &[&str] = &["/opt/homebrew/bin", "/usr/local/bin"];
+#[cfg(all(unix, not(target_os = "macos")))]
+const INSTALL_DIRS: &[&str] = &["/usr/bin", "/usr/local/bin"];
+
+/// OCR 设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OcrSettings {
+    /// 图片进入缓存后自动识别文字
+    pub auto_ocr: bool,
+    /// Tesseract 语言参数，例如 `chi_sim+eng`
+    pub languages: String,
+}
+
+impl Default for OcrSettings {
+    fn default() -> Self {
+        Self {
+            auto_ocr: false,
+            languages: "chi_sim+eng".to_string(),
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<OcrSettings>> = Lazy::new(|| Mutex::new(OcrSettings::default()));
+
+/// 自动识别完成事件
+#[derive(Debug, Clone, Serialize)]
+struct OcrCompleted {
+    url: String,
+    text: String,
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<OcrSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> OcrSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 是否为支持识别的图片文件
+fn is_ocr_image(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| OCR_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 识别结果在缓存中的保存路径
+pub fn text_path(cache_path: &Path) -> PathBuf {
+    let mut path = cache_path.as_os_str().to_owned();
+    path.push(".ocr.txt");
+    PathBuf::from(path)
+}
+
+/// 在 PATH 和常见安装目录中查找 Tesseract（不接受前端指定的可执行文件路径）
+fn find_tesseract() -> Option<PathBuf> {
+    let path_dirs = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    path_dirs
+        .into_iter()
+        .chain(INSTALL_DIRS.iter().map(PathBuf::from))
+        .map(|dir| dir.join(TESSERACT_EXECUTABLE))
+        .find(|path| path.is_file())
+}
+
+/// 语言参数只允许 `chi_sim+eng` 这样的语言代码组合
+fn valid_languages(languages: &str) -> bool {
+    !languages.is_empty()
+        && languages.split('+').all(|lang| {
+            !lang.is_empty() && lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+/// 调用 Tesseract 识别图片中的文字
+pub async fn recognize(path: &Path) -> Result<String, String> {
+    let settings = settings();
+    let program = find_tesseract().ok_or_else(|| "未找到 Tesseract，请确认已安装".to_string())?;
+
+    let mut command = Command::new(program);
+    command
+        .arg(path)
+        .arg("stdout")
+        .arg("-l")
+        .arg(&settings.languages);
+
+    // Windows 上不弹出控制台窗口
+    #[cfg(windows)]
+    command.creation_flags(0x0800_0000);
+
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("启动 Tesseract 失败（请确认已安装）: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "文字识别失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 自动识别流程：图片进入缓存后在后台识别并保存结果
+pub fn spawn_auto_ocr(app: &AppHandle, url: &str, cache_path: &Path) {
+    if !settings().auto_ocr || !is_ocr_image(cache_path) || text_path(cache_path).exists() {
+        return;
+    }
+
+    let app = app.clone();
+    let url = url.to_string();
+    let cache_path = cache_path.to_path_buf();
+    tauri::async_runtime::spawn(async move {
+        match recognize(&cache_path).await {
+            Ok(text) => {
+                if let Err(e) = crate::store::write_atomic(&text_path(&cache_path), text.as_bytes())
+                {
+                    log::warn!("⚠️ 保存识别结果失败: {}", e);
+                    return;
+                }
+                log::info!("✅ 自动识别完成: {} ({} 字符)", url, text.chars().count());
+                if let Err(e) = crate::history::attach_ocr_text(&url, &text) {
+                    log::warn!("⚠️ 更新识别结果索引失败: {}", e);
+                }
+                let _ = app.emit("ocr-completed", OcrCompleted { url, text });
+            }
+            Err(e) => log::warn!("⚠️ 自动识别失败: {}", e),
+        }
+    });
+}
+
+/// Tauri 命令：识别图片中的文字
+#[tauri::command]
+pub async fn ocr_image(webview: Webview, path: String) -> Result<String, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let path = crate::sandbox::check(&webview, &path)?;
+    log::info!("🔍 识别图片文字: {:?}", path);
+    Ok(recognize(&path).await?)
+}
+
+/// Tauri 命令：获取缓存图片的识别结果（尚未识别时返回 None）
+#[tauri::command]
+pub fn get_ocr_text(app: AppHandle, url: String) -> Result<Option<String>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let cache_path = crate::image_cache::cache_path_for_url(&app, &url)?;
+    Ok(std::fs::read_to_string(text_path(&cache_path)).ok())
+}
+
+/// Tauri 命令：获取 OCR 设置
+#[tauri::command]
+pub fn get_ocr_settings() -> OcrSettings {
+    settings()
+}
+
+/// Tauri 命令：更新 OCR 设置
+#[tauri::command]
+pub fn set_ocr_settings(app: AppHandle, settings: OcrSettings) -> Result<(), CloudPasteError> {
+    if !valid_languages(&settings.languages) {
+        return Err(CloudPasteError::InvalidInput(format!(
+            "无效的 OCR 语言: {}",
+            settings.languages
+        )));
+    }
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
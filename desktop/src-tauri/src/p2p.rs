@@ -0,0 +1,513 @@
+use crate::discovery::{self, LanPeer, LAN_PORT};
+use crate::error::CloudPasteError;
+use crate::{device, device_trust, store, upload};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+/// 每个数据帧的明文大小
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// 单帧最大长度（明文 + 认证标签）
+const MAX_FRAME_SIZE: usize = CHUNK_SIZE + 16;
+
+/// 连接对端的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 局域网直连接收的最大文件大小
+const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// 握手签名的协议标识
+const HANDSHAKE_CONTEXT: &[u8] = b"cloudpaste-p2p-v1";
+
+/// 握手后双方交换的设备身份（加密发送）
+///
+/// 签名覆盖双方的临时公钥，中间人无法转发已信任设备的身份
+#[derive(Debug, Serialize, Deserialize)]
+struct PeerIdentity {
+    device_id: String,
+    /// ed25519 签名公钥（Base64）
+    public_key: String,
+    /// 对握手记录的签名（Base64）
+    signature: String,
+}
+
+/// 传输头信息（加密后作为第一帧发送）
+#[derive(Debug, Serialize, Deserialize)]
+struct TransferHeader {
+    device_id: String,
+    file_name: String,
+    file_size: u64,
+}
+
+/// 文件发送结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SendFileResult {
+    /// 实际使用的传输方式：`lan` 或 `server`
+    pub method: String,
+    /// 回退到服务器上传时的文件 URL
+    pub file_url: Option<String>,
+}
+
+/// 局域网收到的文件
+#[derive(Debug, Clone, Serialize)]
+struct ReceivedFile {
+    device_id: String,
+    file_name: String,
+    file_size: u64,
+    path: String,
+}
+
+/// 基于 X25519 协商密钥的加密通道
+///
+/// 发起方与接收方使用不同的 nonce 方向位，避免同一密钥下 nonce 重复
+struct SecureChannel {
+    cipher: ChaCha20Poly1305,
+    initiator: bool,
+    send_counter: u64,
+    recv_counter: u64,
+}
+
+impl SecureChannel {
+    /// 协商密钥并交换签名的设备身份，返回通道和已验证签名的对端身份
+    async fn handshake(
+        stream: &mut TcpStream,
+        initiator: bool,
+    ) -> Result<(Self, PeerIdentity), String> {
+        let secret = EphemeralSecret::random_from_rng(OsRng);
+        let public = PublicKey::from(&secret);
+
+        stream
+            .write_all(public.as_bytes())
+            .await
+            .map_err(|e| format!("发送握手数据失败: {}", e))?;
+
+        let mut peer_public = [0u8; 32];
+        stream
+            .read_exact(&mut peer_public)
+            .await
+            .map_err(|e| format!("读取握手数据失败: {}", e))?;
+
+        let shared = secret.diffie_hellman(&PublicKey::from(peer_public));
+
+        let mut hasher = Sha256::new();
+        hasher.update(HANDSHAKE_CONTEXT);
+        hasher.update(shared.as_bytes());
+        let key = hasher.finalize();
+
+        let mut channel = Self {
+            cipher: ChaCha20Poly1305::new(&key),
+            initiator,
+            send_counter: 0,
+            recv_counter: 0,
+        };
+
+        // 握手记录：发起方和接收方的临时公钥
+        let (initiator_public, responder_public) = if initiator {
+            (*public.as_bytes(), peer_public)
+        } else {
+            (peer_public, *public.as_bytes())
+        };
+        let transcript = |role: u8| {
+            let mut message = HANDSHAKE_CONTEXT.to_vec();
+            message.push(role);
+            message.extend_from_slice(&initiator_public);
+            message.extend_from_slice(&responder_public);
+            message
+        };
+        let (own_role, peer_role) = if initiator { (0, 1) } else { (1, 0) };
+
+        let identity = PeerIdentity {
+            device_id: device::id(),
+            public_key: device::public_key().ok_or_else(|| "设备签名密钥尚未初始化".to_string())?,
+            signature: device::sign(&transcript(own_role))
+                .ok_or_else(|| "设备签名密钥尚未初始化".to_string())?,
+        };
+        let identity =
+            serde_json::to_vec(&identity).map_err(|e| format!("序列化设备身份失败: {}", e))?;
+        channel.send(stream, &identity).await?;
+
+        let peer: PeerIdentity = serde_json::from_slice(&channel.recv(stream).await?)
+            .map_err(|e| format!("解析设备身份失败: {}", e))?;
+        if !device_trust::verify_signature(
+            &peer.public_key,
+            &peer.signature,
+            &transcript(peer_role),
+        ) {
+            return Err(format!("设备身份签名无效: {}", peer.device_id));
+        }
+
+        Ok((channel, peer))
+    }
+
+    fn nonce(direction: u8, counter: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[0] = direction;
+        nonce[4..].copy_from_slice(&counter.to_be_bytes());
+        nonce
+    }
+
+    async fn send(&mut self, stream: &mut TcpStream, plaintext: &[u8]) -> Result<(), String> {
+        let direction = if self.initiator { 0 } else { 1 };
+        let nonce = Self::nonce(direction, self.send_counter);
+        self.send_counter += 1;
+
+        let ciphertext = self
+            .cipher
+            .encrypt(&Nonce::from(nonce), plaintext)
+            .map_err(|e| format!("加密数据失败: {}", e))?;
+
+        stream
+            .write_all(&(ciphertext.len() as u32).to_be_bytes())
+            .await
+            .map_err(|e| format!("发送数据失败: {}", e))?;
+        stream
+            .write_all(&ciphertext)
+            .await
+            .map_err(|e| format!("发送数据失败: {}", e))
+    }
+
+    async fn recv(&mut self, stream: &mut TcpStream) -> Result<Vec<u8>, String> {
+        let mut len_buf = [0u8; 4];
+        stream
+            .read_exact(&mut len_buf)
+            .await
+            .map_err(|e| format!("读取数据失败: {}", e))?;
+
+        let len = u32::from_be_bytes(len_buf) as usize;
+        if len > MAX_FRAME_SIZE {
+            return Err(format!("数据帧过大: {} 字节", len));
+        }
+
+        let mut ciphertext = vec![0u8; len];
+        stream
+            .read_exact(&mut ciphertext)
+            .await
+            .map_err(|e| format!("读取数据失败: {}", e))?;
+
+        let direction = if self.initiator { 1 } else { 0 };
+        let nonce = Self::nonce(direction, self.recv_counter);
+        self.recv_counter += 1;
+
+        self.cipher
+            .decrypt(&Nonce::from(nonce), ciphertext.as_slice())
+            .map_err(|e| format!("解密数据失败: {}", e))
+    }
+}
+
+/// 启动局域网直连接收服务
+pub async fn serve(app: AppHandle) {
+    let listener = match TcpListener::bind(("0.0.0.0", LAN_PORT)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::warn!("⚠️ 局域网直连端口 {} 监听失败: {}", LAN_PORT, e);
+            return;
+        }
+    };
+
+    log::info!("📡 局域网直连服务已启动: 端口 {}", LAN_PORT);
+
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                log::warn!("⚠️ 接受局域网连接失败: {}", e);
+                continue;
+            }
+        };
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match receive_file(&app, stream).await {
+                Ok(received) => {
+                    log::info!("✅ 已通过局域网接收文件: {}", received.path);
+                    let _ = app.emit("p2p-file-received", &received);
+                }
+                Err(e) => log::warn!("⚠️ 局域网接收文件失败 ({}): {}", addr, e),
+            }
+        });
+    }
+}
+
+/// 接收目录：下载目录下的 CloudPaste 文件夹
+fn receive_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .download_dir()
+        .map_err(|e| format!("获取下载目录失败: {}", e))?
+        .join("CloudPaste");
+
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建接收目录失败: {}", e))?;
+    Ok(dir)
+}
+
+/// 生成不与已有文件冲突的保存路径（只保留文件名部分，防止路径穿越）
+fn unique_destination(dir: &Path, file_name: &str) -> Result<PathBuf, String> {
+    let file_name = Path::new(file_name)
+        .file_name()
+        .ok_or_else(|| "无效的文件名".to_string())?;
+
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return Ok(candidate);
+    }
+
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..1000)
+        .map(|i| dir.join(format!("{} ({}){}", stem, i, extension)))
+        .find(|candidate| !candidate.exists())
+        .ok_or_else(|| "无法生成保存路径".to_string())
+}
+
+/// 询问用户是否接收未信任设备发送的文件
+async fn confirm_untrusted(
+    app: &AppHandle,
+    device_name: &str,
+    peer: &PeerIdentity,
+    header: &TransferHeader,
+) -> Result<bool, String> {
+    let message = format!(
+        "未信任的设备「{}」想发送文件：\n{}（{} 字节）\n\n设备指纹：{}\n\n请确认指纹与对方设备上显示的一致。",
+        device_name,
+        header.file_name,
+        header.file_size,
+        device_trust::fingerprint(&peer.public_key)
+    );
+    let dialog = app
+        .dialog()
+        .message(message)
+        .title("局域网文件")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "接收".to_string(),
+            "拒绝".to_string(),
+        ));
+    tauri::async_runtime::spawn_blocking(move || dialog.blocking_show())
+        .await
+        .map_err(|e| format!("显示确认对话框失败: {}", e))
+}
+
+async fn receive_file(app: &AppHandle, mut stream: TcpStream) -> Result<ReceivedFile, String> {
+    let (mut channel, peer) = SecureChannel::handshake(&mut stream, false).await?;
+
+    let header: TransferHeader = serde_json::from_slice(&channel.recv(&mut stream).await?)
+        .map_err(|e| format!("解析传输头失败: {}", e))?;
+    if header.device_id != peer.device_id {
+        return Err(format!(
+            "传输头中的设备 {} 与握手身份 {} 不符",
+            header.device_id, peer.device_id
+        ));
+    }
+    if header.file_size > MAX_FILE_SIZE {
+        return Err(format!("文件过大: {} 字节", header.file_size));
+    }
+
+    // 已信任的设备直接接收；局域网内已发现但未信任的设备需用户确认，其余拒绝
+    if !device_trust::is_trusted(&peer.device_id, &peer.public_key) {
+        let Some(discovered) = discovery::list_peers()
+            .into_iter()
+            .find(|discovered| discovered.device_id == peer.device_id)
+        else {
+            return Err(format!("拒绝未知设备: {}", peer.device_id));
+        };
+        if !confirm_untrusted(app, &discovered.device_name, &peer, &header).await? {
+            return Err(format!(
+                "用户拒绝接收来自 {} 的文件",
+                discovered.device_name
+            ));
+        }
+    }
+
+    log::info!(
+        "📥 开始接收局域网文件: {} ({} 字节) 来自 {}",
+        header.file_name,
+        header.file_size,
+        header.device_id
+    );
+
+    let dest = unique_destination(&receive_dir(app)?, &header.file_name)?;
+    // 先写入临时文件，接收完整后再重命名，避免留下不完整的文件
+    let temp = store::atomic_temp_path(&dest);
+    let mut file = tokio::fs::File::create(&temp)
+        .await
+        .map_err(|e| format!("创建文件失败: {}", e))?;
+
+    let mut received = 0u64;
+    let result: Result<(), String> = async {
+        loop {
+            let chunk = channel.recv(&mut stream).await?;
+            // 空帧表示传输结束
+            if chunk.is_empty() {
+                break;
+            }
+
+            received += chunk.len() as u64;
+            if received > header.file_size {
+                return Err("接收数据超出声明的文件大小".to_string());
+            }
+
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("写入文件失败: {}", e))?;
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| format!("写入文件失败: {}", e))?;
+
+        if received != header.file_size {
+            return Err(format!(
+                "文件不完整: {}/{} 字节",
+                received, header.file_size
+            ));
+        }
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        drop(file);
+        let _ = tokio::fs::remove_file(&temp).await;
+        return Err(e);
+    }
+    store::commit_atomic(file.into_std().await, &temp, &dest)
+        .map_err(|e| format!("保存文件失败: {}", e))?;
+
+    channel.send(&mut stream, b"ok").await?;
+
+    Ok(ReceivedFile {
+        device_id: header.device_id,
+        file_name: header.file_name,
+        file_size: header.file_size,
+        path: dest.to_string_lossy().to_string(),
+    })
+}
+
+/// 依次尝试对端的各个地址
+async fn connect_peer(peer: &LanPeer) -> Result<TcpStream, String> {
+    for address in &peer.addresses {
+        let Ok(ip) = address.parse::<IpAddr>() else {
+            continue;
+        };
+
+        let addr = SocketAddr::new(ip, peer.port);
+        match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(e)) => log::warn!("⚠️ 连接 {} 失败: {}", addr, e),
+            Err(_) => log::warn!("⚠️ 连接 {} 超时", addr),
+        }
+    }
+
+    Err(format!("无法连接局域网设备: {}", peer.device_name))
+}
+
+async fn send_over_lan(peer: &LanPeer, path: &Path) -> Result<(), String> {
+    let device_id = crate::GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| format!("无法锁定配置: {}", e))?
+        .device_id
+        .clone();
+
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("读取文件信息失败: {}", e))?;
+    let header = TransferHeader {
+        device_id,
+        file_name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| "无效的文件路径".to_string())?,
+        file_size: metadata.len(),
+    };
+
+    let mut stream = connect_peer(peer).await?;
+    let (mut channel, identity) = SecureChannel::handshake(&mut stream, true).await?;
+
+    // 只向身份已验证的目标设备发送，局域网内冒充的设备会回退到服务器上传
+    if identity.device_id != peer.device_id
+        || !device_trust::is_trusted(&identity.device_id, &identity.public_key)
+    {
+        return Err(format!("对端设备未被信任: {}", identity.device_id));
+    }
+
+    let header_bytes =
+        serde_json::to_vec(&header).map_err(|e| format!("序列化传输头失败: {}", e))?;
+    channel.send(&mut stream, &header_bytes).await?;
+
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("打开文件失败: {}", e))?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("读取文件失败: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        channel.send(&mut stream, &buffer[..read]).await?;
+    }
+    channel.send(&mut stream, &[]).await?;
+
+    let ack = channel.recv(&mut stream).await?;
+    if ack != b"ok" {
+        return Err("对端未确认接收".to_string());
+    }
+
+    Ok(())
+}
+
+/// Tauri 命令：向指定设备发送文件
+///
+/// 目标设备在局域网内可达时直接加密传输，否则回退到通过服务器上传
+#[tauri::command]
+pub async fn send_file_to_device(
+    webview: Webview,
+    path: String,
+    device_id: String,
+) -> Result<SendFileResult, CloudPasteError> {
+    let path = crate::sandbox::check(&webview, &path)?;
+
+    if let Some(peer) = discovery::list_peers()
+        .into_iter()
+        .find(|peer| peer.device_id == device_id)
+    {
+        log::info!("📡 尝试局域网直连发送: {:?} -> {}", path, peer.device_name);
+        match send_over_lan(&peer, &path).await {
+            Ok(()) => {
+                log::info!("✅ 局域网直连发送完成: {:?}", path);
+                return Ok(SendFileResult {
+                    method: "lan".to_string(),
+                    file_url: None,
+                });
+            }
+            Err(e) => log::warn!("⚠️ 局域网直连失败，回退到服务器上传: {}", e),
+        }
+    }
+
+    let uploaded = upload::upload_file(&path).await?;
+    Ok(SendFileResult {
+        method: "server".to_string(),
+        file_url: Some(uploaded.file_url),
+    })
+}
@@ -0,0 +1,78 @@
+use crate::error::CloudPasteError;
+use crate::events::{self, PasteStackChanged};
+use crate::{clipboard, history, quick_paste};
+use once_cell::sync::Lazy;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// 粘贴队列：每次按下“粘贴下一项”快捷键时按顺序粘贴一项（只保存在内存中）
+static STACK: Lazy<Mutex<VecDeque<i64>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+fn lock() -> Result<std::sync::MutexGuard<'static, VecDeque<i64>>, String> {
+    STACK.lock().map_err(|e| format!("无法锁定粘贴队列: {}", e))
+}
+
+fn notify_changed(stack: &VecDeque<i64>) {
+    events::emit(PasteStackChanged {
+        remaining: stack.iter().copied().collect(),
+    });
+}
+
+/// 粘贴队列中的下一项到当前窗口，队列为空时返回 false
+pub async fn paste_next(app: &AppHandle) -> Result<bool, String> {
+    crate::applock::ensure_unlocked()?;
+    let Some(item_id) = lock()?.pop_front() else {
+        log::info!("📭 粘贴队列为空");
+        return Ok(false);
+    };
+
+    let result = async {
+        clipboard::write_item(app, item_id, None).await?;
+        quick_paste::paste_now().await
+    }
+    .await;
+
+    let stack = lock()?;
+    notify_changed(&stack);
+    result?;
+    log::info!(
+        "📋 已粘贴队列中的记录: {}（剩余 {} 项）",
+        item_id,
+        stack.len()
+    );
+    Ok(true)
+}
+
+/// Tauri 命令：将记录按顺序加入粘贴队列末尾，返回队列中的全部记录
+#[tauri::command]
+pub fn paste_stack_push(item_ids: Vec<i64>) -> Result<Vec<i64>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    // 只接受存在的记录，避免粘贴时才发现记录已被删除（同一记录可以多次加入）
+    let existing: HashSet<i64> = history::get_entries(&item_ids)?
+        .into_iter()
+        .map(|entry| entry.item.id)
+        .collect();
+
+    let mut stack = lock()?;
+    stack.extend(item_ids.into_iter().filter(|id| existing.contains(id)));
+    notify_changed(&stack);
+    Ok(stack.iter().copied().collect())
+}
+
+/// Tauri 命令：获取粘贴队列中的记录（按粘贴顺序）
+#[tauri::command]
+pub fn get_paste_stack() -> Result<Vec<i64>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    Ok(lock()?.iter().copied().collect())
+}
+
+/// Tauri 命令：清空粘贴队列
+#[tauri::command]
+pub fn clear_paste_stack() -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let mut stack = lock()?;
+    stack.clear();
+    notify_changed(&stack);
+    Ok(())
+}
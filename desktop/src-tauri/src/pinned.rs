@@ -0,0 +1,91 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use crate::{http, image_cache};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// 后台检查固定项缓存的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// 记录对应的文件 URL（文本内容没有文件）
+fn file_url(entry: &HistoryEntry) -> Option<String> {
+    if entry.item.content_type == "text" {
+        return None;
+    }
+    http::absolute_url(&entry.item.content).ok()
+}
+
+/// 固定或收藏项的缓存文件路径，清理缓存时需要保留
+pub fn protected_paths(app: &AppHandle) -> HashSet<PathBuf> {
+    let entries = match history::pinned_entries() {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("⚠️ 读取固定项失败: {}", e);
+            return HashSet::new();
+        }
+    };
+
+    entries
+        .iter()
+        .filter_map(file_url)
+        .filter_map(|url| image_cache::cache_path_for_url(app, &url).ok())
+        .collect()
+}
+
+/// 下载所有尚未缓存的固定项文件
+async fn ensure_all(app: &AppHandle) {
+    let entries = match history::pinned_entries() {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("⚠️ 读取固定项失败: {}", e);
+            return;
+        }
+    };
+
+    for url in entries.iter().filter_map(file_url) {
+        if let Err(e) = image_cache::ensure_cached(app, &url).await {
+            log::warn!("⚠️ 缓存固定项失败: {} ({})", url, e);
+        }
+    }
+}
+
+/// 后台任务：定期确保固定项的文件已缓存到本地
+pub async fn keep_cached(app: AppHandle) {
+    loop {
+        ensure_all(&app).await;
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+/// Tauri 命令：固定或取消固定剪贴板项
+///
+/// 固定后立即在后台下载文件内容，保证离线可用
+#[tauri::command]
+pub fn pin_item(app: AppHandle, id: i64, pinned: bool) -> Result<HistoryEntry, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let entry = history::set_pinned(id, pinned)?;
+    log::info!(
+        "📌 {}剪贴板项: {}",
+        if pinned { "固定" } else { "取消固定" },
+        id
+    );
+
+    if let Some(url) = file_url(&entry).filter(|_| pinned) {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = image_cache::ensure_cached(&app, &url).await {
+                log::warn!("⚠️ 缓存固定项失败: {} ({})", url, e);
+            }
+        });
+    }
+
+    Ok(entry)
+}
+
+/// Tauri 命令：获取所有固定或收藏的剪贴板项
+#[tauri::command]
+pub fn list_pinned() -> Result<Vec<HistoryEntry>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    Ok(history::pinned_entries()?)
+}
@@ -0,0 +1,241 @@
+use crate::error::CloudPasteError;
+use crate::{http, store};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// 已生效的受管策略（服务器下发）
+const POLICY_FILE: &str = "managed_policy.json";
+
+/// 管理员预置的受管配置（包含策略签名公钥）
+const MANAGED_FILE: &str = "managed.json";
+
+/// 打码后替换成的文本
+const REDACTED: &str = "[已隐藏]";
+
+/// 受管策略内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// 策略版本号，只接受比当前更新的版本
+    pub version: u64,
+    /// 允许同步的单个文件最大字节数
+    pub max_sync_file_size: Option<u64>,
+    /// 允许同步的内容类型（text/image/file），为空表示不限制
+    pub allowed_content_types: Option<Vec<String>>,
+    /// 同步前需要打码的正则表达式
+    pub redaction_patterns: Vec<String>,
+    /// 强制的同步间隔（秒）
+    pub sync_interval_secs: Option<u64>,
+}
+
+/// 服务器下发的签名策略文档
+///
+/// `payload` 为策略 JSON 原文，`signature` 为对其 UTF-8 字节的 Ed25519 签名（Base64）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedPolicy {
+    pub payload: String,
+    pub signature: String,
+}
+
+/// 管理员预置的受管配置
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct ManagedConfig {
+    /// 策略签名公钥（Base64 编码的 32 字节 Ed25519 公钥）
+    policy_public_key: Option<String>,
+}
+
+/// 当前生效的策略及其来源
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EffectivePolicy {
+    /// `managed` 表示来自服务器的受管策略，`default` 表示未受管
+    pub source: String,
+    pub policy: Policy,
+    /// 策略应用时间（Unix 秒）
+    pub applied_at: Option<u64>,
+}
+
+static EFFECTIVE: Lazy<Mutex<EffectivePolicy>> = Lazy::new(|| {
+    Mutex::new(EffectivePolicy {
+        source: "default".to_string(),
+        ..Default::default()
+    })
+});
+
+/// 当前策略中编译好的打码规则（无效的规则会被跳过）
+static REDACTIONS: Lazy<Mutex<Vec<Regex>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// 当前生效的策略
+pub fn current() -> Policy {
+    EFFECTIVE
+        .lock()
+        .map(|effective| effective.policy.clone())
+        .unwrap_or_default()
+}
+
+/// 编译策略中的打码规则
+fn compile_redactions(policy: &Policy) {
+    let regexes = policy
+        .redaction_patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::error!("❌ 受管策略打码规则无效 ({}): {}", pattern, e);
+                None
+            }
+        })
+        .collect();
+    if let Ok(mut redactions) = REDACTIONS.lock() {
+        *redactions = regexes;
+    }
+}
+
+/// 按受管策略的打码规则处理要同步的文本
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let Ok(redactions) = REDACTIONS.lock() else {
+        return Cow::Borrowed(text);
+    };
+    let mut result = Cow::Borrowed(text);
+    for regex in redactions.iter() {
+        if let Cow::Owned(replaced) = regex.replace_all(&result, REDACTED) {
+            result = Cow::Owned(replaced);
+        }
+    }
+    result
+}
+
+/// 启动时加载已缓存的受管策略
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<EffectivePolicy>(app, POLICY_FILE) {
+        if let Ok(mut effective) = EFFECTIVE.lock() {
+            log::info!("✅ 已加载受管策略: version={}", saved.policy.version);
+            compile_redactions(&saved.policy);
+            *effective = saved;
+        }
+    }
+}
+
+/// 使用管理员预置的公钥校验签名并解析策略
+fn verify(app: &AppHandle, signed: &SignedPolicy) -> Result<Policy, String> {
+    let public_key = store::load_json::<ManagedConfig>(app, MANAGED_FILE)
+        .and_then(|managed| managed.policy_public_key)
+        .ok_or_else(|| "未配置策略签名公钥，拒绝应用受管策略".to_string())?;
+
+    let key_bytes: [u8; 32] = BASE64
+        .decode(public_key.trim())
+        .map_err(|e| format!("解析策略公钥失败: {}", e))?
+        .try_into()
+        .map_err(|_| "策略公钥长度无效".to_string())?;
+    let verifying_key =
+        VerifyingKey::from_bytes(&key_bytes).map_err(|e| format!("策略公钥无效: {}", e))?;
+
+    let signature_bytes: [u8; 64] = BASE64
+        .decode(signed.signature.trim())
+        .map_err(|e| format!("解析策略签名失败: {}", e))?
+        .try_into()
+        .map_err(|_| "策略签名长度无效".to_string())?;
+    let signature = Signature::from_bytes(&signature_bytes);
+
+    verifying_key
+        .verify(signed.payload.as_bytes(), &signature)
+        .map_err(|_| "策略签名校验失败".to_string())?;
+
+    serde_json::from_str::<Policy>(&signed.payload).map_err(|e| format!("解析策略失败: {}", e))
+}
+
+/// 校验并应用签名策略
+fn apply(app: &AppHandle, signed: &SignedPolicy) -> Result<EffectivePolicy, String> {
+    let policy = verify(app, signed)?;
+
+    let mut effective = EFFECTIVE
+        .lock()
+        .map_err(|e| format!("无法锁定策略: {}", e))?;
+
+    if effective.source == "managed" && policy.version <= effective.policy.version {
+        log::info!(
+            "ℹ️ 忽略旧版本策略: version={} (当前 {})",
+            policy.version,
+            effective.policy.version
+        );
+        return Ok(effective.clone());
+    }
+
+    let updated = EffectivePolicy {
+        source: "managed".to_string(),
+        policy,
+        applied_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs()),
+    };
+    store::save_json(app, POLICY_FILE, &updated)?;
+    compile_redactions(&updated.policy);
+    *effective = updated.clone();
+
+    log::info!("✅ 受管策略已更新: version={}", updated.policy.version);
+    let _ = app.emit("policy-updated", &updated);
+
+    Ok(updated)
+}
+
+/// 从服务器拉取本设备的受管策略
+pub async fn fetch(app: &AppHandle) -> Result<EffectivePolicy, CloudPasteError> {
+    let device_id = crate::GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?
+        .device_id
+        .clone();
+
+    let request = http::api_request(Method::GET, &format!("/devices/{}/policy", device_id))?;
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("获取受管策略失败", e))?;
+
+    // 服务器未提供策略时保留当前策略
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return get_effective_policy();
+    }
+
+    let signed: SignedPolicy = http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析受管策略失败: {}", e)))?;
+
+    Ok(apply(app, &signed)?)
+}
+
+/// Tauri 命令：获取当前生效的策略
+#[tauri::command]
+pub fn get_effective_policy() -> Result<EffectivePolicy, CloudPasteError> {
+    EFFECTIVE
+        .lock()
+        .map(|effective| effective.clone())
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定策略: {}", e)))
+}
+
+/// Tauri 命令：从服务器重新拉取受管策略
+#[tauri::command]
+pub async fn refresh_policy(app: AppHandle) -> Result<EffectivePolicy, CloudPasteError> {
+    fetch(&app).await
+}
+
+/// Tauri 命令：应用服务器推送的策略
+///
+/// 前端从 WebSocket 收到 `policy_update` 消息后调用
+#[tauri::command]
+pub fn apply_pushed_policy(
+    app: AppHandle,
+    signed: SignedPolicy,
+) -> Result<EffectivePolicy, CloudPasteError> {
+    Ok(apply(&app, &signed)?)
+}
@@ -0,0 +1,118 @@
+use crate::error::CloudPasteError;
+use crate::{history, http, image_cache, metered, store};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "prefetch.json";
+
+/// 离线预下载设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrefetchSettings {
+    /// 每次同步后是否预下载最近记录的文件
+    pub enabled: bool,
+    /// 预下载最近多少条记录
+    pub recent_items: usize,
+    /// 超过该大小（MB）的文件不预下载
+    pub max_file_mb: u64,
+}
+
+impl Default for PrefetchSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            recent_items: 20,
+            max_file_mb: 20,
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<PrefetchSettings>> =
+    Lazy::new(|| Mutex::new(PrefetchSettings::default()));
+
+/// 是否有预下载正在进行
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<PrefetchSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> PrefetchSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 将最近记录的文件下载到缓存，保证离线时仍可使用
+///
+/// 跳过文本、过大的文件，以及按流量计费网络下暂停同步的文件；已有预下载进行时直接返回
+pub async fn run(app: AppHandle) {
+    let settings = settings();
+    if !settings.enabled || RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let max_size = settings.max_file_mb * 1024 * 1024;
+    match history::recent_entries(settings.recent_items) {
+        Ok(entries) => {
+            let mut fetched = 0;
+            for entry in entries {
+                if entry.item.content_type == "text" {
+                    continue;
+                }
+                let size = entry.item.file_size.unwrap_or_default();
+                if size > max_size || metered::check(size).is_err() {
+                    continue;
+                }
+                let Ok(url) = http::absolute_url(&entry.item.content) else {
+                    continue;
+                };
+                let already_cached = image_cache::cache_path_for_url(&app, &url)
+                    .is_ok_and(|path| image_cache::is_cached(&path));
+                if already_cached {
+                    continue;
+                }
+                match image_cache::ensure_cached(&app, &url).await {
+                    Ok(_) => fetched += 1,
+                    Err(e) => log::warn!("⚠️ 预下载失败: {} ({})", url, e),
+                }
+            }
+            if fetched > 0 {
+                log::info!("📥 已预下载最近记录的文件: {} 个", fetched);
+            }
+        }
+        Err(e) => log::warn!("⚠️ 读取最近记录失败: {}", e),
+    }
+
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+/// Tauri 命令：获取离线预下载设置
+#[tauri::command]
+pub fn get_prefetch_settings() -> PrefetchSettings {
+    settings()
+}
+
+/// Tauri 命令：更新离线预下载设置
+#[tauri::command]
+pub fn set_prefetch_settings(
+    app: AppHandle,
+    settings: PrefetchSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    log::info!(
+        "📥 离线预下载: {}，最近 {} 条",
+        settings.enabled,
+        settings.recent_items
+    );
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use crate::error::CloudPasteError;
+use crate::http;
+use crate::upload::{self, UploadResponse, UploadTracker, UploadedFile};
+use once_cell::sync::Lazy;
+use reqwest::{Body, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// 已确认不支持预签名上传的服务器地址，避免每次上传都查询
+static UNSUPPORTED: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize)]
+struct PresignRequest<'a> {
+    file_name: &'a str,
+    file_size: u64,
+    device_id: &'a str,
+}
+
+/// 服务器签发的对象存储（S3 / R2）直传地址
+#[derive(Debug, Deserialize)]
+pub struct PresignedUpload {
+    /// 服务器记录本次上传的 ID，完成时回传
+    upload_id: String,
+    /// 每个分片的大小（最后一片可以更小）
+    part_size: u64,
+    /// 按分片顺序排列的 PUT 地址，只有一个时为普通上传
+    part_urls: Vec<String>,
+    /// PUT 时需要附带的请求头（如签名时约定的 Content-Type）
+    #[serde(default)]
+    headers: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct PresignResponse {
+    data: Option<PresignedUpload>,
+}
+
+#[derive(Serialize)]
+struct CompletedPart {
+    part_number: usize,
+    etag: String,
+}
+
+#[derive(Serialize)]
+struct CompleteRequest<'a> {
+    upload_id: &'a str,
+    file_name: &'a str,
+    file_size: u64,
+    device_id: &'a str,
+    parts: Vec<CompletedPart>,
+}
+
+/// 要上传的内容：内存中的数据或磁盘上的大文件
+pub enum Source<'a> {
+    Bytes(Vec<u8>),
+    File(&'a Path),
+}
+
+fn server_key() -> Option<String> {
+    http::absolute_url("/").ok()
+}
+
+fn mark_unsupported() {
+    if let Ok(mut guard) = UNSUPPORTED.lock() {
+        *guard = server_key();
+    }
+}
+
+/// 向服务器申请直传地址
+///
+/// 服务器不支持（旧版本或未配置对象存储）或申请失败时返回 None，由调用方走普通上传
+pub async fn request(file_name: &str, file_size: u64, device_id: &str) -> Option<PresignedUpload> {
+    if crate::capabilities::supports(crate::capabilities::PRESIGNED_UPLOAD) == Some(false) {
+        return None;
+    }
+    let key = server_key()?;
+    if UNSUPPORTED
+        .lock()
+        .is_ok_and(|guard| guard.as_deref() == Some(key.as_str()))
+    {
+        return None;
+    }
+
+    let body = PresignRequest {
+        file_name,
+        file_size,
+        device_id,
+    };
+    let request = http::api_request(Method::POST, "/files/presign")
+        .ok()?
+        .json(&body);
+    let response = match http::send(request).await {
+        Ok(response) => response,
+        Err(e) => {
+            log::warn!("⚠️ 申请直传地址失败: {}", e);
+            return None;
+        }
+    };
+    if matches!(
+        response.status(),
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+    ) {
+        log::info!("ℹ️ 服务器不支持对象存储直传，使用普通上传");
+        mark_unsupported();
+        return None;
+    }
+
+    match http::check_response(response).await {
+        Ok(response) => match response.json::<PresignResponse>().await {
+            Ok(body) => body.data,
+            Err(e) => {
+                log::warn!("⚠️ 解析直传地址失败: {}", e);
+                None
+            }
+        },
+        Err(e) => {
+            log::warn!("⚠️ 申请直传地址失败: {}", e);
+            None
+        }
+    }
+}
+
+/// PUT 一个分片，返回对象存储给出的 ETag
+async fn put_part(
+    presigned: &PresignedUpload,
+    url: &str,
+    body: Body,
+    len: u64,
+) -> Result<String, CloudPasteError> {
+    let mut request = http::client()
+        .put(url)
+        .header(reqwest::header::CONTENT_LENGTH, len)
+        .body(body);
+    for (name, value) in &presigned.headers {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CloudPasteError::request("上传到对象存储失败", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(CloudPasteError::Server {
+            status: status.as_u16(),
+            message: format!(
+                "对象存储拒绝上传: {}",
+                response.text().await.unwrap_or_default()
+            ),
+        });
+    }
+    Ok(response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// 分片上传：依次读取每个分片并 PUT，每完成一片更新一次进度
+async fn put_parts(
+    presigned: &PresignedUpload,
+    file_name: &str,
+    source: Source<'_>,
+    size: u64,
+) -> Result<Vec<CompletedPart>, CloudPasteError> {
+    let part_size = presigned.part_size.max(1);
+    if presigned.part_urls.len() as u64 != size.div_ceil(part_size) {
+        return Err(CloudPasteError::InvalidResponse(format!(
+            "服务器返回的分片数 {} 与文件大小不符",
+            presigned.part_urls.len()
+        )));
+    }
+
+    let mut reader: Box<dyn AsyncRead + Unpin + Send> = match source {
+        Source::Bytes(data) => Box::new(std::io::Cursor::new(data)),
+        Source::File(path) => Box::new(
+            tokio::fs::File::open(path)
+                .await
+                .map_err(|e| CloudPasteError::io("打开文件失败", e))?,
+        ),
+    };
+
+    let mut tracker = UploadTracker::new(file_name.to_string(), size);
+    let mut parts = Vec::with_capacity(presigned.part_urls.len());
+    for (index, url) in presigned.part_urls.iter().enumerate() {
+        let mut chunk = Vec::with_capacity(part_size as usize);
+        (&mut reader)
+            .take(part_size)
+            .read_to_end(&mut chunk)
+            .await
+            .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+
+        let len = chunk.len() as u64;
+        let etag = put_part(presigned, url, Body::from(chunk), len).await?;
+        tracker.advance(len as usize);
+        parts.push(CompletedPart {
+            part_number: index + 1,
+            etag,
+        });
+    }
+    Ok(parts)
+}
+
+/// 直传到对象存储，然后向服务器登记文件信息
+pub async fn upload(
+    presigned: PresignedUpload,
+    file_name: String,
+    source: Source<'_>,
+    size: u64,
+    device_id: &str,
+) -> Result<UploadedFile, CloudPasteError> {
+    log::info!(
+        "☁️ 直传到对象存储: {} ({} 字节, {} 个分片)",
+        file_name,
+        size,
+        presigned.part_urls.len()
+    );
+
+    let parts = if presigned.part_urls.len() == 1 {
+        // 单个分片时流式发送，进度更细
+        let body = match source {
+            Source::Bytes(data) => upload::progress_body(file_name.clone(), data),
+            Source::File(path) => {
+                let file = tokio::fs::File::open(path)
+                    .await
+                    .map_err(|e| CloudPasteError::io("打开文件失败", e))?;
+                upload::file_body(file_name.clone(), file, size)
+            }
+        };
+        let etag = put_part(&presigned, &presigned.part_urls[0], body, size).await?;
+        vec![CompletedPart {
+            part_number: 1,
+            etag,
+        }]
+    } else {
+        put_parts(&presigned, &file_name, source, size).await?
+    };
+
+    let complete = CompleteRequest {
+        upload_id: &presigned.upload_id,
+        file_name: &file_name,
+        file_size: size,
+        device_id,
+        parts,
+    };
+    let request = http::api_request(Method::POST, "/files/presign/complete")?.json(&complete);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("登记上传文件失败", e))?;
+    let body: UploadResponse = http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析上传结果失败: {}", e)))?;
+
+    log::info!("✅ 文件已直传: {}", body.data.file_url);
+    Ok(body.data)
+}
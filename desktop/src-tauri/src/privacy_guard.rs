@@ -0,0 +1,186 @@
+use crate::capture::SkipReason;
+use crate::error::CloudPasteError;
+use crate::events::{self, ScreenSharingChanged};
+use crate::store;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use xcap::Window;
+
+const SETTINGS_FILE: &str = "privacy_guard.json";
+
+/// 检测屏幕共享的间隔
+const DETECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 屏幕共享时各会议软件显示的窗口（标题中的关键字，不区分大小写）
+const SHARE_WINDOW_MARKERS: &[&str] = &[
+    // Chrome / Edge 网页会议的共享提示栏（Google Meet 等）
+    "is sharing your screen",
+    "is sharing a window",
+    "正在共享您的屏幕",
+    "正在共享此窗口",
+    // Zoom 共享工具栏
+    "zoom share toolbar",
+    "zoom share statusbar",
+    // Microsoft Teams 共享控制栏
+    "sharing control bar",
+    // 腾讯会议 / 飞书共享工具栏
+    "共享屏幕工具栏",
+    "正在共享屏幕",
+];
+
+/// 浏览器隐私窗口标题中的关键字（不区分大小写）
+const PRIVATE_WINDOW_MARKERS: &[&str] = &[
+    "private browsing",
+    "inprivate",
+    "incognito",
+    "隐私浏览",
+    "无痕",
+];
+
+/// 屏幕共享及隐私浏览时的采集设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyGuardSettings {
+    /// 检测到屏幕共享时暂停采集，共享结束后自动恢复
+    pub pause_on_screen_share: bool,
+    /// 从浏览器隐私窗口复制时不采集（按窗口标题判断）
+    pub skip_private_browsing: bool,
+    /// 额外视为屏幕共享的窗口标题关键字
+    pub share_window_markers: Vec<String>,
+}
+
+impl Default for PrivacyGuardSettings {
+    fn default() -> Self {
+        Self {
+            pause_on_screen_share: true,
+            skip_private_browsing: false,
+            share_window_markers: Vec::new(),
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<PrivacyGuardSettings>> =
+    Lazy::new(|| Mutex::new(PrivacyGuardSettings::default()));
+
+/// 正在共享屏幕的应用，未共享时为空
+static SHARING_APP: Mutex<Option<String>> = Mutex::new(None);
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<PrivacyGuardSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> PrivacyGuardSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn contains_marker<'a>(text: &str, mut markers: impl Iterator<Item = &'a str>) -> bool {
+    let text = text.to_lowercase();
+    markers.any(|marker| !marker.is_empty() && text.contains(&marker.to_lowercase()))
+}
+
+/// 查找屏幕共享时显示的窗口，返回所属应用
+fn detect_screen_share(extra_markers: &[String]) -> Option<String> {
+    let windows = match Window::all() {
+        Ok(windows) => windows,
+        Err(e) => {
+            log::debug!("获取窗口列表失败: {}", e);
+            return None;
+        }
+    };
+
+    windows.into_iter().find_map(|window| {
+        let title = window.title().ok()?;
+        let markers = SHARE_WINDOW_MARKERS
+            .iter()
+            .copied()
+            .chain(extra_markers.iter().map(String::as_str));
+        contains_marker(&title, markers).then(|| window.app_name().unwrap_or(title))
+    })
+}
+
+/// 当前前台窗口是浏览器隐私窗口时返回所属应用
+fn private_browsing_window() -> Option<String> {
+    let window = active_win_pos_rs::get_active_window().ok()?;
+    contains_marker(&window.title, PRIVATE_WINDOW_MARKERS.iter().copied())
+        .then_some(window.app_name)
+}
+
+/// 当前是否因屏幕共享或隐私浏览而不应采集，返回原因及相关应用
+pub fn skip_reason() -> Option<(SkipReason, String)> {
+    let settings = settings();
+    if settings.pause_on_screen_share {
+        if let Some(app_name) = SHARING_APP.lock().ok().and_then(|app| app.clone()) {
+            return Some((SkipReason::ScreenSharing, app_name));
+        }
+    }
+    if settings.skip_private_browsing {
+        if let Some(app_name) = private_browsing_window() {
+            return Some((SkipReason::PrivateBrowsing, app_name));
+        }
+    }
+    None
+}
+
+/// 后台任务：定期检测屏幕共享，开始或结束时发送 screen-sharing-changed 事件
+pub async fn watch() {
+    loop {
+        let settings = settings();
+        let detected = if settings.pause_on_screen_share {
+            tauri::async_runtime::spawn_blocking(move || {
+                detect_screen_share(&settings.share_window_markers)
+            })
+            .await
+            .unwrap_or(None)
+        } else {
+            None
+        };
+
+        let changed = match SHARING_APP.lock() {
+            Ok(mut current) => {
+                let was_sharing = current.is_some();
+                *current = detected.clone();
+                was_sharing != detected.is_some()
+            }
+            Err(_) => false,
+        };
+        if changed {
+            match &detected {
+                Some(app_name) => log::info!("⏸️ 检测到屏幕共享，暂停采集: {}", app_name),
+                None => log::info!("▶️ 屏幕共享已结束，恢复采集"),
+            }
+            events::emit(ScreenSharingChanged {
+                active: detected.is_some(),
+                app_name: detected,
+            });
+        }
+
+        tokio::time::sleep(DETECT_INTERVAL).await;
+    }
+}
+
+/// Tauri 命令：获取屏幕共享及隐私浏览设置
+#[tauri::command]
+pub fn get_privacy_guard_settings() -> PrivacyGuardSettings {
+    settings()
+}
+
+/// Tauri 命令：更新屏幕共享及隐私浏览设置
+#[tauri::command]
+pub fn set_privacy_guard_settings(
+    app: AppHandle,
+    settings: PrivacyGuardSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use crate::error::CloudPasteError;
+use image::Luma;
+use log::info;
+use qrcode::QrCode;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// 默认二维码边长（像素）
+const DEFAULT_QR_SIZE: u32 = 256;
+
+/// 二维码边长的允许范围（像素），避免过大的尺寸占用大量内存
+const MIN_QR_SIZE: u32 = 64;
+const MAX_QR_SIZE: u32 = 4096;
+
+/// 获取二维码缓存目录
+fn get_qr_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let qr_dir = crate::store::cache_dir(app)?.join("qrcodes");
+
+    fs::create_dir_all(&qr_dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+
+    Ok(qr_dir)
+}
+
+/// Tauri 命令：生成二维码 PNG
+///
+/// 用于分享链接或设备配对数据，返回缓存目录中的 PNG 文件路径（前端使用 convertFileSrc 显示）
+#[tauri::command]
+pub async fn generate_qr(
+    app: AppHandle,
+    data: String,
+    size: Option<u32>,
+) -> Result<String, CloudPasteError> {
+    let size = size
+        .unwrap_or(DEFAULT_QR_SIZE)
+        .clamp(MIN_QR_SIZE, MAX_QR_SIZE);
+
+    let mut hasher = Sha256::new();
+    hasher.update(data.as_bytes());
+    hasher.update(size.to_be_bytes());
+    let qr_path = get_qr_dir(&app)?.join(format!("{:x}.png", hasher.finalize()));
+
+    if !qr_path.exists() {
+        let code = QrCode::new(data.as_bytes()).map_err(|e| format!("生成二维码失败: {}", e))?;
+        let image = code.render::<Luma<u8>>().min_dimensions(size, size).build();
+
+        image
+            .save(&qr_path)
+            .map_err(|e| format!("保存二维码失败: {}", e))?;
+
+        info!("✅ 二维码已生成: {:?}", qr_path);
+    }
+
+    qr_path
+        .to_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| CloudPasteError::Internal("路径转换失败".to_string()))
+}
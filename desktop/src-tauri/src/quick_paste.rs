@@ -0,0 +1,272 @@
+use crate::clipboard::{self, ClipboardFormat};
+use crate::error::CloudPasteError;
+use crate::store;
+use clipboard_rs::ClipboardContent;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+const SETTINGS_FILE: &str = "quick_paste.json";
+
+/// 快速粘贴面板窗口标签
+const WINDOW_LABEL: &str = "quick-paste";
+
+const WINDOW_WIDTH: f64 = 420.0;
+const WINDOW_HEIGHT: f64 = 480.0;
+
+/// 快速粘贴设置（快捷键由 `hotkeys` 统一管理）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuickPasteSettings {
+    /// 选择记录后是否自动向之前的窗口发送粘贴按键（Cmd/Ctrl+V）
+    pub auto_paste: bool,
+    /// 切回之前的窗口后等待多久再发送粘贴按键（毫秒），等待目标窗口获得焦点
+    pub paste_delay_ms: u64,
+}
+
+impl Default for QuickPasteSettings {
+    fn default() -> Self {
+        Self {
+            auto_paste: false,
+            paste_delay_ms: 150,
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<QuickPasteSettings>> =
+    Lazy::new(|| Mutex::new(QuickPasteSettings::default()));
+
+/// 打开面板前处于前台的窗口
+#[derive(Debug, Clone)]
+struct PreviousWindow {
+    app_name: String,
+    /// Windows 下的窗口句柄，用于切回该窗口
+    #[cfg(windows)]
+    hwnd: isize,
+}
+
+static PREVIOUS_WINDOW: Lazy<Mutex<Option<PreviousWindow>>> = Lazy::new(|| Mutex::new(None));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<QuickPasteSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> QuickPasteSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 记录当前前台窗口（本应用的窗口除外），粘贴时切回该窗口
+fn remember_focused_window() {
+    let Ok(window) = active_win_pos_rs::get_active_window() else {
+        return;
+    };
+    if window.process_id == u64::from(std::process::id()) {
+        return;
+    }
+
+    let previous = PreviousWindow {
+        app_name: window.app_name,
+        #[cfg(windows)]
+        hwnd: unsafe { windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow().0 as isize },
+    };
+    if let Ok(mut guard) = PREVIOUS_WINDOW.lock() {
+        *guard = Some(previous);
+    }
+}
+
+/// 打开快速粘贴面板（先记录当前前台窗口）
+pub fn show(app: &AppHandle) -> Result<(), String> {
+    remember_focused_window();
+
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.center();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("quick-paste".into()))
+        .title("CloudPaste")
+        .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .center()
+        .focused(true)
+        .build()
+        .map_err(|e| format!("创建快速粘贴面板失败: {}", e))?;
+    Ok(())
+}
+
+/// 隐藏面板并切回之前的窗口
+fn restore_focus(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+
+    let previous = PREVIOUS_WINDOW.lock().ok().and_then(|guard| guard.clone());
+
+    // macOS 隐藏应用后系统自动激活之前的应用
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app.hide();
+    }
+
+    #[cfg(windows)]
+    if let Some(previous) = &previous {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+        // 面板当前在前台，允许将前台切换给其他窗口
+        let activated = unsafe { SetForegroundWindow(HWND(previous.hwnd as *mut _)) };
+        if !activated.as_bool() {
+            log::warn!("⚠️ 切回窗口失败: {}", previous.app_name);
+        }
+    }
+
+    if let Some(previous) = previous {
+        log::debug!("切回窗口: {}", previous.app_name);
+    }
+}
+
+/// 模拟按下粘贴快捷键（macOS 为 Cmd+V，其他平台为 Ctrl+V）
+///
+/// macOS 需要授予辅助功能权限
+fn send_paste_keystroke() -> Result<(), String> {
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| format!("初始化按键模拟失败: {}", e))?;
+    let modifier = if cfg!(target_os = "macos") {
+        Key::Meta
+    } else {
+        Key::Control
+    };
+
+    // 由快捷键触发时用户可能仍按着其他修饰键，先松开以免组合成其他快捷键
+    for key in [Key::Shift, Key::Alt, Key::Control, Key::Meta] {
+        let _ = enigo.key(key, Direction::Release);
+    }
+
+    let result = enigo
+        .key(modifier, Direction::Press)
+        .and_then(|_| enigo.key(Key::Unicode('v'), Direction::Click));
+    // 无论是否成功都松开修饰键，避免修饰键保持按下状态
+    let released = enigo.key(modifier, Direction::Release);
+    result
+        .and(released)
+        .map_err(|e| format!("发送粘贴按键失败: {}", e))
+}
+
+/// 等待设置的延迟后向当前前台窗口发送粘贴按键
+pub async fn paste_now() -> Result<(), String> {
+    tokio::time::sleep(Duration::from_millis(settings().paste_delay_ms)).await;
+    tauri::async_runtime::spawn_blocking(send_paste_keystroke)
+        .await
+        .map_err(|e| format!("发送粘贴按键失败: {}", e))?
+}
+
+/// 切回之前的窗口并发送粘贴按键
+pub async fn paste_to_previous_window(app: &AppHandle) -> Result<(), String> {
+    restore_focus(app);
+    paste_now().await
+}
+
+/// 只保留剪贴板中的纯文本（去掉 HTML / RTF 等格式），可选向当前窗口发送粘贴按键
+///
+/// 剪贴板中没有文本时返回错误
+pub async fn paste_clipboard_as_plain_text(paste: bool) -> Result<(), String> {
+    let text = clipboard::read_text()?.ok_or_else(|| "剪贴板中没有文本".to_string())?;
+    clipboard::write(vec![ClipboardContent::Text(text)])?;
+    log::info!("📋 已将剪贴板内容转为纯文本");
+    if paste {
+        paste_now().await?;
+    }
+    Ok(())
+}
+
+/// Tauri 命令：打开快速粘贴面板
+#[tauri::command]
+pub fn show_quick_paste(app: AppHandle) -> Result<(), CloudPasteError> {
+    Ok(show(&app)?)
+}
+
+/// Tauri 命令：关闭快速粘贴面板，切回之前的窗口
+#[tauri::command]
+pub fn hide_quick_paste(app: AppHandle) {
+    restore_focus(&app);
+}
+
+/// Tauri 命令：在快速粘贴面板中选择记录
+///
+/// 将记录写入剪贴板并关闭面板；`paste` 为空时按设置决定是否自动向之前的窗口发送粘贴按键。
+/// 返回是否发送了粘贴按键
+#[tauri::command]
+pub async fn quick_paste_item(
+    app: AppHandle,
+    item_id: i64,
+    format: Option<ClipboardFormat>,
+    paste: Option<bool>,
+) -> Result<bool, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    clipboard::write_item(&app, item_id, format).await?;
+
+    if !paste.unwrap_or(settings().auto_paste) {
+        restore_focus(&app);
+        return Ok(false);
+    }
+    paste_to_previous_window(&app).await?;
+    log::info!("📋 已粘贴到之前的窗口: 剪贴板项={}", item_id);
+    Ok(true)
+}
+
+/// Tauri 命令：以纯文本粘贴
+///
+/// 指定 `item_id` 时将该记录的纯文本写入剪贴板（文件记录为下载链接），否则将当前剪贴板内容转为纯文本；
+/// `paste` 为 true 时随后发送粘贴按键（从快速粘贴面板调用时先切回之前的窗口）
+#[tauri::command]
+pub async fn paste_plain_text(
+    app: AppHandle,
+    item_id: Option<i64>,
+    paste: Option<bool>,
+) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let paste = paste.unwrap_or(false);
+    let Some(item_id) = item_id else {
+        return Ok(paste_clipboard_as_plain_text(paste).await?);
+    };
+
+    clipboard::write_item(&app, item_id, Some(ClipboardFormat::Text)).await?;
+    if paste {
+        paste_to_previous_window(&app).await?;
+    } else {
+        restore_focus(&app);
+    }
+    Ok(())
+}
+
+/// Tauri 命令：获取快速粘贴设置
+#[tauri::command]
+pub fn get_quick_paste_settings() -> QuickPasteSettings {
+    settings()
+}
+
+/// Tauri 命令：更新快速粘贴设置
+#[tauri::command]
+pub fn set_quick_paste_settings(
+    app: AppHandle,
+    settings: QuickPasteSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
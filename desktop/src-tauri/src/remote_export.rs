@@ -0,0 +1,457 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use crate::{http, image_cache, store};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use ssh2::{HashType, Session};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "remote_export.json";
+
+/// 连接和读写超时
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 导出协议
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteProtocol {
+    #[default]
+    Sftp,
+    /// 明文 FTP（被动模式），仅用于内网中的旧设备
+    Ftp,
+}
+
+/// 导出目标服务器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteExportSettings {
+    pub protocol: RemoteProtocol,
+    pub host: String,
+    /// 为空时使用协议默认端口（SFTP 22，FTP 21）
+    pub port: Option<u16>,
+    pub username: String,
+    pub password: String,
+    /// SFTP 私钥路径，设置后使用密钥登录（`password` 作为私钥口令）
+    pub private_key_path: Option<String>,
+    /// 远程目录，不存在时自动创建
+    pub remote_dir: String,
+    /// 首次连接时记录的 SFTP 主机密钥指纹（SHA-256，Base64），之后连接时校验
+    pub host_key_sha256: Option<String>,
+}
+
+impl Default for RemoteExportSettings {
+    fn default() -> Self {
+        Self {
+            protocol: RemoteProtocol::Sftp,
+            host: String::new(),
+            port: None,
+            username: String::new(),
+            password: String::new(),
+            private_key_path: None,
+            remote_dir: "CloudPaste".to_string(),
+            host_key_sha256: None,
+        }
+    }
+}
+
+impl RemoteExportSettings {
+    fn address(&self) -> String {
+        let port = self.port.unwrap_or(match self.protocol {
+            RemoteProtocol::Sftp => 22,
+            RemoteProtocol::Ftp => 21,
+        });
+        format!("{}:{}", self.host, port)
+    }
+}
+
+static SETTINGS: Lazy<Mutex<RemoteExportSettings>> =
+    Lazy::new(|| Mutex::new(RemoteExportSettings::default()));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<RemoteExportSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> RemoteExportSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn save(app: &AppHandle, settings: RemoteExportSettings) -> Result<(), CloudPasteError> {
+    store::save_json(app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定设置: {}", e)))? = settings;
+    Ok(())
+}
+
+/// 要导出的一个文件
+struct ExportFile {
+    name: String,
+    source: PathBuf,
+    /// 文本记录写出的临时文件，导出后删除
+    temporary: bool,
+}
+
+fn connect_tcp(address: &str) -> Result<TcpStream, CloudPasteError> {
+    let stream = TcpStream::connect(address)
+        .map_err(|e| CloudPasteError::Network(format!("连接 {} 失败: {}", address, e)))?;
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+    Ok(stream)
+}
+
+fn sftp_error(context: &str, e: ssh2::Error) -> CloudPasteError {
+    CloudPasteError::Network(format!("{}: {}", context, e))
+}
+
+/// 建立 SSH 会话并登录，返回会话和主机密钥指纹
+///
+/// 已记录指纹时校验主机密钥，不一致说明服务器被替换或遭到中间人攻击
+fn sftp_session(settings: &RemoteExportSettings) -> Result<(Session, String), CloudPasteError> {
+    let mut session = Session::new().map_err(|e| sftp_error("创建 SSH 会话失败", e))?;
+    session.set_tcp_stream(connect_tcp(&settings.address())?);
+    session
+        .handshake()
+        .map_err(|e| sftp_error("SSH 握手失败", e))?;
+
+    let fingerprint = session
+        .host_key_hash(HashType::Sha256)
+        .map(|hash| BASE64.encode(hash))
+        .ok_or_else(|| CloudPasteError::Network("无法获取主机密钥".to_string()))?;
+    if let Some(expected) = &settings.host_key_sha256 {
+        if *expected != fingerprint {
+            return Err(CloudPasteError::Unauthorized(format!(
+                "{} 的主机密钥已变化（SHA256:{}），请确认后重新测试连接",
+                settings.host, fingerprint
+            )));
+        }
+    }
+
+    match &settings.private_key_path {
+        Some(key) if !key.is_empty() => {
+            let passphrase = (!settings.password.is_empty()).then_some(settings.password.as_str());
+            session.userauth_pubkey_file(&settings.username, None, Path::new(key), passphrase)
+        }
+        _ => session.userauth_password(&settings.username, &settings.password),
+    }
+    .map_err(|e| CloudPasteError::Unauthorized(format!("SSH 登录失败: {}", e)))?;
+    Ok((session, fingerprint))
+}
+
+fn sftp_upload(
+    settings: &RemoteExportSettings,
+    files: &[ExportFile],
+) -> Result<Vec<String>, CloudPasteError> {
+    if settings.host_key_sha256.is_none() {
+        return Err(CloudPasteError::InvalidInput(
+            "请先测试连接并确认主机密钥".to_string(),
+        ));
+    }
+    let (session, _) = sftp_session(settings)?;
+    let sftp = session
+        .sftp()
+        .map_err(|e| sftp_error("打开 SFTP 失败", e))?;
+
+    let dir = Path::new(&settings.remote_dir);
+    if !settings.remote_dir.is_empty() && sftp.stat(dir).is_err() {
+        sftp.mkdir(dir, 0o755)
+            .map_err(|e| sftp_error("创建远程目录失败", e))?;
+    }
+
+    let mut uploaded = Vec::with_capacity(files.len());
+    for file in files {
+        let remote = dir.join(&file.name);
+        let mut local = std::fs::File::open(&file.source)
+            .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+        let mut target = sftp
+            .create(&remote)
+            .map_err(|e| sftp_error("创建远程文件失败", e))?;
+        std::io::copy(&mut local, &mut target)
+            .map_err(|e| CloudPasteError::Network(format!("上传 {} 失败: {}", file.name, e)))?;
+        uploaded.push(remote.to_string_lossy().replace('\\', "/"));
+    }
+    Ok(uploaded)
+}
+
+/// 最简单的 FTP 客户端：只支持登录、建目录和被动模式上传
+struct FtpClient {
+    control: BufReader<TcpStream>,
+}
+
+impl FtpClient {
+    fn connect(settings: &RemoteExportSettings) -> Result<Self, CloudPasteError> {
+        let mut client = Self {
+            control: BufReader::new(connect_tcp(&settings.address())?),
+        };
+        client.expect(&[220], "连接 FTP 服务器失败")?;
+        let (code, message) = client.command(&format!("USER {}", settings.username))?;
+        let (code, message) = if code == 331 {
+            client.command(&format!("PASS {}", settings.password))?
+        } else {
+            (code, message)
+        };
+        check_code(code, message, &[230, 202], "FTP 登录失败")?;
+        let (code, message) = client.command("TYPE I")?;
+        check_code(code, message, &[200], "切换到二进制模式失败")?;
+        Ok(client)
+    }
+
+    /// 读取一条应答（多行应答以 `xyz-` 开头，以 `xyz ` 结束）
+    fn reply(&mut self) -> Result<(u32, String), CloudPasteError> {
+        let mut message = String::new();
+        loop {
+            let mut line = String::new();
+            self.control
+                .read_line(&mut line)
+                .map_err(|e| CloudPasteError::Network(format!("读取 FTP 应答失败: {}", e)))?;
+            if line.is_empty() {
+                return Err(CloudPasteError::Network("FTP 服务器断开了连接".to_string()));
+            }
+            message.push_str(&line);
+            let code = line.get(..3).and_then(|code| code.parse::<u32>().ok());
+            if let (Some(code), Some(b' ')) = (code, line.as_bytes().get(3)) {
+                return Ok((code, message.trim_end().to_string()));
+            }
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<(u32, String), CloudPasteError> {
+        let stream = self.control.get_mut();
+        stream
+            .write_all(format!("{}\r\n", command).as_bytes())
+            .map_err(|e| CloudPasteError::Network(format!("发送 FTP 命令失败: {}", e)))?;
+        self.reply()
+    }
+
+    fn expect(&mut self, codes: &[u32], context: &str) -> Result<String, CloudPasteError> {
+        let (code, message) = self.reply()?;
+        check_code(code, message, codes, context)
+    }
+
+    /// 进入被动模式并建立数据连接（忽略应答中的地址，使用控制连接的地址以便穿过 NAT）
+    fn data_connection(&mut self) -> Result<TcpStream, CloudPasteError> {
+        let (code, message) = self.command("PASV")?;
+        let message = check_code(code, message, &[227], "进入被动模式失败")?;
+        let numbers: Vec<u8> = message
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .filter_map(|part| part.parse().ok())
+            .collect();
+        let [.., high, low] = numbers[..] else {
+            return Err(CloudPasteError::InvalidResponse(format!(
+                "无法解析被动模式应答: {}",
+                message
+            )));
+        };
+        let peer = self
+            .control
+            .get_ref()
+            .peer_addr()
+            .map_err(|e| CloudPasteError::Network(format!("获取服务器地址失败: {}", e)))?;
+        connect_tcp(&SocketAddr::new(peer.ip(), u16::from(high) * 256 + u16::from(low)).to_string())
+    }
+
+    fn make_dir(&mut self, dir: &str) -> Result<(), CloudPasteError> {
+        // 目录已存在时服务器返回 550，随后的 CWD 会检查目录是否可用
+        self.command(&format!("MKD {}", dir))?;
+        let (code, message) = self.command(&format!("CWD {}", dir))?;
+        check_code(code, message, &[250], "进入远程目录失败")?;
+        Ok(())
+    }
+
+    fn store(&mut self, name: &str, source: &Path) -> Result<(), CloudPasteError> {
+        let mut data = self.data_connection()?;
+        let (code, message) = self.command(&format!("STOR {}", name))?;
+        check_code(code, message, &[125, 150], "开始上传失败")?;
+
+        let mut local =
+            std::fs::File::open(source).map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+        std::io::copy(&mut local, &mut data)
+            .map_err(|e| CloudPasteError::Network(format!("上传 {} 失败: {}", name, e)))?;
+        // 关闭数据连接表示文件传输结束
+        drop(data);
+        self.expect(&[226, 250], "上传失败")?;
+        Ok(())
+    }
+
+    fn quit(mut self) {
+        let _ = self.command("QUIT");
+    }
+}
+
+fn check_code(
+    code: u32,
+    message: String,
+    codes: &[u32],
+    context: &str,
+) -> Result<String, CloudPasteError> {
+    if codes.contains(&code) {
+        return Ok(message);
+    }
+    if code == 530 {
+        return Err(CloudPasteError::Unauthorized(format!(
+            "{}: {}",
+            context, message
+        )));
+    }
+    Err(CloudPasteError::Network(format!(
+        "{}: {}",
+        context, message
+    )))
+}
+
+fn ftp_upload(
+    settings: &RemoteExportSettings,
+    files: &[ExportFile],
+) -> Result<Vec<String>, CloudPasteError> {
+    let mut client = FtpClient::connect(settings)?;
+    if !settings.remote_dir.is_empty() {
+        client.make_dir(&settings.remote_dir)?;
+    }
+
+    let mut uploaded = Vec::with_capacity(files.len());
+    for file in files {
+        client.store(&file.name, &file.source)?;
+        uploaded.push(format!(
+            "{}/{}",
+            settings.remote_dir.trim_end_matches('/'),
+            file.name
+        ));
+    }
+    client.quit();
+    Ok(uploaded)
+}
+
+/// 远程文件名：只保留文件名部分，避免写到远程目录之外
+fn remote_name(entry: &HistoryEntry) -> String {
+    let item = &entry.item;
+    let name = item
+        .file_name
+        .as_deref()
+        .and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string());
+    match (item.content_type.as_str(), name) {
+        ("text", _) => format!("clipboard-{}.txt", item.id),
+        (_, Some(name)) => format!("{}-{}", item.id, name),
+        _ => format!("clipboard-{}", item.id),
+    }
+}
+
+/// 准备要导出的本地文件：文本写到临时文件，图片和文件先下载到缓存
+async fn prepare(app: &AppHandle, item_ids: &[i64]) -> Result<Vec<ExportFile>, CloudPasteError> {
+    let temp_dir = std::env::temp_dir().join("CloudPaste").join("export");
+    std::fs::create_dir_all(&temp_dir).map_err(|e| CloudPasteError::io("创建临时目录失败", e))?;
+
+    let mut files = Vec::with_capacity(item_ids.len());
+    for &id in item_ids {
+        let entry = history::get_entry(id)?;
+        let name = remote_name(&entry);
+        let temporary = entry.item.content_type == "text";
+        let source = if temporary {
+            let path = temp_dir.join(&name);
+            std::fs::write(&path, &entry.item.content)
+                .map_err(|e| CloudPasteError::io("写入临时文件失败", e))?;
+            path
+        } else {
+            let url = http::absolute_url(&entry.item.content)?;
+            image_cache::ensure_cached(app, &url).await?
+        };
+        files.push(ExportFile {
+            name,
+            source,
+            temporary,
+        });
+    }
+    Ok(files)
+}
+
+/// Tauri 命令：获取导出目标设置
+#[tauri::command]
+pub fn get_remote_export_settings() -> RemoteExportSettings {
+    settings()
+}
+
+/// Tauri 命令：更新导出目标设置（更换主机时清除已记录的主机密钥）
+#[tauri::command]
+pub fn set_remote_export_settings(
+    app: AppHandle,
+    mut settings: RemoteExportSettings,
+) -> Result<(), CloudPasteError> {
+    let current = self::settings();
+    if current.host != settings.host || current.port != settings.port {
+        settings.host_key_sha256 = None;
+    }
+    save(&app, settings)
+}
+
+/// Tauri 命令：测试连接
+///
+/// SFTP 首次连接时记录主机密钥指纹并返回，供用户与服务器上的指纹比对
+#[tauri::command]
+pub async fn test_remote_export(app: AppHandle) -> Result<Option<String>, CloudPasteError> {
+    let mut settings = settings();
+    let tested = settings.clone();
+    let fingerprint = tauri::async_runtime::spawn_blocking(move || match tested.protocol {
+        RemoteProtocol::Sftp => sftp_session(&tested).map(|(_, fingerprint)| Some(fingerprint)),
+        RemoteProtocol::Ftp => FtpClient::connect(&tested).map(|client| {
+            client.quit();
+            None
+        }),
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("测试连接失败: {}", e)))??;
+
+    if settings.host_key_sha256.is_none() && fingerprint.is_some() {
+        log::info!(
+            "🔑 已记录 {} 的主机密钥: SHA256:{}",
+            settings.host,
+            fingerprint.as_deref().unwrap_or_default()
+        );
+        settings.host_key_sha256 = fingerprint.clone();
+        save(&app, settings)?;
+    }
+    Ok(fingerprint)
+}
+
+/// Tauri 命令：将选中的记录导出到 SFTP / FTP 服务器，返回远程文件路径
+#[tauri::command]
+pub async fn export_to_remote(
+    app: AppHandle,
+    item_ids: Vec<i64>,
+) -> Result<Vec<String>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let settings = settings();
+    if settings.host.is_empty() {
+        return Err(CloudPasteError::InvalidInput(
+            "尚未配置导出服务器".to_string(),
+        ));
+    }
+
+    let files = prepare(&app, &item_ids).await?;
+    let uploaded = tauri::async_runtime::spawn_blocking(move || {
+        let uploaded = match settings.protocol {
+            RemoteProtocol::Sftp => sftp_upload(&settings, &files),
+            RemoteProtocol::Ftp => ftp_upload(&settings, &files),
+        };
+        for file in files.iter().filter(|file| file.temporary) {
+            let _ = std::fs::remove_file(&file.source);
+        }
+        uploaded
+    })
+    .await
+    .map_err(|e| CloudPasteError::Internal(format!("导出失败: {}", e)))??;
+
+    log::info!("📤 已导出 {} 个文件到远程服务器", uploaded.len());
+    Ok(uploaded)
+}
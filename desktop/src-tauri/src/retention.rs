@@ -0,0 +1,204 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use crate::{http, image_cache, store};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "retention.json";
+
+/// 定期清理的间隔
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 本地历史保留策略（为空表示不限制），固定和收藏的记录不受影响
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    /// 最多保留的记录条数
+    pub max_items: Option<usize>,
+    /// 最多保留的天数
+    pub max_days: Option<u32>,
+    /// 记录及缓存文件最多占用的空间（MB）
+    pub max_mb: Option<u64>,
+    /// 按内容类型分别限制的条数，与 `max_items` 同时生效
+    pub type_limits: TypeLimits,
+}
+
+/// 按内容类型分别限制保留的记录条数（为空表示不限制），
+/// 例如保留 5000 条文本但只保留 200 张图片
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TypeLimits {
+    pub text: Option<usize>,
+    pub image: Option<usize>,
+    /// 文件及其他非文本、非图片的记录
+    pub file: Option<usize>,
+}
+
+impl TypeLimits {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none() && self.image.is_none() && self.file.is_none()
+    }
+
+    /// 内容类型对应的条数上限
+    pub fn limit_for(&self, content_type: &str) -> Option<usize> {
+        match content_type {
+            "text" => self.text,
+            "image" => self.image,
+            _ => self.file,
+        }
+    }
+}
+
+/// 一次清理的结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupResult {
+    /// 删除的记录数
+    pub removed_items: usize,
+    /// 释放的缓存空间（字节）
+    pub freed_bytes: u64,
+}
+
+static SETTINGS: Lazy<Mutex<RetentionSettings>> =
+    Lazy::new(|| Mutex::new(RetentionSettings::default()));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<RetentionSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> RetentionSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 删除记录对应的缓存文件，返回释放的字节数
+fn evict_cached_files(app: &AppHandle, entries: &[HistoryEntry]) -> u64 {
+    let mut freed_bytes = 0;
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.item.content_type != "text")
+    {
+        let Ok(url) = http::absolute_url(&entry.item.content) else {
+            continue;
+        };
+        match image_cache::evict(app, &url) {
+            Ok(freed) => freed_bytes += freed,
+            Err(e) => log::warn!("⚠️ {}", e),
+        }
+    }
+    freed_bytes
+}
+
+/// 按保留策略清理本地历史，并删除对应的缓存文件
+fn cleanup(app: &AppHandle) -> Result<CleanupResult, String> {
+    let settings = settings();
+    if settings.max_items.is_none()
+        && settings.max_days.is_none()
+        && settings.max_mb.is_none()
+        && settings.type_limits.is_empty()
+    {
+        return Ok(CleanupResult::default());
+    }
+
+    let removed = history::prune(
+        settings.max_items,
+        &settings.type_limits,
+        settings.max_days,
+        settings.max_mb.map(|mb| mb * 1024 * 1024),
+    )?;
+
+    let result = CleanupResult {
+        removed_items: removed.len(),
+        freed_bytes: evict_cached_files(app, &removed),
+    };
+
+    if result.removed_items > 0 {
+        log::info!(
+            "🧹 已清理过期历史: {} 条，释放缓存 {} 字节",
+            result.removed_items,
+            result.freed_bytes
+        );
+    }
+    Ok(result)
+}
+
+/// 后台任务：定期按保留策略清理
+pub async fn run_periodic(app: AppHandle) {
+    loop {
+        if let Err(e) = cleanup(&app) {
+            log::warn!("⚠️ 清理过期历史失败: {}", e);
+        }
+        if let Err(e) = image_cache::purge_expired(&app) {
+            log::warn!("⚠️ 清理过期缓存失败: {}", e);
+        }
+        tokio::time::sleep(CLEANUP_INTERVAL).await;
+    }
+}
+
+/// Tauri 命令：立即按保留策略清理
+#[tauri::command]
+pub fn run_retention_cleanup(app: AppHandle) -> Result<CleanupResult, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    Ok(cleanup(&app)?)
+}
+
+/// Tauri 命令：从本地彻底删除记录（用户删除记录后调用）
+///
+/// 删除数据库中的记录（内容被覆盖）及缓存文件（删除前覆盖文件内容），固定和收藏的记录同样删除
+#[tauri::command]
+pub fn delete_history_items(
+    app: AppHandle,
+    ids: Vec<i64>,
+) -> Result<CleanupResult, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let removed = history::delete_entries(&ids)?;
+    let result = CleanupResult {
+        removed_items: removed.len(),
+        freed_bytes: evict_cached_files(&app, &removed),
+    };
+    log::info!(
+        "🗑️ 已从本地删除 {} 条记录，覆盖并删除缓存 {} 字节",
+        result.removed_items,
+        result.freed_bytes
+    );
+    Ok(result)
+}
+
+/// 删除其他设备已在服务器上删除的记录及其缓存文件，返回删除的数量
+pub fn apply_remote_deletions(app: &AppHandle, ids: &[i64]) -> Result<usize, String> {
+    let removed = history::delete_entries(ids)?;
+    if !removed.is_empty() {
+        let freed_bytes = evict_cached_files(app, &removed);
+        log::info!(
+            "🗑️ 同步服务器删除: {} 条记录，释放缓存 {} 字节",
+            removed.len(),
+            freed_bytes
+        );
+    }
+    Ok(removed.len())
+}
+
+/// Tauri 命令：获取保留策略
+#[tauri::command]
+pub fn get_retention_settings() -> RetentionSettings {
+    settings()
+}
+
+/// Tauri 命令：更新保留策略
+#[tauri::command]
+pub fn set_retention_settings(
+    app: AppHandle,
+    settings: RetentionSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
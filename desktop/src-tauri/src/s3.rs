@@ -0,0 +1,242 @@
+use crate::error::CloudPasteError;
+use crate::http;
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// ListObjectsV2 响应中的对象键
+static KEY: Lazy<Regex> = Lazy::new(|| Regex::new(r"<Key>([^<]+)</Key>").expect("对象键规则无效"));
+
+/// S3 兼容的对象存储（AWS S3、Cloudflare R2、MinIO 等）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    /// 服务地址，如 `https://s3.us-east-1.amazonaws.com` 或 `https://<账户>.r2.cloudflarestorage.com`
+    pub endpoint: String,
+    /// 区域，R2 使用 `auto`
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// 对象键前缀，如 `cloudpaste/`
+    pub prefix: String,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC 接受任意长度的密钥");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// 按 SigV4 规则编码对象键（保留 `/`）
+fn encode_key(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// 按 SigV4 规则编码查询参数（`/` 也需要编码）
+fn encode_query(value: &str) -> String {
+    encode_key(value).replace('/', "%2F")
+}
+
+/// 发送签名请求（路径风格地址，使用 AWS Signature V4 签名），`key` 为空时请求存储桶本身
+async fn send(
+    config: &S3Config,
+    method: Method,
+    key: &str,
+    query: &[(&str, String)],
+    body: Vec<u8>,
+    content_type: Option<&str>,
+    context: &str,
+) -> Result<reqwest::Response, CloudPasteError> {
+    let endpoint = url::Url::parse(&config.endpoint)
+        .map_err(|e| CloudPasteError::InvalidInput(format!("对象存储地址无效: {}", e)))?;
+    let host = match (endpoint.host_str(), endpoint.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => {
+            return Err(CloudPasteError::InvalidInput(
+                "对象存储地址缺少主机名".to_string(),
+            ))
+        }
+    };
+
+    let path = if key.is_empty() {
+        format!("/{}", config.bucket)
+    } else {
+        format!("/{}/{}", config.bucket, encode_key(key))
+    };
+    let mut query: Vec<(String, String)> = query
+        .iter()
+        .map(|(name, value)| (encode_query(name), encode_query(value)))
+        .collect();
+    query.sort();
+    let canonical_query = query
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex(&Sha256::digest(&body));
+
+    let mut headers = Vec::new();
+    if let Some(content_type) = content_type {
+        headers.push(("content-type", content_type.to_string()));
+    }
+    headers.push(("host", host));
+    headers.push(("x-amz-content-sha256", payload_hash.clone()));
+    headers.push(("x-amz-date", amz_date.clone()));
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method.as_str(),
+        path,
+        canonical_query,
+        canonical_headers,
+        signed_headers,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let secret = format!("AWS4{}", config.secret_access_key);
+    let date_key = hmac_sha256(secret.as_bytes(), &date);
+    let region_key = hmac_sha256(&date_key, &config.region);
+    let service_key = hmac_sha256(&region_key, "s3");
+    let signing_key = hmac_sha256(&service_key, "aws4_request");
+    let signature = hex(&hmac_sha256(&signing_key, &string_to_sign));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        config.access_key_id, scope, signed_headers, signature
+    );
+
+    let mut url = format!("{}{}", config.endpoint.trim_end_matches('/'), path);
+    if !canonical_query.is_empty() {
+        url = format!("{}?{}", url, canonical_query);
+    }
+    let mut request = http::client()
+        .request(method, &url)
+        .header("Authorization", authorization)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("x-amz-date", amz_date)
+        .body(body);
+    if let Some(content_type) = content_type {
+        request = request.header("Content-Type", content_type);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CloudPasteError::request(context, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(CloudPasteError::Server {
+            status: status.as_u16(),
+            message: format!("{}: {}", context, response.text().await.unwrap_or_default()),
+        });
+    }
+    Ok(response)
+}
+
+/// 上传对象，返回对象键
+pub async fn put_object(
+    config: &S3Config,
+    name: &str,
+    body: Vec<u8>,
+    content_type: &str,
+) -> Result<String, CloudPasteError> {
+    let key = format!("{}{}", config.prefix, name);
+    send(
+        config,
+        Method::PUT,
+        &key,
+        &[],
+        body,
+        Some(content_type),
+        "上传到对象存储失败",
+    )
+    .await?;
+    Ok(key)
+}
+
+/// 下载对象（`key` 为 `put_object` 返回的对象键）
+pub async fn get_object(config: &S3Config, key: &str) -> Result<Vec<u8>, CloudPasteError> {
+    let response = send(
+        config,
+        Method::GET,
+        key,
+        &[],
+        Vec::new(),
+        None,
+        "从对象存储下载失败",
+    )
+    .await?;
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| CloudPasteError::request("读取对象存储内容失败", e))?;
+    Ok(body.to_vec())
+}
+
+/// 列出目录下排在 `start_after` 之后的对象名（不含前缀和目录，按字典序）
+pub async fn list_objects(
+    config: &S3Config,
+    dir: &str,
+    start_after: Option<&str>,
+) -> Result<Vec<String>, CloudPasteError> {
+    let prefix = format!("{}{}/", config.prefix, dir);
+    let mut query = vec![("list-type", "2".to_string()), ("prefix", prefix.clone())];
+    if let Some(start_after) = start_after {
+        query.push(("start-after", format!("{}{}", prefix, start_after)));
+    }
+    let body = send(
+        config,
+        Method::GET,
+        "",
+        &query,
+        Vec::new(),
+        None,
+        "列出对象存储目录失败",
+    )
+    .await?
+    .text()
+    .await
+    .map_err(|e| CloudPasteError::request("读取对象存储目录失败", e))?;
+
+    let mut names: Vec<String> = KEY
+        .captures_iter(&body)
+        .filter_map(|captures| captures[1].strip_prefix(&prefix).map(str::to_string))
+        .collect();
+    names.sort();
+    Ok(names)
+}
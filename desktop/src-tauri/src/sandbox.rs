@@ -0,0 +1,178 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Webview};
+use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, FilePath};
+
+/// 允许读写本地文件的窗口（与 capabilities/default.json 中授予文件权限的窗口一致）
+///
+/// 通知窗口等只展示内容的窗口不能调用文件命令，即使其页面被注入脚本也无法读写文件
+const FILE_ACCESS_WINDOWS: &[&str] = &["main"];
+
+/// 用户通过系统对话框选择过的文件（规范化后的路径）
+static GRANTED: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 对话框的文件类型过滤器
+#[derive(Debug, Clone, Deserialize)]
+pub struct DialogFilter {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+fn denied(path: &Path) -> CloudPasteError {
+    CloudPasteError::PermissionDenied(format!("不允许访问该路径: {}", path.display()))
+}
+
+/// 规范化路径：解析符号链接和 `..`，文件尚不存在时规范化其所在目录
+fn normalize(path: &Path) -> Result<PathBuf, CloudPasteError> {
+    if !path.is_absolute() {
+        return Err(denied(path));
+    }
+    if let Ok(canonical) = path.canonicalize() {
+        return Ok(canonical);
+    }
+
+    let (Some(parent), Some(Component::Normal(name))) =
+        (path.parent(), path.components().next_back())
+    else {
+        return Err(denied(path));
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| CloudPasteError::io("无效的路径", e))?;
+    Ok(parent.join(name))
+}
+
+/// 前端可以直接访问的目录：缓存目录和下载目录
+fn allowed_roots(app: &AppHandle) -> Vec<PathBuf> {
+    [store::cache_dir(app).ok(), app.path().download_dir().ok()]
+        .into_iter()
+        .flatten()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect()
+}
+
+/// 检查调用命令的窗口是否允许读写本地文件
+pub fn check_window(webview: &Webview) -> Result<(), CloudPasteError> {
+    if FILE_ACCESS_WINDOWS.contains(&webview.label()) {
+        return Ok(());
+    }
+    log::warn!("🚫 窗口 {} 无权调用文件命令", webview.label());
+    Err(CloudPasteError::PermissionDenied(format!(
+        "窗口 {} 无权访问本地文件",
+        webview.label()
+    )))
+}
+
+/// 记录用户通过对话框选择的文件，之后允许前端读写
+pub fn grant(path: &Path) {
+    match normalize(path) {
+        Ok(path) => {
+            if let Ok(mut granted) = GRANTED.lock() {
+                granted.insert(path);
+            }
+        }
+        Err(e) => log::warn!("⚠️ {}", e),
+    }
+}
+
+/// 检查前端传入的路径是否允许访问，返回规范化后的路径
+///
+/// 调用窗口须允许读写文件，且只允许缓存目录、下载目录下的文件，以及用户通过对话框选择过的文件
+pub fn check(webview: &Webview, path: &str) -> Result<PathBuf, CloudPasteError> {
+    check_window(webview)?;
+    let app = webview.app_handle();
+    let normalized = normalize(Path::new(path))?;
+
+    let granted = GRANTED
+        .lock()
+        .map(|granted| granted.contains(&normalized))
+        .unwrap_or(false);
+    if granted
+        || allowed_roots(app)
+            .iter()
+            .any(|root| normalized.starts_with(root))
+    {
+        return Ok(normalized);
+    }
+
+    log::warn!("🚫 拒绝访问路径: {}", path);
+    Err(denied(Path::new(path)))
+}
+
+fn with_filters(
+    mut dialog: FileDialogBuilder<tauri::Wry>,
+    filters: Option<Vec<DialogFilter>>,
+) -> FileDialogBuilder<tauri::Wry> {
+    for filter in filters.unwrap_or_default() {
+        let extensions: Vec<&str> = filter.extensions.iter().map(String::as_str).collect();
+        dialog = dialog.add_filter(&filter.name, &extensions);
+    }
+    dialog
+}
+
+/// 打开对话框并授权选择的文件或目录
+async fn pick(
+    dialog: FileDialogBuilder<tauri::Wry>,
+    show: fn(FileDialogBuilder<tauri::Wry>) -> Option<FilePath>,
+) -> Result<Option<String>, CloudPasteError> {
+    let selected = tauri::async_runtime::spawn_blocking(move || show(dialog))
+        .await
+        .map_err(|e| CloudPasteError::Internal(format!("打开文件对话框失败: {}", e)))?;
+
+    let Some(path) = selected.and_then(|selected| selected.into_path().ok()) else {
+        return Ok(None);
+    };
+    grant(&path);
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Tauri 命令：打开保存对话框，返回用户选择的路径（之后允许写入该文件），取消时返回空
+#[tauri::command]
+pub async fn pick_save_path(
+    app: AppHandle,
+    webview: Webview,
+    default_name: Option<String>,
+    filters: Option<Vec<DialogFilter>>,
+) -> Result<Option<String>, CloudPasteError> {
+    check_window(&webview)?;
+    let mut dialog = app.dialog().file();
+    if let Some(name) = default_name {
+        dialog = dialog.set_file_name(name);
+    }
+    pick(
+        with_filters(dialog, filters),
+        FileDialogBuilder::blocking_save_file,
+    )
+    .await
+}
+
+/// Tauri 命令：打开选择文件对话框，返回用户选择的路径（之后允许读取该文件），取消时返回空
+#[tauri::command]
+pub async fn pick_open_path(
+    app: AppHandle,
+    webview: Webview,
+    filters: Option<Vec<DialogFilter>>,
+) -> Result<Option<String>, CloudPasteError> {
+    check_window(&webview)?;
+    let dialog = app.dialog().file();
+    pick(
+        with_filters(dialog, filters),
+        FileDialogBuilder::blocking_pick_file,
+    )
+    .await
+}
+
+/// Tauri 命令：打开选择目录对话框，返回用户选择的目录（之后允许用作监听目录或本地存储目录），取消时返回空
+#[tauri::command]
+pub async fn pick_folder_path(
+    app: AppHandle,
+    webview: Webview,
+) -> Result<Option<String>, CloudPasteError> {
+    check_window(&webview)?;
+    pick(app.dialog().file(), FileDialogBuilder::blocking_pick_folder).await
+}
@@ -0,0 +1,188 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use image::RgbaImage;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use xcap::{Monitor, Window};
+
+const SETTINGS_FILE: &str = "screenshot.json";
+
+/// 截图范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// 主显示器全屏
+    FullScreen,
+    /// 当前前台窗口
+    ActiveWindow,
+    /// 指定区域（虚拟桌面坐标，物理像素）
+    Region {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+}
+
+/// 截图设置（快捷键由 `hotkeys` 统一管理）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreenshotSettings {
+    /// 快捷键触发时的截图范围
+    pub mode: CaptureMode,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            mode: CaptureMode::FullScreen,
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<ScreenshotSettings>> =
+    Lazy::new(|| Mutex::new(ScreenshotSettings::default()));
+
+/// 截图结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenshotResult {
+    /// 截图文件的本地路径
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<ScreenshotSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> ScreenshotSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 按设置的截图范围截图（快捷键触发）
+pub async fn capture_with_settings(app: AppHandle) -> Result<ScreenshotResult, CloudPasteError> {
+    capture_screenshot(app, settings().mode).await
+}
+
+/// 执行截图（阻塞调用）
+fn capture(mode: &CaptureMode) -> Result<RgbaImage, String> {
+    match mode {
+        CaptureMode::FullScreen => {
+            let monitors = Monitor::all().map_err(|e| format!("获取显示器失败: {}", e))?;
+            let monitor = monitors
+                .iter()
+                .find(|m| m.is_primary().unwrap_or(false))
+                .or_else(|| monitors.first())
+                .ok_or_else(|| "未找到显示器".to_string())?;
+            monitor
+                .capture_image()
+                .map_err(|e| format!("截取屏幕失败: {}", e))
+        }
+        CaptureMode::ActiveWindow => {
+            let windows = Window::all().map_err(|e| format!("获取窗口列表失败: {}", e))?;
+            let window = windows
+                .iter()
+                .find(|w| w.is_focused().unwrap_or(false) && !w.is_minimized().unwrap_or(true))
+                .ok_or_else(|| "未找到前台窗口".to_string())?;
+            window
+                .capture_image()
+                .map_err(|e| format!("截取窗口失败: {}", e))
+        }
+        CaptureMode::Region {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            let monitor =
+                Monitor::from_point(*x, *y).map_err(|e| format!("获取显示器失败: {}", e))?;
+            let origin_x = monitor
+                .x()
+                .map_err(|e| format!("获取显示器位置失败: {}", e))?;
+            let origin_y = monitor
+                .y()
+                .map_err(|e| format!("获取显示器位置失败: {}", e))?;
+            monitor
+                .capture_region(
+                    (*x - origin_x) as u32,
+                    (*y - origin_y) as u32,
+                    *width,
+                    *height,
+                )
+                .map_err(|e| format!("截取区域失败: {}", e))
+        }
+    }
+}
+
+/// Tauri 命令：截图
+///
+/// 截图保存为 PNG 后写入系统剪贴板，由剪贴板监听流程识别并按正常流程上传
+#[tauri::command]
+pub async fn capture_screenshot(
+    app: AppHandle,
+    mode: CaptureMode,
+) -> Result<ScreenshotResult, CloudPasteError> {
+    log::info!("📸 开始截图: {:?}", mode);
+
+    let dir = store::cache_dir(&app)?.join("screenshots");
+    fs::create_dir_all(&dir).map_err(|e| format!("创建截图目录失败: {}", e))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let path = dir.join(format!("screenshot-{}.png", timestamp));
+
+    let save_path = path.clone();
+    let (width, height) = tauri::async_runtime::spawn_blocking(move || {
+        let image = capture(&mode)?;
+        image
+            .save(&save_path)
+            .map_err(|e| format!("保存截图失败: {}", e))?;
+        Ok::<_, String>((image.width(), image.height()))
+    })
+    .await
+    .map_err(|e| format!("截图任务失败: {}", e))??;
+
+    let result = ScreenshotResult {
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+    };
+
+    tauri_plugin_clipboard_x::write_image(result.path.clone()).await?;
+
+    log::info!("✅ 截图完成: {}x{} -> {}", width, height, result.path);
+    let _ = app.emit("screenshot-captured", &result);
+
+    Ok(result)
+}
+
+/// Tauri 命令：获取截图设置
+#[tauri::command]
+pub fn get_screenshot_settings() -> ScreenshotSettings {
+    settings()
+}
+
+/// Tauri 命令：更新截图设置
+#[tauri::command]
+pub fn set_screenshot_settings(
+    app: AppHandle,
+    settings: ScreenshotSettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
@@ -0,0 +1,428 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use crate::store;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{BooleanQuery, Occur, Query, QueryParser, TermQuery};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, INDEXED, STORED,
+    STRING,
+};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::tokenizer::{TextAnalyzer, Token, TokenStream, Tokenizer};
+use tantivy::{doc, Index, IndexReader, IndexWriter, TantivyDocument, Term};
+use tauri::AppHandle;
+
+const INDEX_DIR: &str = "search_index";
+
+/// 分词器名称
+const TOKENIZER: &str = "cloudpaste";
+
+/// 索引写入缓冲区大小
+const WRITER_MEMORY: usize = 32 * 1024 * 1024;
+
+/// 默认返回的结果数
+const DEFAULT_LIMIT: usize = 50;
+
+/// 片段最大字符数
+const SNIPPET_CHARS: usize = 160;
+
+struct Fields {
+    id: Field,
+    content: Field,
+    ocr_text: Field,
+    file_name: Field,
+    content_type: Field,
+    device_id: Field,
+    favorite: Field,
+}
+
+struct SearchIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: IndexWriter,
+    fields: Fields,
+}
+
+static INDEX: Lazy<Mutex<Option<SearchIndex>>> = Lazy::new(|| Mutex::new(None));
+
+/// 搜索筛选条件
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchFilters {
+    /// 只搜索这些类型（text/image/file），为空时不限
+    pub content_types: Vec<String>,
+    pub device_id: Option<String>,
+    pub favorite: Option<bool>,
+    pub limit: Option<usize>,
+}
+
+/// 搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub entry: HistoryEntry,
+    pub score: f32,
+    /// 高亮片段（HTML，匹配词以 `<b>` 包裹）
+    pub snippet: String,
+}
+
+/// 分词器：连续的字母数字作为一个词（转小写），中日韩文字按单字切分
+///
+/// 查询时连续的单字会组成短语查询，因此无需词典也能匹配中文
+#[derive(Clone, Default)]
+struct CjkTokenizer;
+
+struct CjkTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}')
+}
+
+impl Tokenizer for CjkTokenizer {
+    type TokenStream<'a> = CjkTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CjkTokenStream {
+        let mut tokens = Vec::new();
+        let mut word_start: Option<usize> = None;
+
+        let push = |tokens: &mut Vec<Token>, from: usize, to: usize| {
+            tokens.push(Token {
+                offset_from: from,
+                offset_to: to,
+                position: tokens.len(),
+                text: text[from..to].to_lowercase(),
+                position_length: 1,
+            });
+        };
+
+        for (offset, c) in text.char_indices() {
+            if c.is_alphanumeric() && !is_cjk(c) {
+                word_start.get_or_insert(offset);
+                continue;
+            }
+
+            if let Some(start) = word_start.take() {
+                push(&mut tokens, start, offset);
+            }
+            if is_cjk(c) {
+                push(&mut tokens, offset, offset + c.len_utf8());
+            }
+        }
+        if let Some(start) = word_start {
+            push(&mut tokens, start, text.len());
+        }
+
+        CjkTokenStream { tokens, index: 0 }
+    }
+}
+
+impl TokenStream for CjkTokenStream {
+    fn advance(&mut self) -> bool {
+        self.index += 1;
+        self.index <= self.tokens.len()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+fn build_schema() -> (Schema, Fields) {
+    let text = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        id: builder.add_i64_field("id", INDEXED | STORED),
+        content: builder.add_text_field("content", text.clone()),
+        ocr_text: builder.add_text_field("ocr_text", text.clone()),
+        file_name: builder.add_text_field("file_name", text),
+        content_type: builder.add_text_field("content_type", STRING),
+        device_id: builder.add_text_field("device_id", STRING),
+        favorite: builder.add_u64_field("favorite", INDEXED),
+    };
+    (builder.build(), fields)
+}
+
+fn open_index(dir: &std::path::Path, schema: Schema) -> tantivy::Result<Index> {
+    let index = Index::open_or_create(MmapDirectory::open(dir)?, schema)?;
+    index
+        .tokenizers()
+        .register(TOKENIZER, TextAnalyzer::from(CjkTokenizer));
+    Ok(index)
+}
+
+/// 打开（必要时创建）搜索索引，索引为空或结构不兼容时从本地历史重建
+pub fn open(app: &AppHandle) -> Result<(), String> {
+    let dir = store::data_dir(app)?.join(INDEX_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建搜索索引目录失败: {}", e))?;
+
+    let (schema, fields) = build_schema();
+    let index = match open_index(&dir, schema.clone()) {
+        Ok(index) => index,
+        Err(e) => {
+            log::warn!("⚠️ 搜索索引不可用，重新创建: {}", e);
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("删除搜索索引失败: {}", e))?;
+            std::fs::create_dir_all(&dir).map_err(|e| format!("创建搜索索引目录失败: {}", e))?;
+            open_index(&dir, schema).map_err(|e| format!("创建搜索索引失败: {}", e))?
+        }
+    };
+
+    let reader = index
+        .reader()
+        .map_err(|e| format!("打开搜索索引失败: {}", e))?;
+    let writer = index
+        .writer(WRITER_MEMORY)
+        .map_err(|e| format!("打开搜索索引失败: {}", e))?;
+    let is_empty = reader.searcher().num_docs() == 0;
+
+    *INDEX
+        .lock()
+        .map_err(|e| format!("无法锁定搜索索引: {}", e))? = Some(SearchIndex {
+        index,
+        reader,
+        writer,
+        fields,
+    });
+
+    if is_empty {
+        let entries = history::all_entries()?;
+        if !entries.is_empty() {
+            log::info!("🔎 从本地历史重建搜索索引: {} 条", entries.len());
+            index_entries(&entries)?;
+        }
+    }
+
+    log::info!("✅ 搜索索引已打开: {:?}", dir);
+    Ok(())
+}
+
+/// 写入或更新索引中的记录
+pub fn index_entries(entries: &[HistoryEntry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut guard = INDEX
+        .lock()
+        .map_err(|e| format!("无法锁定搜索索引: {}", e))?;
+    // 索引尚未打开时跳过，打开时会从本地历史重建
+    let Some(search) = guard.as_mut() else {
+        return Ok(());
+    };
+    let fields = &search.fields;
+
+    for entry in entries {
+        let item = &entry.item;
+        search
+            .writer
+            .delete_term(Term::from_field_i64(fields.id, item.id));
+
+        let mut document = doc!(
+            fields.id => item.id,
+            fields.content_type => item.content_type.clone(),
+            fields.favorite => item.favorite as u64,
+        );
+        // 图片和文件的 content 是下载地址，不参与全文检索
+        if item.content_type == "text" {
+            document.add_text(fields.content, &item.content);
+        }
+        if let Some(ocr_text) = &entry.ocr_text {
+            document.add_text(fields.ocr_text, ocr_text);
+        }
+        if let Some(file_name) = &item.file_name {
+            document.add_text(fields.file_name, file_name);
+        }
+        if let Some(device_id) = &item.device_id {
+            document.add_text(fields.device_id, device_id);
+        }
+
+        search
+            .writer
+            .add_document(document)
+            .map_err(|e| format!("写入搜索索引失败: {}", e))?;
+    }
+
+    search
+        .writer
+        .commit()
+        .map_err(|e| format!("提交搜索索引失败: {}", e))?;
+    search
+        .reader
+        .reload()
+        .map_err(|e| format!("刷新搜索索引失败: {}", e))
+}
+
+/// 从索引中删除记录
+pub fn remove_entries(ids: &[i64]) -> Result<(), String> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut guard = INDEX
+        .lock()
+        .map_err(|e| format!("无法锁定搜索索引: {}", e))?;
+    let Some(search) = guard.as_mut() else {
+        return Ok(());
+    };
+
+    for id in ids {
+        search
+            .writer
+            .delete_term(Term::from_field_i64(search.fields.id, *id));
+    }
+
+    search
+        .writer
+        .commit()
+        .map_err(|e| format!("提交搜索索引失败: {}", e))?;
+    search
+        .reader
+        .reload()
+        .map_err(|e| format!("刷新搜索索引失败: {}", e))
+}
+
+/// 生成高亮片段，依次尝试正文、识别文字和文件名
+fn make_snippet(
+    searcher: &tantivy::Searcher,
+    query: &dyn Query,
+    fields: &Fields,
+    entry: &HistoryEntry,
+) -> String {
+    let item = &entry.item;
+    let candidates = [
+        (
+            fields.content,
+            (item.content_type == "text").then_some(item.content.as_str()),
+        ),
+        (fields.ocr_text, entry.ocr_text.as_deref()),
+        (fields.file_name, item.file_name.as_deref()),
+    ];
+
+    for (field, text) in candidates {
+        let Some(text) = text else { continue };
+        let Ok(mut generator) = SnippetGenerator::create(searcher, query, field) else {
+            continue;
+        };
+        generator.set_max_num_chars(SNIPPET_CHARS);
+        let snippet = generator.snippet(text);
+        if !snippet.is_empty() {
+            return snippet.to_html();
+        }
+    }
+    String::new()
+}
+
+/// Tauri 命令：全文搜索本地历史（正文、图片识别文字和文件名）
+///
+/// 结果按相关度排序，附带高亮片段
+#[tauri::command]
+pub fn search_history(
+    query: String,
+    filters: Option<SearchFilters>,
+) -> Result<Vec<SearchHit>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let filters = filters.unwrap_or_default();
+    let guard = INDEX
+        .lock()
+        .map_err(|e| format!("无法锁定搜索索引: {}", e))?;
+    let search = guard
+        .as_ref()
+        .ok_or_else(|| "搜索索引尚未就绪".to_string())?;
+    let fields = &search.fields;
+
+    let mut parser = QueryParser::for_index(
+        &search.index,
+        vec![fields.content, fields.ocr_text, fields.file_name],
+    );
+    parser.set_conjunction_by_default();
+    parser.set_field_boost(fields.file_name, 2.0);
+    let (text_query, _) = parser.parse_query_lenient(&query);
+
+    let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, text_query.box_clone())];
+    if !filters.content_types.is_empty() {
+        let types = filters
+            .content_types
+            .iter()
+            .map(|t| {
+                let term = Term::from_field_text(fields.content_type, t);
+                (
+                    Occur::Should,
+                    Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>,
+                )
+            })
+            .collect();
+        clauses.push((Occur::Must, Box::new(BooleanQuery::new(types))));
+    }
+    if let Some(device_id) = &filters.device_id {
+        let term = Term::from_field_text(fields.device_id, device_id);
+        clauses.push((
+            Occur::Must,
+            Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+        ));
+    }
+    if let Some(favorite) = filters.favorite {
+        let term = Term::from_field_u64(fields.favorite, favorite as u64);
+        clauses.push((
+            Occur::Must,
+            Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+        ));
+    }
+    let combined = BooleanQuery::new(clauses);
+
+    let searcher = search.reader.searcher();
+    let top_docs = searcher
+        .search(
+            &combined,
+            &TopDocs::with_limit(filters.limit.unwrap_or(DEFAULT_LIMIT)),
+        )
+        .map_err(|e| format!("搜索失败: {}", e))?;
+
+    let mut scored = Vec::with_capacity(top_docs.len());
+    for (score, address) in top_docs {
+        let document: TantivyDocument = searcher
+            .doc(address)
+            .map_err(|e| format!("读取搜索结果失败: {}", e))?;
+        if let Some(id) = document.get_first(fields.id).and_then(|v| v.as_i64()) {
+            scored.push((id, score));
+        }
+    }
+
+    let ids: Vec<i64> = scored.iter().map(|(id, _)| *id).collect();
+    let entries = history::get_entries(&ids)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let score = scored
+                .iter()
+                .find(|(id, _)| *id == entry.item.id)
+                .map(|(_, score)| *score)
+                .unwrap_or_default();
+            let snippet = make_snippet(&searcher, text_query.as_ref(), fields, &entry);
+            SearchHit {
+                entry,
+                score,
+                snippet,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,84 @@
+use crate::api::NewClipboardItem;
+use crate::error::CloudPasteError;
+use crate::{storage, upload};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// 可选的子命令：`cloudpaste send <文件>...`
+const SEND_COMMAND: &str = "send";
+
+/// 从命令行参数中取出要发送的文件（跳过程序路径、子命令和 `--` 开头的选项）
+///
+/// 相对路径按启动命令时的工作目录解析，不存在的文件忽略
+fn files_from_args(args: &[String], cwd: &Path) -> Vec<PathBuf> {
+    args.iter()
+        .skip(1)
+        .enumerate()
+        .filter(|(index, arg)| !(*index == 0 && arg.as_str() == SEND_COMMAND))
+        .map(|(_, arg)| arg)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// 上传文件并创建剪贴板项，返回分享链接
+pub async fn send_file(path: &Path) -> Result<String, CloudPasteError> {
+    let uploaded = upload::upload_file(path).await?;
+    let item = NewClipboardItem::from_upload(&uploaded)?;
+    storage::create_item(&item).await?;
+    storage::file_link(&uploaded.file_url)
+}
+
+fn notify(app: &AppHandle, title: &str, body: String) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("⚠️ 发送通知失败: {}", e);
+    }
+}
+
+/// 处理命令行传入的文件（`cloudpaste <文件>` 或将文件拖到程序图标上）
+///
+/// 逐个上传，完成后把分享链接写入剪贴板并发送通知
+pub fn handle_args(app: &AppHandle, args: Vec<String>, cwd: &Path) {
+    let files = files_from_args(&args, cwd);
+    if files.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut links = Vec::new();
+        for path in files {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            log::info!("📨 发送命令行文件: {:?}", path);
+            match send_file(&path).await {
+                Ok(link) => links.push(link),
+                Err(e) => {
+                    log::error!("❌ 发送 {} 失败: {}", name, e);
+                    notify(&app, "发送文件失败", format!("{}: {}", name, e));
+                }
+            }
+        }
+        if links.is_empty() {
+            return;
+        }
+
+        let count = links.len();
+        match tauri_plugin_clipboard_x::write_text(links.join("\n")).await {
+            Ok(()) => notify(
+                &app,
+                "文件已发送",
+                format!("{} 个文件已上传，分享链接已复制到剪贴板", count),
+            ),
+            Err(e) => notify(
+                &app,
+                "文件已发送",
+                format!("{} 个文件已上传，但复制链接失败: {}", count, e),
+            ),
+        }
+    });
+}
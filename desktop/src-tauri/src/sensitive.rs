@@ -0,0 +1,134 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// 敏感内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveKind {
+    PrivateKey,
+    ApiKey,
+    Password,
+    CreditCard,
+    /// 无空白的高熵字符串（疑似密码或密钥）
+    HighEntropy,
+}
+
+/// 按类型分组的检测规则
+static PATTERNS: Lazy<Vec<(SensitiveKind, Regex)>> = Lazy::new(|| {
+    [
+        (
+            SensitiveKind::PrivateKey,
+            r"-----BEGIN [A-Z ]*PRIVATE KEY-----",
+        ),
+        (SensitiveKind::ApiKey, r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+        (SensitiveKind::ApiKey, r"\bgh[pousr]_[A-Za-z0-9]{36,}"),
+        (SensitiveKind::ApiKey, r"\bgithub_pat_[A-Za-z0-9_]{22,}"),
+        (SensitiveKind::ApiKey, r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+        (SensitiveKind::ApiKey, r"\bsk-[A-Za-z0-9_-]{20,}"),
+        (SensitiveKind::ApiKey, r"\bAIza[0-9A-Za-z_-]{35}"),
+        (
+            SensitiveKind::ApiKey,
+            r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+        ),
+        (
+            SensitiveKind::Password,
+            r#"(?i)\b(?:password|passwd|pwd|secret|api[_-]?key|access[_-]?token)\b["']?\s*[:=]\s*["']?[^\s"']{6,}"#,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(kind, pattern)| match Regex::new(pattern) {
+        Ok(regex) => Some((kind, regex)),
+        Err(e) => {
+            log::error!("❌ 敏感内容规则无效 ({}): {}", pattern, e);
+            None
+        }
+    })
+    .collect()
+});
+
+/// 可能的银行卡号（13-19 位数字，允许空格或连字符分隔）
+static CARD_NUMBER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").expect("银行卡号规则无效"));
+
+/// Luhn 校验
+fn luhn_valid(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn contains_card_number(text: &str) -> bool {
+    CARD_NUMBER.find_iter(text).any(|m| {
+        let digits: Vec<u32> = m.as_str().chars().filter_map(|c| c.to_digit(10)).collect();
+        (13..=19).contains(&digits.len()) && luhn_valid(&digits)
+    })
+}
+
+/// 每个字符的香农熵（比特）
+fn shannon_entropy(text: &str) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    let mut total = 0usize;
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+        total += 1;
+    }
+
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// 单个无空白、字符类别多样且熵较高的字符串，通常是密码或密钥
+fn looks_like_secret(text: &str) -> bool {
+    let text = text.trim();
+    let len = text.chars().count();
+    if !(12..=128).contains(&len) || text.chars().any(char::is_whitespace) {
+        return false;
+    }
+    // 链接和路径不算
+    if text.contains("://") || text.starts_with('/') {
+        return false;
+    }
+
+    let classes = [
+        text.chars().any(|c| c.is_ascii_lowercase()),
+        text.chars().any(|c| c.is_ascii_uppercase()),
+        text.chars().any(|c| c.is_ascii_digit()),
+        text.chars().any(|c| c.is_ascii_punctuation()),
+    ]
+    .iter()
+    .filter(|present| **present)
+    .count();
+
+    classes >= 3 && shannon_entropy(text) >= 3.5
+}
+
+/// 检测文本是否疑似包含敏感信息，返回命中的类型
+pub fn detect(text: &str) -> Option<SensitiveKind> {
+    PATTERNS
+        .iter()
+        .find(|(_, regex)| regex.is_match(text))
+        .map(|(kind, _)| *kind)
+        .or_else(|| contains_card_number(text).then_some(SensitiveKind::CreditCard))
+        .or_else(|| looks_like_secret(text).then_some(SensitiveKind::HighEntropy))
+}
@@ -0,0 +1,185 @@
+use crate::error::CloudPasteError;
+use crate::history::{self, HistoryEntry};
+use crate::{http, image_cache, upload};
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+
+/// 加密分享文件的开头标记（含格式版本）
+const MAGIC: &[u8; 4] = b"CPS1";
+
+/// 分享链接片段中的密钥参数（URL 片段不会发送给服务器）
+const KEY_PARAM: &str = "key=";
+
+/// 加密分享文件的扩展名
+const SHARE_EXTENSION: &str = "cpshare";
+
+/// 加密前的分享内容
+#[derive(Debug, Serialize, Deserialize)]
+struct SharePayload {
+    content_type: String,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+    /// 文本内容或文件数据（Base64）
+    data: String,
+}
+
+/// 打开分享链接得到的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedContent {
+    pub content_type: String,
+    pub file_name: Option<String>,
+    pub mime_type: Option<String>,
+    /// 文本内容
+    pub text: Option<String>,
+    /// 文件解密后保存的本地路径
+    pub path: Option<String>,
+}
+
+/// 读取要分享的记录内容：文本直接使用，图片和文件先下载到缓存
+async fn read_payload(
+    app: &AppHandle,
+    entry: &HistoryEntry,
+) -> Result<SharePayload, CloudPasteError> {
+    let item = &entry.item;
+    let data = if item.content_type == "text" {
+        item.content.as_bytes().to_vec()
+    } else {
+        let url = http::absolute_url(&item.content)?;
+        let cached = image_cache::ensure_cached(app, &url).await?;
+        tokio::fs::read(&cached)
+            .await
+            .map_err(|e| CloudPasteError::io("读取文件失败", e))?
+    };
+
+    Ok(SharePayload {
+        content_type: item.content_type.clone(),
+        file_name: item.file_name.clone(),
+        mime_type: item.mime_type.clone(),
+        data: BASE64.encode(data),
+    })
+}
+
+fn encrypt(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, CloudPasteError> {
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = ChaCha20Poly1305::new(&(*key).into())
+        .encrypt(&Nonce::from(nonce), plaintext)
+        .map_err(|e| CloudPasteError::Internal(format!("加密分享内容失败: {}", e)))?;
+
+    let mut blob = MAGIC.to_vec();
+    blob.extend_from_slice(&nonce);
+    blob.extend(ciphertext);
+    Ok(blob)
+}
+
+fn decrypt(key: &[u8; 32], blob: &[u8]) -> Result<Vec<u8>, CloudPasteError> {
+    let invalid = || CloudPasteError::InvalidInput("不是有效的加密分享".to_string());
+    let rest = blob.strip_prefix(MAGIC.as_slice()).ok_or_else(invalid)?;
+    let (nonce, ciphertext) = rest.split_first_chunk::<12>().ok_or_else(invalid)?;
+    ChaCha20Poly1305::new(&(*key).into())
+        .decrypt(&Nonce::from(*nonce), ciphertext)
+        .map_err(|_| CloudPasteError::InvalidInput("分享密钥不正确或内容已损坏".to_string()))
+}
+
+/// 拆分分享链接为下载地址和密钥
+fn parse_link(link: &str) -> Result<(&str, [u8; 32]), CloudPasteError> {
+    let invalid = || CloudPasteError::InvalidInput("分享链接缺少密钥".to_string());
+    let (url, fragment) = link.split_once('#').ok_or_else(invalid)?;
+    let encoded = fragment
+        .split('&')
+        .find_map(|param| param.strip_prefix(KEY_PARAM))
+        .ok_or_else(invalid)?;
+    let key = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .ok()
+        .and_then(|key| <[u8; 32]>::try_from(key).ok())
+        .ok_or_else(invalid)?;
+    Ok((url, key))
+}
+
+/// Tauri 命令：生成记录的加密分享链接
+///
+/// 每次分享生成随机密钥，只用它加密这一条记录后上传；密钥放在链接的 URL 片段中，
+/// 服务器只能看到密文，分享一条记录也不会暴露主密钥
+#[tauri::command]
+pub async fn create_encrypted_share(
+    app: AppHandle,
+    item_id: i64,
+) -> Result<String, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let entry = history::get_entry(item_id)?;
+    let payload = read_payload(&app, &entry).await?;
+    let plaintext =
+        serde_json::to_vec(&payload).map_err(|e| CloudPasteError::Internal(e.to_string()))?;
+
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    let blob = encrypt(&key, &plaintext)?;
+
+    let file_name = format!("share-{}.{}", item_id, SHARE_EXTENSION);
+    let uploaded = upload::upload_bytes_to_server(file_name, blob).await?;
+    let url = http::absolute_url(&uploaded.file_url)?;
+
+    log::info!("🔗 已生成加密分享链接: 剪贴板项={}", item_id);
+    Ok(format!(
+        "{}#{}{}",
+        url,
+        KEY_PARAM,
+        URL_SAFE_NO_PAD.encode(key)
+    ))
+}
+
+/// Tauri 命令：打开加密分享链接，文本直接返回，文件解密后保存到临时目录
+#[tauri::command]
+pub async fn open_encrypted_share(
+    app: AppHandle,
+    link: String,
+) -> Result<SharedContent, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let (url, key) = parse_link(&link)?;
+    let cached = image_cache::ensure_cached(&app, url).await?;
+    let blob = tokio::fs::read(&cached)
+        .await
+        .map_err(|e| CloudPasteError::io("读取分享内容失败", e))?;
+
+    let plaintext = decrypt(&key, &blob)?;
+    let payload: SharePayload = serde_json::from_slice(&plaintext)
+        .map_err(|e| CloudPasteError::InvalidInput(format!("分享内容格式错误: {}", e)))?;
+    let data = BASE64
+        .decode(&payload.data)
+        .map_err(|e| CloudPasteError::InvalidInput(format!("分享内容格式错误: {}", e)))?;
+
+    let mut shared = SharedContent {
+        content_type: payload.content_type,
+        file_name: payload.file_name,
+        mime_type: payload.mime_type,
+        text: None,
+        path: None,
+    };
+    if shared.content_type == "text" {
+        shared.text = Some(
+            String::from_utf8(data)
+                .map_err(|e| CloudPasteError::InvalidInput(format!("分享内容格式错误: {}", e)))?,
+        );
+        return Ok(shared);
+    }
+
+    let name = shared
+        .file_name
+        .as_deref()
+        .and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "shared".to_string());
+    let dir = std::env::temp_dir().join("CloudPaste").join("shared");
+    std::fs::create_dir_all(&dir).map_err(|e| CloudPasteError::io("创建临时目录失败", e))?;
+    let path = dir.join(name);
+    std::fs::write(&path, data).map_err(|e| CloudPasteError::io("保存分享文件失败", e))?;
+    shared.path = Some(path.to_string_lossy().to_string());
+    Ok(shared)
+}
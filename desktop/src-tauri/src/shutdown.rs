@@ -0,0 +1,108 @@
+use crate::{conflict, connectivity, history};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+/// 等待进行中的操作和推送本地修改的总时长上限
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 检查进行中操作的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 是否正在退出
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// 进行中的操作（上传、同步）数量
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// 进行中的操作，释放时计数减一
+pub struct WorkGuard(());
+
+impl Drop for WorkGuard {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 开始一项退出前需要完成的操作；正在退出时返回错误
+pub fn begin_work() -> Result<WorkGuard, String> {
+    if SHUTTING_DOWN.load(Ordering::SeqCst) {
+        return Err("应用正在退出".to_string());
+    }
+    IN_FLIGHT.fetch_add(1, Ordering::SeqCst);
+    Ok(WorkGuard(()))
+}
+
+/// 退出进度（作为 shutdown-progress 事件的内容）
+#[derive(Debug, Clone, Serialize)]
+pub struct ShutdownProgress {
+    /// 当前阶段：`waiting`、`flushing`、`closing`
+    pub stage: &'static str,
+    pub message: String,
+}
+
+fn progress(app: &AppHandle, stage: &'static str, message: String) {
+    log::info!("🚪 {}", message);
+    let _ = app.emit("shutdown-progress", ShutdownProgress { stage, message });
+}
+
+/// 等待进行中的操作完成，超过截止时间时返回 false
+async fn wait_in_flight(deadline: Instant) -> bool {
+    while IN_FLIGHT.load(Ordering::SeqCst) > 0 {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    true
+}
+
+/// 退出流程：等待进行中的上传和同步，推送本地修改，关闭数据库后退出
+///
+/// 超时未完成的本地修改已保存在数据库中，下次启动同步时继续推送
+pub async fn shutdown(app: AppHandle) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+    let in_flight = IN_FLIGHT.load(Ordering::SeqCst);
+    if in_flight > 0 {
+        let message = format!("正在完成 {} 项进行中的上传或同步…", in_flight);
+        let _ = app
+            .notification()
+            .builder()
+            .title("CloudPaste 正在退出")
+            .body(&message)
+            .show();
+        progress(&app, "waiting", message);
+        if !wait_in_flight(deadline).await {
+            log::warn!(
+                "⚠️ 等待超时，仍有 {} 项操作未完成",
+                IN_FLIGHT.load(Ordering::SeqCst)
+            );
+        }
+    }
+
+    if connectivity::is_online() && crate::get_api_config_status().unwrap_or(false) {
+        progress(&app, "flushing", "正在推送尚未同步的本地修改…".to_string());
+        let flush = async {
+            history::push_pending_tags().await;
+            conflict::push_pending_edits().await;
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if tokio::time::timeout(remaining, flush).await.is_err() {
+            log::warn!("⚠️ 推送本地修改超时，下次启动时继续");
+        }
+    }
+
+    progress(&app, "closing", "正在关闭本地数据库…".to_string());
+    if let Err(e) = history::close() {
+        log::warn!("⚠️ {}", e);
+    }
+
+    log::info!("👋 应用退出");
+    app.exit(0);
+}
@@ -0,0 +1,208 @@
+use crate::error::CloudPasteError;
+use crate::store;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::menu::{MenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Wry};
+
+const SNIPPETS_FILE: &str = "snippets.json";
+
+/// 托盘菜单项 ID 前缀
+pub const TRAY_ID_PREFIX: &str = "snippet:";
+
+/// 文本片段模板
+///
+/// 内容支持占位符：`{date}` 当前日期，`{clipboard}` 当前剪贴板文本，`{cursor}` 粘贴后光标位置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    /// 为空时视为新建，保存时自动生成
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub content: String,
+}
+
+/// 展开后的片段
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpandedSnippet {
+    pub text: String,
+    /// `{cursor}` 所在位置（按字符计）
+    pub cursor: Option<usize>,
+}
+
+static SNIPPETS: Lazy<Mutex<Vec<Snippet>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// 托盘中的片段子菜单
+static TRAY_MENU: Lazy<Mutex<Option<Submenu<Wry>>>> = Lazy::new(|| Mutex::new(None));
+
+/// 启动时从磁盘加载片段并填充托盘菜单
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<Vec<Snippet>>(app, SNIPPETS_FILE) {
+        if let Ok(mut snippets) = SNIPPETS.lock() {
+            *snippets = saved;
+        }
+    }
+    refresh_tray_menu(app);
+}
+
+fn snippets() -> Vec<Snippet> {
+    SNIPPETS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn find(id: &str) -> Result<Snippet, String> {
+    snippets()
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("文本片段不存在: {}", id))
+}
+
+/// 保存托盘中的片段子菜单，片段变化时更新
+pub fn attach_tray_menu(submenu: Submenu<Wry>) {
+    if let Ok(mut menu) = TRAY_MENU.lock() {
+        *menu = Some(submenu);
+    }
+}
+
+/// 按当前片段列表重建托盘子菜单
+fn refresh_tray_menu(app: &AppHandle) {
+    let Ok(guard) = TRAY_MENU.lock() else {
+        return;
+    };
+    let Some(submenu) = guard.as_ref() else {
+        return;
+    };
+
+    let result = (|| -> tauri::Result<()> {
+        for item in submenu.items()? {
+            submenu.remove(&item)?;
+        }
+
+        let snippets = snippets();
+        if snippets.is_empty() {
+            let empty = MenuItem::with_id(app, "snippet-empty", "（无）", false, None::<&str>)?;
+            return submenu.append(&empty);
+        }
+        for snippet in &snippets {
+            let item = MenuItem::with_id(
+                app,
+                format!("{}{}", TRAY_ID_PREFIX, snippet.id),
+                &snippet.name,
+                true,
+                None::<&str>,
+            )?;
+            submenu.append(&item)?;
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        log::warn!("⚠️ 更新托盘片段菜单失败: {}", e);
+    }
+}
+
+/// 展开模板中的占位符
+async fn expand(content: &str) -> ExpandedSnippet {
+    let mut text = content.replace(
+        "{date}",
+        &chrono::Local::now().format("%Y-%m-%d").to_string(),
+    );
+
+    if text.contains("{clipboard}") {
+        let clipboard = tauri_plugin_clipboard_x::read_text()
+            .await
+            .unwrap_or_default();
+        text = text.replace("{clipboard}", &clipboard);
+    }
+
+    // 只保留第一个光标位置
+    let cursor = text
+        .find("{cursor}")
+        .map(|index| text[..index].chars().count());
+    text = text.replace("{cursor}", "");
+
+    ExpandedSnippet { text, cursor }
+}
+
+/// 展开片段并写入剪贴板（应用锁定时拒绝，快捷键和托盘触发也一样）
+async fn paste(app: &AppHandle, id: &str) -> Result<ExpandedSnippet, String> {
+    crate::applock::ensure_unlocked()?;
+    let snippet = find(id)?;
+    let expanded = expand(&snippet.content).await;
+    tauri_plugin_clipboard_x::write_text(expanded.text.clone()).await?;
+
+    log::info!("📋 已展开文本片段: {}", snippet.name);
+    let _ = app.emit("snippet-expanded", &expanded);
+    Ok(expanded)
+}
+
+/// 在后台展开片段（快捷键或托盘触发，无需主窗口）
+pub fn paste_in_background(app: &AppHandle, id: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = paste(&app, &id).await {
+            log::error!("❌ 展开文本片段失败: {}", e);
+        }
+    });
+}
+
+fn save(app: &AppHandle, snippets: Vec<Snippet>) -> Result<(), String> {
+    store::save_json(app, SNIPPETS_FILE, &snippets)?;
+    *SNIPPETS
+        .lock()
+        .map_err(|e| format!("无法锁定文本片段: {}", e))? = snippets;
+    refresh_tray_menu(app);
+    Ok(())
+}
+
+/// Tauri 命令：获取所有文本片段
+#[tauri::command]
+pub fn list_snippets() -> Result<Vec<Snippet>, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    Ok(snippets())
+}
+
+/// Tauri 命令：新建或更新文本片段，返回保存后的片段
+#[tauri::command]
+pub fn save_snippet(app: AppHandle, mut snippet: Snippet) -> Result<Snippet, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    if snippet.id.is_empty() {
+        snippet.id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default()
+            .to_string();
+    }
+
+    let mut all = snippets();
+    match all.iter().position(|s| s.id == snippet.id) {
+        Some(index) => all[index] = snippet.clone(),
+        None => all.push(snippet.clone()),
+    }
+    save(&app, all)?;
+    Ok(snippet)
+}
+
+/// Tauri 命令：删除文本片段（同时删除该片段的快捷键）
+#[tauri::command]
+pub fn delete_snippet(app: AppHandle, id: String) -> Result<(), CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let mut all = snippets();
+    all.retain(|s| s.id != id);
+    save(&app, all)?;
+    Ok(crate::hotkeys::remove_snippet_bindings(&app, &id)?)
+}
+
+/// Tauri 命令：展开文本片段（不写入剪贴板）
+#[tauri::command]
+pub async fn expand_snippet(id: String) -> Result<ExpandedSnippet, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    Ok(expand(&find(&id)?.content).await)
+}
+
+/// Tauri 命令：展开文本片段并写入剪贴板
+#[tauri::command]
+pub async fn paste_snippet(app: AppHandle, id: String) -> Result<ExpandedSnippet, CloudPasteError> {
+    Ok(paste(&app, &id).await?)
+}
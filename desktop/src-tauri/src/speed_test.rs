@@ -0,0 +1,134 @@
+use crate::error::CloudPasteError;
+use crate::{http, store, upload};
+use rand_core::{OsRng, RngCore};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const HISTORY_FILE: &str = "speed_tests.json";
+
+/// 测试数据大小（2 MiB，随机内容避免被压缩）
+const PAYLOAD_SIZE: usize = 2 * 1024 * 1024;
+
+/// 最多保留的历史记录数
+const MAX_HISTORY: usize = 20;
+
+/// 单次测速结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeedTestResult {
+    /// 测试时间（Unix 秒）
+    pub timestamp: u64,
+    pub server: String,
+    /// 预热请求往返延迟（毫秒）
+    pub latency_ms: u64,
+    /// 上传速度（字节/秒）
+    pub upload_bps: u64,
+    /// 下载速度（字节/秒）
+    pub download_bps: u64,
+    pub payload_size: u64,
+}
+
+fn bytes_per_second(bytes: usize, started: Instant) -> u64 {
+    let secs = started.elapsed().as_secs_f64();
+    if secs <= 0.0 {
+        return 0;
+    }
+    (bytes as f64 / secs) as u64
+}
+
+async fn measure() -> Result<SpeedTestResult, CloudPasteError> {
+    let server = crate::GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?
+        .base_url
+        .clone();
+
+    // 预热连接并测量延迟
+    let started = Instant::now();
+    let response = http::send(http::api_request(Method::GET, "/auth/me")?)
+        .await
+        .map_err(|e| CloudPasteError::request("连接服务器失败", e))?;
+    http::check_response(response).await?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let mut payload = vec![0u8; PAYLOAD_SIZE];
+    OsRng.fill_bytes(&mut payload);
+
+    // 上传测试
+    let started = Instant::now();
+    let uploaded =
+        upload::upload_bytes_to_server("cloudpaste-speedtest.bin".to_string(), payload).await?;
+    let upload_bps = bytes_per_second(PAYLOAD_SIZE, started);
+
+    // 下载测试
+    let started = Instant::now();
+    let download = async {
+        let request = http::api_request(
+            Method::GET,
+            &format!("/files/download/{}", uploaded.file_id),
+        )?;
+        let response = http::send(request)
+            .await
+            .map_err(|e| CloudPasteError::request("下载测试数据失败", e))?;
+        http::check_response(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| CloudPasteError::request("读取测试数据失败", e))
+    }
+    .await;
+    let download_bps = download.map(|bytes| bytes_per_second(bytes.len(), started));
+
+    // 无论下载是否成功都清理测试文件
+    if let Ok(request) = http::api_request(
+        Method::DELETE,
+        &format!("/files/delete/{}", uploaded.file_id),
+    ) {
+        if let Err(e) = http::send(request).await {
+            log::warn!("⚠️ 删除测速文件失败: {}", e);
+        }
+    }
+
+    Ok(SpeedTestResult {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        server,
+        latency_ms,
+        upload_bps,
+        download_bps: download_bps?,
+        payload_size: PAYLOAD_SIZE as u64,
+    })
+}
+
+/// Tauri 命令：对当前服务器进行上传/下载测速
+///
+/// 结果会追加到历史记录，返回包含本次结果在内的全部历史（最新在前）
+#[tauri::command]
+pub async fn run_speed_test(app: AppHandle) -> Result<Vec<SpeedTestResult>, CloudPasteError> {
+    log::info!("🚀 开始测速");
+
+    let result = measure().await?;
+
+    log::info!(
+        "✅ 测速完成: 延迟={}ms, 上传={}B/s, 下载={}B/s",
+        result.latency_ms,
+        result.upload_bps,
+        result.download_bps
+    );
+
+    let mut history = get_speed_test_history(app.clone());
+    history.insert(0, result);
+    history.truncate(MAX_HISTORY);
+    store::save_json(&app, HISTORY_FILE, &history)?;
+
+    Ok(history)
+}
+
+/// Tauri 命令：获取历史测速结果（最新在前）
+#[tauri::command]
+pub fn get_speed_test_history(app: AppHandle) -> Vec<SpeedTestResult> {
+    store::load_json(&app, HISTORY_FILE).unwrap_or_default()
+}
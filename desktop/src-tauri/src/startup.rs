@@ -0,0 +1,126 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// 进程启动时间（在 `run()` 开头调用 `mark_launch` 初始化）
+static LAUNCHED_AT: Lazy<Instant> = Lazy::new(Instant::now);
+
+static REPORT: Lazy<Mutex<StartupReport>> = Lazy::new(|| Mutex::new(StartupReport::default()));
+
+/// 延迟初始化是否已开始（只执行一次）
+static DEFERRED_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 单个启动阶段的耗时
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupStage {
+    pub name: String,
+    /// 相对进程启动的开始时间（毫秒）
+    pub started_ms: u64,
+    pub duration_ms: u64,
+    /// 是否在首帧之后延迟执行
+    pub deferred: bool,
+}
+
+/// 启动性能报告
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+    /// 托盘可用时距进程启动的毫秒数
+    pub time_to_tray_ms: Option<u64>,
+    /// 主窗口首次加载完成的毫秒数
+    pub first_frame_ms: Option<u64>,
+    /// 延迟初始化全部完成的毫秒数
+    pub deferred_completed_ms: Option<u64>,
+    pub stages: Vec<StartupStage>,
+}
+
+fn since_launch(instant: Instant) -> u64 {
+    instant.saturating_duration_since(*LAUNCHED_AT).as_millis() as u64
+}
+
+fn with_report(f: impl FnOnce(&mut StartupReport)) {
+    if let Ok(mut report) = REPORT.lock() {
+        f(&mut report);
+    }
+}
+
+/// 记录进程启动时间
+pub fn mark_launch() {
+    Lazy::force(&LAUNCHED_AT);
+}
+
+/// 执行并记录一个启动阶段
+///
+/// 托盘就绪之后执行的阶段计为延迟阶段
+pub fn stage<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let span = tracing::info_span!("startup_stage", stage = name);
+    let _entered = span.enter();
+
+    let started = Instant::now();
+    let result = f();
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    tracing::info!(stage = name, duration_ms, "⏱️ 启动阶段完成");
+
+    with_report(|report| {
+        report.stages.push(StartupStage {
+            name: name.to_string(),
+            started_ms: since_launch(started),
+            duration_ms,
+            deferred: report.time_to_tray_ms.is_some(),
+        })
+    });
+    result
+}
+
+/// 记录从进程启动到现在的阶段（例如插件初始化）
+pub fn record_since_launch(name: &str) {
+    let duration_ms = since_launch(Instant::now());
+    tracing::info!(stage = name, duration_ms, "⏱️ 启动阶段完成");
+
+    with_report(|report| {
+        report.stages.push(StartupStage {
+            name: name.to_string(),
+            started_ms: 0,
+            duration_ms,
+            deferred: false,
+        })
+    });
+}
+
+/// 关键路径结束，托盘已可用
+pub fn mark_tray_ready() {
+    let elapsed = since_launch(Instant::now());
+    tracing::info!(elapsed_ms = elapsed, "🚀 托盘已就绪");
+    with_report(|report| report.time_to_tray_ms = Some(elapsed));
+}
+
+/// 主窗口首次加载完成
+pub fn mark_first_frame() {
+    let elapsed = since_launch(Instant::now());
+    with_report(|report| {
+        if report.first_frame_ms.is_none() {
+            tracing::info!(elapsed_ms = elapsed, "🖼️ 主窗口首帧已加载");
+            report.first_frame_ms = Some(elapsed);
+        }
+    });
+}
+
+/// 标记延迟初始化开始，已开始过时返回 false
+pub fn begin_deferred() -> bool {
+    !DEFERRED_STARTED.swap(true, Ordering::SeqCst)
+}
+
+/// 延迟初始化全部完成
+pub fn mark_deferred_completed() {
+    let elapsed = since_launch(Instant::now());
+    tracing::info!(elapsed_ms = elapsed, "✅ 延迟初始化完成");
+    with_report(|report| report.deferred_completed_ms = Some(elapsed));
+}
+
+/// Tauri 命令：获取启动性能报告
+#[tauri::command]
+pub fn get_startup_report() -> StartupReport {
+    REPORT.lock().map(|r| r.clone()).unwrap_or_default()
+}
@@ -0,0 +1,713 @@
+use crate::api::{self, ClipboardItem, NewClipboardItem};
+use crate::error::CloudPasteError;
+use crate::events::{self, StorageStatusChanged, WebDavItemReceived};
+use crate::s3::{self, S3Config};
+use crate::{api_v2, device, history, http, image_cache, store, upload, webdav};
+use futures_util::future::{join_all, BoxFuture};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Webview};
+
+const SETTINGS_FILE: &str = "storage.json";
+
+/// 存放剪贴板项的子目录
+const ITEMS_DIR: &str = "items";
+
+/// 存放文件的子目录
+const FILES_DIR: &str = "files";
+
+/// 从 CloudPaste 服务器列出的最近记录数
+const LIST_LIMIT: u32 = 50;
+
+/// 轮询主后端的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+/// 存储后端保存剪贴板项的结果
+pub enum Stored {
+    /// CloudPaste 服务器创建的记录
+    Server(ClipboardItem),
+    /// 写入后端的记录（文件类记录的内容已换成文件在后端中的位置）
+    Item(NewClipboardItem),
+}
+
+/// 剪贴板项的存储后端
+///
+/// 文件类记录附带文件内容，由后端决定如何保存（上传后把记录内容换成后端中的位置）；
+/// 读取时按名称列出和获取记录，名称按字典序即按时间排序
+pub trait StorageBackend: Send + Sync {
+    fn store<'a>(
+        &'a self,
+        item: &'a NewClipboardItem,
+        file: Option<Vec<u8>>,
+    ) -> BoxFuture<'a, Result<Stored, CloudPasteError>>;
+
+    /// 列出名称排在 `after` 之后的剪贴板项
+    fn list<'a>(
+        &'a self,
+        after: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<String>, CloudPasteError>>;
+
+    /// 读取一个剪贴板项
+    fn fetch<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<NewClipboardItem, CloudPasteError>>;
+
+    /// 读取文件类记录内容指向的文件
+    fn read_file<'a>(
+        &'a self,
+        location: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<u8>, CloudPasteError>>;
+}
+
+/// 后端类型及其配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackendConfig {
+    /// CloudPaste 服务器
+    CloudPaste,
+    /// WebDAV（使用 WebDAV 同步设置中的地址和账号）
+    WebDav,
+    /// S3 兼容的对象存储
+    S3(S3Config),
+    /// 本地文件夹（可放在网盘同步目录中）
+    LocalFolder { path: String },
+}
+
+/// 一个镜像目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorTarget {
+    /// 用户自定义的名称，也用作状态的键
+    pub id: String,
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub backend: BackendConfig,
+}
+
+/// 存储设置：主后端之外同时写入的镜像目标
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageSettings {
+    pub mirrors: Vec<MirrorTarget>,
+}
+
+/// 单个后端的同步状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackendStatus {
+    pub id: String,
+    /// 成功写入的记录数
+    pub synced: u64,
+    /// 写入失败的记录数
+    pub failed: u64,
+    /// 最近一次成功的时间（RFC 3339）
+    pub last_synced_at: Option<String>,
+    /// 最近一次失败的原因，成功后清除
+    pub last_error: Option<String>,
+}
+
+static SETTINGS: Lazy<Mutex<StorageSettings>> =
+    Lazy::new(|| Mutex::new(StorageSettings::default()));
+
+static STATUS: Lazy<Mutex<HashMap<String, BackendStatus>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 轮询主后端时已处理到的最新剪贴板项名称
+static LAST_SEEN: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+struct CloudPasteBackend;
+
+impl StorageBackend for CloudPasteBackend {
+    fn store<'a>(
+        &'a self,
+        item: &'a NewClipboardItem,
+        file: Option<Vec<u8>>,
+    ) -> BoxFuture<'a, Result<Stored, CloudPasteError>> {
+        Box::pin(async move {
+            let mut item = item.clone();
+            if let Some(data) = file {
+                let uploaded = upload::upload_bytes_to_server(file_name(&item), data).await?;
+                item.content = uploaded.file_url;
+            }
+            Ok(Stored::Server(api::create_clipboard_item(&item).await?))
+        })
+    }
+
+    fn list<'a>(
+        &'a self,
+        after: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<String>, CloudPasteError>> {
+        Box::pin(async move {
+            // 最近的一页记录，名称为补零的 ID（ID 随创建时间递增）
+            let items = if api_v2::active() {
+                api_v2::list_clipboard_items(None, LIST_LIMIT).await?.items
+            } else {
+                api::list_clipboard_items(1, LIST_LIMIT).await?.items
+            };
+            let mut names: Vec<String> = items
+                .iter()
+                .map(|item| format!("{:020}", item.id))
+                .filter(|name| after.is_none_or(|after| name.as_str() > after))
+                .collect();
+            names.sort();
+            Ok(names)
+        })
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<NewClipboardItem, CloudPasteError>> {
+        Box::pin(async move {
+            let id = name
+                .parse::<i64>()
+                .map_err(|_| CloudPasteError::InvalidInput(format!("无效的剪贴板项: {}", name)))?;
+            Ok(api::get_clipboard_item(id).await?.into())
+        })
+    }
+
+    fn read_file<'a>(
+        &'a self,
+        location: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<u8>, CloudPasteError>> {
+        Box::pin(async move {
+            // 从服务器下载到缓存
+            let app = APP
+                .get()
+                .ok_or_else(|| CloudPasteError::Internal("应用尚未初始化".to_string()))?;
+            let url = http::absolute_url(location)?;
+            let cached = image_cache::ensure_cached(app, &url).await?;
+            tokio::fs::read(&cached)
+                .await
+                .map_err(|e| CloudPasteError::io("读取文件失败", e))
+        })
+    }
+}
+
+struct WebDavBackend;
+
+impl StorageBackend for WebDavBackend {
+    fn store<'a>(
+        &'a self,
+        item: &'a NewClipboardItem,
+        file: Option<Vec<u8>>,
+    ) -> BoxFuture<'a, Result<Stored, CloudPasteError>> {
+        Box::pin(async move {
+            let mut item = item.clone();
+            if let Some(data) = file {
+                let name = unique_file_name(&file_name(&item));
+                item.content = webdav::put_file(&name, data).await?;
+            }
+            webdav::push_item(&item).await?;
+            Ok(Stored::Item(item))
+        })
+    }
+
+    fn list<'a>(
+        &'a self,
+        after: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<String>, CloudPasteError>> {
+        Box::pin(webdav::list(after))
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<NewClipboardItem, CloudPasteError>> {
+        Box::pin(webdav::fetch(name))
+    }
+
+    fn read_file<'a>(
+        &'a self,
+        location: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<u8>, CloudPasteError>> {
+        Box::pin(webdav::get_file(location))
+    }
+}
+
+struct S3Backend(S3Config);
+
+impl StorageBackend for S3Backend {
+    fn store<'a>(
+        &'a self,
+        item: &'a NewClipboardItem,
+        file: Option<Vec<u8>>,
+    ) -> BoxFuture<'a, Result<Stored, CloudPasteError>> {
+        Box::pin(async move {
+            let mut item = item.clone();
+            if let Some(data) = file {
+                let name = format!("{}/{}", FILES_DIR, file_name(&item));
+                item.content =
+                    s3::put_object(&self.0, &name, data, "application/octet-stream").await?;
+            }
+            let body =
+                serde_json::to_vec(&item).map_err(|e| format!("序列化剪贴板项失败: {}", e))?;
+            let name = format!("{}/{}", ITEMS_DIR, item_file_name(&item));
+            s3::put_object(&self.0, &name, body, "application/json").await?;
+            Ok(Stored::Item(item))
+        })
+    }
+
+    fn list<'a>(
+        &'a self,
+        after: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<String>, CloudPasteError>> {
+        Box::pin(s3::list_objects(&self.0, ITEMS_DIR, after))
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<NewClipboardItem, CloudPasteError>> {
+        Box::pin(async move {
+            let key = format!("{}{}/{}", self.0.prefix, ITEMS_DIR, name);
+            let body = s3::get_object(&self.0, &key).await?;
+            serde_json::from_slice(&body)
+                .map_err(|e| CloudPasteError::InvalidResponse(format!("解析剪贴板项失败: {}", e)))
+        })
+    }
+
+    fn read_file<'a>(
+        &'a self,
+        location: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<u8>, CloudPasteError>> {
+        Box::pin(s3::get_object(&self.0, location))
+    }
+}
+
+struct LocalFolderBackend(PathBuf);
+
+impl StorageBackend for LocalFolderBackend {
+    fn store<'a>(
+        &'a self,
+        item: &'a NewClipboardItem,
+        file: Option<Vec<u8>>,
+    ) -> BoxFuture<'a, Result<Stored, CloudPasteError>> {
+        Box::pin(async move {
+            let mut item = item.clone();
+            if let Some(data) = file {
+                let path = self.0.join(FILES_DIR).join(file_name(&item));
+                write_file(&path, &data).await?;
+                item.content = path.to_string_lossy().to_string();
+            }
+            let body = serde_json::to_vec_pretty(&item)
+                .map_err(|e| format!("序列化剪贴板项失败: {}", e))?;
+            write_file(&self.0.join(ITEMS_DIR).join(item_file_name(&item)), &body).await?;
+            Ok(Stored::Item(item))
+        })
+    }
+
+    fn list<'a>(
+        &'a self,
+        after: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<String>, CloudPasteError>> {
+        Box::pin(async move {
+            let mut entries = match tokio::fs::read_dir(self.0.join(ITEMS_DIR)).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(CloudPasteError::io("读取目录失败", e)),
+            };
+            let mut names = Vec::new();
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(|e| CloudPasteError::io("读取目录失败", e))?
+            {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.ends_with(".json") && after.is_none_or(|after| name.as_str() > after) {
+                    names.push(name);
+                }
+            }
+            names.sort();
+            Ok(names)
+        })
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<NewClipboardItem, CloudPasteError>> {
+        Box::pin(async move {
+            let file_name = Path::new(name).file_name().ok_or_else(|| {
+                CloudPasteError::InvalidInput(format!("无效的剪贴板项: {}", name))
+            })?;
+            let body = tokio::fs::read(self.0.join(ITEMS_DIR).join(file_name))
+                .await
+                .map_err(|e| CloudPasteError::io("读取剪贴板项失败", e))?;
+            serde_json::from_slice(&body)
+                .map_err(|e| CloudPasteError::InvalidResponse(format!("解析剪贴板项失败: {}", e)))
+        })
+    }
+
+    fn read_file<'a>(
+        &'a self,
+        location: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<u8>, CloudPasteError>> {
+        Box::pin(async move {
+            // 只读取本目标文件目录中的文件
+            let path = Path::new(location);
+            let inside = path.starts_with(self.0.join(FILES_DIR))
+                && !path
+                    .components()
+                    .any(|component| component == std::path::Component::ParentDir);
+            if !inside {
+                return Err(CloudPasteError::PermissionDenied(format!(
+                    "文件不在本地文件夹目标中: {}",
+                    location
+                )));
+            }
+            tokio::fs::read(path)
+                .await
+                .map_err(|e| CloudPasteError::io("读取文件失败", e))
+        })
+    }
+}
+
+async fn write_file(path: &Path, data: &[u8]) -> Result<(), CloudPasteError> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| CloudPasteError::io("创建目录失败", e))?;
+    }
+    tokio::fs::write(path, data)
+        .await
+        .map_err(|e| CloudPasteError::io("写入文件失败", e))
+}
+
+fn build(config: &BackendConfig) -> Box<dyn StorageBackend> {
+    match config {
+        BackendConfig::CloudPaste => Box::new(CloudPasteBackend),
+        BackendConfig::WebDav => Box::new(WebDavBackend),
+        BackendConfig::S3(config) => Box::new(S3Backend(config.clone())),
+        BackendConfig::LocalFolder { path } => Box::new(LocalFolderBackend(PathBuf::from(path))),
+    }
+}
+
+/// 剪贴板项在后端中的文件名：毫秒时间戳开头，按字典序即按时间排序
+pub fn item_file_name(item: &NewClipboardItem) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let device_id = item.device_id.clone().unwrap_or_else(device::id);
+    format!("{:015}-{}.json", millis, device_id)
+}
+
+/// 上传到共享目录的文件名：以毫秒时间戳和本机设备 ID 开头，避免不同设备的同名文件互相覆盖
+///
+/// 只保留字母、数字和 `.-_`，原始文件名保存在剪贴板项中
+pub fn unique_file_name(name: &str) -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{:015}-{}-{}", millis, device::id(), name)
+}
+
+/// 文件在后端中的文件名：只保留文件名部分
+fn file_name(item: &NewClipboardItem) -> String {
+    item.file_name
+        .as_deref()
+        .and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| item_file_name(item).replace(".json", ".bin"))
+}
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    if let Some(saved) = store::load_json::<StorageSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> StorageSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 当前的主后端：选择 WebDAV 同步时为 WebDAV，否则为 CloudPaste 服务器
+fn primary() -> BackendConfig {
+    if webdav::is_primary() {
+        BackendConfig::WebDav
+    } else {
+        BackendConfig::CloudPaste
+    }
+}
+
+fn kind(config: &BackendConfig) -> &'static str {
+    match config {
+        BackendConfig::CloudPaste => "cloud_paste",
+        BackendConfig::WebDav => "web_dav",
+        BackendConfig::S3(_) => "s3",
+        BackendConfig::LocalFolder { .. } => "local_folder",
+    }
+}
+
+fn record(id: &str, error: Option<&CloudPasteError>) {
+    let Ok(mut statuses) = STATUS.lock() else {
+        return;
+    };
+    let status = statuses
+        .entry(id.to_string())
+        .or_insert_with(|| BackendStatus {
+            id: id.to_string(),
+            ..Default::default()
+        });
+    match error {
+        None => {
+            status.synced += 1;
+            status.last_synced_at = Some(chrono::Utc::now().to_rfc3339());
+            status.last_error = None;
+        }
+        Some(e) => {
+            status.failed += 1;
+            status.last_error = Some(e.to_string());
+            log::warn!("⚠️ 写入 {} 失败: {}", id, e);
+        }
+    }
+    events::emit(StorageStatusChanged {
+        status: status.clone(),
+    });
+}
+
+/// 文件类记录内容在主后端中的访问地址
+pub fn file_link(location: &str) -> Result<String, CloudPasteError> {
+    match primary() {
+        BackendConfig::WebDav => Ok(webdav::file_url(location)),
+        _ => http::absolute_url(location),
+    }
+}
+
+/// 把文件类记录内容指向的文件下载到缓存，返回缓存路径
+///
+/// 主后端为 CloudPaste 服务器时按地址下载，其他后端按记录内容中的位置读取
+pub async fn ensure_cached(app: &AppHandle, location: &str) -> Result<PathBuf, CloudPasteError> {
+    let primary = primary();
+    if matches!(primary, BackendConfig::CloudPaste) {
+        return image_cache::ensure_cached(app, location).await;
+    }
+    let cache_path = image_cache::cache_path_for_url(app, location)?;
+    if image_cache::is_cached(&cache_path) {
+        return Ok(cache_path);
+    }
+    let data = build(&primary).read_file(location).await?;
+    write_file(&cache_path, &data).await?;
+    Ok(cache_path)
+}
+
+/// 删除 `ensure_cached` 下载的缓存文件
+pub fn evict(app: &AppHandle, location: &str) -> Result<u64, CloudPasteError> {
+    image_cache::evict(app, location)
+}
+
+/// 从主后端读取文件类记录的内容
+async fn read_file(
+    primary: &BackendConfig,
+    item: &NewClipboardItem,
+) -> Result<Option<Vec<u8>>, CloudPasteError> {
+    if item.content_type == "text" {
+        return Ok(None);
+    }
+    build(primary).read_file(&item.content).await.map(Some)
+}
+
+/// 将已写入主后端的记录同时写入所有启用的镜像目标（跳过与主后端相同的目标）
+pub async fn mirror(item: &NewClipboardItem) {
+    let primary = primary();
+    let targets: Vec<MirrorTarget> = settings()
+        .mirrors
+        .into_iter()
+        .filter(|target| target.enabled && kind(&target.backend) != kind(&primary))
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let file = match read_file(&primary, item).await {
+        Ok(file) => file,
+        Err(e) => {
+            log::warn!("⚠️ 读取要镜像的文件失败: {}", e);
+            return;
+        }
+    };
+
+    let stores = targets.iter().map(|target| {
+        let file = file.clone();
+        async move {
+            let result = build(&target.backend).store(item, file).await;
+            record(&target.id, result.err().as_ref());
+        }
+    });
+    join_all(stores).await;
+}
+
+/// 在主后端创建剪贴板项，并在后台镜像到其他后端
+///
+/// 主后端不是 CloudPaste 服务器时记录没有服务器 ID，作为不在服务器上的记录保存到本地历史
+pub async fn create_item(item: &NewClipboardItem) -> Result<ClipboardItem, CloudPasteError> {
+    let primary = primary();
+    let stored = build(&primary).store(item, None).await;
+    record(kind(&primary), stored.as_ref().err());
+    let created = match stored? {
+        Stored::Server(created) => created,
+        Stored::Item(stored) => history::insert_local_item(stored)?.item,
+    };
+
+    let mut mirrored = item.clone();
+    mirrored.content = created.content.clone();
+    tauri::async_runtime::spawn(async move { mirror(&mirrored).await });
+    Ok(created)
+}
+
+/// 主后端设置变化后从最新位置重新开始轮询
+pub fn restart_polling() {
+    if let Ok(mut guard) = LAST_SEEN.lock() {
+        *guard = None;
+    }
+}
+
+/// 拉取其他设备新写入主后端的剪贴板项并通知前端
+async fn poll(backend: &dyn StorageBackend) -> Result<(), CloudPasteError> {
+    let last_seen = LAST_SEEN.lock().ok().and_then(|guard| guard.clone());
+    let Some(last_seen) = last_seen else {
+        // 首次轮询只记录位置，不重放历史内容
+        let names = backend.list(None).await?;
+        if let Ok(mut guard) = LAST_SEEN.lock() {
+            *guard = Some(names.last().cloned().unwrap_or_default());
+        }
+        return Ok(());
+    };
+
+    let this_device = format!("-{}.json", device::id());
+    for name in backend.list(Some(&last_seen)).await? {
+        if !name.ends_with(&this_device) {
+            match backend.fetch(&name).await {
+                Ok(item) => events::emit(WebDavItemReceived { item }),
+                Err(e) => log::warn!("⚠️ 读取剪贴板项失败: {}: {}", name, e),
+            }
+        }
+        if let Ok(mut guard) = LAST_SEEN.lock() {
+            *guard = Some(name);
+        }
+    }
+    Ok(())
+}
+
+/// 主后端不是 CloudPaste 服务器（通过 WebSocket 实时推送）时定期轮询
+pub async fn watch() {
+    loop {
+        let primary = primary();
+        if !matches!(primary, BackendConfig::CloudPaste) {
+            match poll(build(&primary).as_ref()).await {
+                Ok(()) | Err(CloudPasteError::NotConfigured) => {}
+                Err(e) => log::warn!("⚠️ 轮询 {} 失败: {}", kind(&primary), e),
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Tauri 命令：获取存储设置
+#[tauri::command]
+pub fn get_storage_settings() -> StorageSettings {
+    settings()
+}
+
+/// 本地文件夹目标的路径
+fn local_folder(target: &MirrorTarget) -> Option<&str> {
+    match &target.backend {
+        BackendConfig::LocalFolder { path } => Some(path),
+        _ => None,
+    }
+}
+
+/// Tauri 命令：更新存储设置
+///
+/// 新增的本地文件夹须是用户通过对话框选择过的目录（见 `sandbox::pick_folder_path`）
+#[tauri::command]
+pub fn set_storage_settings(
+    app: AppHandle,
+    webview: Webview,
+    settings: StorageSettings,
+) -> Result<(), CloudPasteError> {
+    crate::sandbox::check_window(&webview)?;
+    let current = self::settings();
+    for path in settings.mirrors.iter().filter_map(local_folder) {
+        if !current
+            .mirrors
+            .iter()
+            .any(|existing| local_folder(existing) == Some(path))
+        {
+            crate::sandbox::check(&webview, path)?;
+        }
+    }
+
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
+
+/// Tauri 命令：获取各后端的同步状态
+#[tauri::command]
+pub fn get_storage_status() -> Vec<BackendStatus> {
+    let mut statuses: Vec<BackendStatus> = STATUS
+        .lock()
+        .map(|statuses| statuses.values().cloned().collect())
+        .unwrap_or_default();
+    statuses.sort_by(|a, b| a.id.cmp(&b.id));
+    statuses
+}
+
+/// Tauri 命令：把收到的图片或文件从主后端下载到缓存，返回本地路径（写入剪贴板时使用）
+///
+/// 传入 `file_name` 时复制为缓存中 `cloudpaste` 目录下的同名文件，粘贴时保留原始文件名
+#[tauri::command]
+pub async fn cache_item_file(
+    app: AppHandle,
+    location: String,
+    file_name: Option<String>,
+) -> Result<String, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let cached = ensure_cached(&app, &location).await?;
+    let Some(name) = file_name
+        .as_deref()
+        .and_then(|name| Path::new(name).file_name())
+    else {
+        return Ok(cached.to_string_lossy().to_string());
+    };
+
+    let dir = store::cache_dir(&app)?.join("cloudpaste");
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| CloudPasteError::io("创建目录失败", e))?;
+    let path = dir.join(name);
+    tokio::fs::copy(&cached, &path)
+        .await
+        .map_err(|e| CloudPasteError::io("复制文件失败", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Tauri 命令：将前端已同步到主后端的记录镜像到其他后端
+#[tauri::command]
+pub async fn mirror_clipboard_item(item: NewClipboardItem) {
+    mirror(&item).await;
+}
@@ -0,0 +1,180 @@
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// 便携模式启动参数
+const PORTABLE_FLAG: &str = "--portable";
+
+/// 可执行文件旁存在该文件时同样启用便携模式
+const PORTABLE_MARKER: &str = "portable";
+
+/// 便携模式下的数据目录名（位于可执行文件旁）
+const PORTABLE_DATA_DIR: &str = "data";
+
+/// 便携模式的数据目录，未启用便携模式时为 None
+static PORTABLE_DIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    let exe_dir = std::env::current_exe()
+        .ok()?
+        .parent()
+        .map(|dir| dir.to_path_buf())?;
+    let enabled =
+        std::env::args().any(|arg| arg == PORTABLE_FLAG) || exe_dir.join(PORTABLE_MARKER).exists();
+    enabled.then(|| exe_dir.join(PORTABLE_DATA_DIR))
+});
+
+/// 便携模式下的数据目录（可执行文件旁的 data 目录），未启用时返回 None
+pub fn portable_dir() -> Option<&'static PathBuf> {
+    PORTABLE_DIR.as_ref()
+}
+
+/// 获取应用数据目录（不存在时自动创建）
+///
+/// 便携模式下使用可执行文件旁的 data 目录
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    // 使用 Tauri 提供的跨平台 API 获取应用数据目录
+    let app_data_dir = match portable_dir() {
+        Some(dir) => dir.clone(),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("获取应用数据目录失败: {}", e))?,
+    };
+
+    // 确保目录存在
+    fs::create_dir_all(&app_data_dir).map_err(|e| format!("创建应用数据目录失败: {}", e))?;
+
+    Ok(app_data_dir)
+}
+
+/// 获取应用缓存目录（不存在时自动创建）
+///
+/// 便携模式下使用 data 目录下的 cache 目录
+pub fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let cache_dir = match portable_dir() {
+        Some(dir) => dir.join("cache"),
+        None => app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| format!("获取缓存目录失败: {}", e))?,
+    };
+
+    fs::create_dir_all(&cache_dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+
+    Ok(cache_dir)
+}
+
+/// 获取应用数据目录下指定文件的完整路径
+pub fn data_file_path(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    Ok(data_dir(app)?.join(file_name))
+}
+
+/// 从应用数据目录读取 JSON 文件
+///
+/// 文件不存在或解析失败时返回 None
+pub fn load_json<T: DeserializeOwned>(app: &AppHandle, file_name: &str) -> Option<T> {
+    let path = data_file_path(app, file_name).ok()?;
+    if !path.exists() {
+        return None;
+    }
+
+    let content = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<T>(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("⚠️ 解析 {} 失败: {}", file_name, e);
+            None
+        }
+    }
+}
+
+/// 将数据以 JSON 格式写入应用数据目录
+pub fn save_json<T: Serialize>(app: &AppHandle, file_name: &str, value: &T) -> Result<(), String> {
+    let path = data_file_path(app, file_name)?;
+    let content = serde_json::to_string_pretty(value).map_err(|e| format!("序列化失败: {}", e))?;
+
+    write_atomic(&path, content.as_bytes()).map_err(|e| format!("写入文件失败: {}", e))?;
+
+    log::info!("✅ 已保存到磁盘: {:?}", path);
+    Ok(())
+}
+
+/// 原子写入使用的临时文件路径（与目标文件位于同一目录，保证可以直接重命名）
+pub fn atomic_temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/// 完成原子写入：将临时文件刷新到磁盘后重命名覆盖目标文件
+///
+/// 重命名前失败时删除临时文件，目标文件保持原样
+pub fn commit_atomic(file: File, temp: &Path, path: &Path) -> std::io::Result<()> {
+    let result = file.sync_all().and_then(|_| {
+        drop(file);
+        fs::rename(temp, path)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(temp);
+        return result;
+    }
+
+    // 同时刷新目录项，确保重命名在断电后仍然有效（Windows 不支持打开目录）
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// 原子写入文件：先写入同目录下的临时文件，再重命名覆盖目标文件
+///
+/// 写入过程中崩溃或断电时，目标文件要么是旧内容，要么是完整的新内容
+pub fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let temp = atomic_temp_path(path);
+    let mut file = File::create(&temp)?;
+    if let Err(e) = file.write_all(data) {
+        drop(file);
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    commit_atomic(file, &temp, path)
+}
+
+/// 原子写入文件，由 `write` 向临时文件写入内容并返回文件
+///
+/// 写入失败时删除临时文件，目标文件保持原样
+pub fn write_atomic_with<E: From<String>>(
+    path: &Path,
+    write: impl FnOnce(File) -> Result<File, E>,
+) -> Result<(), E> {
+    let temp = atomic_temp_path(path);
+    let file = File::create(&temp).map_err(|e| format!("创建文件失败: {}", e))?;
+    match write(file) {
+        Ok(file) => {
+            Ok(commit_atomic(file, &temp, path).map_err(|e| format!("写入文件失败: {}", e))?)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            Err(e)
+        }
+    }
+}
+
+/// 原子复制文件：先复制到目标目录下的临时文件，再重命名覆盖目标文件
+pub fn copy_atomic(source: &Path, path: &Path) -> std::io::Result<()> {
+    let temp = atomic_temp_path(path);
+    if let Err(e) = fs::copy(source, &temp) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    let file = File::options().write(true).open(&temp)?;
+    commit_atomic(file, &temp, path)
+}
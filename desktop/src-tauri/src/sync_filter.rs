@@ -0,0 +1,191 @@
+use crate::api::ClipboardItem;
+use crate::error::CloudPasteError;
+use crate::store;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "sync_filters.json";
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "avi", "mov", "mkv", "webm", "flv", "wmv", "m4v"];
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "ogg", "flac", "m4a", "aac", "wma"];
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp", "svg", "ico"];
+
+/// 本机的同步过滤设置（上传和下载都生效，各设备可分别设置）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncFilters {
+    /// 超过该大小（MB）的内容不同步，为空表示不限制
+    pub max_size_mb: Option<u64>,
+    /// 不同步的内容类别：`text`、`image`、`video`、`audio`、`file`（`file` 包含所有文件）
+    pub excluded_types: Vec<String>,
+    /// 只同步这些内容类别（`file` 指图片、视频、音频以外的文件），为空表示全部
+    pub included_types: Vec<String>,
+}
+
+static FILTERS: Lazy<Mutex<SyncFilters>> = Lazy::new(|| Mutex::new(SyncFilters::default()));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<SyncFilters>(app, SETTINGS_FILE) {
+        if let Ok(mut filters) = FILTERS.lock() {
+            *filters = saved;
+        }
+    }
+}
+
+fn filters() -> SyncFilters {
+    FILTERS.lock().map(|f| f.clone()).unwrap_or_default()
+}
+
+/// 判断内容类别：优先使用 MIME 类型，其次使用文件扩展名
+fn category(content_type: &str, file_name: Option<&str>, mime_type: Option<&str>) -> &'static str {
+    if content_type == "text" {
+        return "text";
+    }
+
+    if let Some(mime) = mime_type {
+        for prefix in ["image", "video", "audio"] {
+            if mime.starts_with(&format!("{}/", prefix)) {
+                return prefix;
+            }
+        }
+    }
+
+    let extension = file_name
+        .and_then(|name| Path::new(name).extension())
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        "video"
+    } else if AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        "audio"
+    } else if content_type == "image" || IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        "image"
+    } else {
+        "file"
+    }
+}
+
+/// 上传文件对应的剪贴板内容类型：图片为 `image`，其余为 `file`
+pub fn upload_content_type(file_name: &str) -> &'static str {
+    if category("file", Some(file_name), None) == "image" {
+        "image"
+    } else {
+        "file"
+    }
+}
+
+/// 按本机的大小和类别设置检查内容是否同步，不同步时返回原因
+fn check_preferences(
+    filters: &SyncFilters,
+    content_type: &str,
+    file_name: Option<&str>,
+    mime_type: Option<&str>,
+    size: u64,
+) -> Result<(), String> {
+    if let Some(max_mb) = filters.max_size_mb {
+        if size > max_mb * 1024 * 1024 {
+            return Err(format!("大小超过本机同步限制 {} MB", max_mb));
+        }
+    }
+
+    let category = category(content_type, file_name, mime_type);
+    let excluded = filters
+        .excluded_types
+        .iter()
+        .any(|excluded| excluded == category || (excluded == "file" && category != "text"));
+    let included = filters.included_types.is_empty()
+        || filters
+            .included_types
+            .iter()
+            .any(|included| included == category);
+    if excluded || !included {
+        return Err(format!("本机设置为不同步 {} 类型的内容", category));
+    }
+    Ok(())
+}
+
+/// 按受管策略检查内容类型和文件大小，不允许同步时返回原因
+fn check_policy(content_type: &str, size: u64) -> Result<(), String> {
+    let policy = crate::policy::current();
+    if let Some(allowed) = &policy.allowed_content_types {
+        if !allowed.is_empty() && !allowed.iter().any(|allowed| allowed == content_type) {
+            return Err(format!("受管策略不允许同步 {} 类型的内容", content_type));
+        }
+    }
+    if content_type != "text" {
+        if let Some(max_size) = policy.max_sync_file_size {
+            if size > max_size {
+                return Err(format!(
+                    "文件大小 {} 字节超过受管策略限制 {} 字节",
+                    size, max_size
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 检查内容是否允许同步（包括受管策略和按流量计费网络的大文件限制），不允许时返回原因
+pub fn check(
+    content_type: &str,
+    file_name: Option<&str>,
+    mime_type: Option<&str>,
+    size: u64,
+) -> Result<(), String> {
+    check_policy(content_type, size)?;
+    check_preferences(&filters(), content_type, file_name, mime_type, size)?;
+    crate::metered::check(size)
+}
+
+/// 从服务器收到的剪贴板项是否同步到本机（受受管策略限制，不受按流量计费网络影响）
+pub fn accepts(item: &ClipboardItem) -> bool {
+    let size = item.file_size.unwrap_or(if item.content_type == "text" {
+        item.content.len() as u64
+    } else {
+        0
+    });
+    check_policy(&item.content_type, size).is_ok()
+        && check_preferences(
+            &filters(),
+            &item.content_type,
+            item.file_name.as_deref(),
+            item.mime_type.as_deref(),
+            size,
+        )
+        .is_ok()
+}
+
+/// Tauri 命令：检查文件或图片是否允许同步，不允许时返回原因
+#[tauri::command]
+pub fn check_sync_filters(
+    content_type: String,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+    size: u64,
+) -> Option<String> {
+    check(
+        &content_type,
+        file_name.as_deref(),
+        mime_type.as_deref(),
+        size,
+    )
+    .err()
+}
+
+/// Tauri 命令：获取同步过滤设置
+#[tauri::command]
+pub fn get_sync_filters() -> SyncFilters {
+    filters()
+}
+
+/// Tauri 命令：更新同步过滤设置
+#[tauri::command]
+pub fn set_sync_filters(app: AppHandle, filters: SyncFilters) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &filters)?;
+    *FILTERS.lock().map_err(|e| format!("无法锁定设置: {}", e))? = filters;
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use crate::error::CloudPasteError;
+use crate::{connectivity, history, policy, prefetch, store, telemetry};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const SETTINGS_FILE: &str = "sync_schedule.json";
+
+/// 调度器检查是否到期的间隔
+const TICK: Duration = Duration::from_secs(5);
+
+/// 最短同步间隔（秒）
+const MIN_INTERVAL_SECS: u64 = 10;
+
+/// 同步计划设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSchedule {
+    /// 自动同步间隔（秒），为空表示仅手动同步
+    pub interval_secs: Option<u64>,
+    /// 同步间隔由受管策略强制设置（只读，不保存）
+    #[serde(skip_deserializing)]
+    pub managed: bool,
+}
+
+impl Default for SyncSchedule {
+    fn default() -> Self {
+        Self {
+            interval_secs: Some(300),
+            managed: false,
+        }
+    }
+}
+
+/// 同步的触发方式
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncTrigger {
+    Scheduled,
+    Manual,
+}
+
+/// 一次同步的结果（作为 sync-completed / sync-failed 事件的内容）
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReport {
+    pub trigger: SyncTrigger,
+    /// 同步的记录数
+    pub synced: u64,
+    pub error: Option<String>,
+    pub finished_at: String,
+}
+
+static SCHEDULE: Lazy<Mutex<SyncSchedule>> = Lazy::new(|| Mutex::new(SyncSchedule::default()));
+
+/// 上一次同步完成的时间
+static LAST_SYNC: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 是否有同步正在进行
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<SyncSchedule>(app, SETTINGS_FILE) {
+        if let Ok(mut schedule) = SCHEDULE.lock() {
+            *schedule = saved;
+        }
+    }
+}
+
+/// 当前生效的同步计划（受管策略设置的间隔优先于本机设置）
+fn schedule() -> SyncSchedule {
+    let schedule = SCHEDULE.lock().map(|s| s.clone()).unwrap_or_default();
+    match policy::current().sync_interval_secs {
+        Some(interval) => SyncSchedule {
+            interval_secs: Some(interval),
+            managed: true,
+        },
+        None => schedule,
+    }
+}
+
+/// 执行一次同步（已有同步进行时直接返回错误），完成后发送事件
+async fn run_sync(app: &AppHandle, trigger: SyncTrigger) -> Result<u64, CloudPasteError> {
+    let _work = crate::shutdown::begin_work()?;
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(CloudPasteError::InvalidInput("同步正在进行中".to_string()));
+    }
+
+    let result = history::sync_history(app.clone(), None).await;
+    RUNNING.store(false, Ordering::SeqCst);
+    telemetry::record(
+        match trigger {
+            SyncTrigger::Scheduled => "sync.scheduled",
+            SyncTrigger::Manual => "sync.manual",
+        },
+        &result,
+    );
+    if let Ok(mut last) = LAST_SYNC.lock() {
+        *last = Some(Instant::now());
+    }
+
+    let report = SyncReport {
+        trigger,
+        synced: *result.as_ref().unwrap_or(&0),
+        error: result.as_ref().err().map(|e| e.to_string()),
+        finished_at: chrono::Utc::now().to_rfc3339(),
+    };
+    match &result {
+        Ok(_) => {
+            let _ = app.emit("sync-completed", &report);
+            // 同步完成后预下载最近记录的文件，保证离线可用
+            tauri::async_runtime::spawn(prefetch::run(app.clone()));
+        }
+        Err(e) => {
+            log::warn!("⚠️ 同步失败 ({:?}): {}", trigger, e);
+            let _ = app.emit("sync-failed", &report);
+        }
+    }
+    result
+}
+
+/// 按计划是否应当同步：已配置、在线且距上次同步已超过间隔
+fn is_due() -> bool {
+    let Some(interval) = schedule().interval_secs else {
+        return false;
+    };
+    if !crate::get_api_config_status().unwrap_or(false) || !connectivity::is_online() {
+        return false;
+    }
+
+    let interval = Duration::from_secs(interval.max(MIN_INTERVAL_SECS));
+    LAST_SYNC
+        .lock()
+        .map(|last| last.is_none_or(|last| last.elapsed() >= interval))
+        .unwrap_or(false)
+}
+
+/// 后台任务：按同步计划定期从服务器同步历史
+pub async fn run(app: AppHandle) {
+    loop {
+        if is_due() {
+            let _ = run_sync(&app, SyncTrigger::Scheduled).await;
+        }
+        tokio::time::sleep(TICK).await;
+    }
+}
+
+/// Tauri 命令：立即同步一次，返回同步的记录数
+#[tauri::command]
+pub async fn sync_now(app: AppHandle) -> Result<u64, CloudPasteError> {
+    log::info!("🔄 手动触发同步");
+    run_sync(&app, SyncTrigger::Manual).await
+}
+
+/// Tauri 命令：获取同步计划
+#[tauri::command]
+pub fn get_sync_schedule() -> SyncSchedule {
+    schedule()
+}
+
+/// Tauri 命令：更新同步计划（interval_secs 为空表示仅手动同步）
+#[tauri::command]
+pub fn set_sync_schedule(app: AppHandle, schedule: SyncSchedule) -> Result<(), CloudPasteError> {
+    if policy::current().sync_interval_secs.is_some() {
+        return Err(CloudPasteError::InvalidInput(
+            "同步间隔由受管策略设置，无法修改".to_string(),
+        ));
+    }
+    let schedule = SyncSchedule {
+        interval_secs: schedule
+            .interval_secs
+            .map(|secs| secs.max(MIN_INTERVAL_SECS)),
+        managed: false,
+    };
+    store::save_json(&app, SETTINGS_FILE, &schedule)?;
+    log::info!("⏱️ 同步计划已更新: {:?}", schedule.interval_secs);
+    *SCHEDULE
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = schedule;
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::error::CloudPasteError;
+use crate::{connectivity, http, store};
+use once_cell::sync::Lazy;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "telemetry.json";
+
+/// 上传统计数据的间隔
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// 使用统计设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    /// 用户主动开启后才统计和上传，默认关闭
+    pub enabled: bool,
+}
+
+/// 一项功能的使用次数和失败次数
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeatureUsage {
+    pub count: u64,
+    /// 按错误码统计的失败次数
+    pub errors: BTreeMap<String, u64>,
+}
+
+/// 一批待上传的统计数据，不含设备标识、服务器地址和剪贴板内容
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryBatch {
+    pub app_version: String,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// 统计开始时间
+    pub since: String,
+    pub features: BTreeMap<String, FeatureUsage>,
+}
+
+struct Counters {
+    since: chrono::DateTime<chrono::Utc>,
+    features: BTreeMap<String, FeatureUsage>,
+}
+
+static SETTINGS: Lazy<Mutex<TelemetrySettings>> =
+    Lazy::new(|| Mutex::new(TelemetrySettings::default()));
+
+static COUNTERS: Lazy<Mutex<Counters>> = Lazy::new(|| {
+    Mutex::new(Counters {
+        since: chrono::Utc::now(),
+        features: BTreeMap::new(),
+    })
+});
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<TelemetrySettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn enabled() -> bool {
+    SETTINGS.lock().map(|s| s.enabled).unwrap_or(false)
+}
+
+/// 记录一次功能使用及其结果（未开启统计时不记录）
+pub fn record<T>(feature: &str, result: &Result<T, CloudPasteError>) {
+    if !enabled() {
+        return;
+    }
+    if let Ok(mut counters) = COUNTERS.lock() {
+        let usage = counters.features.entry(feature.to_string()).or_default();
+        usage.count += 1;
+        if let Err(e) = result {
+            *usage.errors.entry(e.code().to_string()).or_default() += 1;
+        }
+    }
+}
+
+fn snapshot(app: &AppHandle) -> TelemetryBatch {
+    let (since, features) = COUNTERS
+        .lock()
+        .map(|c| (c.since, c.features.clone()))
+        .unwrap_or_else(|_| (chrono::Utc::now(), BTreeMap::new()));
+    TelemetryBatch {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        since: since.to_rfc3339(),
+        features,
+    }
+}
+
+fn reset() {
+    if let Ok(mut counters) = COUNTERS.lock() {
+        counters.since = chrono::Utc::now();
+        counters.features.clear();
+    }
+}
+
+/// 上传当前统计数据，成功后清零
+async fn upload(app: &AppHandle) -> Result<(), CloudPasteError> {
+    let batch = snapshot(app);
+    if batch.features.is_empty() {
+        return Ok(());
+    }
+
+    let request = http::api_request(Method::POST, "/telemetry")?.json(&batch);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("上传使用统计失败", e))?;
+    http::check_response(response).await?;
+
+    // 上传期间新增的计数一并清零，统计本身允许少量误差
+    reset();
+    log::info!("📊 使用统计已上传: {} 项功能", batch.features.len());
+    Ok(())
+}
+
+/// 后台任务：开启统计时定期上传
+pub async fn run(app: AppHandle) {
+    loop {
+        tokio::time::sleep(UPLOAD_INTERVAL).await;
+        if !enabled()
+            || !connectivity::is_online()
+            || !crate::get_api_config_status().unwrap_or(false)
+        {
+            continue;
+        }
+        if let Err(e) = upload(&app).await {
+            log::warn!("⚠️ {}", e);
+        }
+    }
+}
+
+/// Tauri 命令：获取使用统计设置
+#[tauri::command]
+pub fn get_telemetry_settings() -> TelemetrySettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Tauri 命令：开启或关闭使用统计，关闭时丢弃尚未上传的数据
+#[tauri::command]
+pub fn set_telemetry_settings(
+    app: AppHandle,
+    settings: TelemetrySettings,
+) -> Result<(), CloudPasteError> {
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    log::info!("📊 使用统计: {}", settings.enabled);
+    if !settings.enabled {
+        reset();
+    }
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    Ok(())
+}
+
+/// Tauri 命令：查看下次将要上传的统计数据
+#[tauri::command]
+pub fn get_telemetry_snapshot(app: AppHandle) -> TelemetryBatch {
+    snapshot(&app)
+}
@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// 计算传输速度时使用的滑动窗口
+const WINDOW: Duration = Duration::from_secs(5);
+
+/// 窗口内的时间跨度小于该值时不计算速度，避免开始传输时数值跳动
+const MIN_SPAN: Duration = Duration::from_millis(500);
+
+/// 单个传输任务的速度统计，按最近几秒的已传输字节数计算速度和剩余时间
+pub struct RateMeter {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl RateMeter {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::from([(Instant::now(), 0)]),
+        }
+    }
+
+    /// 记录当前已传输的总字节数
+    pub fn record(&mut self, transferred: u64) {
+        let now = Instant::now();
+        self.samples.push_back((now, transferred));
+        while self.samples.len() > 2
+            && self
+                .samples
+                .front()
+                .is_some_and(|(at, _)| now.duration_since(*at) > WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// 最近窗口内的平均速度（字节/秒），数据不足时为空
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        let (first_at, first_bytes) = self.samples.front()?;
+        let (last_at, last_bytes) = self.samples.back()?;
+        let span = last_at.duration_since(*first_at);
+        if span < MIN_SPAN {
+            return None;
+        }
+        Some((last_bytes.saturating_sub(*first_bytes) as f64 / span.as_secs_f64()) as u64)
+    }
+
+    /// 按当前速度估算的剩余秒数，总大小未知或速度为 0 时为空
+    pub fn eta_secs(&self, transferred: u64, total: Option<u64>) -> Option<u64> {
+        let rate = self.bytes_per_sec().filter(|rate| *rate > 0)?;
+        Some(total?.saturating_sub(transferred).div_ceil(rate))
+    }
+}
+
+impl Default for RateMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,406 @@
+use crate::error::CloudPasteError;
+use crate::events::{self, UploadProgress};
+use crate::transfer::RateMeter;
+use crate::{compression, http, presigned};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Body, Method};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::ipc::{InvokeBody, Request};
+use tauri::Webview;
+use tokio::io::AsyncReadExt;
+
+/// 上传时每次发送的块大小
+const UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
+
+/// 超过该大小的文件从磁盘流式上传
+const STREAM_THRESHOLD: u64 = 32 * 1024 * 1024;
+
+/// 剪贴板图片文件名所在的请求头（`encodeURIComponent` 编码）
+const FILE_NAME_HEADER: &str = "file-name";
+
+/// 剪贴板图片缺少文件名时使用的名称
+const DEFAULT_IMAGE_NAME: &str = "clipboard-image.png";
+
+/// 上传进度事件的最短发送间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 已上传文件信息（服务器返回，写入 WebDAV 时由本机生成）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadedFile {
+    pub file_id: String,
+    pub file_name: String,
+    pub file_size: u64,
+    pub mime_type: String,
+    pub file_url: String,
+    pub content_type: String,
+    /// 上传内容的 SHA-256（由本机计算，服务器不返回），签名时使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UploadResponse {
+    pub data: UploadedFile,
+}
+
+/// 单个上传任务的进度统计
+pub struct UploadTracker {
+    file_name: String,
+    uploaded: u64,
+    total: u64,
+    meter: RateMeter,
+    last_progress: Instant,
+}
+
+impl UploadTracker {
+    pub fn new(file_name: String, total: u64) -> Self {
+        Self {
+            file_name,
+            uploaded: 0,
+            total,
+            meter: RateMeter::new(),
+            last_progress: Instant::now(),
+        }
+    }
+
+    /// 记录新发送的字节数，按间隔发送进度事件，全部发送后发送完成事件
+    pub fn advance(&mut self, sent: usize) {
+        self.uploaded += sent as u64;
+        self.meter.record(self.uploaded);
+        let done = self.uploaded >= self.total;
+        if !done && self.last_progress.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_progress = Instant::now();
+        events::emit(UploadProgress {
+            file_name: self.file_name.clone(),
+            uploaded: self.uploaded,
+            total: self.total,
+            bytes_per_sec: self.meter.bytes_per_sec(),
+            eta_secs: self.meter.eta_secs(self.uploaded, Some(self.total)),
+            done,
+        });
+    }
+}
+
+/// 从磁盘按块读取文件作为流式请求体，发送过程中上报上传进度
+pub fn file_body(file_name: String, file: tokio::fs::File, total: u64) -> Body {
+    let tracker = UploadTracker::new(file_name, total);
+    let stream =
+        futures_util::stream::unfold((file, tracker), |(mut file, mut tracker)| async move {
+            let mut chunk = vec![0u8; UPLOAD_CHUNK_SIZE];
+            match file.read(&mut chunk).await {
+                Ok(0) => None,
+                Ok(read) => {
+                    chunk.truncate(read);
+                    tracker.advance(read);
+                    Some((Ok(chunk), (file, tracker)))
+                }
+                Err(e) => Some((Err(e), (file, tracker))),
+            }
+        });
+    Body::wrap_stream(stream)
+}
+
+/// 将内存中的数据按块包装为流式请求体，发送过程中上报上传进度
+pub fn progress_body(file_name: String, data: Vec<u8>) -> Body {
+    let tracker = UploadTracker::new(file_name, data.len() as u64);
+    let stream = futures_util::stream::unfold(
+        (data, 0usize, tracker),
+        |(data, offset, mut tracker)| async move {
+            if offset >= data.len() {
+                return None;
+            }
+            let end = (offset + UPLOAD_CHUNK_SIZE).min(data.len());
+            let chunk = data[offset..end].to_vec();
+            tracker.advance(chunk.len());
+            Some((Ok::<_, std::io::Error>(chunk), (data, end, tracker)))
+        },
+    );
+    Body::wrap_stream(stream)
+}
+
+/// 上传本地文件到主后端（WebDAV 为主后端时写入 WebDAV）
+///
+/// 超过 `STREAM_THRESHOLD` 的文件直接从磁盘流式上传，不读入内存（也不做图片处理和压缩）；
+/// 开启移除元数据时，这类大图片无法处理，拒绝上传
+pub async fn upload_file(path: &Path) -> Result<UploadedFile, CloudPasteError> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| CloudPasteError::InvalidInput("无效的文件路径".to_string()))?;
+
+    log::info!("📤 开始上传文件: {:?}", path);
+
+    let size = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| CloudPasteError::io("读取文件失败", e))?
+        .len();
+    if size > STREAM_THRESHOLD {
+        if crate::image_processing::strip_metadata_enabled() && is_strippable_image(path).await? {
+            return Err(CloudPasteError::InvalidInput(format!(
+                "图片超过 {} MB，无法移除元数据，已拒绝上传",
+                STREAM_THRESHOLD / 1024 / 1024
+            )));
+        }
+        let result = upload_stream_inner(path, file_name, size).await;
+        crate::telemetry::record("upload", &result);
+        return result;
+    }
+
+    let data = tokio::fs::read(path)
+        .await
+        .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+
+    upload_bytes(file_name, data).await
+}
+
+/// 根据文件头判断是否为需要移除元数据的图片
+async fn is_strippable_image(path: &Path) -> Result<bool, CloudPasteError> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+    let mut header = Vec::with_capacity(12);
+    (&mut file)
+        .take(12)
+        .read_to_end(&mut header)
+        .await
+        .map_err(|e| CloudPasteError::io("读取文件失败", e))?;
+    Ok(crate::metadata_strip::is_supported(&header))
+}
+
+/// 上传内存中的数据到主后端（WebDAV 为主后端时写入 WebDAV）
+pub async fn upload_bytes(
+    file_name: String,
+    data: Vec<u8>,
+) -> Result<UploadedFile, CloudPasteError> {
+    let result = upload_bytes_inner(file_name, data, crate::webdav::is_primary()).await;
+    crate::telemetry::record("upload", &result);
+    result
+}
+
+/// 上传内存中的数据到 CloudPaste 服务器（分享、测速和镜像到服务器时使用）
+pub async fn upload_bytes_to_server(
+    file_name: String,
+    data: Vec<u8>,
+) -> Result<UploadedFile, CloudPasteError> {
+    let result = upload_bytes_inner(file_name, data, false).await;
+    crate::telemetry::record("upload", &result);
+    result
+}
+
+/// 写入 WebDAV 的上传结果：地址和文件 ID 均为文件在 WebDAV 上的路径
+fn webdav_uploaded(
+    file_name: String,
+    file_size: u64,
+    path: String,
+    mime_type: Option<&str>,
+    file_hash: String,
+) -> UploadedFile {
+    log::info!("✅ 文件已写入 WebDAV: {}", path);
+    UploadedFile {
+        file_id: path.clone(),
+        content_type: crate::sync_filter::upload_content_type(&file_name).to_string(),
+        mime_type: mime_type.unwrap_or("application/octet-stream").to_string(),
+        file_name,
+        file_size,
+        file_url: path,
+        file_hash: Some(file_hash),
+    }
+}
+
+/// 检查同步过滤规则和受管策略是否允许上传该文件
+fn check_allowed(file_name: &str, size: u64) -> Result<(), CloudPasteError> {
+    let content_type = crate::sync_filter::upload_content_type(file_name);
+    crate::sync_filter::check(content_type, Some(file_name), None, size).map_err(|reason| {
+        CloudPasteError::InvalidInput(format!("跳过上传 {}: {}", file_name, reason))
+    })
+}
+
+fn device_id() -> Result<String, CloudPasteError> {
+    Ok(crate::GLOBAL_API_CONFIG
+        .lock()
+        .map_err(|e| CloudPasteError::Internal(format!("无法锁定配置: {}", e)))?
+        .device_id
+        .clone())
+}
+
+/// 以 multipart 表单发送文件内容，返回服务器保存的文件信息
+async fn send_upload(
+    file_name: String,
+    query: &[(&str, String)],
+    body: Body,
+    total: u64,
+) -> Result<UploadedFile, CloudPasteError> {
+    let form = Form::new().part(
+        "file",
+        Part::stream_with_length(body, total).file_name(file_name),
+    );
+
+    let request = http::transfer_request(Method::POST, "/files/upload")?
+        .query(query)
+        .multipart(form);
+    let response = http::send(request)
+        .await
+        .map_err(|e| CloudPasteError::request("上传文件失败", e))?;
+
+    let body: UploadResponse = http::check_response(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::InvalidResponse(format!("解析上传结果失败: {}", e)))?;
+
+    log::info!("✅ 文件已上传: {}", body.data.file_url);
+    Ok(body.data)
+}
+
+async fn upload_bytes_inner(
+    file_name: String,
+    data: Vec<u8>,
+    to_webdav: bool,
+) -> Result<UploadedFile, CloudPasteError> {
+    let _work = crate::shutdown::begin_work()?;
+    let (file_name, mut data) = crate::image_processing::preprocess(file_name, data).await?;
+    check_allowed(&file_name, data.len() as u64)?;
+    let file_hash = crate::device_trust::content_hash(&data);
+
+    if to_webdav {
+        let size = data.len() as u64;
+        let mime_type = infer::get(&data).map(|kind| kind.mime_type());
+        let name = crate::storage::unique_file_name(&file_name);
+        let path = crate::webdav::put_file(&name, data).await?;
+        return Ok(webdav_uploaded(file_name, size, path, mime_type, file_hash));
+    }
+
+    // 服务器提供对象存储直传地址时直接上传，不经服务器中转（也不压缩）
+    let device_id = device_id()?;
+    let size = data.len() as u64;
+    if let Some(upload) = presigned::request(&file_name, size, &device_id).await {
+        let source = presigned::Source::Bytes(data);
+        return presigned::upload(upload, file_name, source, size, &device_id)
+            .await
+            .map(|uploaded| with_hash(uploaded, file_hash));
+    }
+
+    let mut query = vec![("device_id", device_id)];
+
+    // 较大的文本在服务器支持时压缩传输，由服务器解压保存
+    if compression::should_compress(&file_name, data.len())
+        && compression::server_supports_zstd().await
+    {
+        let compressed = compression::compress(&data)?;
+        log::info!(
+            "🗜️ zstd 压缩上传: {} -> {} 字节",
+            data.len(),
+            compressed.len()
+        );
+        data = compressed;
+        query.push(("encoding", "zstd".to_string()));
+    }
+
+    let total = data.len() as u64;
+    let body = progress_body(file_name.clone(), data);
+    send_upload(file_name, &query, body, total)
+        .await
+        .map(|uploaded| with_hash(uploaded, file_hash))
+}
+
+fn with_hash(mut uploaded: UploadedFile, file_hash: String) -> UploadedFile {
+    uploaded.file_hash = Some(file_hash);
+    uploaded
+}
+
+async fn upload_stream_inner(
+    path: &Path,
+    file_name: String,
+    size: u64,
+) -> Result<UploadedFile, CloudPasteError> {
+    let _work = crate::shutdown::begin_work()?;
+    check_allowed(&file_name, size)?;
+    let file_hash = crate::device_trust::file_content_hash(path).await?;
+
+    if crate::webdav::is_primary() {
+        let mime_type = infer::get_from_path(path)
+            .ok()
+            .flatten()
+            .map(|kind| kind.mime_type());
+        let file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| CloudPasteError::io("打开文件失败", e))?;
+        let name = crate::storage::unique_file_name(&file_name);
+        let body = file_body(file_name.clone(), file, size);
+        let path = crate::webdav::put_file_stream(&name, body).await?;
+        return Ok(webdav_uploaded(file_name, size, path, mime_type, file_hash));
+    }
+
+    let device_id = device_id()?;
+    if let Some(upload) = presigned::request(&file_name, size, &device_id).await {
+        let source = presigned::Source::File(path);
+        return presigned::upload(upload, file_name, source, size, &device_id)
+            .await
+            .map(|uploaded| with_hash(uploaded, file_hash));
+    }
+
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| CloudPasteError::io("打开文件失败", e))?;
+    log::info!("📤 流式上传大文件: {} ({} 字节)", file_name, size);
+
+    let query = [("device_id", device_id)];
+    let body = file_body(file_name.clone(), file, size);
+    send_upload(file_name, &query, body, size)
+        .await
+        .map(|uploaded| with_hash(uploaded, file_hash))
+}
+
+/// Tauri 命令：从本地路径上传文件（路径须为用户通过对话框选择的文件），返回上传结果
+///
+/// 由 Rust 端直接读取文件，大文件从磁盘流式上传，前端不需要持有文件内容；
+/// 进度通过 `upload-progress` 事件获取
+#[tauri::command]
+pub async fn upload_file_from_path(
+    webview: Webview,
+    path: String,
+) -> Result<UploadedFile, CloudPasteError> {
+    let path = crate::sandbox::check(&webview, &path)?;
+    upload_file(&path).await
+}
+
+/// Tauri 命令：上传剪贴板中的图片（包括截图），返回上传结果
+///
+/// 图片以二进制请求体传入，文件名放在 `file-name` 请求头中：
+/// `invoke('upload_clipboard_image', bytes, { headers: { 'file-name': encodeURIComponent(name) } })`；
+/// 与其他上传一样经过元数据移除、图片压缩、直传和受管策略的大小限制
+#[tauri::command]
+pub async fn upload_clipboard_image(
+    webview: Webview,
+    request: Request<'_>,
+) -> Result<UploadedFile, CloudPasteError> {
+    crate::sandbox::check_window(&webview)?;
+    let file_name = request
+        .headers()
+        .get(FILE_NAME_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            url::form_urlencoded::parse(format!("name={}", value).as_bytes())
+                .next()
+                .map(|(_, name)| name.into_owned())
+        })
+        .and_then(|name| {
+            Path::new(&name)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| DEFAULT_IMAGE_NAME.to_string());
+    let data = match request.body() {
+        InvokeBody::Raw(data) => data.clone(),
+        InvokeBody::Json(_) => {
+            return Err(CloudPasteError::InvalidInput(
+                "图片数据需以二进制形式传入".to_string(),
+            ))
+        }
+    };
+    upload_bytes(file_name, data).await
+}
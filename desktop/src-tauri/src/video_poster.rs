@@ -0,0 +1,81 @@
+use crate::error::CloudPasteError;
+use crate::image_cache;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tokio::process::Command;
+
+/// 支持生成封面的视频扩展名
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm", "mkv"];
+
+/// 封面在缓存中的保存路径
+pub fn poster_path(cache_path: &Path) -> PathBuf {
+    let mut path = cache_path.as_os_str().to_owned();
+    path.push(".poster.jpg");
+    PathBuf::from(path)
+}
+
+fn is_video(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 调用 ffmpeg 提取第一个关键帧
+async fn extract_poster(video: &Path, poster: &Path) -> Result<(), String> {
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args(["-skip_frame", "nokey"])
+        .arg("-i")
+        .arg(video)
+        .args(["-frames:v", "1", "-q:v", "3"])
+        .arg(poster);
+
+    // Windows 上不弹出控制台窗口
+    #[cfg(windows)]
+    command.creation_flags(0x0800_0000);
+
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("启动 ffmpeg 失败（请确认已安装）: {}", e))?;
+
+    if !output.status.success() || !poster.exists() {
+        let _ = std::fs::remove_file(poster);
+        return Err(format!(
+            "提取视频封面失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Tauri 命令：获取视频封面图的本地路径
+///
+/// 视频未缓存时先下载缓存，封面生成后与视频一起保存在缓存目录中
+#[tauri::command]
+pub async fn get_video_poster(app: AppHandle, url: String) -> Result<String, CloudPasteError> {
+    crate::applock::ensure_unlocked()?;
+    let cache_path = image_cache::cache_path_for_url(&app, &url)?;
+    if !is_video(&cache_path) {
+        return Err(CloudPasteError::InvalidInput(
+            "不支持的视频格式".to_string(),
+        ));
+    }
+
+    let poster = poster_path(&cache_path);
+    if !poster.exists() {
+        let cached = image_cache::get_cached_file_path(app, url.clone()).await?;
+        if cached == url {
+            return Err(CloudPasteError::Internal(
+                "视频下载失败，无法生成封面".to_string(),
+            ));
+        }
+
+        log::info!("🎞️ 生成视频封面: {}", url);
+        extract_poster(&cache_path, &poster).await?;
+    }
+
+    Ok(poster.to_string_lossy().to_string())
+}
@@ -0,0 +1,373 @@
+use crate::api::NewClipboardItem;
+use crate::error::CloudPasteError;
+use crate::{http, storage, store};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const SETTINGS_FILE: &str = "webdav.json";
+
+/// 存放剪贴板项的子目录
+const ITEMS_DIR: &str = "items";
+
+/// 存放镜像文件的子目录
+const FILES_DIR: &str = "files";
+
+/// PROPFIND 响应中的资源路径
+static HREF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<(?:\w+:)?href>([^<]+)</(?:\w+:)?href>").expect("href 规则无效"));
+
+/// 剪贴板内容同步到哪里
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncBackend {
+    /// CloudPaste 服务器（WebSocket 实时同步）
+    #[default]
+    CloudPaste,
+    /// WebDAV 服务器（如 Nextcloud），定期轮询（见 `storage::watch`）
+    WebDav,
+}
+
+/// WebDAV 同步设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebDavSettings {
+    pub backend: SyncBackend,
+    /// WebDAV 地址，如 `https://cloud.example.com/remote.php/dav/files/alice`
+    pub url: String,
+    pub username: String,
+    /// 密码或应用专用密码（Nextcloud 建议使用应用密码）
+    pub password: String,
+    /// 远程目录，所有设备需使用同一目录
+    pub folder: String,
+}
+
+impl Default for WebDavSettings {
+    fn default() -> Self {
+        Self {
+            backend: SyncBackend::CloudPaste,
+            url: String::new(),
+            username: String::new(),
+            password: String::new(),
+            folder: "CloudPaste".to_string(),
+        }
+    }
+}
+
+static SETTINGS: Lazy<Mutex<WebDavSettings>> = Lazy::new(|| Mutex::new(WebDavSettings::default()));
+
+/// 启动时从磁盘加载设置
+pub fn load(app: &AppHandle) {
+    if let Some(saved) = store::load_json::<WebDavSettings>(app, SETTINGS_FILE) {
+        if let Ok(mut settings) = SETTINGS.lock() {
+            *settings = saved;
+        }
+    }
+}
+
+fn settings() -> WebDavSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// 是否选择 WebDAV 作为主同步后端
+pub fn is_primary() -> bool {
+    settings().backend == SyncBackend::WebDav
+}
+
+fn folder_url(settings: &WebDavSettings) -> String {
+    format!(
+        "{}/{}",
+        settings.url.trim_end_matches('/'),
+        settings.folder.trim_matches('/')
+    )
+}
+
+fn request(settings: &WebDavSettings, method: Method, url: &str) -> RequestBuilder {
+    http::client()
+        .request(method, url)
+        .basic_auth(&settings.username, Some(&settings.password))
+}
+
+/// 检查 WebDAV 响应状态码
+async fn check(
+    response: reqwest::Response,
+    context: &str,
+) -> Result<reqwest::Response, CloudPasteError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(CloudPasteError::Unauthorized(format!(
+            "{}: WebDAV 用户名或密码错误",
+            context
+        )));
+    }
+    Err(CloudPasteError::Server {
+        status: status.as_u16(),
+        message: format!("{}: {}", context, response.text().await.unwrap_or_default()),
+    })
+}
+
+/// 创建远程目录（已存在时服务器返回 405，视为成功）
+async fn ensure_folders(settings: &WebDavSettings) -> Result<(), CloudPasteError> {
+    let mkcol = Method::from_bytes(b"MKCOL").expect("MKCOL 是合法的 HTTP 方法");
+    let folder = folder_url(settings);
+    let dirs = [
+        folder.clone(),
+        format!("{}/{}", folder, ITEMS_DIR),
+        format!("{}/{}", folder, FILES_DIR),
+    ];
+    for url in dirs {
+        let response = request(settings, mkcol.clone(), &url)
+            .send()
+            .await
+            .map_err(|e| CloudPasteError::request("创建 WebDAV 目录失败", e))?;
+        if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+            check(response, "创建 WebDAV 目录失败").await?;
+        }
+    }
+    Ok(())
+}
+
+/// 列出远程的剪贴板项文件名（按时间排序）
+async fn list_items(settings: &WebDavSettings) -> Result<Vec<String>, CloudPasteError> {
+    let propfind = Method::from_bytes(b"PROPFIND").expect("PROPFIND 是合法的 HTTP 方法");
+    let url = format!("{}/{}/", folder_url(settings), ITEMS_DIR);
+    let response = request(settings, propfind, &url)
+        .header("Depth", "1")
+        .send()
+        .await
+        .map_err(|e| CloudPasteError::request("列出 WebDAV 目录失败", e))?;
+    let body = check(response, "列出 WebDAV 目录失败")
+        .await?
+        .text()
+        .await
+        .map_err(|e| CloudPasteError::request("读取 WebDAV 目录失败", e))?;
+
+    let mut names: Vec<String> = HREF
+        .captures_iter(&body)
+        .filter_map(|captures| captures[1].rsplit('/').next().map(str::to_string))
+        .filter(|name| name.ends_with(".json"))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+async fn fetch_item(
+    settings: &WebDavSettings,
+    name: &str,
+) -> Result<NewClipboardItem, CloudPasteError> {
+    let url = format!("{}/{}/{}", folder_url(settings), ITEMS_DIR, name);
+    let response = request(settings, Method::GET, &url)
+        .send()
+        .await
+        .map_err(|e| CloudPasteError::request("下载 WebDAV 剪贴板项失败", e))?;
+    check(response, "下载 WebDAV 剪贴板项失败")
+        .await?
+        .json()
+        .await
+        .map_err(|e| CloudPasteError::request("解析 WebDAV 剪贴板项失败", e))
+}
+
+/// 上传到远程目录下的子目录，目录不存在时先创建再重试
+async fn put(
+    settings: &WebDavSettings,
+    dir: &str,
+    name: &str,
+    body: Vec<u8>,
+    content_type: &str,
+) -> Result<(), CloudPasteError> {
+    if settings.url.is_empty() {
+        return Err(CloudPasteError::NotConfigured);
+    }
+    let url = format!("{}/{}/{}", folder_url(settings), dir, name);
+    let send = |body: Vec<u8>| {
+        request(settings, Method::PUT, &url)
+            .header("Content-Type", content_type)
+            .body(body)
+            .send()
+    };
+
+    let mut response = send(body.clone())
+        .await
+        .map_err(|e| CloudPasteError::request("上传到 WebDAV 失败", e))?;
+    if response.status() == StatusCode::NOT_FOUND || response.status() == StatusCode::CONFLICT {
+        ensure_folders(settings).await?;
+        response = send(body)
+            .await
+            .map_err(|e| CloudPasteError::request("上传到 WebDAV 失败", e))?;
+    }
+    check(response, "上传到 WebDAV 失败").await?;
+    Ok(())
+}
+
+/// 文件在 WebDAV 上的远程路径（相对于 WebDAV 地址）
+fn file_path(settings: &WebDavSettings, name: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        settings.folder.trim_matches('/'),
+        FILES_DIR,
+        name
+    )
+}
+
+/// 是否为 `put_file` 写入同步目录的文件路径
+fn is_file_path(settings: &WebDavSettings, path: &str) -> bool {
+    path.strip_prefix(&file_path(settings, ""))
+        .is_some_and(|name| !name.is_empty() && !name.contains('/') && name != "..")
+}
+
+/// 将剪贴板项写入 WebDAV，返回远程文件名
+///
+/// 图片和文件须先通过 `put_file` 写入，记录内容为其返回的远程路径
+pub async fn push_item(item: &NewClipboardItem) -> Result<String, CloudPasteError> {
+    let settings = settings();
+    if item.content_type != "text" && !is_file_path(&settings, &item.content) {
+        return Err(CloudPasteError::InvalidInput(
+            "WebDAV 同步的图片和文件需先上传到 WebDAV".to_string(),
+        ));
+    }
+
+    let mut item = item.clone();
+    crate::device_trust::sign_item(&mut item);
+    let body = serde_json::to_vec(&item).map_err(|e| format!("序列化剪贴板项失败: {}", e))?;
+    let name = storage::item_file_name(&item);
+    put(&settings, ITEMS_DIR, &name, body, "application/json").await?;
+    log::info!("☁️ 已同步到 WebDAV: {}", name);
+    Ok(name)
+}
+
+/// 将文件写入 WebDAV 的文件目录，返回远程路径
+pub async fn put_file(name: &str, data: Vec<u8>) -> Result<String, CloudPasteError> {
+    let settings = settings();
+    put(&settings, FILES_DIR, name, data, "application/octet-stream").await?;
+    Ok(file_path(&settings, name))
+}
+
+/// 将大文件流式写入 WebDAV 的文件目录，返回远程路径（请求体无法重发，先创建目录）
+pub async fn put_file_stream(name: &str, body: reqwest::Body) -> Result<String, CloudPasteError> {
+    let settings = settings();
+    if settings.url.is_empty() {
+        return Err(CloudPasteError::NotConfigured);
+    }
+    ensure_folders(&settings).await?;
+    let url = format!("{}/{}/{}", folder_url(&settings), FILES_DIR, name);
+    let response = request(&settings, Method::PUT, &url)
+        .header("Content-Type", "application/octet-stream")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| CloudPasteError::request("上传到 WebDAV 失败", e))?;
+    check(response, "上传到 WebDAV 失败").await?;
+    Ok(file_path(&settings, name))
+}
+
+/// 文件的完整地址（访问时需要 WebDAV 账号）
+pub fn file_url(path: &str) -> String {
+    format!(
+        "{}/{}",
+        settings().url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+/// 下载 `put_file` 写入的文件（`path` 为其返回的远程路径，只允许读取同步目录的文件）
+pub async fn get_file(path: &str) -> Result<Vec<u8>, CloudPasteError> {
+    let settings = settings();
+    if settings.url.is_empty() {
+        return Err(CloudPasteError::NotConfigured);
+    }
+    if !is_file_path(&settings, path) {
+        return Err(CloudPasteError::PermissionDenied(format!(
+            "不是 WebDAV 同步目录中的文件: {}",
+            path
+        )));
+    }
+    let url = file_url(path);
+    let response = request(&settings, Method::GET, &url)
+        .send()
+        .await
+        .map_err(|e| CloudPasteError::request("下载 WebDAV 文件失败", e))?;
+    let body = check(response, "下载 WebDAV 文件失败")
+        .await?
+        .bytes()
+        .await
+        .map_err(|e| CloudPasteError::request("读取 WebDAV 文件失败", e))?;
+    Ok(body.to_vec())
+}
+
+/// 列出名称排在 `after` 之后的剪贴板项文件名（按时间排序）
+pub async fn list(after: Option<&str>) -> Result<Vec<String>, CloudPasteError> {
+    let settings = settings();
+    if settings.url.is_empty() {
+        return Err(CloudPasteError::NotConfigured);
+    }
+    let names = list_items(&settings).await?;
+    Ok(names
+        .into_iter()
+        .filter(|name| after.is_none_or(|after| name.as_str() > after))
+        .collect())
+}
+
+/// 读取一个剪贴板项
+pub async fn fetch(name: &str) -> Result<NewClipboardItem, CloudPasteError> {
+    fetch_item(&settings(), name).await
+}
+
+/// 前端传回的设置未填写密码时沿用已保存的密码
+fn with_saved_password(mut settings: WebDavSettings) -> WebDavSettings {
+    if settings.password.is_empty() {
+        settings.password = self::settings().password;
+    }
+    settings
+}
+
+/// Tauri 命令：获取当前的主同步后端
+#[tauri::command]
+pub fn get_sync_backend() -> SyncBackend {
+    settings().backend
+}
+
+/// Tauri 命令：获取 WebDAV 同步设置（不返回密码）
+#[tauri::command]
+pub fn get_webdav_settings() -> WebDavSettings {
+    WebDavSettings {
+        password: String::new(),
+        ..settings()
+    }
+}
+
+/// Tauri 命令：更新 WebDAV 同步设置（密码为空时沿用已保存的密码，切换目录后从最新位置开始轮询）
+#[tauri::command]
+pub fn set_webdav_settings(
+    app: AppHandle,
+    settings: WebDavSettings,
+) -> Result<(), CloudPasteError> {
+    let settings = with_saved_password(settings);
+    store::save_json(&app, SETTINGS_FILE, &settings)?;
+    *SETTINGS
+        .lock()
+        .map_err(|e| format!("无法锁定设置: {}", e))? = settings;
+    storage::restart_polling();
+    Ok(())
+}
+
+/// Tauri 命令：测试 WebDAV 连接并创建远程目录
+#[tauri::command]
+pub async fn test_webdav_connection(settings: WebDavSettings) -> Result<(), CloudPasteError> {
+    let settings = with_saved_password(settings);
+    ensure_folders(&settings).await?;
+    list_items(&settings).await?;
+    log::info!("✅ WebDAV 连接正常: {}", folder_url(&settings));
+    Ok(())
+}
+
+/// Tauri 命令：将剪贴板项同步到 WebDAV
+#[tauri::command]
+pub async fn webdav_push_item(item: NewClipboardItem) -> Result<String, CloudPasteError> {
+    push_item(&item).await
+}
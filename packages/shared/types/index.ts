@@ -41,6 +41,13 @@ export interface UserSettingsUpdate {
 }
 
 // ==================== 剪贴板相关 ====================
+/** 剪贴板项的一种表示格式（同一内容的纯文本 / HTML / RTF / 图片） */
+export interface ClipboardRepresentation {
+  format: 'text' | 'html' | 'rtf' | 'image';
+  content: string;
+  mime_type?: string;
+}
+
 export interface ClipboardItem {
   id: number;
   content: string;
@@ -54,6 +61,10 @@ export interface ClipboardItem {
   mime_type?: string;
   updated_at: string;
   synced: boolean;
+  representations?: ClipboardRepresentation[];
+  signature?: string;
+  public_key?: string;
+  file_hash?: string;
 }
 
 export interface ClipboardItemCreate {
@@ -117,6 +128,10 @@ export interface ClipboardSyncData {
   file_id?: string;
   file_url?: string;
   clipboard_id?: number;
+  representations?: ClipboardRepresentation[];
+  signature?: string;
+  public_key?: string;
+  file_hash?: string;
 }
 
 export interface OnlineDevice {
@@ -142,5 +157,6 @@ export interface FileUploadResponse {
     mime_type: string;
     file_url: string;
     content_type: 'image' | 'file';
+    file_hash?: string;
   };
 }